@@ -0,0 +1,82 @@
+#![feature(test)]
+#![cfg(feature = "bytecode")]
+
+///! Test evaluating bytecode
+extern crate test;
+
+use rhai::{Engine, OptimizationLevel};
+use test::Bencher;
+
+#[bench]
+fn bench_bytecode_iterations_1000(bench: &mut Bencher) {
+    let script = "
+            let x = 1_000;
+
+            while x > 0 {
+                x -= 1;
+            }
+        ";
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let bytecode = engine.compile_bytecode(&engine.compile(script).unwrap());
+
+    bench.iter(|| engine.run_bytecode(&bytecode).unwrap());
+}
+
+#[bench]
+fn bench_bytecode_fibonacci(bench: &mut Bencher) {
+    let script = "
+        fn fibonacci(n) {
+            if n < 2 {
+                n
+            } else {
+                fibonacci(n-1) + fibonacci(n-2)
+            }
+        }
+
+        fibonacci(20)
+    ";
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let bytecode = engine.compile_bytecode(&engine.compile(script).unwrap());
+
+    bench.iter(|| engine.run_bytecode(&bytecode).unwrap());
+}
+
+#[bench]
+fn bench_bytecode_primes(bench: &mut Bencher) {
+    let script = "
+        const MAX_NUMBER_TO_CHECK = 1_000;
+
+        let prime_mask = [];
+        prime_mask.pad(MAX_NUMBER_TO_CHECK, true);
+
+        prime_mask[0] = false;
+        prime_mask[1] = false;
+
+        let total_primes_found = 0;
+
+        for p in 2..MAX_NUMBER_TO_CHECK {
+            if prime_mask[p] {
+                total_primes_found += 1;
+                let i = 2 * p;
+
+                while i < MAX_NUMBER_TO_CHECK {
+                    prime_mask[i] = false;
+                    i += p;
+                }
+            }
+        }
+    ";
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let bytecode = engine.compile_bytecode(&engine.compile(script).unwrap());
+
+    bench.iter(|| engine.run_bytecode(&bytecode).unwrap());
+}
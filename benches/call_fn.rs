@@ -0,0 +1,62 @@
+#![feature(test)]
+#![cfg(not(feature = "no_function"))]
+#![cfg(not(feature = "no_index"))]
+#![cfg(not(feature = "no_object"))]
+
+///! Test calling script-defined functions repeatedly
+extern crate test;
+
+use rhai::{CallFnOptions, Engine, EngineCache, OptimizationLevel, Scope, INT};
+use test::Bencher;
+
+// Functions with `Dynamic` parameters, such as `push`, are the most expensive to resolve.
+const SCRIPT: &str = "
+    fn calc(x, y) {
+        let a = [x];
+        a.push(y);
+        a.insert(0, y);
+        if a.contains(x) { a.len() } else { 0 }
+    }
+";
+
+#[bench]
+fn bench_call_fn_small(bench: &mut Bencher) {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let ast = engine.compile(SCRIPT).unwrap();
+    let mut scope = Scope::new();
+
+    bench.iter(|| {
+        for i in 0..100 {
+            engine
+                .call_fn::<INT>(&mut scope, &ast, "calc", (i as INT, 7 as INT))
+                .unwrap();
+        }
+    });
+}
+
+#[bench]
+fn bench_call_fn_small_with_cache(bench: &mut Bencher) {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let ast = engine.compile(SCRIPT).unwrap();
+    let mut scope = Scope::new();
+    let mut cache = EngineCache::new();
+
+    bench.iter(|| {
+        for i in 0..100 {
+            let options = CallFnOptions::new().with_cache(&mut cache);
+            engine
+                .call_fn_with_options::<INT>(
+                    options,
+                    &mut scope,
+                    &ast,
+                    "calc",
+                    (i as INT, 7 as INT),
+                )
+                .unwrap();
+        }
+    });
+}
@@ -0,0 +1,85 @@
+//! An example showing how to run many scripts waiting on events without a thread per script,
+//! by suspending and resuming them.
+//!
+//! Compare with `pause_and_resume.rs`, which ties up a thread while a script is paused.
+
+use rhai::{Dynamic, Engine, EvalAltResult, Resumable, Suspended};
+use std::collections::VecDeque;
+
+fn main() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // Suspend the script until the event happens, returning the event data
+    engine.register_fn(
+        "wait_for",
+        |event: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+            Ok(
+                rhai::suspend(event)
+                    .ok_or("`wait_for` can only be called in a resumable script")?,
+            )
+        },
+    );
+
+    let script = r#"
+        print(`[Script ${id}] Waiting for a player...`);
+        let player = wait_for("join");
+        print(`[Script ${id}] ${player} joined!`);
+
+        let score = 0;
+
+        while score < 3 {
+            score += wait_for("score");
+            print(`[Script ${id}] ${player} has ${score} points`);
+        }
+
+        `${player} wins`
+    "#;
+
+    // Start all scripts
+    let mut waiting: Vec<(usize, Suspended<String>)> = Vec::new();
+    let mut finished = 0;
+
+    for id in 0..5 {
+        let script = format!("let id = {id};{script}");
+
+        match engine.eval_resumable::<String>(&script)? {
+            Resumable::Suspended(suspended) => waiting.push((id, suspended)),
+            Resumable::Done(result) => println!("[Main] Script {id} finished: {result}"),
+        }
+    }
+
+    // Simulate a stream of in-game events for each script
+    let mut events: VecDeque<(usize, &str, Dynamic)> = (0..5)
+        .map(|id| (id, "join", format!("Player{id}").into()))
+        .chain(
+            (1..=3).flat_map(|n| (0..5).map(move |id| (id, "score", (n * id as rhai::INT).into()))),
+        )
+        .collect();
+
+    // Resume scripts waiting on each event, all on the main thread
+    while let Some((target, event, data)) = events.pop_front() {
+        let Some(n) = waiting
+            .iter()
+            .position(|(id, s)| *id == target && s.value().to_string() == event)
+        else {
+            continue;
+        };
+
+        let (id, suspended) = waiting.swap_remove(n);
+
+        match suspended.resume(data)? {
+            Resumable::Suspended(suspended) => waiting.push((id, suspended)),
+            Resumable::Done(result) => {
+                println!("[Main] Script {id} finished: {result}");
+                finished += 1;
+            }
+        }
+    }
+
+    println!(
+        "[Main] {finished} scripts finished, {} still waiting",
+        waiting.len()
+    );
+
+    Ok(())
+}
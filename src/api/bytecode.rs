@@ -0,0 +1,184 @@
+//! Module that defines the bytecode evaluation API of [`Engine`].
+#![cfg(feature = "bytecode")]
+
+use crate::eval::{Caches, GlobalRuntimeState};
+use crate::types::dynamic::Variant;
+use crate::{Bytecode, Dynamic, Engine, Position, RhaiResult, RhaiResultOf, Scope, AST, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    any::{type_name, TypeId},
+    mem,
+};
+
+impl Engine {
+    /// Compile an [`AST`] into [`Bytecode`] that runs on a register-based virtual machine.
+    ///
+    /// Script bodies and functions that cannot be compiled are evaluated by walking the [`AST`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("let x = 0; for i in 0..10 { x += i; } x")?;
+    ///
+    /// let bytecode = engine.compile_bytecode(&ast);
+    ///
+    /// assert!(bytecode.is_fully_compiled());
+    /// assert_eq!(engine.eval_bytecode::<i64>(&bytecode)?, 45);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn compile_bytecode(&self, ast: &AST) -> Bytecode {
+        Bytecode::compile(self, ast)
+    }
+    /// Evaluate [`Bytecode`], returning the result value or an error.
+    #[inline(always)]
+    pub fn eval_bytecode<T: Variant + Clone>(&self, bytecode: &Bytecode) -> RhaiResultOf<T> {
+        self.eval_bytecode_with_scope(&mut Scope::new(), bytecode)
+    }
+    /// Evaluate [`Bytecode`] with own scope, returning the result value or an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// // Create initialized scope
+    /// let mut scope = Scope::new();
+    /// scope.push("x", 40_i64);
+    ///
+    /// let bytecode = engine.compile_bytecode(&engine.compile("x += 2; x")?);
+    ///
+    /// assert_eq!(engine.eval_bytecode_with_scope::<i64>(&mut scope, &bytecode)?, 42);
+    /// assert_eq!(engine.eval_bytecode_with_scope::<i64>(&mut scope, &bytecode)?, 44);
+    ///
+    /// // The variable in the scope is modified
+    /// assert_eq!(scope.get_value::<i64>("x").expect("variable x should exist"), 44);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn eval_bytecode_with_scope<T: Variant + Clone>(
+        &self,
+        scope: &mut Scope,
+        bytecode: &Bytecode,
+    ) -> RhaiResultOf<T> {
+        let global = &mut self.new_global_runtime_state();
+        let caches = &mut Caches::new();
+
+        let result = self.eval_bytecode_with_scope_raw(global, caches, scope, bytecode)?;
+
+        // Bail out early if the return type needs no cast
+        if TypeId::of::<T>() == TypeId::of::<Dynamic>() {
+            return Ok(reify! { result => T });
+        }
+
+        result.try_cast_result::<T>().map_err(|v| {
+            let typename = match type_name::<T>() {
+                typ if typ.contains("::") => self.map_type_name(typ),
+                typ => typ,
+            };
+
+            ERR::ErrorMismatchOutputType(
+                typename.into(),
+                self.map_type_name(v.type_name()).into(),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+    /// Evaluate [`Bytecode`].
+    #[inline(always)]
+    pub fn run_bytecode(&self, bytecode: &Bytecode) -> RhaiResultOf<()> {
+        self.run_bytecode_with_scope(&mut Scope::new(), bytecode)
+    }
+    /// Evaluate [`Bytecode`] with own scope.
+    #[inline]
+    pub fn run_bytecode_with_scope(
+        &self,
+        scope: &mut Scope,
+        bytecode: &Bytecode,
+    ) -> RhaiResultOf<()> {
+        let global = &mut self.new_global_runtime_state();
+        let caches = &mut Caches::new();
+
+        self.eval_bytecode_with_scope_raw(global, caches, scope, bytecode)
+            .map(|_| ())
+    }
+    /// Evaluate [`Bytecode`] with own scope, returning the result value or an error.
+    ///
+    /// The [`AST`] is evaluated instead if the [`Engine`] settings no longer match the bytecode.
+    pub(crate) fn eval_bytecode_with_scope_raw(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        bytecode: &Bytecode,
+    ) -> RhaiResult {
+        let program = bytecode.program();
+        let ast = &program.ast;
+
+        #[cfg(feature = "debugging")]
+        let has_debugger = self.is_debugger_registered();
+        #[cfg(not(feature = "debugging"))]
+        let has_debugger = false;
+
+        if has_debugger
+            || self.resolve_var.is_some()
+            || self.def_var_filter.is_some()
+            || self.fast_operators() != program.fast_operators
+        {
+            return self.eval_ast_with_scope_raw(global, caches, scope, ast);
+        }
+
+        let orig_source = mem::replace(&mut global.source, ast.source_raw().cloned());
+
+        #[cfg(not(feature = "no_function"))]
+        let orig_lib_len = global.lib.len();
+
+        #[cfg(not(feature = "no_function"))]
+        global.lib.push(ast.shared_lib().clone());
+
+        #[cfg(not(feature = "no_function"))]
+        let orig_bytecode = global.bytecode.replace(program.clone());
+
+        #[cfg(not(feature = "no_module"))]
+        let orig_embedded_module_resolver =
+            mem::replace(&mut global.embedded_module_resolver, ast.resolver.clone());
+
+        defer! { global => move |g| {
+            #[cfg(not(feature = "no_module"))]
+            {
+                g.embedded_module_resolver = orig_embedded_module_resolver;
+            }
+
+            #[cfg(not(feature = "no_function"))]
+            {
+                g.bytecode = orig_bytecode;
+                g.lib.truncate(orig_lib_len);
+            }
+
+            g.source = orig_source;
+        }}
+
+        match program.main {
+            Some(ref chunk) => self.run_chunk(global, caches, scope, None, chunk, false),
+            None => self.eval_stmt_block(global, caches, scope, None, ast.statements(), false),
+        }
+        .or_else(|err| match *err {
+            ERR::Return(out, ..) | ERR::Exit(out, ..) => Ok(out),
+            ERR::LoopBreak(..) => unreachable!("no outer loop scope to break out of"),
+            _ => Err(err),
+        })
+    }
+}
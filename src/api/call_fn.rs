@@ -4,15 +4,19 @@
 use crate::eval::{Caches, GlobalRuntimeState};
 use crate::types::dynamic::Variant;
 use crate::{
-    Dynamic, Engine, FnArgsVec, FuncArgs, Position, RhaiResult, RhaiResultOf, Scope, StaticVec,
-    AST, ERR,
+    Dynamic, Engine, EngineCache, FnArgsVec, FuncArgs, Position, RhaiResult, RhaiResultOf, Scope,
+    StaticVec, AST, ERR,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{any::type_name, mem};
+use std::{
+    any::type_name,
+    hash::{Hash, Hasher},
+    mem,
+};
 
 /// Options for calling a script-defined function via [`Engine::call_fn_with_options`].
-#[derive(Debug, Hash)]
+#[derive(Debug)]
 #[non_exhaustive]
 pub struct CallFnOptions<'t> {
     /// A value for binding to the `this` pointer (if any). Default [`None`].
@@ -23,6 +27,18 @@ pub struct CallFnOptions<'t> {
     pub eval_ast: bool,
     /// Rewind the [`Scope`] after the function call? Default `true`.
     pub rewind_scope: bool,
+    /// A cache of function resolution results to keep across calls (if any). Default [`None`].
+    pub cache: Option<&'t mut EngineCache>,
+}
+
+impl Hash for CallFnOptions<'_> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.this_ptr.hash(state);
+        self.tag.hash(state);
+        self.eval_ast.hash(state);
+        self.rewind_scope.hash(state);
+    }
 }
 
 impl Default for CallFnOptions<'_> {
@@ -42,6 +58,7 @@ impl<'a> CallFnOptions<'a> {
             tag: None,
             eval_ast: true,
             rewind_scope: true,
+            cache: None,
         }
     }
     /// Bind to the `this` pointer.
@@ -72,6 +89,16 @@ impl<'a> CallFnOptions<'a> {
         self.rewind_scope = value;
         self
     }
+    /// Keep function resolution results in an [`EngineCache`] across calls.
+    ///
+    /// This speeds up repeated calls into the same [`AST`] because functions called by the script
+    /// need not be looked up again.
+    #[inline(always)]
+    #[must_use]
+    pub fn with_cache(mut self, cache: &'a mut EngineCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
 }
 
 impl Engine {
@@ -231,6 +258,10 @@ impl Engine {
 
         let rewind_scope = options.rewind_scope;
 
+        // Continue with function resolution results kept from previous calls
+        let mut cache = options.cache;
+        let cache_index = cache.as_deref_mut().map(|c| c.load(self, ast, caches));
+
         defer! { global => move |g| {
             #[cfg(not(feature = "no_module"))]
             {
@@ -283,6 +314,10 @@ impl Engine {
                 })
         });
 
+        if let (Some(cache), Some(index)) = (cache, cache_index) {
+            cache.store(caches, index, global);
+        }
+
         #[cfg(feature = "debugging")]
         if self.is_debugger_registered() {
             global.debugger_mut().status = crate::eval::DebuggerStatus::Terminate;
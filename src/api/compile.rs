@@ -312,4 +312,42 @@ impl Engine {
             self.optimization_level,
         )
     }
+
+    /// Load an [`AST`] previously encoded into binary format via [`AST::to_bytes`].
+    ///
+    /// No parsing or optimization is performed. Strings in the [`AST`] are interned using this
+    /// [`Engine`]'s strings interner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a valid [`AST`] in binary format, or if they were
+    /// encoded by a build of Rhai with a different format version or a different set of language
+    /// feature flags.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("fn add(x, y) { x + y } add(40, 2)")?;
+    /// let bytes = ast.to_bytes()?;
+    ///
+    /// // ... persist 'bytes' and read them back later ...
+    ///
+    /// let ast = engine.compile_from_bytes(&bytes)?;
+    ///
+    /// assert_eq!(engine.eval_ast::<i64>(&ast)?, 42);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn compile_from_bytes(&self, bytes: impl AsRef<[u8]>) -> crate::RhaiResultOf<AST> {
+        crate::ast::binary::decode(self, bytes.as_ref())
+    }
 }
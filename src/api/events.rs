@@ -246,6 +246,39 @@ impl Engine {
         self.progress = Some(Box::new(callback));
         self
     }
+    /// Override the clock used by the `now` function to get the current [`DateTime`][crate::DateTime]
+    /// (default is the system clock).
+    ///
+    /// This is useful for sandboxing, deterministic testing, or on platforms without a system clock.
+    ///
+    /// Not available under `no_time`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{DateTime, Engine};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_clock(|| DateTime::from_ymd_hms(2024, 2, 29, 12, 30, 0).unwrap());
+    ///
+    /// assert_eq!(engine.eval::<String>("to_string(now())")?, "2024-02-29T12:30:00Z");
+    /// # #[cfg(not(feature = "no_object"))]
+    /// assert_eq!(engine.eval::<rhai::INT>("now().year")?, 2024);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_time"))]
+    #[inline(always)]
+    pub fn set_clock(
+        &mut self,
+        callback: impl Fn() -> crate::DateTime + SendSync + 'static,
+    ) -> &mut Self {
+        self.clock = Some(Box::new(callback));
+        self
+    }
     /// Override default action of `print` (print to stdout using [`println!`])
     ///
     /// # Example
@@ -0,0 +1,635 @@
+//! Module that defines the source code formatter of [`Engine`].
+#![cfg(not(feature = "no_position"))]
+
+use crate::parser::ParseResult;
+use crate::tokenizer::Token;
+use crate::{Engine, Position};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Options for formatting a script via [`Engine::format_script`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level. Default 4.
+    pub indent_size: usize,
+    /// Indent with tabs instead of spaces? Default `false`.
+    pub use_tabs: bool,
+    /// Maximum line width before lists of arguments, array literals and object map literals are
+    /// broken into multiple lines. Default 100.
+    pub max_width: usize,
+}
+
+impl Default for FormatOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatOptions {
+    /// Create a default [`FormatOptions`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            indent_size: 4,
+            use_tabs: false,
+            max_width: 100,
+        }
+    }
+    /// Set the number of spaces per indentation level.
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_indent_size(mut self, value: usize) -> Self {
+        self.indent_size = value;
+        self
+    }
+    /// Indent with tabs instead of spaces?
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_tabs(mut self, value: bool) -> Self {
+        self.use_tabs = value;
+        self
+    }
+    /// Set the maximum line width.
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_max_width(mut self, value: usize) -> Self {
+        self.max_width = value;
+        self
+    }
+}
+
+/// Role of a `|` token.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum PipeKind {
+    /// Not part of a closure (i.e. the binary `|` operator).
+    None,
+    /// Start of the parameters list of a closure.
+    Open,
+    /// End of the parameters list of a closure.
+    Close,
+}
+
+/// A token in the source text, together with its original text.
+#[derive(Debug)]
+struct Atom {
+    /// The token.
+    ///
+    /// An entire interpolated string is kept as a single [`Token::StringConstant`].
+    token: Token,
+    /// Original text of the token.
+    text: String,
+    /// Original line of the start of the token.
+    line: usize,
+    /// Original line of the end of the token.
+    end_line: usize,
+    /// Role of a `|` token.
+    pipe: PipeKind,
+}
+
+impl Atom {
+    /// Can this token end an operand?
+    const fn ends_operand(&self) -> bool {
+        matches!(
+            self.token,
+            Token::Identifier(..)
+                | Token::IntegerConstant(..)
+                | Token::CharConstant(..)
+                | Token::StringConstant(..)
+                | Token::True
+                | Token::False
+                | Token::Unit
+                | Token::RightParen
+                | Token::RightBracket
+                | Token::RightBrace
+        ) || self.is_float()
+    }
+    /// Is this token a floating-point or decimal number?
+    #[allow(clippy::missing_const_for_fn)]
+    const fn is_float(&self) -> bool {
+        #[cfg(not(feature = "no_float"))]
+        if matches!(self.token, Token::FloatConstant(..)) {
+            return true;
+        }
+        #[cfg(feature = "decimal")]
+        if matches!(self.token, Token::DecimalConstant(..)) {
+            return true;
+        }
+        false
+    }
+    /// Is this token the `?.` or `?[` symbol?
+    ///
+    /// These symbols are not available under `no_object` and `no_index` respectively.
+    fn is_optional_access(&self) -> bool {
+        matches!(self.text.as_str(), "?." | "?[")
+    }
+    /// Does this token open a parenthesized arguments list, an array literal or an object map
+    /// literal?
+    fn opens_group(&self) -> bool {
+        matches!(
+            self.token,
+            Token::LeftParen | Token::LeftBracket | Token::MapStart
+        ) || self.text == "?["
+    }
+    /// Is this token a line comment?
+    fn is_line_comment(&self) -> bool {
+        matches!(self.token, Token::Comment(..)) && self.text.starts_with("//")
+    }
+}
+
+/// Is a space needed between two tokens on the same line?
+fn needs_space(prev: &Atom, next: &Atom) -> bool {
+    #[allow(clippy::enum_glob_use)]
+    use Token::*;
+
+    if matches!(prev.token, Comment(..)) || matches!(next.token, Comment(..)) {
+        return true;
+    }
+    if prev.pipe == PipeKind::Open || next.pipe == PipeKind::Close {
+        return false;
+    }
+    if prev.is_optional_access() || next.is_optional_access() {
+        return false;
+    }
+
+    match (&prev.token, &next.token) {
+        // Rest of an array pattern
+        (Comma, ExclusiveRange) => true,
+        (
+            _,
+            Comma | SemiColon | Colon | RightParen | RightBracket | Period | DoubleColon
+            | ExclusiveRange | InclusiveRange,
+        ) => false,
+        // Empty object map or block
+        (MapStart | LeftBrace, RightBrace) => false,
+        (
+            LeftParen | LeftBracket | Period | DoubleColon | Bang | UnaryMinus | UnaryPlus
+            | ExclusiveRange | InclusiveRange,
+            _,
+        ) => false,
+        // Function call
+        (Identifier(..) | Reserved(..) | RightParen | RightBracket, LeftParen | Unit) => false,
+        // Indexing
+        (.., LeftBracket) => !prev.ends_operand(),
+        _ => true,
+    }
+}
+
+/// Kind of a statements block.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum BlockKind {
+    /// Normal statements block.
+    Normal,
+    /// Block of a `switch` or `match` expression.
+    Switch,
+    /// Body of a `do` loop.
+    Do,
+}
+
+/// A nesting level during formatting.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Frame {
+    /// Statements block.
+    Block(BlockKind),
+    /// Parenthesized arguments list, array literal or object map literal.
+    Group {
+        /// Is this group broken into multiple lines?
+        broken: bool,
+    },
+}
+
+/// Pretty-printer of a list of [atoms][Atom].
+struct Printer<'a> {
+    /// Formatting options.
+    options: FormatOptions,
+    /// Tokens to print.
+    atoms: &'a [Atom],
+    /// Index of the matching closing token for each opening token.
+    matching: Vec<Option<usize>>,
+    /// Output text.
+    out: String,
+    /// Current indentation level.
+    indent: usize,
+    /// Index of the last printed token.
+    prev: Option<usize>,
+}
+
+impl<'a> Printer<'a> {
+    /// Create a new [`Printer`].
+    fn new(atoms: &'a [Atom], options: FormatOptions) -> Self {
+        let mut matching = vec![None; atoms.len()];
+        let mut stack = Vec::new();
+
+        for (i, atom) in atoms.iter().enumerate() {
+            match atom.token {
+                Token::LeftBrace => stack.push(i),
+                _ if atom.opens_group() => stack.push(i),
+                Token::RightParen | Token::RightBracket | Token::RightBrace => {
+                    if let Some(open) = stack.pop() {
+                        matching[open] = Some(i);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Self {
+            options,
+            atoms,
+            matching,
+            out: String::new(),
+            indent: 0,
+            prev: None,
+        }
+    }
+    /// Is the output at the start of a line?
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+    /// Current column of the output.
+    fn column(&self) -> usize {
+        self.out
+            .rsplit('\n')
+            .next()
+            .map_or(0, |line| line.chars().count())
+    }
+    /// Start a new line.
+    fn newline(&mut self) {
+        if !self.at_line_start() {
+            self.out.truncate(self.out.trim_end_matches(' ').len());
+            self.out.push('\n');
+        }
+    }
+    /// Start a new line after a token, unless it is followed by a comment on the same line.
+    fn break_line(&mut self, index: usize) {
+        match self.atoms.get(index + 1) {
+            Some(next)
+                if matches!(next.token, Token::Comment(..))
+                    && next.line == self.atoms[index].end_line => {}
+            _ => self.newline(),
+        }
+    }
+    /// Print a token.
+    fn emit(&mut self, index: usize) {
+        let atom = &self.atoms[index];
+
+        if self.at_line_start() {
+            // Keep one blank line between statements
+            if let Some(prev) = self.prev.map(|i| &self.atoms[i]) {
+                if atom.line > prev.end_line + 1
+                    && !matches!(
+                        prev.token,
+                        Token::LeftBrace | Token::LeftParen | Token::LeftBracket | Token::MapStart
+                    )
+                    && !matches!(
+                        atom.token,
+                        Token::RightBrace | Token::RightParen | Token::RightBracket
+                    )
+                {
+                    self.out.push('\n');
+                }
+            }
+
+            if self.options.use_tabs {
+                self.out.extend(std::iter::repeat('\t').take(self.indent));
+            } else {
+                let width = self.indent * self.options.indent_size;
+                self.out.extend(std::iter::repeat(' ').take(width));
+            }
+        } else if self
+            .prev
+            .map_or(false, |prev| needs_space(&self.atoms[prev], atom))
+        {
+            self.out.push(' ');
+        }
+
+        self.out.push_str(&atom.text);
+        self.prev = Some(index);
+    }
+    /// Render a range of tokens into a single line.
+    fn render_flat(&self, start: usize, end: usize) -> String {
+        let mut text = String::new();
+
+        for i in start..=end {
+            if i > start && needs_space(&self.atoms[i - 1], &self.atoms[i]) {
+                text.push(' ');
+            }
+            text.push_str(&self.atoms[i].text);
+        }
+
+        text
+    }
+    /// Print all the tokens.
+    fn run(mut self) -> String {
+        let mut stack = Vec::new();
+        let mut pending = BlockKind::Normal;
+        let mut i = 0;
+
+        while i < self.atoms.len() {
+            let atom = &self.atoms[i];
+
+            match atom.token {
+                Token::Comment(..) => {
+                    let same_line = !self.at_line_start()
+                        && self
+                            .prev
+                            .map_or(false, |prev| self.atoms[prev].end_line == atom.line);
+
+                    if !same_line {
+                        self.newline();
+                    }
+                    self.emit(i);
+
+                    if atom.is_line_comment() {
+                        self.newline();
+                    } else {
+                        match self.atoms.get(i + 1) {
+                            Some(next) if next.line > atom.end_line => self.newline(),
+                            _ => (),
+                        }
+                    }
+                }
+                Token::Switch | Token::Match => {
+                    pending = BlockKind::Switch;
+                    self.emit(i);
+                }
+                Token::Do => {
+                    pending = BlockKind::Do;
+                    self.emit(i);
+                }
+                Token::LeftBrace => {
+                    let kind = std::mem::replace(&mut pending, BlockKind::Normal);
+                    self.emit(i);
+                    stack.push(Frame::Block(kind));
+                    self.indent += 1;
+
+                    if self.matching[i] != Some(i + 1) {
+                        self.break_line(i);
+                    }
+                }
+                _ if atom.opens_group() => {
+                    let Some(end) = self.matching[i] else {
+                        self.emit(i);
+                        i += 1;
+                        continue;
+                    };
+
+                    let inner = &self.atoms[i + 1..end];
+                    let has_comments = inner
+                        .iter()
+                        .any(|atom| matches!(atom.token, Token::Comment(..)));
+                    // Groups containing statements blocks are only broken when they contain comments
+                    let has_blocks = inner.iter().any(|atom| {
+                        matches!(
+                            atom.token,
+                            Token::LeftBrace | Token::Switch | Token::Match | Token::Do
+                        ) || atom.line != atom.end_line
+                    });
+                    let fits = || {
+                        self.column() + 1 + self.render_flat(i, end).chars().count()
+                            <= self.options.max_width
+                    };
+
+                    if end == i + 1 || (!has_comments && !has_blocks && fits()) {
+                        // Print the entire group on one line
+                        for index in i..=end {
+                            self.emit(index);
+                        }
+                        i = end;
+                    } else if has_comments || !has_blocks {
+                        self.emit(i);
+                        stack.push(Frame::Group { broken: true });
+                        self.indent += 1;
+                        self.break_line(i);
+                    } else {
+                        self.emit(i);
+                        stack.push(Frame::Group { broken: false });
+                    }
+                }
+                Token::RightParen | Token::RightBracket | Token::RightBrace => match stack.pop() {
+                    Some(Frame::Block(kind)) => {
+                        self.indent = self.indent.saturating_sub(1);
+                        if self
+                            .prev
+                            .map_or(true, |prev| self.matching[prev] != Some(i))
+                        {
+                            self.newline();
+                        }
+                        self.emit(i);
+
+                        // Continue on the same line?
+                        let same_line = self.atoms.get(i + 1).map_or(false, |next| {
+                            matches!(
+                                next.token,
+                                Token::Else
+                                    | Token::Catch
+                                    | Token::Comma
+                                    | Token::SemiColon
+                                    | Token::RightParen
+                                    | Token::RightBracket
+                                    | Token::Period
+                                    | Token::DoubleQuestion
+                            ) || next.is_optional_access()
+                                || (kind == BlockKind::Do
+                                    && matches!(next.token, Token::While | Token::Until))
+                        });
+
+                        if !same_line {
+                            self.break_line(i);
+                        }
+                    }
+                    Some(Frame::Group { broken: true }) => {
+                        self.indent = self.indent.saturating_sub(1);
+                        self.newline();
+                        self.emit(i);
+                    }
+                    Some(Frame::Group { broken: false }) | None => self.emit(i),
+                },
+                Token::Comma => {
+                    self.emit(i);
+
+                    if matches!(
+                        stack.last(),
+                        Some(Frame::Group { broken: true } | Frame::Block(BlockKind::Switch))
+                    ) {
+                        self.break_line(i);
+                    }
+                }
+                Token::SemiColon => {
+                    self.emit(i);
+
+                    if matches!(stack.last(), None | Some(Frame::Block(..))) {
+                        self.break_line(i);
+                    }
+                }
+                _ => self.emit(i),
+            }
+
+            i += 1;
+        }
+
+        self.newline();
+        self.out
+    }
+}
+
+/// Convert a [`Position`] into a byte offset into the source text.
+fn to_offset(lines: &[usize], text: &str, pos: Position) -> usize {
+    let (Some(line), Some(column)) = (pos.line(), pos.position()) else {
+        return text.len();
+    };
+    let Some(&start) = lines.get(line - 1) else {
+        return text.len();
+    };
+
+    text[start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(text.len(), |(offset, ..)| start + offset)
+}
+
+impl Engine {
+    /// Format a script.
+    ///
+    /// Not available under `no_position`.
+    ///
+    /// All comments are kept. Consecutive blank lines are collapsed into one.
+    ///
+    /// The script is first parsed to make sure that it is valid. A syntax error is returned if
+    /// the script does not compile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, FormatOptions};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let script = "let x=40 ;// the answer\nif x>0{x+=2}";
+    ///
+    /// let formatted = engine.format_script(script, FormatOptions::new())?;
+    ///
+    /// assert_eq!(formatted, "let x = 40; // the answer\nif x > 0 {\n    x += 2\n}\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_script(
+        &self,
+        script: impl AsRef<str>,
+        options: FormatOptions,
+    ) -> ParseResult<String> {
+        let script = script.as_ref();
+        let scripts = [script];
+
+        // Make sure the script is valid
+        self.compile_scripts_with_scope_raw(
+            None,
+            scripts,
+            #[cfg(not(feature = "no_optimize"))]
+            crate::OptimizationLevel::None,
+        )?;
+
+        let (mut stream, control) = self.lex(&scripts);
+        stream.state.include_comments = true;
+
+        // Collect top-level tokens, keeping interpolated strings whole
+        let mut tokens = Vec::new();
+        let mut interpolated = Vec::new();
+        let mut depth = 0_usize;
+        let mut within_text = false;
+
+        let end = loop {
+            let (token, pos) = stream.next().expect("never ends");
+
+            match token {
+                Token::EOF => break pos,
+                Token::LexError(err) => return Err(err.into_err(pos)),
+                _ => (),
+            }
+
+            if interpolated.is_empty() && !within_text {
+                tokens.push((token.clone(), pos));
+            }
+            within_text = false;
+
+            match token {
+                Token::InterpolatedString(..) => interpolated.push(depth),
+                Token::LeftBrace | Token::MapStart => depth += 1,
+                // Format specifier of an interpolated expression
+                Token::Colon if interpolated.last().map_or(false, |&d| depth == d + 1) => {
+                    control.borrow_mut().is_within_format_spec = true;
+                }
+                Token::RightBrace => {
+                    depth = depth.saturating_sub(1);
+
+                    if interpolated.last() == Some(&depth) {
+                        interpolated.pop();
+                        // Switch the tokenizer back to text mode
+                        control.borrow_mut().is_within_text = true;
+                        within_text = true;
+                    }
+                }
+                _ => (),
+            }
+        };
+
+        // Extract the original text of each token
+        let lines: Vec<_> = std::iter::once(0)
+            .chain(script.match_indices('\n').map(|(i, ..)| i + 1))
+            .collect();
+
+        let offsets: Vec<_> = tokens
+            .iter()
+            .map(|(.., pos)| to_offset(&lines, script, *pos))
+            .chain(std::iter::once(
+                to_offset(&lines, script, end).max(script.len()),
+            ))
+            .collect();
+
+        let mut atoms: Vec<Atom> = tokens
+            .into_iter()
+            .enumerate()
+            .map(|(i, (token, pos))| {
+                let text = script[offsets[i]..offsets[i + 1]].trim_end().to_string();
+                let line = pos.line().unwrap_or(0);
+                let end_line = line + text.matches('\n').count();
+
+                Atom {
+                    token,
+                    text,
+                    line,
+                    end_line,
+                    pipe: PipeKind::None,
+                }
+            })
+            .collect();
+
+        // Find closure parameter lists
+        let mut i = 0;
+
+        while i < atoms.len() {
+            if atoms[i].token == Token::Pipe
+                && !atoms[..i]
+                    .iter()
+                    .rev()
+                    .find(|atom| !matches!(atom.token, Token::Comment(..)))
+                    .map_or(false, Atom::ends_operand)
+            {
+                if let Some(close) = atoms[i + 1..]
+                    .iter()
+                    .position(|atom| atom.token == Token::Pipe)
+                {
+                    atoms[i].pipe = PipeKind::Open;
+                    atoms[i + 1 + close].pipe = PipeKind::Close;
+                    i += close + 1;
+                }
+            }
+            i += 1;
+        }
+
+        Ok(Printer::new(&atoms, options).run())
+    }
+}
@@ -39,6 +39,10 @@ pub fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     if name == type_name::<rust_decimal::Decimal>() {
         return if shorthands { "decimal" } else { "Decimal" };
     }
+    #[cfg(feature = "bigint")]
+    if name == type_name::<crate::BigInt>() || name == "BigInt" {
+        return if shorthands { "bigint" } else { "BigInt" };
+    }
     if name == type_name::<FnPtr>() || name == "FnPtr" {
         return if shorthands { "Fn" } else { "FnPtr" };
     }
@@ -50,6 +54,14 @@ pub fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     if name == type_name::<crate::Blob>() || name == "Blob" {
         return if shorthands { "blob" } else { "Blob" };
     }
+    #[cfg(not(feature = "no_index"))]
+    if name == type_name::<crate::Set>() || name == "Set" {
+        return if shorthands { "set" } else { "Set" };
+    }
+    #[cfg(not(feature = "no_index"))]
+    if name == type_name::<crate::LazyIter>() || name == "LazyIter" {
+        return if shorthands { "iterator" } else { "LazyIter" };
+    }
     #[cfg(not(feature = "no_object"))]
     if name == type_name::<crate::Map>() || name == "Map" {
         return if shorthands { "map" } else { "Map" };
@@ -58,6 +70,19 @@ pub fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     if name == type_name::<crate::Instant>() || name == "Instant" {
         return if shorthands { "timestamp" } else { "Instant" };
     }
+    #[cfg(not(feature = "no_time"))]
+    if name == type_name::<crate::DateTime>() || name == "DateTime" {
+        return if shorthands { "datetime" } else { "DateTime" };
+    }
+    #[cfg(feature = "regex")]
+    if name == type_name::<regex::Regex>() {
+        return if shorthands { "regex" } else { "Regex" };
+    }
+    #[cfg(feature = "resumable")]
+    #[cfg(not(feature = "no_function"))]
+    if name == type_name::<crate::Generator>() || name == "Generator" {
+        return if shorthands { "generator" } else { "Generator" };
+    }
     if name == type_name::<ExclusiveRange>() || name == "ExclusiveRange" {
         return if shorthands {
             "range"
@@ -206,6 +206,22 @@ fn format_dynamic_as_json(result: &mut String, value: &Dynamic) {
             }
             *result += "]";
         }
+        #[cfg(not(feature = "no_index"))]
+        Union::Variant(ref v, _, _) if (***v).is::<crate::Set>() => {
+            *result += "[";
+            for (i, x) in value
+                .downcast_ref::<crate::Set>()
+                .unwrap()
+                .iter()
+                .enumerate()
+            {
+                if i > 0 {
+                    *result += ",";
+                }
+                format_dynamic_as_json(result, x);
+            }
+            *result += "]";
+        }
         #[cfg(not(feature = "no_closure"))]
         Union::Shared(ref v, _, _) => {
             let value = &*crate::func::locked_read(v).unwrap();
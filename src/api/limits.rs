@@ -13,6 +13,12 @@ pub mod default_limits {
     /// Not available under `no_function`.
     #[cfg(not(feature = "no_function"))]
     pub const MAX_CALL_STACK_DEPTH: usize = 8;
+    /// Maximum number of generators running at any instant.
+    ///
+    /// Only available under `resumable`. Not available under `no_function`.
+    #[cfg(feature = "resumable")]
+    #[cfg(not(feature = "no_function"))]
+    pub const MAX_GENERATORS: usize = 8;
     /// Maximum levels of expressions.
     pub const MAX_EXPR_DEPTH: usize = 32;
     /// Maximum levels of expressions in function bodies.
@@ -28,6 +34,12 @@ pub mod default_limits {
     /// Not available under `no_function`.
     #[cfg(not(feature = "no_function"))]
     pub const MAX_CALL_STACK_DEPTH: usize = 64;
+    /// Maximum number of generators running at any instant.
+    ///
+    /// Only available under `resumable`. Not available under `no_function`.
+    #[cfg(feature = "resumable")]
+    #[cfg(not(feature = "no_function"))]
+    pub const MAX_GENERATORS: usize = 64;
     /// Maximum levels of expressions.
     pub const MAX_EXPR_DEPTH: usize = 64;
     /// Maximum levels of expressions in function bodies.
@@ -76,6 +88,15 @@ pub struct Limits {
     /// Not available under `no_module`.
     #[cfg(not(feature = "no_module"))]
     pub num_modules: usize,
+    /// Maximum number of generators running at any instant, each of which takes up its own
+    /// [stack][Engine::set_resumable_stack_size].
+    ///
+    /// Set to zero to effectively disable iterating over generators.
+    ///
+    /// Only available under `resumable`. Not available under `no_function`.
+    #[cfg(feature = "resumable")]
+    #[cfg(not(feature = "no_function"))]
+    pub generators: usize,
     /// Maximum length of a [string][crate::ImmutableString].
     pub string_len: Option<NonZeroUsize>,
     /// Maximum length of an [array][crate::Array].
@@ -108,6 +129,9 @@ impl Limits {
             num_functions: usize::MAX,
             #[cfg(not(feature = "no_module"))]
             num_modules: usize::MAX,
+            #[cfg(feature = "resumable")]
+            #[cfg(not(feature = "no_function"))]
+            generators: default_limits::MAX_GENERATORS,
             string_len: None,
             #[cfg(not(feature = "no_index"))]
             array_size: None,
@@ -238,6 +262,29 @@ impl Engine {
     pub const fn max_modules(&self) -> usize {
         self.limits.num_modules
     }
+    /// Set the maximum number of generators allowed to run at any instant.
+    ///
+    /// Each running generator takes up its own [stack][Engine::set_resumable_stack_size], so this
+    /// limits the memory used by generators across all evaluations using this [`Engine`].
+    ///
+    /// Only available under `resumable`. Not available under `unchecked` or `no_function`.
+    #[cfg(feature = "resumable")]
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn set_max_generators(&mut self, generators: usize) -> &mut Self {
+        self.limits.generators = generators;
+        self
+    }
+    /// The maximum number of generators allowed to run at any instant.
+    ///
+    /// Only available under `resumable`. Not available under `unchecked` or `no_function`.
+    #[cfg(feature = "resumable")]
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn max_generators(&self) -> usize {
+        self.limits.generators
+    }
     /// Set the depth limits for expressions (0 for unlimited).
     ///
     /// Not available under `unchecked`.
@@ -24,6 +24,8 @@ pub mod events;
 
 pub mod formatting;
 
+pub mod formatter;
+
 pub mod custom_syntax;
 
 pub mod build_type;
@@ -31,11 +33,17 @@ pub mod build_type;
 #[cfg(feature = "metadata")]
 pub mod definitions;
 
+#[cfg(feature = "resumable")]
+pub mod resumable;
+
+#[cfg(feature = "bytecode")]
+pub mod bytecode;
+
 pub mod deprecated;
 
 use crate::func::{locked_read, locked_write};
 use crate::types::StringsInterner;
-use crate::{Dynamic, Engine, Identifier};
+use crate::{Dynamic, Engine, Identifier, Locked};
 
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -50,6 +58,9 @@ pub mod default_limits {
     pub const MAX_DYNAMIC_PARAMETERS: usize = 16;
     /// Maximum number of strings interned.
     pub const MAX_STRINGS_INTERNED: usize = 256;
+    /// Maximum number of compiled regular expressions cached.
+    #[cfg(feature = "regex")]
+    pub const MAX_REGEX_CACHED: usize = 64;
 }
 
 impl Engine {
@@ -258,4 +269,33 @@ impl Engine {
         self.def_tag = value.into();
         self
     }
+
+    /// Seed the pseudo-random number generator used by functions such as `rand` and `shuffle`.
+    ///
+    /// By default, the generator is seeded randomly (or with a fixed seed under `no_std`).
+    /// Seeding it makes the sequence of random numbers reproducible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, INT};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_random_seed(42);
+    /// let x = engine.eval::<INT>("rand(1..=100)")?;
+    ///
+    /// engine.set_random_seed(42);
+    /// let y = engine.eval::<INT>("rand(1..=100)")?;
+    ///
+    /// assert_eq!(x, y);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn set_random_seed(&mut self, seed: u64) -> &mut Self {
+        self.rng = Locked::new(crate::packages::random_basic::Rng::from_seed(seed));
+        self
+    }
 }
@@ -25,6 +25,88 @@ impl Engine {
         self.optimization_level
     }
 
+    /// Set the maximum size (in number of statements and expressions) of a script-defined
+    /// function body that is inlined at its call sites under [`OptimizationLevel::Full`].
+    ///
+    /// Only small, non-recursive functions that do not access `this`, the calling scope or other
+    /// script-defined functions, and only call pure functions, are inlined. Errors raised by an
+    /// inlined function body are still wrapped in
+    /// [`ErrorInFunctionCall`][crate::EvalAltResult::ErrorInFunctionCall] at the position of the
+    /// call. Set to zero to disable inlining.
+    ///
+    /// Not available under `no_optimize` or `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn set_max_inline_size(&mut self, size: usize) -> &mut Self {
+        self.max_inline_size = size;
+        self
+    }
+
+    /// The maximum size (in number of statements and expressions) of a script-defined function
+    /// body that is inlined at its call sites under [`OptimizationLevel::Full`].
+    ///
+    /// Not available under `no_optimize` or `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn max_inline_size(&self) -> usize {
+        self.max_inline_size
+    }
+
+    /// _(internals)_ Register a user-defined [optimization pass][crate::OptimizerPass] that is
+    /// run as part of optimizing an [`AST`].
+    /// Exported under the `internals` feature only.
+    ///
+    /// Not available under `no_optimize`.
+    ///
+    /// Passes are run in order of registration, unless the optimization level is
+    /// [`OptimizationLevel::None`].
+    ///
+    /// # WARNING - Unstable API
+    ///
+    /// This API is volatile and may change in the future.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Expr, OptimizerContext, OptimizerPass, INT};
+    ///
+    /// // Replace calls to the deprecated `old_inc` function with `inc`
+    /// struct ReplaceOldInc;
+    ///
+    /// impl OptimizerPass for ReplaceOldInc {
+    ///     fn optimize_expr(&self, expr: &mut Expr, context: &mut OptimizerContext) -> bool {
+    ///         match expr {
+    ///             Expr::FnCall(x, ..) if !x.is_qualified() && x.name == "old_inc" => {
+    ///                 x.name = "inc".into();
+    ///                 x.hashes = context.calc_fn_call_hashes(&x.name, x.args.len());
+    ///                 true
+    ///             }
+    ///             _ => false,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_fn("inc", |x: INT| x + 1)
+    ///       .register_optimizer_pass(ReplaceOldInc);
+    ///
+    /// assert_eq!(engine.eval::<INT>("let x = old_inc(41); x")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "internals")]
+    #[inline(always)]
+    pub fn register_optimizer_pass(
+        &mut self,
+        pass: impl crate::OptimizerPass + 'static,
+    ) -> &mut Self {
+        self.optimizer_passes.push(Box::new(pass));
+        self
+    }
+
     /// Optimize the [`AST`] with constants defined in an external Scope.
     /// An optimized copy of the [`AST`] is returned while the original [`AST`] is consumed.
     ///
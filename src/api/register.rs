@@ -19,6 +19,12 @@ impl Engine {
     #[inline(always)]
     #[must_use]
     pub(crate) fn global_namespace_mut(&mut self) -> &mut Module {
+        // Function resolution results cached for the old set of functions are no longer valid
+        #[cfg(not(feature = "no_function"))]
+        {
+            self.fn_stamp = Some(Shared::new(()));
+        }
+
         if self.global_modules.is_empty() {
             let mut global_namespace = Module::new();
             global_namespace.set_internal(true);
@@ -712,6 +718,13 @@ impl Engine {
         }
 
         register_static_module_raw(&mut self.global_sub_modules, name.as_ref(), module);
+
+        // Function resolution results cached for the old set of modules are no longer valid
+        #[cfg(not(feature = "no_function"))]
+        {
+            self.fn_stamp = Some(Shared::new(()));
+        }
+
         self
     }
     /// _(metadata)_ Generate a list of all registered functions.
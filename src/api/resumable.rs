@@ -0,0 +1,302 @@
+//! Module that defines the resumable evaluation API of [`Engine`].
+#![cfg(feature = "resumable")]
+
+use crate::types::dynamic::Variant;
+use crate::{Dynamic, Engine, RhaiResultOf, Scope, AST, ERR};
+use corosensei::stack::DefaultStack;
+use corosensei::{CoroutineResult, ScopedCoroutine, Yielder};
+use std::cell::Cell;
+use std::fmt;
+use std::ptr;
+
+/// Default size of the stack of a resumable evaluation (or a running generator).
+///
+/// This matches the default stack size of a spawned thread, which the default
+/// [limits][Engine::max_call_levels] are tuned for. Only touched pages are actually allocated.
+pub(crate) const STACK_SIZE: usize = 2 * 1024 * 1024;
+
+/// A coroutine running a resumable evaluation.
+type Coroutine<'e, T> = ScopedCoroutine<'e, Dynamic, Dynamic, RhaiResultOf<T>, DefaultStack>;
+
+thread_local! {
+    /// [`Yielder`] of the resumable evaluation currently running on this thread, if any.
+    static YIELDER: Cell<*const Yielder<Dynamic, Dynamic>> = const { Cell::new(ptr::null()) };
+}
+
+/// Set the [`Yielder`] of the resumable evaluation currently running on this thread.
+///
+/// This is never inlined, so that the address of the thread-local is not reused across a
+/// suspension, after which the evaluation may be running on another thread.
+#[inline(never)]
+fn set_yielder(yielder: *const Yielder<Dynamic, Dynamic>) {
+    YIELDER.with(|y| y.set(yielder));
+}
+
+/// Suspend the resumable evaluation currently running (started via [`Engine::eval_resumable`] or
+/// [`Engine::eval_ast_with_scope_resumable`]), passing a value to the host.
+///
+/// This can be called from anywhere within the evaluation, e.g. inside a native Rust function
+/// or the [progress callback][Engine::on_progress].
+///
+/// When the evaluation is [resumed][Suspended::resume], it continues from this point on with the
+/// value passed to [`Suspended::resume`] returned.
+///
+/// Returns [`None`] if not called within a resumable evaluation.
+///
+/// # Thread Safety
+///
+/// Under the `sync` feature, the evaluation may be resumed on another thread, so the caller must
+/// not hold anything tied to the current thread across the call, e.g. [`Rc`][std::rc::Rc] values,
+/// lock guards or references to thread-locals. This includes calling `suspend` from a method
+/// called on a shared value (such as a variable captured by a closure), because the value stays
+/// locked during the method call.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, Resumable};
+///
+/// let mut engine = Engine::new();
+///
+/// // Wait for the host to supply a value
+/// engine.register_fn("wait", |name: &str| -> Result<i64, Box<rhai::EvalAltResult>> {
+///     let value = rhai::suspend(name).ok_or("not resumable")?;
+///     Ok(value.as_int().unwrap_or(0))
+/// });
+///
+/// let suspended = match engine.eval_resumable::<i64>("wait(\"x\") + 1")? {
+///     Resumable::Suspended(suspended) => suspended,
+///     Resumable::Done(..) => unreachable!(),
+/// };
+///
+/// assert_eq!(suspended.value().clone_cast::<String>(), "x");
+///
+/// match suspended.resume(41_i64)? {
+///     Resumable::Done(result) => assert_eq!(result, 42),
+///     Resumable::Suspended(..) => unreachable!(),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn suspend(value: impl Into<Dynamic>) -> Option<Dynamic> {
+    let yielder = YIELDER.with(Cell::get);
+
+    if yielder.is_null() {
+        return None;
+    }
+
+    // SAFETY: The pointer is only set while a resumable evaluation is running on this thread,
+    //         so this code is running on the stack of that coroutine, which owns the `Yielder`.
+    let yielder = unsafe { &*yielder };
+
+    let value = yielder.suspend(value.into());
+
+    // The evaluation may be resumed on another thread (under `sync`), or after other resumable
+    // evaluations have run on this thread
+    set_yielder(yielder);
+
+    Some(value)
+}
+
+/// The result of a resumable evaluation, which is either completed or suspended.
+///
+/// Not available under `no_std` or WASM.
+pub enum Resumable<'e, T> {
+    /// The evaluation has completed with a result value.
+    Done(T),
+    /// The evaluation is suspended via [`suspend`][crate::suspend].
+    Suspended(Suspended<'e, T>),
+}
+
+impl<T: fmt::Debug> fmt::Debug for Resumable<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Done(value) => f.debug_tuple("Done").field(value).finish(),
+            Self::Suspended(suspended) => f.debug_tuple("Suspended").field(suspended).finish(),
+        }
+    }
+}
+
+/// A suspended resumable evaluation.
+///
+/// The whole state of the evaluation is kept on a separate stack, so no thread is tied up while
+/// it is suspended.
+///
+/// Dropping a [`Suspended`] evaluation without resuming it cancels it.
+///
+/// # Thread Safety
+///
+/// Under the `sync` feature, a [`Suspended`] evaluation is [`Send`] and can be resumed on any
+/// thread, as long as no native Rust function holds anything tied to the thread across the call
+/// to [`suspend`][crate::suspend] (see there).
+///
+/// Otherwise, it is not [`Send`] and must be resumed on the thread that started it.
+pub struct Suspended<'e, T> {
+    /// Coroutine running the evaluation.
+    coroutine: Coroutine<'e, T>,
+    /// Value passed to [`suspend`].
+    value: Dynamic,
+}
+
+// SAFETY: Under `sync`, the `Engine`, `Scope`, `AST` and all values held by the evaluation are
+//         `Send + Sync`. The thread-locals used by the evaluation are set again after each
+//         suspension, and `suspend` requires its callers not to hold anything tied to the thread
+//         across the call.
+#[cfg(feature = "sync")]
+unsafe impl<T: Send> Send for Suspended<'_, T> {}
+
+impl<T> fmt::Debug for Suspended<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Suspended")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'e, T> Suspended<'e, T> {
+    /// Get the value passed to [`suspend`][crate::suspend] when the evaluation was suspended.
+    #[inline(always)]
+    pub const fn value(&self) -> &Dynamic {
+        &self.value
+    }
+    /// Resume the evaluation, with a value returned from [`suspend`][crate::suspend].
+    ///
+    /// The evaluation runs until it is suspended again or completes.
+    #[inline(always)]
+    pub fn resume(self, value: impl Into<Dynamic>) -> RhaiResultOf<Resumable<'e, T>> {
+        run(self.coroutine, value.into())
+    }
+}
+
+/// Guard restoring the outer resumable evaluation (if any) when dropped, even on panic.
+struct Restore(*const Yielder<Dynamic, Dynamic>);
+
+impl Drop for Restore {
+    #[inline(always)]
+    fn drop(&mut self) {
+        YIELDER.with(|y| y.set(self.0));
+    }
+}
+
+/// Run a function with [`suspend`] disabled (e.g. while running a generator on its own stack,
+/// which cannot suspend the evaluation on another stack).
+#[cfg(not(feature = "no_function"))]
+pub(crate) fn without_suspend<R>(f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(YIELDER.with(|y| y.replace(ptr::null())));
+    f()
+}
+
+/// Run a coroutine until it is suspended or completes.
+fn run<T>(mut coroutine: Coroutine<T>, value: Dynamic) -> RhaiResultOf<Resumable<T>> {
+    let _restore = Restore(YIELDER.with(Cell::get));
+    let result = coroutine.resume(value);
+
+    match result {
+        CoroutineResult::Yield(value) => Ok(Resumable::Suspended(Suspended { coroutine, value })),
+        CoroutineResult::Return(result) => result.map(Resumable::Done),
+    }
+}
+
+impl Engine {
+    /// Set the size (in bytes) of the stack allocated for each resumable evaluation and each
+    /// running [generator][crate::Generator].
+    ///
+    /// The default is 2 MB, matching the default stack size of a spawned thread, which the default
+    /// [limits][Engine::max_call_levels] are tuned for. Only touched pages are actually allocated,
+    /// but the whole range of address space is reserved. A smaller stack size may need lower
+    /// limits on [call levels][Engine::set_max_call_levels] and
+    /// [expression depths][Engine::set_max_expr_depths] to avoid overflowing the stack.
+    ///
+    /// Not available under `no_std` or WASM.
+    #[inline(always)]
+    pub fn set_resumable_stack_size(&mut self, size: usize) -> &mut Self {
+        self.resumable_stack_size = size;
+        self
+    }
+    /// The size (in bytes) of the stack allocated for each resumable evaluation and each running
+    /// [generator][crate::Generator].
+    ///
+    /// Not available under `no_std` or WASM.
+    #[inline(always)]
+    #[must_use]
+    pub const fn resumable_stack_size(&self) -> usize {
+        self.resumable_stack_size
+    }
+    /// Evaluate a string as a script in a resumable manner.
+    ///
+    /// The evaluation can be suspended at any point by calling [`suspend`][crate::suspend]
+    /// (e.g. inside a native Rust function or the [progress callback][Engine::on_progress]),
+    /// in which case [`Resumable::Suspended`] is returned for the evaluation to be resumed later.
+    ///
+    /// Not available under `no_std` or WASM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Resumable};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Suspend the script every 100 operations
+    /// engine.on_progress(|ops| {
+    ///     if ops % 100 == 0 {
+    ///         rhai::suspend(());
+    ///     }
+    ///     None
+    /// });
+    ///
+    /// let mut state = engine.eval_resumable::<i64>("let x = 0; for n in 0..100 { x += n; } x")?;
+    /// let mut count = 0;
+    ///
+    /// let result = loop {
+    ///     match state {
+    ///         Resumable::Done(result) => break result,
+    ///         Resumable::Suspended(suspended) => state = suspended.resume(())?,
+    ///     }
+    ///     count += 1;
+    /// };
+    ///
+    /// assert_eq!(result, 4950);
+    /// assert!(count > 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn eval_resumable<T: Variant + Clone>(
+        &self,
+        script: &str,
+    ) -> RhaiResultOf<Resumable<'_, T>> {
+        let ast = self.compile(script)?;
+        self.start_resumable(move |engine| engine.eval_ast_with_scope(&mut Scope::new(), &ast))
+    }
+    /// Evaluate an [`AST`] with own scope in a resumable manner.
+    ///
+    /// See [`eval_resumable`][Engine::eval_resumable] for details.
+    ///
+    /// The scope and the [`AST`] stay borrowed until the evaluation completes or is dropped.
+    ///
+    /// Not available under `no_std` or WASM.
+    pub fn eval_ast_with_scope_resumable<'e, T: Variant + Clone>(
+        &'e self,
+        scope: &'e mut Scope,
+        ast: &'e AST,
+    ) -> RhaiResultOf<Resumable<'e, T>> {
+        self.start_resumable(move |engine| engine.eval_ast_with_scope(scope, ast))
+    }
+    /// Start a resumable evaluation on a new stack.
+    fn start_resumable<'e, T: 'e>(
+        &'e self,
+        eval: impl FnOnce(&Self) -> RhaiResultOf<T> + 'e,
+    ) -> RhaiResultOf<Resumable<'e, T>> {
+        let stack = DefaultStack::new(self.resumable_stack_size)
+            .map_err(|err| ERR::ErrorSystem("Cannot allocate stack".into(), err.into()))?;
+
+        let coroutine = Coroutine::with_stack(stack, move |yielder, _| {
+            set_yielder(yielder);
+            eval(self)
+        });
+
+        run(coroutine, Dynamic::UNIT)
+    }
+}
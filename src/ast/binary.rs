@@ -0,0 +1,1814 @@
+//! Module implementing a compact binary format for [`AST`].
+//!
+//! # Format
+//!
+//! The binary format starts with a header containing a magic string, the format version and a
+//! set of bit-flags encoding the feature flags that affect the layout of the [`AST`].
+//!
+//! All integers are encoded as LEB128 variable-length integers.
+//! Signed integers are zig-zag encoded first.
+//!
+//! All strings are stored in a string table the first time they are encountered, and referred to
+//! by index afterwards.
+//!
+//! Pre-calculated hashes are _not_ stored because they depend on the hashing seed of the running
+//! process. They are re-calculated when the [`AST`] is loaded.
+
+use super::{
+    ASTFlags, BinaryExpr, Expr, FlowControl, FnCallExpr, FnCallHashes, Ident, MatchArm,
+    OpAssignment, Pattern, RangeCase, Stmt, StmtBlock, SwitchCasesCollection, AST,
+};
+use crate::func::{get_hasher, StraightHashMap};
+use crate::tokenizer::Token;
+use crate::types::dynamic::{AccessMode, Union};
+use crate::types::Span;
+use crate::{
+    calc_fn_hash, Dynamic, Engine, FnArgsVec, FnPtr, ImmutableString, Position, RhaiResultOf,
+    SmartString, StaticVec, ThinVec, ERR, INT,
+};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    collections::BTreeMap,
+    convert::{TryFrom, TryInto},
+    hash::{Hash, Hasher},
+    num::{NonZeroU8, NonZeroUsize},
+};
+
+/// Magic string at the beginning of the binary format.
+const MAGIC: &[u8; 7] = b"RHAIAST";
+
+/// Version of the binary format.
+///
+/// Bump this whenever the layout of the binary format changes.
+const FORMAT_VERSION: u8 = 1;
+
+/// Maximum levels of nested values, expressions, statements and patterns when decoding.
+///
+/// This guards against stack overflows on malformed or malicious input.
+#[cfg(debug_assertions)]
+const MAX_NESTING_DEPTH: usize = 128;
+/// Maximum levels of nested values, expressions, statements and patterns when decoding.
+///
+/// This guards against stack overflows on malformed or malicious input.
+#[cfg(not(debug_assertions))]
+const MAX_NESTING_DEPTH: usize = 512;
+
+/// Bit-flags encoding the feature flags that affect the layout of the binary format.
+const fn config_flags() -> u16 {
+    let mut flags = 0;
+
+    if cfg!(feature = "only_i32") {
+        flags |= 0b_0000_0000_0001;
+    }
+    if cfg!(feature = "no_float") {
+        flags |= 0b_0000_0000_0010;
+    }
+    if cfg!(feature = "f32_float") {
+        flags |= 0b_0000_0000_0100;
+    }
+    if cfg!(feature = "decimal") {
+        flags |= 0b_0000_0000_1000;
+    }
+    if cfg!(feature = "no_index") {
+        flags |= 0b_0000_0001_0000;
+    }
+    if cfg!(feature = "no_object") {
+        flags |= 0b_0000_0010_0000;
+    }
+    if cfg!(feature = "no_function") {
+        flags |= 0b_0000_0100_0000;
+    }
+    if cfg!(feature = "no_module") {
+        flags |= 0b_0000_1000_0000;
+    }
+    if cfg!(feature = "no_closure") {
+        flags |= 0b_0001_0000_0000;
+    }
+    if cfg!(feature = "no_custom_syntax") {
+        flags |= 0b_0010_0000_0000;
+    }
+    if cfg!(feature = "resumable") {
+        flags |= 0b_0100_0000_0000;
+    }
+    if cfg!(feature = "bigint") {
+        flags |= 0b_1000_0000_0000;
+    }
+
+    flags
+}
+
+/// Create an error for an [`AST`] that cannot be encoded.
+#[cold]
+#[inline(never)]
+fn encode_error(msg: impl Into<String>) -> Box<ERR> {
+    let msg: String = msg.into();
+    ERR::ErrorSystem("Cannot encode AST".into(), msg.into()).into()
+}
+
+/// Create an error for a binary stream that cannot be decoded.
+#[cold]
+#[inline(never)]
+fn decode_error(msg: impl Into<String>) -> Box<ERR> {
+    let msg: String = msg.into();
+    ERR::ErrorSystem("Cannot decode AST".into(), msg.into()).into()
+}
+
+/// Tags for [`Stmt`] variants.
+mod stmt_tag {
+    pub const NOOP: u8 = 0;
+    pub const IF: u8 = 1;
+    pub const SWITCH: u8 = 2;
+    pub const WHILE: u8 = 3;
+    pub const DO: u8 = 4;
+    pub const FOR: u8 = 5;
+    pub const VAR: u8 = 6;
+    pub const ASSIGNMENT: u8 = 7;
+    pub const FN_CALL: u8 = 8;
+    pub const BLOCK: u8 = 9;
+    pub const TRY_CATCH: u8 = 10;
+    pub const EXPR: u8 = 11;
+    pub const BREAK_LOOP: u8 = 12;
+    pub const RETURN: u8 = 13;
+    #[cfg(not(feature = "no_module"))]
+    pub const IMPORT: u8 = 14;
+    #[cfg(not(feature = "no_module"))]
+    pub const EXPORT: u8 = 15;
+    #[cfg(not(feature = "no_closure"))]
+    pub const SHARE: u8 = 16;
+    #[cfg(feature = "resumable")]
+    pub const YIELD: u8 = 17;
+    pub const MATCH: u8 = 18;
+    pub const DESTRUCTURE: u8 = 19;
+}
+
+/// Tags for [`Expr`] variants.
+mod expr_tag {
+    pub const DYNAMIC_CONSTANT: u8 = 0;
+    pub const BOOL_CONSTANT: u8 = 1;
+    pub const INTEGER_CONSTANT: u8 = 2;
+    #[cfg(not(feature = "no_float"))]
+    pub const FLOAT_CONSTANT: u8 = 3;
+    pub const CHAR_CONSTANT: u8 = 4;
+    pub const STRING_CONSTANT: u8 = 5;
+    pub const INTERPOLATED_STRING: u8 = 6;
+    pub const ARRAY: u8 = 7;
+    pub const MAP: u8 = 8;
+    pub const UNIT: u8 = 9;
+    pub const VARIABLE: u8 = 10;
+    pub const THIS_PTR: u8 = 11;
+    pub const PROPERTY: u8 = 12;
+    pub const METHOD_CALL: u8 = 13;
+    pub const STMT: u8 = 14;
+    pub const FN_CALL: u8 = 15;
+    pub const DOT: u8 = 16;
+    pub const INDEX: u8 = 17;
+    pub const AND: u8 = 18;
+    pub const OR: u8 = 19;
+    pub const COALESCE: u8 = 20;
+    #[cfg(not(feature = "no_custom_syntax"))]
+    pub const CUSTOM: u8 = 21;
+    #[cfg(not(feature = "no_function"))]
+    pub const INLINED_FN: u8 = 22;
+}
+
+/// Tags for [`Pattern`] variants.
+mod pattern_tag {
+    pub const WILDCARD: u8 = 0;
+    pub const VALUE: u8 = 1;
+    pub const BIND: u8 = 2;
+    pub const TYPED: u8 = 3;
+    #[cfg(not(feature = "no_index"))]
+    pub const ARRAY: u8 = 4;
+    #[cfg(not(feature = "no_index"))]
+    pub const REST: u8 = 5;
+    #[cfg(not(feature = "no_object"))]
+    pub const MAP: u8 = 6;
+    pub const OR: u8 = 7;
+}
+
+/// Tags for [`Dynamic`] values.
+mod value_tag {
+    pub const UNIT: u8 = 0;
+    pub const BOOL: u8 = 1;
+    pub const STR: u8 = 2;
+    pub const CHAR: u8 = 3;
+    pub const INT: u8 = 4;
+    #[cfg(not(feature = "no_float"))]
+    pub const FLOAT: u8 = 5;
+    #[cfg(feature = "decimal")]
+    pub const DECIMAL: u8 = 6;
+    #[cfg(not(feature = "no_index"))]
+    pub const ARRAY: u8 = 7;
+    #[cfg(not(feature = "no_index"))]
+    pub const BLOB: u8 = 8;
+    #[cfg(not(feature = "no_object"))]
+    pub const MAP: u8 = 9;
+    pub const FN_PTR: u8 = 10;
+    pub const EXCLUSIVE_RANGE: u8 = 11;
+    pub const INCLUSIVE_RANGE: u8 = 12;
+}
+
+/// Tags for operator [tokens][Token].
+mod token_tag {
+    pub const NONE: u8 = 0;
+    pub const SYMBOL: u8 = 1;
+    #[cfg(not(feature = "no_custom_syntax"))]
+    pub const CUSTOM: u8 = 2;
+    pub const UNARY_PLUS: u8 = 3;
+    pub const UNARY_MINUS: u8 = 4;
+}
+
+/// Encoder of an [`AST`] into the binary format.
+struct Encoder {
+    /// Output buffer.
+    buf: Vec<u8>,
+    /// Index of each string already written.
+    strings: BTreeMap<SmartString, usize>,
+}
+
+impl Encoder {
+    /// Create a new [`Encoder`] with the header already written.
+    fn new() -> Self {
+        let mut encoder = Self {
+            buf: Vec::new(),
+            strings: BTreeMap::new(),
+        };
+        encoder.buf.extend_from_slice(MAGIC);
+        encoder.u8(FORMAT_VERSION);
+        encoder.uint(config_flags().into());
+        encoder
+    }
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+    fn bool(&mut self, value: bool) {
+        self.u8(u8::from(value));
+    }
+    fn uint(&mut self, mut value: u64) {
+        loop {
+            #[allow(clippy::cast_possible_truncation)]
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.u8(byte);
+                break;
+            }
+            self.u8(byte | 0x80);
+        }
+    }
+    fn usize(&mut self, value: usize) {
+        self.uint(value as u64);
+    }
+    #[allow(clippy::cast_sign_loss)]
+    fn i64(&mut self, value: i64) {
+        self.uint(((value << 1) ^ (value >> 63)) as u64);
+    }
+    #[allow(clippy::useless_conversion)]
+    fn int(&mut self, value: INT) {
+        self.i64(value.into());
+    }
+    fn opt_index(&mut self, value: Option<NonZeroUsize>) {
+        self.usize(value.map_or(0, NonZeroUsize::get));
+    }
+    fn str(&mut self, s: &str) {
+        if let Some(&index) = self.strings.get(s) {
+            self.usize((index << 1) | 1);
+        } else {
+            let index = self.strings.len();
+            self.strings.insert(s.into(), index);
+            self.usize(s.len() << 1);
+            self.buf.extend_from_slice(s.as_bytes());
+        }
+    }
+    fn opt_str(&mut self, s: Option<&str>) {
+        self.bool(s.is_some());
+        if let Some(s) = s {
+            self.str(s);
+        }
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    fn pos(&mut self, pos: Position) {
+        self.usize(pos.line().unwrap_or(0));
+        self.usize(pos.position().unwrap_or(0));
+    }
+    fn span(&mut self, span: Span) {
+        self.pos(span.start());
+        self.pos(span.end());
+    }
+    fn flags(&mut self, flags: ASTFlags) {
+        self.u8(flags.bits());
+    }
+    fn ident(&mut self, ident: &Ident) {
+        self.str(&ident.name);
+        self.pos(ident.pos);
+    }
+    fn token(&mut self, token: Option<&Token>) {
+        match token {
+            None => self.u8(token_tag::NONE),
+            // Unary operators share the same syntax as binary operators
+            Some(Token::UnaryPlus) => self.u8(token_tag::UNARY_PLUS),
+            Some(Token::UnaryMinus) => self.u8(token_tag::UNARY_MINUS),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Some(Token::Custom(s)) => {
+                self.u8(token_tag::CUSTOM);
+                self.str(s);
+            }
+            Some(token) => {
+                self.u8(token_tag::SYMBOL);
+                self.str(token.literal_syntax());
+            }
+        }
+    }
+
+    fn value(&mut self, value: &Dynamic) -> RhaiResultOf<()> {
+        let tag = i64::from(value.tag());
+        let read_only = value.is_read_only();
+
+        match value.0 {
+            Union::Unit(..) => self.u8(value_tag::UNIT),
+            Union::Bool(b, ..) => {
+                self.u8(value_tag::BOOL);
+                self.bool(b);
+            }
+            Union::Str(ref s, ..) => {
+                self.u8(value_tag::STR);
+                self.str(s);
+            }
+            Union::Char(c, ..) => {
+                self.u8(value_tag::CHAR);
+                self.uint(c.into());
+            }
+            Union::Int(n, ..) => {
+                self.u8(value_tag::INT);
+                self.int(n);
+            }
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(f, ..) => {
+                self.u8(value_tag::FLOAT);
+                self.buf.extend_from_slice(&f.to_le_bytes());
+            }
+            #[cfg(feature = "decimal")]
+            Union::Decimal(ref d, ..) => {
+                self.u8(value_tag::DECIMAL);
+                self.buf.extend_from_slice(&d.serialize());
+            }
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(ref a, ..) => {
+                self.u8(value_tag::ARRAY);
+                self.usize(a.len());
+                for item in a.iter() {
+                    self.value(item)?;
+                }
+            }
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref b, ..) => {
+                self.u8(value_tag::BLOB);
+                self.usize(b.len());
+                self.buf.extend_from_slice(b);
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(ref m, ..) => {
+                self.u8(value_tag::MAP);
+                self.usize(m.len());
+                for (k, v) in m.iter() {
+                    self.str(k);
+                    self.value(v)?;
+                }
+            }
+            Union::FnPtr(ref f, ..) => {
+                #[cfg(not(feature = "no_function"))]
+                if f.env.is_some() {
+                    return Err(encode_error(format!(
+                        "function pointer {f} with an encapsulated environment cannot be encoded"
+                    )));
+                }
+                if !matches!(f.typ, crate::types::fn_ptr::FnPtrType::Normal) {
+                    return Err(encode_error(format!(
+                        "function pointer {f} linked to a function cannot be encoded"
+                    )));
+                }
+
+                self.u8(value_tag::FN_PTR);
+                self.str(f.fn_name());
+                self.usize(f.curry().len());
+                for item in f.curry() {
+                    self.value(item)?;
+                }
+            }
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(..) => return self.value(&value.flatten_clone()),
+            _ => {
+                if let Some(range) = value.read_lock::<crate::ExclusiveRange>() {
+                    self.u8(value_tag::EXCLUSIVE_RANGE);
+                    self.int(range.start);
+                    self.int(range.end);
+                } else if let Some(range) = value.read_lock::<crate::InclusiveRange>() {
+                    self.u8(value_tag::INCLUSIVE_RANGE);
+                    self.int(*range.start());
+                    self.int(*range.end());
+                } else {
+                    return Err(encode_error(format!(
+                        "constant value of type '{}' cannot be encoded",
+                        value.type_name()
+                    )));
+                }
+            }
+        }
+
+        self.i64(tag);
+        self.bool(read_only);
+
+        Ok(())
+    }
+
+    fn fn_hash(&mut self, x: &FnCallExpr, hash: u64) -> RhaiResultOf<()> {
+        // Find the number of parameters used to calculate the hash.
+        // Method calls have the object as an extra parameter.
+        for num in 0..=x.args.len() + 1 {
+            if calc_fn_hash(None, &x.name, num) == hash {
+                self.usize(num << 1);
+                return Ok(());
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            if x.is_qualified()
+                && calc_fn_hash(x.namespace.path.iter().map(Ident::as_str), &x.name, num) == hash
+            {
+                self.usize((num << 1) | 1);
+                return Ok(());
+            }
+        }
+
+        Err(encode_error(format!(
+            "hash of function call '{}' cannot be re-calculated",
+            x.name
+        )))
+    }
+
+    fn fn_call(&mut self, x: &FnCallExpr) -> RhaiResultOf<()> {
+        #[cfg(not(feature = "no_module"))]
+        self.namespace(&x.namespace);
+        self.str(&x.name);
+
+        #[cfg(not(feature = "no_function"))]
+        if x.hashes.is_native_only() {
+            self.u8(0);
+            self.fn_hash(x, x.hashes.native())?;
+        } else {
+            self.u8(1);
+            self.fn_hash(x, x.hashes.native())?;
+            self.fn_hash(x, x.hashes.script())?;
+        }
+        #[cfg(feature = "no_function")]
+        {
+            self.u8(0);
+            self.fn_hash(x, x.hashes.native())?;
+        }
+
+        self.exprs(&x.args)?;
+        self.bool(x.capture_parent_scope);
+        self.token(x.op_token.as_ref());
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "no_module"))]
+    fn namespace(&mut self, ns: &super::Namespace) {
+        self.usize(ns.path.len());
+        for ident in &ns.path {
+            self.ident(ident);
+        }
+        self.opt_index(ns.index);
+    }
+
+    fn binary_expr(&mut self, x: &BinaryExpr) -> RhaiResultOf<()> {
+        self.expr(&x.lhs)?;
+        self.expr(&x.rhs)
+    }
+
+    fn exprs(&mut self, exprs: &[Expr]) -> RhaiResultOf<()> {
+        self.usize(exprs.len());
+        exprs.iter().try_for_each(|expr| self.expr(expr))
+    }
+
+    fn expr(&mut self, expr: &Expr) -> RhaiResultOf<()> {
+        match expr {
+            Expr::DynamicConstant(value, pos) => {
+                self.u8(expr_tag::DYNAMIC_CONSTANT);
+                self.value(value)?;
+                self.pos(*pos);
+            }
+            Expr::BoolConstant(b, pos) => {
+                self.u8(expr_tag::BOOL_CONSTANT);
+                self.bool(*b);
+                self.pos(*pos);
+            }
+            Expr::IntegerConstant(n, pos) => {
+                self.u8(expr_tag::INTEGER_CONSTANT);
+                self.int(*n);
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(f, pos) => {
+                self.u8(expr_tag::FLOAT_CONSTANT);
+                self.buf.extend_from_slice(&f.to_le_bytes());
+                self.pos(*pos);
+            }
+            Expr::CharConstant(c, pos) => {
+                self.u8(expr_tag::CHAR_CONSTANT);
+                self.uint((*c).into());
+                self.pos(*pos);
+            }
+            Expr::StringConstant(s, pos) => {
+                self.u8(expr_tag::STRING_CONSTANT);
+                self.str(s);
+                self.pos(*pos);
+            }
+            Expr::InterpolatedString(x, pos) => {
+                self.u8(expr_tag::INTERPOLATED_STRING);
+                self.exprs(x)?;
+                self.pos(*pos);
+            }
+            Expr::Array(x, pos) => {
+                self.u8(expr_tag::ARRAY);
+                self.exprs(x)?;
+                self.pos(*pos);
+            }
+            Expr::Map(x, pos) => {
+                self.u8(expr_tag::MAP);
+                self.usize(x.0.len());
+                for (ident, expr) in &x.0 {
+                    self.ident(ident);
+                    self.expr(expr)?;
+                }
+                self.usize(x.1.len());
+                for (k, v) in &x.1 {
+                    self.str(k);
+                    self.value(v)?;
+                }
+                self.pos(*pos);
+            }
+            Expr::Unit(pos) => {
+                self.u8(expr_tag::UNIT);
+                self.pos(*pos);
+            }
+            Expr::Variable(x, index, pos) => {
+                self.u8(expr_tag::VARIABLE);
+                self.opt_index(x.0);
+                self.str(&x.1);
+                #[cfg(not(feature = "no_module"))]
+                {
+                    self.namespace(&x.2);
+                    self.bool(x.3 != 0);
+                }
+                self.usize(index.map_or(0, |n| n.get().into()));
+                self.pos(*pos);
+            }
+            Expr::ThisPtr(pos) => {
+                self.u8(expr_tag::THIS_PTR);
+                self.pos(*pos);
+            }
+            Expr::Property(x, pos) => {
+                self.u8(expr_tag::PROPERTY);
+                self.str(&(x.0).0);
+                self.str(&(x.1).0);
+                self.str(&x.2);
+                self.pos(*pos);
+            }
+            Expr::MethodCall(x, pos) => {
+                self.u8(expr_tag::METHOD_CALL);
+                self.fn_call(x)?;
+                self.pos(*pos);
+            }
+            Expr::Stmt(x) => {
+                self.u8(expr_tag::STMT);
+                self.block(x)?;
+            }
+            Expr::FnCall(x, pos) => {
+                self.u8(expr_tag::FN_CALL);
+                self.fn_call(x)?;
+                self.pos(*pos);
+            }
+            Expr::Dot(x, flags, pos) | Expr::Index(x, flags, pos) => {
+                self.u8(if matches!(expr, Expr::Dot(..)) {
+                    expr_tag::DOT
+                } else {
+                    expr_tag::INDEX
+                });
+                self.binary_expr(x)?;
+                self.flags(*flags);
+                self.pos(*pos);
+            }
+            Expr::And(x, pos) | Expr::Or(x, pos) | Expr::Coalesce(x, pos) => {
+                self.u8(match expr {
+                    Expr::And(..) => expr_tag::AND,
+                    Expr::Or(..) => expr_tag::OR,
+                    _ => expr_tag::COALESCE,
+                });
+                self.binary_expr(x)?;
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, pos) => {
+                self.u8(expr_tag::CUSTOM);
+                self.exprs(&x.inputs)?;
+                self.usize(x.tokens.len());
+                for token in &x.tokens {
+                    self.str(token);
+                }
+                self.value(&x.state)?;
+                self.bool(x.scope_may_be_changed);
+                self.bool(x.self_terminated);
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_function"))]
+            Expr::InlinedFn(x, pos) => {
+                self.u8(expr_tag::INLINED_FN);
+                self.str(&x.0);
+                self.block(&x.1)?;
+                self.pos(*pos);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn block(&mut self, block: &StmtBlock) -> RhaiResultOf<()> {
+        self.stmts(block.statements())?;
+        self.span(block.span());
+        Ok(())
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) -> RhaiResultOf<()> {
+        self.usize(stmts.len());
+        stmts.iter().try_for_each(|stmt| self.stmt(stmt))
+    }
+
+    fn flow_control(&mut self, x: &FlowControl) -> RhaiResultOf<()> {
+        self.expr(&x.expr)?;
+        self.block(&x.body)?;
+        self.block(&x.branch)
+    }
+
+    fn switch_cases(&mut self, x: &SwitchCasesCollection) -> RhaiResultOf<()> {
+        self.usize(x.expressions.len());
+        for expr in &x.expressions {
+            self.binary_expr(expr)?;
+        }
+
+        // Case hashes depend on the hashing seed, so store the case values instead
+        let mut cases = Vec::with_capacity(x.cases.len());
+
+        for value in &x.values {
+            let hasher = &mut get_hasher();
+            value.hash(hasher);
+
+            if let Some(blocks) = x.cases.get(&hasher.finish()) {
+                cases.push((value, blocks));
+            }
+        }
+
+        if cases.len() != x.cases.len() {
+            return Err(encode_error("switch case values are missing"));
+        }
+
+        self.usize(cases.len());
+        for (value, blocks) in cases {
+            self.value(value)?;
+            self.usize(blocks.len());
+            blocks.iter().for_each(|&index| self.usize(index));
+        }
+
+        self.usize(x.ranges.len());
+        for range in &x.ranges {
+            match range {
+                RangeCase::ExclusiveInt(r, index) => {
+                    self.bool(false);
+                    self.int(r.start);
+                    self.int(r.end);
+                    self.usize(*index);
+                }
+                RangeCase::InclusiveInt(r, index) => {
+                    self.bool(true);
+                    self.int(*r.start());
+                    self.int(*r.end());
+                    self.usize(*index);
+                }
+            }
+        }
+
+        self.usize(x.def_case.map_or(0, |index| index + 1));
+
+        Ok(())
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) -> RhaiResultOf<()> {
+        match pattern {
+            Pattern::Wildcard(pos) => {
+                self.u8(pattern_tag::WILDCARD);
+                self.pos(*pos);
+            }
+            Pattern::Value(x, pos) => {
+                self.u8(pattern_tag::VALUE);
+                self.value(x)?;
+                self.pos(*pos);
+            }
+            Pattern::Bind(var, index) => {
+                self.u8(pattern_tag::BIND);
+                self.ident(var);
+                self.usize(*index);
+            }
+            Pattern::Typed(x) => {
+                self.u8(pattern_tag::TYPED);
+                self.pattern(&x.0)?;
+                self.ident(&x.1);
+            }
+            #[cfg(not(feature = "no_index"))]
+            Pattern::Array(x, pos) => {
+                self.u8(pattern_tag::ARRAY);
+                self.usize(x.len());
+                x.iter().try_for_each(|p| self.pattern(p))?;
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_index"))]
+            Pattern::Rest(var, pos) => {
+                self.u8(pattern_tag::REST);
+                self.bool(var.is_some());
+                if let Some((var, index)) = var {
+                    self.ident(var);
+                    self.usize(*index);
+                }
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_object"))]
+            Pattern::Map(x, pos) => {
+                self.u8(pattern_tag::MAP);
+                self.usize(x.len());
+                for (key, p) in x.iter() {
+                    self.ident(key);
+                    self.pattern(p)?;
+                }
+                self.pos(*pos);
+            }
+            Pattern::Or(x) => {
+                self.u8(pattern_tag::OR);
+                self.usize(x.len());
+                x.iter().try_for_each(|p| self.pattern(p))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> RhaiResultOf<()> {
+        match stmt {
+            Stmt::Noop(pos) => {
+                self.u8(stmt_tag::NOOP);
+                self.pos(*pos);
+            }
+            Stmt::If(x, pos) => {
+                self.u8(stmt_tag::IF);
+                self.flow_control(x)?;
+                self.pos(*pos);
+            }
+            Stmt::Switch(x, pos) => {
+                self.u8(stmt_tag::SWITCH);
+                self.expr(&x.0)?;
+                self.switch_cases(&x.1)?;
+                self.pos(*pos);
+            }
+            Stmt::Match(x, pos) => {
+                self.u8(stmt_tag::MATCH);
+                self.expr(&x.0)?;
+                self.usize(x.1.len());
+                for arm in &x.1 {
+                    self.usize(arm.vars.len());
+                    arm.vars.iter().for_each(|var| self.ident(var));
+                    self.pattern(&arm.pattern)?;
+                    self.expr(&arm.condition)?;
+                    self.expr(&arm.expr)?;
+                }
+                self.pos(*pos);
+            }
+            Stmt::While(x, pos) => {
+                self.u8(stmt_tag::WHILE);
+                self.flow_control(x)?;
+                self.pos(*pos);
+            }
+            Stmt::Do(x, flags, pos) => {
+                self.u8(stmt_tag::DO);
+                self.flow_control(x)?;
+                self.flags(*flags);
+                self.pos(*pos);
+            }
+            Stmt::For(x, pos) => {
+                self.u8(stmt_tag::FOR);
+                self.ident(&x.0);
+                self.bool(x.1.is_some());
+                if let Some(ref counter) = x.1 {
+                    self.ident(counter);
+                }
+                self.flow_control(&x.2)?;
+                self.pos(*pos);
+            }
+            Stmt::Var(x, flags, pos) => {
+                self.u8(stmt_tag::VAR);
+                self.ident(&x.0);
+                self.expr(&x.1)?;
+                self.opt_index(x.2);
+                self.flags(*flags);
+                self.pos(*pos);
+            }
+            Stmt::Destructure(x, flags, pos) => {
+                self.u8(stmt_tag::DESTRUCTURE);
+                self.usize(x.1.len());
+                x.1.iter().for_each(|var| self.ident(var));
+                self.pattern(&x.0)?;
+                self.expr(&x.2)?;
+                self.flags(*flags);
+                self.pos(*pos);
+            }
+            Stmt::Assignment(x) => {
+                self.u8(stmt_tag::ASSIGNMENT);
+                self.token(x.0.get_op_assignment_info().map(|info| info.2));
+                self.pos(x.0.position());
+                self.binary_expr(&x.1)?;
+            }
+            Stmt::FnCall(x, pos) => {
+                self.u8(stmt_tag::FN_CALL);
+                self.fn_call(x)?;
+                self.pos(*pos);
+            }
+            Stmt::Block(x) => {
+                self.u8(stmt_tag::BLOCK);
+                self.block(x)?;
+            }
+            Stmt::TryCatch(x, pos) => {
+                self.u8(stmt_tag::TRY_CATCH);
+                self.flow_control(x)?;
+                self.pos(*pos);
+            }
+            Stmt::Expr(x) => {
+                self.u8(stmt_tag::EXPR);
+                self.expr(x)?;
+            }
+            Stmt::BreakLoop(x, flags, pos) | Stmt::Return(x, flags, pos) => {
+                self.u8(if matches!(stmt, Stmt::BreakLoop(..)) {
+                    stmt_tag::BREAK_LOOP
+                } else {
+                    stmt_tag::RETURN
+                });
+                self.bool(x.is_some());
+                if let Some(expr) = x {
+                    self.expr(expr)?;
+                }
+                self.flags(*flags);
+                self.pos(*pos);
+            }
+            #[cfg(feature = "resumable")]
+            Stmt::Yield(x, pos) => {
+                self.u8(stmt_tag::YIELD);
+                self.bool(x.is_some());
+                if let Some(expr) = x {
+                    self.expr(expr)?;
+                }
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, pos) => {
+                self.u8(stmt_tag::IMPORT);
+                self.expr(&x.0)?;
+                self.ident(&x.1);
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(x, pos) => {
+                self.u8(stmt_tag::EXPORT);
+                self.ident(&x.0);
+                self.ident(&x.1);
+                self.pos(*pos);
+            }
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(x) => {
+                self.u8(stmt_tag::SHARE);
+                self.usize(x.len());
+                for (ident, index) in x.iter() {
+                    self.ident(ident);
+                    self.opt_index(*index);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    fn fn_def(&mut self, fn_def: &super::ScriptFuncDef) -> RhaiResultOf<()> {
+        self.str(&fn_def.name);
+        self.bool(fn_def.access.is_private());
+        #[cfg(not(feature = "no_object"))]
+        self.opt_str(fn_def.this_type.as_deref());
+        self.usize(fn_def.params.len());
+        for param in &fn_def.params {
+            self.str(param);
+        }
+        #[cfg(feature = "metadata")]
+        {
+            self.usize(fn_def.comments.len());
+            for comment in &fn_def.comments {
+                self.str(comment);
+            }
+        }
+        #[cfg(not(feature = "metadata"))]
+        self.usize(0);
+
+        self.block(&fn_def.body)
+    }
+}
+
+/// Decoder of an [`AST`] from the binary format.
+struct Decoder<'a> {
+    /// [`Engine`] used to intern strings.
+    engine: &'a Engine,
+    /// Input bytes stream.
+    bytes: &'a [u8],
+    /// Strings already read.
+    strings: Vec<ImmutableString>,
+    /// Current level of nesting.
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Create a new [`Decoder`], checking the header.
+    fn new(engine: &'a Engine, bytes: &'a [u8]) -> RhaiResultOf<Self> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(decode_error("not an AST in binary format"));
+        }
+
+        let mut decoder = Self {
+            engine,
+            bytes: &bytes[MAGIC.len()..],
+            strings: Vec::new(),
+            depth: 0,
+        };
+
+        match decoder.u8()? {
+            FORMAT_VERSION => (),
+            version => {
+                return Err(decode_error(format!(
+                    "unsupported format version {version} (expecting {FORMAT_VERSION})"
+                )))
+            }
+        }
+
+        if decoder.uint()? != u64::from(config_flags()) {
+            return Err(decode_error(
+                "AST was encoded with a different set of feature flags",
+            ));
+        }
+
+        Ok(decoder)
+    }
+    /// Go down one level of nesting, returning an error if nested too deeply.
+    ///
+    /// Call [`leave`][Self::leave] after decoding the nested item.
+    fn enter(&mut self) -> RhaiResultOf<()> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(decode_error(format!(
+                "nesting deeper than {MAX_NESTING_DEPTH} levels"
+            )));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+    /// Go up one level of nesting.
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+    fn take(&mut self, len: usize) -> RhaiResultOf<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(decode_error("unexpected end of input"));
+        }
+        let (data, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(data)
+    }
+    fn u8(&mut self) -> RhaiResultOf<u8> {
+        Ok(self.take(1)?[0])
+    }
+    fn bool(&mut self) -> RhaiResultOf<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            n => Err(decode_error(format!("invalid boolean value {n}"))),
+        }
+    }
+    fn uint(&mut self) -> RhaiResultOf<u64> {
+        let mut value = 0_u64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+
+            if shift >= 64 {
+                return Err(decode_error("integer overflow"));
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+    fn usize(&mut self) -> RhaiResultOf<usize> {
+        let value = self.uint()?;
+        usize::try_from(value).map_err(|_| decode_error(format!("invalid size {value}")))
+    }
+    /// Read a length which must not exceed the remaining number of bytes.
+    ///
+    /// This guards against allocating huge buffers for malformed inputs.
+    fn len(&mut self) -> RhaiResultOf<usize> {
+        let len = self.usize()?;
+        if len > self.bytes.len() {
+            return Err(decode_error("unexpected end of input"));
+        }
+        Ok(len)
+    }
+    #[allow(clippy::cast_possible_wrap)]
+    fn i64(&mut self) -> RhaiResultOf<i64> {
+        let value = self.uint()?;
+        Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+    }
+    fn int(&mut self) -> RhaiResultOf<INT> {
+        let value = self.i64()?;
+        INT::try_from(value).map_err(|_| decode_error(format!("integer {value} out of range")))
+    }
+    fn char(&mut self) -> RhaiResultOf<char> {
+        let value = self.uint()?;
+        u32::try_from(value)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| decode_error(format!("invalid character code {value}")))
+    }
+    #[cfg(not(feature = "no_float"))]
+    fn float(&mut self) -> RhaiResultOf<crate::types::FloatWrapper<crate::FLOAT>> {
+        const FLOAT_BYTES: usize = std::mem::size_of::<crate::FLOAT>();
+        let mut buf = [0_u8; FLOAT_BYTES];
+        buf.copy_from_slice(self.take(FLOAT_BYTES)?);
+        Ok(crate::FLOAT::from_le_bytes(buf).into())
+    }
+    fn opt_index(&mut self) -> RhaiResultOf<Option<NonZeroUsize>> {
+        self.usize().map(NonZeroUsize::new)
+    }
+    fn str(&mut self) -> RhaiResultOf<ImmutableString> {
+        let value = self.usize()?;
+        let index = value >> 1;
+
+        if value & 1 == 1 {
+            return self
+                .strings
+                .get(index)
+                .cloned()
+                .ok_or_else(|| decode_error(format!("invalid string index {index}")));
+        }
+
+        let bytes = self.take(index)?;
+        let s = std::str::from_utf8(bytes).map_err(|_| decode_error("invalid UTF-8 string"))?;
+        let s = self.engine.get_interned_string(s);
+        self.strings.push(s.clone());
+        Ok(s)
+    }
+    fn opt_str(&mut self) -> RhaiResultOf<Option<ImmutableString>> {
+        Ok(if self.bool()? {
+            Some(self.str()?)
+        } else {
+            None
+        })
+    }
+    fn pos(&mut self) -> RhaiResultOf<Position> {
+        let line = self.usize()?;
+        let pos = self.usize()?;
+
+        match (u16::try_from(line), u16::try_from(pos)) {
+            (Ok(0), Ok(0)) => Ok(Position::NONE),
+            (Ok(0), _) => Err(decode_error("invalid position")),
+            (Ok(line), Ok(pos)) => Ok(Position::new(line, pos)),
+            _ => Err(decode_error(format!("invalid position {line}:{pos}"))),
+        }
+    }
+    fn span(&mut self) -> RhaiResultOf<Span> {
+        Ok(Span::new(self.pos()?, self.pos()?))
+    }
+    fn flags(&mut self) -> RhaiResultOf<ASTFlags> {
+        let bits = self.u8()?;
+        ASTFlags::from_bits(bits).ok_or_else(|| decode_error(format!("invalid flags {bits}")))
+    }
+    fn ident(&mut self) -> RhaiResultOf<Ident> {
+        Ok(Ident {
+            name: self.str()?,
+            pos: self.pos()?,
+        })
+    }
+    fn token(&mut self) -> RhaiResultOf<Option<Token>> {
+        match self.u8()? {
+            token_tag::NONE => Ok(None),
+            token_tag::UNARY_PLUS => Ok(Some(Token::UnaryPlus)),
+            token_tag::UNARY_MINUS => Ok(Some(Token::UnaryMinus)),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            token_tag::CUSTOM => Ok(Some(Token::Custom(Box::new(self.str()?.as_str().into())))),
+            token_tag::SYMBOL => {
+                let syntax = self.str()?;
+                Token::lookup_symbol_from_syntax(&syntax)
+                    .map(Some)
+                    .ok_or_else(|| decode_error(format!("unknown operator '{syntax}'")))
+            }
+            tag => Err(decode_error(format!("invalid operator tag {tag}"))),
+        }
+    }
+
+    fn value(&mut self) -> RhaiResultOf<Dynamic> {
+        self.enter()?;
+
+        let mut value = match self.u8()? {
+            value_tag::UNIT => Dynamic::UNIT,
+            value_tag::BOOL => self.bool()?.into(),
+            value_tag::STR => self.str()?.into(),
+            value_tag::CHAR => self.char()?.into(),
+            value_tag::INT => self.int()?.into(),
+            #[cfg(not(feature = "no_float"))]
+            value_tag::FLOAT => self.float()?.into(),
+            #[cfg(feature = "decimal")]
+            value_tag::DECIMAL => {
+                let mut buf = [0_u8; 16];
+                buf.copy_from_slice(self.take(16)?);
+                rust_decimal::Decimal::deserialize(buf).into()
+            }
+            #[cfg(not(feature = "no_index"))]
+            value_tag::ARRAY => {
+                let len = self.len()?;
+                let mut array = crate::Array::with_capacity(len);
+                for _ in 0..len {
+                    array.push(self.value()?);
+                }
+                Dynamic::from_array(array)
+            }
+            #[cfg(not(feature = "no_index"))]
+            value_tag::BLOB => {
+                let len = self.len()?;
+                Dynamic::from_blob(self.take(len)?.to_vec())
+            }
+            #[cfg(not(feature = "no_object"))]
+            value_tag::MAP => {
+                let len = self.len()?;
+                let mut map = crate::Map::new();
+                for _ in 0..len {
+                    let key = self.str()?;
+                    map.insert(key.as_str().into(), self.value()?);
+                }
+                Dynamic::from_map(map)
+            }
+            value_tag::FN_PTR => {
+                let name = self.str()?;
+                let len = self.len()?;
+                let mut curry = ThinVec::with_capacity(len);
+                for _ in 0..len {
+                    curry.push(self.value()?);
+                }
+                FnPtr {
+                    name,
+                    curry,
+                    #[cfg(not(feature = "no_function"))]
+                    env: None,
+                    typ: <_>::default(),
+                }
+                .into()
+            }
+            value_tag::EXCLUSIVE_RANGE => (self.int()?..self.int()?).into(),
+            value_tag::INCLUSIVE_RANGE => (self.int()?..=self.int()?).into(),
+            tag => return Err(decode_error(format!("invalid value tag {tag}"))),
+        };
+
+        let tag = self.i64()?;
+        let tag = tag
+            .try_into()
+            .map_err(|_| decode_error(format!("invalid value tag data {tag}")))?;
+        value.set_tag(tag);
+
+        if self.bool()? {
+            value.set_access_mode(AccessMode::ReadOnly);
+        }
+
+        self.leave();
+        Ok(value)
+    }
+
+    fn fn_hash(
+        &mut self,
+        #[cfg(not(feature = "no_module"))] namespace: &super::Namespace,
+        name: &str,
+    ) -> RhaiResultOf<u64> {
+        let value = self.usize()?;
+        let num = value >> 1;
+
+        #[cfg(not(feature = "no_module"))]
+        if value & 1 == 1 {
+            return Ok(calc_fn_hash(
+                namespace.path.iter().map(Ident::as_str),
+                name,
+                num,
+            ));
+        }
+
+        Ok(calc_fn_hash(None, name, num))
+    }
+
+    fn fn_call(&mut self) -> RhaiResultOf<FnCallExpr> {
+        #[cfg(not(feature = "no_module"))]
+        let namespace = self.namespace()?;
+        let name = self.str()?;
+
+        let hashes = match self.u8()? {
+            0 => FnCallHashes::from_native_only(self.fn_hash(
+                #[cfg(not(feature = "no_module"))]
+                &namespace,
+                &name,
+            )?),
+            #[cfg(not(feature = "no_function"))]
+            1 => {
+                let native = self.fn_hash(
+                    #[cfg(not(feature = "no_module"))]
+                    &namespace,
+                    &name,
+                )?;
+                let script = self.fn_hash(
+                    #[cfg(not(feature = "no_module"))]
+                    &namespace,
+                    &name,
+                )?;
+                FnCallHashes::from_script_and_native(script, native)
+            }
+            kind => return Err(decode_error(format!("invalid function call hashes {kind}"))),
+        };
+
+        Ok(FnCallExpr {
+            #[cfg(not(feature = "no_module"))]
+            namespace,
+            name,
+            hashes,
+            args: self.exprs()?.into_iter().collect(),
+            capture_parent_scope: self.bool()?,
+            op_token: self.token()?,
+        })
+    }
+
+    #[cfg(not(feature = "no_module"))]
+    fn namespace(&mut self) -> RhaiResultOf<super::Namespace> {
+        let len = self.len()?;
+        let mut path = StaticVec::with_capacity(len);
+        for _ in 0..len {
+            path.push(self.ident()?);
+        }
+        Ok(super::Namespace {
+            path,
+            index: self.opt_index()?,
+        })
+    }
+
+    fn binary_expr(&mut self) -> RhaiResultOf<BinaryExpr> {
+        Ok(BinaryExpr {
+            lhs: self.expr()?,
+            rhs: self.expr()?,
+        })
+    }
+
+    fn exprs(&mut self) -> RhaiResultOf<ThinVec<Expr>> {
+        let len = self.len()?;
+        let mut exprs = ThinVec::with_capacity(len);
+        for _ in 0..len {
+            exprs.push(self.expr()?);
+        }
+        Ok(exprs)
+    }
+
+    fn expr(&mut self) -> RhaiResultOf<Expr> {
+        self.enter()?;
+
+        let expr = match self.u8()? {
+            expr_tag::DYNAMIC_CONSTANT => Expr::DynamicConstant(self.value()?.into(), self.pos()?),
+            expr_tag::BOOL_CONSTANT => Expr::BoolConstant(self.bool()?, self.pos()?),
+            expr_tag::INTEGER_CONSTANT => Expr::IntegerConstant(self.int()?, self.pos()?),
+            #[cfg(not(feature = "no_float"))]
+            expr_tag::FLOAT_CONSTANT => Expr::FloatConstant(self.float()?, self.pos()?),
+            expr_tag::CHAR_CONSTANT => Expr::CharConstant(self.char()?, self.pos()?),
+            expr_tag::STRING_CONSTANT => Expr::StringConstant(self.str()?, self.pos()?),
+            expr_tag::INTERPOLATED_STRING => Expr::InterpolatedString(self.exprs()?, self.pos()?),
+            expr_tag::ARRAY => Expr::Array(self.exprs()?, self.pos()?),
+            expr_tag::MAP => {
+                let len = self.len()?;
+                let mut items = StaticVec::with_capacity(len);
+                for _ in 0..len {
+                    items.push((self.ident()?, self.expr()?));
+                }
+                let len = self.len()?;
+                let mut template = BTreeMap::new();
+                for _ in 0..len {
+                    let key = self.str()?;
+                    template.insert(key.as_str().into(), self.value()?);
+                }
+                Expr::Map((items, template).into(), self.pos()?)
+            }
+            expr_tag::UNIT => Expr::Unit(self.pos()?),
+            expr_tag::VARIABLE => {
+                let index = self.opt_index()?;
+                let name = self.str()?;
+                #[cfg(not(feature = "no_module"))]
+                let x = {
+                    let namespace = self.namespace()?;
+                    let hash = if self.bool()? {
+                        crate::calc_var_hash(namespace.path.iter().map(Ident::as_str), &name)
+                    } else {
+                        0
+                    };
+                    (index, name, namespace, hash)
+                };
+                #[cfg(feature = "no_module")]
+                let x = (index, name);
+
+                let short_index = self.usize()?;
+                let short_index = u8::try_from(short_index)
+                    .map_err(|_| decode_error(format!("invalid variable index {short_index}")))?;
+
+                Expr::Variable(x.into(), NonZeroU8::new(short_index), self.pos()?)
+            }
+            expr_tag::THIS_PTR => Expr::ThisPtr(self.pos()?),
+            expr_tag::PROPERTY => {
+                let getter = self.str()?;
+                let hash_get = calc_fn_hash(None, &getter, 1);
+                let setter = self.str()?;
+                let hash_set = calc_fn_hash(None, &setter, 2);
+                let prop = self.str()?;
+                Expr::Property(
+                    ((getter, hash_get), (setter, hash_set), prop).into(),
+                    self.pos()?,
+                )
+            }
+            expr_tag::METHOD_CALL => Expr::MethodCall(self.fn_call()?.into(), self.pos()?),
+            expr_tag::STMT => Expr::Stmt(self.block()?.into()),
+            expr_tag::FN_CALL => Expr::FnCall(self.fn_call()?.into(), self.pos()?),
+            expr_tag::DOT => Expr::Dot(self.binary_expr()?.into(), self.flags()?, self.pos()?),
+            expr_tag::INDEX => Expr::Index(self.binary_expr()?.into(), self.flags()?, self.pos()?),
+            expr_tag::AND => Expr::And(self.binary_expr()?.into(), self.pos()?),
+            expr_tag::OR => Expr::Or(self.binary_expr()?.into(), self.pos()?),
+            expr_tag::COALESCE => Expr::Coalesce(self.binary_expr()?.into(), self.pos()?),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            expr_tag::CUSTOM => {
+                let inputs = self.exprs()?.into_iter().collect();
+                let len = self.len()?;
+                let mut tokens = FnArgsVec::with_capacity(len);
+                for _ in 0..len {
+                    tokens.push(self.str()?);
+                }
+                let custom = super::CustomExpr {
+                    inputs,
+                    tokens,
+                    state: self.value()?,
+                    scope_may_be_changed: self.bool()?,
+                    self_terminated: self.bool()?,
+                };
+                Expr::Custom(custom.into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_function"))]
+            expr_tag::INLINED_FN => {
+                let name = self.str()?;
+                Expr::InlinedFn((name, self.block()?).into(), self.pos()?)
+            }
+            tag => return Err(decode_error(format!("invalid expression tag {tag}"))),
+        };
+
+        self.leave();
+        Ok(expr)
+    }
+
+    fn block(&mut self) -> RhaiResultOf<StmtBlock> {
+        let statements = self.stmts()?;
+        Ok(StmtBlock::new_with_span(statements, self.span()?))
+    }
+
+    fn stmts(&mut self) -> RhaiResultOf<Vec<Stmt>> {
+        let len = self.len()?;
+        let mut stmts = Vec::with_capacity(len);
+        for _ in 0..len {
+            stmts.push(self.stmt()?);
+        }
+        Ok(stmts)
+    }
+
+    fn flow_control(&mut self) -> RhaiResultOf<FlowControl> {
+        Ok(FlowControl {
+            expr: self.expr()?,
+            body: self.block()?,
+            branch: self.block()?,
+        })
+    }
+
+    fn switch_cases(&mut self) -> RhaiResultOf<SwitchCasesCollection> {
+        let len = self.len()?;
+        let mut expressions = FnArgsVec::with_capacity(len);
+        for _ in 0..len {
+            expressions.push(self.binary_expr()?);
+        }
+
+        let check_index = |index: usize| {
+            if index < expressions.len() {
+                Ok(index)
+            } else {
+                Err(decode_error(format!("invalid switch case index {index}")))
+            }
+        };
+
+        let len = self.len()?;
+        let mut cases = StraightHashMap::default();
+        let mut values = StaticVec::with_capacity(len);
+        for _ in 0..len {
+            let value = self.value()?;
+            let hasher = &mut get_hasher();
+            value.hash(hasher);
+            values.push(value);
+
+            let len = self.len()?;
+            let mut blocks = super::CaseBlocksList::with_capacity(len);
+            for _ in 0..len {
+                blocks.push(check_index(self.usize()?)?);
+            }
+            cases.insert(hasher.finish(), blocks);
+        }
+
+        let len = self.len()?;
+        let mut ranges = StaticVec::with_capacity(len);
+        for _ in 0..len {
+            let inclusive = self.bool()?;
+            let (start, end) = (self.int()?, self.int()?);
+            let index = check_index(self.usize()?)?;
+            ranges.push(if inclusive {
+                RangeCase::InclusiveInt(start..=end, index)
+            } else {
+                RangeCase::ExclusiveInt(start..end, index)
+            });
+        }
+
+        let def_case = match self.usize()? {
+            0 => None,
+            n => Some(check_index(n - 1)?),
+        };
+
+        Ok(SwitchCasesCollection {
+            expressions,
+            cases,
+            values,
+            ranges,
+            def_case,
+        })
+    }
+
+    fn pattern(&mut self, num_vars: usize) -> RhaiResultOf<Pattern> {
+        let check_index = |index: usize| {
+            if index < num_vars {
+                Ok(index)
+            } else {
+                Err(decode_error(format!(
+                    "invalid pattern variable index {index}"
+                )))
+            }
+        };
+
+        self.enter()?;
+
+        let pattern = match self.u8()? {
+            pattern_tag::WILDCARD => Pattern::Wildcard(self.pos()?),
+            pattern_tag::VALUE => Pattern::Value(self.value()?.into(), self.pos()?),
+            pattern_tag::BIND => Pattern::Bind(self.ident()?, check_index(self.usize()?)?),
+            pattern_tag::TYPED => Pattern::Typed((self.pattern(num_vars)?, self.ident()?).into()),
+            #[cfg(not(feature = "no_index"))]
+            pattern_tag::ARRAY => {
+                let len = self.len()?;
+                let mut items = StaticVec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.pattern(num_vars)?);
+                }
+                Pattern::Array(items.into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_index"))]
+            pattern_tag::REST => {
+                let var = if self.bool()? {
+                    Some((self.ident()?, check_index(self.usize()?)?))
+                } else {
+                    None
+                };
+                Pattern::Rest(var, self.pos()?)
+            }
+            #[cfg(not(feature = "no_object"))]
+            pattern_tag::MAP => {
+                let len = self.len()?;
+                let mut items = StaticVec::with_capacity(len);
+                for _ in 0..len {
+                    items.push((self.ident()?, self.pattern(num_vars)?));
+                }
+                Pattern::Map(items.into(), self.pos()?)
+            }
+            pattern_tag::OR => {
+                let len = self.len()?;
+                if len == 0 {
+                    return Err(decode_error("empty alternatives in pattern"));
+                }
+                let mut items = StaticVec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.pattern(num_vars)?);
+                }
+                Pattern::Or(items.into())
+            }
+            tag => return Err(decode_error(format!("invalid pattern tag {tag}"))),
+        };
+
+        self.leave();
+        Ok(pattern)
+    }
+
+    fn stmt(&mut self) -> RhaiResultOf<Stmt> {
+        self.enter()?;
+
+        let stmt = match self.u8()? {
+            stmt_tag::NOOP => Stmt::Noop(self.pos()?),
+            stmt_tag::IF => Stmt::If(self.flow_control()?.into(), self.pos()?),
+            stmt_tag::SWITCH => {
+                let expr = self.expr()?;
+                let cases = self.switch_cases()?;
+                Stmt::Switch((expr, cases).into(), self.pos()?)
+            }
+            stmt_tag::MATCH => {
+                let expr = self.expr()?;
+                let len = self.len()?;
+                let mut arms = StaticVec::with_capacity(len);
+                for _ in 0..len {
+                    let len = self.len()?;
+                    let mut vars = StaticVec::with_capacity(len);
+                    for _ in 0..len {
+                        vars.push(self.ident()?);
+                    }
+                    arms.push(MatchArm {
+                        pattern: self.pattern(vars.len())?,
+                        vars,
+                        condition: self.expr()?,
+                        expr: self.expr()?,
+                    });
+                }
+                Stmt::Match((expr, arms).into(), self.pos()?)
+            }
+            stmt_tag::WHILE => Stmt::While(self.flow_control()?.into(), self.pos()?),
+            stmt_tag::DO => Stmt::Do(self.flow_control()?.into(), self.flags()?, self.pos()?),
+            stmt_tag::FOR => {
+                let var = self.ident()?;
+                let counter = if self.bool()? {
+                    Some(self.ident()?)
+                } else {
+                    None
+                };
+                let x = (var, counter, self.flow_control()?);
+                Stmt::For(x.into(), self.pos()?)
+            }
+            stmt_tag::VAR => {
+                let x = (self.ident()?, self.expr()?, self.opt_index()?);
+                Stmt::Var(x.into(), self.flags()?, self.pos()?)
+            }
+            stmt_tag::DESTRUCTURE => {
+                let len = self.len()?;
+                let mut vars = StaticVec::with_capacity(len);
+                for _ in 0..len {
+                    vars.push(self.ident()?);
+                }
+                let x = (self.pattern(vars.len())?, vars, self.expr()?);
+                Stmt::Destructure(x.into(), self.flags()?, self.pos()?)
+            }
+            stmt_tag::ASSIGNMENT => {
+                let op = self.token()?;
+                let pos = self.pos()?;
+                let op = match op {
+                    None => OpAssignment::new_assignment(pos),
+                    Some(token) if token.get_base_op_from_assignment().is_some() => {
+                        OpAssignment::new_op_assignment_from_token(token, pos)
+                    }
+                    Some(token) => {
+                        return Err(decode_error(format!(
+                            "'{token}' is not an op-assignment operator"
+                        )))
+                    }
+                };
+                Stmt::Assignment((op, self.binary_expr()?).into())
+            }
+            stmt_tag::FN_CALL => Stmt::FnCall(self.fn_call()?.into(), self.pos()?),
+            stmt_tag::BLOCK => Stmt::Block(self.block()?.into()),
+            stmt_tag::TRY_CATCH => Stmt::TryCatch(self.flow_control()?.into(), self.pos()?),
+            stmt_tag::EXPR => Stmt::Expr(self.expr()?.into()),
+            tag @ (stmt_tag::BREAK_LOOP | stmt_tag::RETURN) => {
+                let expr = if self.bool()? {
+                    Some(self.expr()?.into())
+                } else {
+                    None
+                };
+                let flags = self.flags()?;
+                let pos = self.pos()?;
+
+                if tag == stmt_tag::BREAK_LOOP {
+                    Stmt::BreakLoop(expr, flags, pos)
+                } else {
+                    Stmt::Return(expr, flags, pos)
+                }
+            }
+            #[cfg(feature = "resumable")]
+            stmt_tag::YIELD => {
+                let expr = if self.bool()? {
+                    Some(self.expr()?.into())
+                } else {
+                    None
+                };
+                Stmt::Yield(expr, self.pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            stmt_tag::IMPORT => {
+                let x = (self.expr()?, self.ident()?);
+                Stmt::Import(x.into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            stmt_tag::EXPORT => {
+                let x = (self.ident()?, self.ident()?);
+                Stmt::Export(x.into(), self.pos()?)
+            }
+            #[cfg(not(feature = "no_closure"))]
+            stmt_tag::SHARE => {
+                let len = self.len()?;
+                if len == 0 {
+                    return Err(decode_error("empty list of shared variables"));
+                }
+                let mut vars = FnArgsVec::with_capacity(len);
+                for _ in 0..len {
+                    vars.push((self.ident()?, self.opt_index()?));
+                }
+                Stmt::Share(vars.into())
+            }
+            tag => return Err(decode_error(format!("invalid statement tag {tag}"))),
+        };
+
+        self.leave();
+        Ok(stmt)
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    fn fn_def(&mut self) -> RhaiResultOf<super::ScriptFuncDef> {
+        let name = self.str()?;
+        let access = if self.bool()? {
+            super::FnAccess::Private
+        } else {
+            super::FnAccess::Public
+        };
+        #[cfg(not(feature = "no_object"))]
+        let this_type = self.opt_str()?;
+        let len = self.len()?;
+        let mut params = FnArgsVec::with_capacity(len);
+        for _ in 0..len {
+            params.push(self.str()?);
+        }
+
+        let len = self.len()?;
+        #[cfg(feature = "metadata")]
+        let mut comments = StaticVec::with_capacity(len);
+        for _ in 0..len {
+            let _comment = self.str()?;
+            #[cfg(feature = "metadata")]
+            comments.push(_comment.as_str().into());
+        }
+
+        let body = self.block()?;
+
+        Ok(super::ScriptFuncDef {
+            #[cfg(feature = "resumable")]
+            is_generator: super::ScriptFuncDef::is_generator_body(&body),
+            body,
+            name,
+            access,
+            #[cfg(not(feature = "no_object"))]
+            this_type,
+            params,
+            #[cfg(feature = "metadata")]
+            comments,
+        })
+    }
+}
+
+impl AST {
+    /// Encode this [`AST`] into a compact binary format.
+    ///
+    /// The result can be persisted (e.g. cached on disk) and loaded back later via
+    /// [`Engine::compile_from_bytes`] without parsing the script again.
+    ///
+    /// The binary format contains a version header and can only be loaded by a build of Rhai with
+    /// the same format version and the same set of language feature flags.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [`AST`] contains constant values that cannot be encoded
+    /// (e.g. custom types or timestamps), or if it contains an embedded module resolver
+    /// (e.g. an [`AST`] returned by [`Engine::compile_into_self_contained`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("let x = 40; x + 2")?;
+    ///
+    /// // Encode the AST into bytes
+    /// let bytes = ast.to_bytes()?;
+    ///
+    /// // Load the AST back without parsing
+    /// let ast = engine.compile_from_bytes(&bytes)?;
+    ///
+    /// assert_eq!(engine.eval_ast::<i64>(&ast)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bytes(&self) -> RhaiResultOf<Vec<u8>> {
+        #[cfg(not(feature = "no_module"))]
+        if self.resolver.as_ref().map_or(false, |r| !r.is_empty()) {
+            return Err(encode_error(
+                "AST with an embedded module resolver cannot be encoded",
+            ));
+        }
+
+        let mut encoder = Encoder::new();
+
+        encoder.opt_str(self.source());
+        #[cfg(feature = "metadata")]
+        encoder.str(self.doc());
+        #[cfg(not(feature = "metadata"))]
+        encoder.str("");
+
+        encoder.stmts(self.statements())?;
+
+        #[cfg(not(feature = "no_function"))]
+        {
+            let lib = self.shared_lib();
+            encoder.usize(lib.iter_script_fn().count());
+            for (.., fn_def) in lib.iter_script_fn() {
+                encoder.fn_def(fn_def)?;
+            }
+        }
+
+        Ok(encoder.buf)
+    }
+}
+
+/// Decode an [`AST`] from the binary format.
+///
+/// Strings are interned using the [`Engine`]'s strings interner.
+pub(crate) fn decode(engine: &Engine, bytes: &[u8]) -> RhaiResultOf<AST> {
+    let mut decoder = Decoder::new(engine, bytes)?;
+
+    let source = decoder.opt_str()?;
+    let _doc = decoder.str()?;
+
+    let statements = decoder.stmts()?;
+
+    #[cfg(not(feature = "no_function"))]
+    let functions = {
+        let len = decoder.len()?;
+        let mut functions = StaticVec::<crate::Shared<_>>::with_capacity(len);
+        for _ in 0..len {
+            functions.push(decoder.fn_def()?.into());
+        }
+        crate::Module::from(functions)
+    };
+
+    if !decoder.bytes.is_empty() {
+        return Err(decode_error("unexpected trailing bytes"));
+    }
+
+    let mut ast = AST::new(
+        statements,
+        #[cfg(not(feature = "no_function"))]
+        functions,
+    );
+
+    if let Some(source) = source {
+        ast.set_source(source);
+    }
+    #[cfg(feature = "metadata")]
+    {
+        ast.doc = _doc.as_str().into();
+    }
+
+    Ok(ast)
+}
@@ -312,6 +312,12 @@ pub enum Expr {
     MethodCall(Box<FnCallExpr>, Position),
     /// { [statement][Stmt] ... }
     Stmt(Box<StmtBlock>),
+    /// Body of a script-defined function inlined at a call site - (function name, body)
+    ///
+    /// Errors are wrapped in [`ErrorInFunctionCall`][crate::EvalAltResult::ErrorInFunctionCall] at
+    /// the position of the call, just like a function call.
+    #[cfg(not(feature = "no_function"))]
+    InlinedFn(Box<(ImmutableString, StmtBlock)>, Position),
     /// func `(` expr `,` ... `)`
     FnCall(Box<FnCallExpr>, Position),
     /// lhs `.` rhs | lhs `?.` rhs
@@ -409,6 +415,14 @@ impl fmt::Debug for Expr {
                 f.write_str("ExprStmtBlock")?;
                 f.debug_list().entries(x.iter()).finish()
             }
+            #[cfg(not(feature = "no_function"))]
+            Self::InlinedFn(x, pos) => {
+                if !pos.is_none() {
+                    display_pos = *pos;
+                }
+                write!(f, "InlinedFn({})", x.0)?;
+                f.debug_list().entries(x.1.iter()).finish()
+            }
             Self::FnCall(x, ..) => fmt::Debug::fmt(x, f),
             Self::Index(x, options, pos) => {
                 if !pos.is_none() {
@@ -641,6 +655,9 @@ impl Expr {
             | Self::Property(..)
             | Self::Stmt(..) => ASTFlags::empty(),
 
+            #[cfg(not(feature = "no_function"))]
+            Self::InlinedFn(..) => ASTFlags::empty(),
+
             #[cfg(not(feature = "no_custom_syntax"))]
             Self::Custom(..) => ASTFlags::empty(),
         }
@@ -673,6 +690,9 @@ impl Expr {
             | Self::InterpolatedString(.., pos)
             | Self::Property(.., pos) => *pos,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::InlinedFn(.., pos) => *pos,
+
             #[cfg(not(feature = "no_custom_syntax"))]
             Self::Custom(.., pos) => *pos,
 
@@ -732,6 +752,9 @@ impl Expr {
             | Self::InterpolatedString(.., pos)
             | Self::Property(.., pos) => *pos = new_pos,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::InlinedFn(.., pos) => *pos = new_pos,
+
             #[cfg(not(feature = "no_custom_syntax"))]
             Self::Custom(.., pos) => *pos = new_pos,
 
@@ -757,6 +780,9 @@ impl Expr {
 
             Self::Stmt(x) => x.iter().all(Stmt::is_pure),
 
+            #[cfg(not(feature = "no_function"))]
+            Self::InlinedFn(x, ..) => x.1.iter().all(Stmt::is_pure),
+
             Self::Variable(..) => true,
 
             _ => self.is_constant(),
@@ -826,6 +852,9 @@ impl Expr {
             | Self::Array(..)
             | Self::Map(..) => false,
 
+            #[cfg(not(feature = "no_function"))]
+            Self::InlinedFn(..) => false,
+
             #[cfg(not(feature = "no_custom_syntax"))]
             Self::Custom(..) => false,
 
@@ -865,6 +894,14 @@ impl Expr {
                     }
                 }
             }
+            #[cfg(not(feature = "no_function"))]
+            Self::InlinedFn(x, ..) => {
+                for s in &x.1 {
+                    if !s.walk(path, on_node) {
+                        return false;
+                    }
+                }
+            }
             Self::InterpolatedString(x, ..) | Self::Array(x, ..) => {
                 for e in &**x {
                     if !e.walk(path, on_node) {
@@ -2,10 +2,12 @@
 
 #[allow(clippy::module_inception)]
 pub mod ast;
+pub mod binary;
 pub mod expr;
 pub mod flags;
 pub mod ident;
 pub mod namespace;
+pub mod pattern;
 pub mod script_fn;
 pub mod stmt;
 
@@ -17,6 +19,7 @@ pub use flags::{ASTFlags, FnAccess};
 pub use ident::Ident;
 #[cfg(not(feature = "no_module"))]
 pub use namespace::Namespace;
+pub use pattern::{MatchArm, Pattern};
 #[cfg(not(feature = "no_function"))]
 pub use script_fn::{ScriptFnMetadata, ScriptFuncDef};
 pub use stmt::{
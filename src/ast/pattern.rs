@@ -0,0 +1,101 @@
+//! Module defining patterns for `match` expressions.
+
+use super::{Expr, Ident};
+use crate::{Dynamic, Position, StaticVec};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// _(internals)_ A pattern in an arm of a `match` expression.
+/// Exported under the `internals` feature only.
+///
+/// Variables bound by a pattern are stored in a list held by the [`MatchArm`], and each binding
+/// refers to its variable by index into this list.
+#[derive(Debug, Clone, Hash)]
+#[non_exhaustive]
+pub enum Pattern {
+    /// `_`
+    Wildcard(Position),
+    /// A literal value, e.g. `42`, `"hello"`, `true` or `()`.
+    ///
+    /// An integer range (e.g. `1..10`) matches any number within the range.
+    Value(Box<Dynamic>, Position),
+    /// A variable binding.
+    Bind(Ident, usize),
+    /// pattern `:` type
+    ///
+    /// The pattern is always a [`Wildcard`][Pattern::Wildcard] or a [`Bind`][Pattern::Bind].
+    Typed(Box<(Pattern, Ident)>),
+    /// `[` pattern `,` ... `]`
+    ///
+    /// Not available under `no_index`.
+    #[cfg(not(feature = "no_index"))]
+    Array(Box<StaticVec<Pattern>>, Position),
+    /// `..` or `..` var
+    ///
+    /// Only appears directly inside an [`Array`][Pattern::Array] pattern, at most once.
+    ///
+    /// Not available under `no_index`.
+    #[cfg(not(feature = "no_index"))]
+    Rest(Option<(Ident, usize)>, Position),
+    /// `#{` key `:` pattern `,` ... `}`
+    ///
+    /// Properties not listed in the pattern are ignored.
+    ///
+    /// Not available under `no_object`.
+    #[cfg(not(feature = "no_object"))]
+    Map(Box<StaticVec<(Ident, Pattern)>>, Position),
+    /// pattern `|` pattern `|` ...
+    ///
+    /// All alternatives bind the same variables.
+    Or(Box<StaticVec<Pattern>>),
+}
+
+impl Pattern {
+    /// Get the [position][Position] of this pattern.
+    #[must_use]
+    pub fn position(&self) -> Position {
+        match self {
+            Self::Wildcard(pos) | Self::Value(.., pos) => *pos,
+            Self::Bind(var, ..) => var.pos,
+            Self::Typed(x) => x.0.position(),
+            #[cfg(not(feature = "no_index"))]
+            Self::Array(.., pos) | Self::Rest(.., pos) => *pos,
+            #[cfg(not(feature = "no_object"))]
+            Self::Map(.., pos) => *pos,
+            Self::Or(x) => x[0].position(),
+        }
+    }
+    /// Change the index of each variable bound by this pattern.
+    pub fn remap_vars(&mut self, f: &impl Fn(usize) -> usize) {
+        match self {
+            Self::Wildcard(..) | Self::Value(..) => (),
+            Self::Bind(_, index) => *index = f(*index),
+            Self::Typed(x) => x.0.remap_vars(f),
+            #[cfg(not(feature = "no_index"))]
+            Self::Array(x, ..) => x.iter_mut().for_each(|p| p.remap_vars(f)),
+            #[cfg(not(feature = "no_index"))]
+            Self::Rest(var, ..) => {
+                if let Some((_, index)) = var {
+                    *index = f(*index);
+                }
+            }
+            #[cfg(not(feature = "no_object"))]
+            Self::Map(x, ..) => x.iter_mut().for_each(|(_, p)| p.remap_vars(f)),
+            Self::Or(x) => x.iter_mut().for_each(|p| p.remap_vars(f)),
+        }
+    }
+}
+
+/// _(internals)_ An arm of a `match` expression.
+/// Exported under the `internals` feature only.
+#[derive(Debug, Clone, Hash)]
+pub struct MatchArm {
+    /// Pattern to match.
+    pub pattern: Pattern,
+    /// Variables bound by the pattern, in the order they are pushed into the scope.
+    pub vars: StaticVec<Ident>,
+    /// Guard condition, which is `true` if there is none.
+    pub condition: Expr,
+    /// Expression to evaluate when the arm is matched.
+    pub expr: Expr,
+}
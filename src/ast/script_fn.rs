@@ -38,6 +38,14 @@ pub struct ScriptFuncDef {
     /// Each line in non-block doc-comments starts with `///`.
     #[cfg(feature = "metadata")]
     pub comments: crate::StaticVec<crate::SmartString>,
+    /// Is this function a generator (i.e. does its body contain `yield` statements)?
+    ///
+    /// Calling a generator function returns a [`Generator`][crate::Generator] instead of running
+    /// its body.
+    ///
+    /// Only available under the `resumable` feature.
+    #[cfg(feature = "resumable")]
+    pub is_generator: bool,
 }
 
 impl ScriptFuncDef {
@@ -55,8 +63,27 @@ impl ScriptFuncDef {
             params: self.params.clone(),
             #[cfg(feature = "metadata")]
             comments: <_>::default(),
+            #[cfg(feature = "resumable")]
+            is_generator: self.is_generator,
         }
     }
+    /// Does a function body contain `yield` statements (i.e. is it the body of a generator)?
+    ///
+    /// Closures defined within the body are separate functions and not searched.
+    #[cfg(feature = "resumable")]
+    #[must_use]
+    pub(crate) fn is_generator_body(body: &StmtBlock) -> bool {
+        let path = &mut Vec::new();
+
+        !body.iter().all(|stmt| {
+            stmt.walk(path, &mut |path| {
+                !matches!(
+                    path.last(),
+                    Some(super::ASTNode::Stmt(super::Stmt::Yield(..)))
+                )
+            })
+        })
+    }
 }
 
 impl fmt::Display for ScriptFuncDef {
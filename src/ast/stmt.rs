@@ -1,6 +1,6 @@
 //! Module defining script statements.
 
-use super::{ASTFlags, ASTNode, BinaryExpr, Expr, FnCallExpr, Ident};
+use super::{ASTFlags, ASTNode, BinaryExpr, Expr, FnCallExpr, Ident, MatchArm, Pattern};
 use crate::engine::{KEYWORD_EVAL, OP_EQUALS};
 use crate::func::StraightHashMap;
 use crate::tokenizer::Token;
@@ -335,6 +335,11 @@ pub struct SwitchCasesCollection {
     pub expressions: FnArgsVec<BinaryExpr>,
     /// Dictionary mapping value hashes to [`CaseBlocksList`]'s.
     pub cases: StraightHashMap<CaseBlocksList>,
+    /// List of constant case values.
+    ///
+    /// Hashes in `cases` can be re-calculated from these values, which is necessary when the
+    /// hashing seed changes (e.g. when an [`AST`][crate::AST] is loaded in another process).
+    pub values: StaticVec<Dynamic>,
     /// List of range cases.
     pub ranges: StaticVec<RangeCase>,
     /// Statements block for the default case (there can be no condition for the default case).
@@ -600,6 +605,8 @@ pub enum Stmt {
     /// 1) Default block
     /// 2) List of ranges: (start, end, inclusive, condition, statement)
     Switch(Box<(Expr, SwitchCasesCollection)>, Position),
+    /// `match` expr `{` pattern `if` condition `=>` stmt `,` ... `}`
+    Match(Box<(Expr, StaticVec<MatchArm>)>, Position),
     /// `while` expr `{` stmt `}` | `loop` `{` stmt `}`
     ///
     /// If the guard expression is [`UNIT`][Expr::Unit], then it is a `loop` statement.
@@ -620,6 +627,15 @@ pub enum Stmt {
     /// * [`EXPORTED`][ASTFlags::EXPORTED] = `export`  
     /// * [`CONSTANT`][ASTFlags::CONSTANT] = `const`
     Var(Box<(Ident, Expr, Option<NonZeroUsize>)>, ASTFlags, Position),
+    /// \[`export`\] `let`|`const` pattern `=` expr
+    ///
+    /// The variables bound by the pattern are always pushed as new entries in the order listed.
+    ///
+    /// ### Flags
+    ///
+    /// * [`EXPORTED`][ASTFlags::EXPORTED] = `export`  
+    /// * [`CONSTANT`][ASTFlags::CONSTANT] = `const`
+    Destructure(Box<(Pattern, StaticVec<Ident>, Expr)>, ASTFlags, Position),
     /// expr op`=` expr
     Assignment(Box<(OpAssignment, BinaryExpr)>),
     /// func `(` expr `,` ... `)`
@@ -647,6 +663,11 @@ pub enum Stmt {
     /// * [`NONE`][ASTFlags::NONE] = `return`
     /// * [`BREAK`][ASTFlags::BREAK] = `throw`
     Return(Option<Box<Expr>>, ASTFlags, Position),
+    /// `yield` expr
+    ///
+    /// Only available under the `resumable` feature.
+    #[cfg(feature = "resumable")]
+    Yield(Option<Box<Expr>>, Position),
     /// `import` expr `as` alias
     ///
     /// Not available under `no_module`.
@@ -691,12 +712,14 @@ impl Stmt {
         match self {
             Self::Do(_, options, _)
             | Self::Var(_, options, _)
+            | Self::Destructure(_, options, _)
             | Self::BreakLoop(_, options, _)
             | Self::Return(_, options, _) => *options,
 
             Self::Noop(..)
             | Self::If(..)
             | Self::Switch(..)
+            | Self::Match(..)
             | Self::Block(..)
             | Self::Expr(..)
             | Self::FnCall(..)
@@ -708,6 +731,9 @@ impl Stmt {
             #[cfg(not(feature = "no_module"))]
             Self::Import(..) | Self::Export(..) => ASTFlags::empty(),
 
+            #[cfg(feature = "resumable")]
+            Self::Yield(..) => ASTFlags::empty(),
+
             #[cfg(not(feature = "no_closure"))]
             Self::Share(..) => ASTFlags::empty(),
         }
@@ -721,13 +747,18 @@ impl Stmt {
             | Self::FnCall(.., pos)
             | Self::If(.., pos)
             | Self::Switch(.., pos)
+            | Self::Match(.., pos)
             | Self::While(.., pos)
             | Self::Do(.., pos)
             | Self::For(.., pos)
             | Self::Return(.., pos)
             | Self::Var(.., pos)
+            | Self::Destructure(.., pos)
             | Self::TryCatch(.., pos) => *pos,
 
+            #[cfg(feature = "resumable")]
+            Self::Yield(.., pos) => *pos,
+
             Self::Assignment(x) => x.0.pos,
 
             Self::Block(x) => x.position(),
@@ -751,13 +782,18 @@ impl Stmt {
             | Self::FnCall(.., pos)
             | Self::If(.., pos)
             | Self::Switch(.., pos)
+            | Self::Match(.., pos)
             | Self::While(.., pos)
             | Self::Do(.., pos)
             | Self::For(.., pos)
             | Self::Return(.., pos)
             | Self::Var(.., pos)
+            | Self::Destructure(.., pos)
             | Self::TryCatch(.., pos) => *pos = new_pos,
 
+            #[cfg(feature = "resumable")]
+            Self::Yield(.., pos) => *pos = new_pos,
+
             Self::Assignment(x) => x.0.pos = new_pos,
 
             Self::Block(x) => x.set_position(new_pos, x.end_position()),
@@ -783,6 +819,7 @@ impl Stmt {
         match self {
             Self::If(..)
             | Self::Switch(..)
+            | Self::Match(..)
             | Self::Block(..)
             | Self::Expr(..)
             | Self::FnCall(..) => true,
@@ -793,7 +830,14 @@ impl Stmt {
             | Self::For(..)
             | Self::TryCatch(..) => false,
 
-            Self::Var(..) | Self::Assignment(..) | Self::BreakLoop(..) | Self::Return(..) => false,
+            Self::Var(..)
+            | Self::Destructure(..)
+            | Self::Assignment(..)
+            | Self::BreakLoop(..)
+            | Self::Return(..) => false,
+
+            #[cfg(feature = "resumable")]
+            Self::Yield(..) => false,
 
             #[cfg(not(feature = "no_module"))]
             Self::Import(..) | Self::Export(..) => false,
@@ -808,6 +852,7 @@ impl Stmt {
         match self {
             Self::If(..)
             | Self::Switch(..)
+            | Self::Match(..)
             | Self::While(..)
             | Self::For(..)
             | Self::Block(..)
@@ -823,12 +868,16 @@ impl Stmt {
             },
 
             Self::Var(..)
+            | Self::Destructure(..)
             | Self::Assignment(..)
             | Self::FnCall(..)
             | Self::Do(..)
             | Self::BreakLoop(..)
             | Self::Return(..) => false,
 
+            #[cfg(feature = "resumable")]
+            Self::Yield(..) => false,
+
             #[cfg(not(feature = "no_module"))]
             Self::Import(..) | Self::Export(..) => false,
 
@@ -863,6 +912,12 @@ impl Stmt {
                     && sw.def_case.is_some()
                     && sw.expressions[sw.def_case.unwrap()].rhs.is_pure()
             }
+            Self::Match(x, ..) => {
+                x.0.is_pure()
+                    && x.1
+                        .iter()
+                        .all(|arm| arm.condition.is_pure() && arm.expr.is_pure())
+            }
 
             // Loops that exit can be pure because it can never be infinite.
             Self::While(x, ..) if matches!(x.expr, Expr::BoolConstant(false, ..)) => true,
@@ -880,9 +935,13 @@ impl Stmt {
             // so infinite loops can never occur.
             Self::For(x, ..) => x.2.expr.is_pure() && x.2.body.iter().all(Self::is_pure),
 
-            Self::Var(..) | Self::Assignment(..) | Self::FnCall(..) => false,
+            Self::Var(..) | Self::Destructure(..) | Self::Assignment(..) | Self::FnCall(..) => {
+                false
+            }
             Self::Block(block, ..) => block.iter().all(Self::is_pure),
             Self::BreakLoop(..) | Self::Return(..) => false,
+            #[cfg(feature = "resumable")]
+            Self::Yield(..) => false,
             Self::TryCatch(x, ..) => {
                 x.expr.is_pure()
                     && x.body.iter().all(Self::is_pure)
@@ -909,7 +968,7 @@ impl Stmt {
     #[must_use]
     pub fn is_block_dependent(&self) -> bool {
         match self {
-            Self::Var(..) => true,
+            Self::Var(..) | Self::Destructure(..) => true,
 
             Self::Expr(e) => match &**e {
                 Expr::Stmt(s) => s.iter().all(Self::is_block_dependent),
@@ -991,6 +1050,11 @@ impl Stmt {
                     return false;
                 }
             }
+            Self::Destructure(x, ..) => {
+                if !x.2.walk(path, on_node) {
+                    return false;
+                }
+            }
             Self::If(x, ..) => {
                 if !x.expr.walk(path, on_node) {
                     return false;
@@ -1040,6 +1104,19 @@ impl Stmt {
                     }
                 }
             }
+            Self::Match(x, ..) => {
+                if !x.0.walk(path, on_node) {
+                    return false;
+                }
+                for arm in &x.1 {
+                    if !arm.condition.walk(path, on_node) {
+                        return false;
+                    }
+                    if !arm.expr.walk(path, on_node) {
+                        return false;
+                    }
+                }
+            }
             Self::While(x, ..) | Self::Do(x, ..) => {
                 if !x.expr.walk(path, on_node) {
                     return false;
@@ -1104,6 +1181,12 @@ impl Stmt {
                     return false;
                 }
             }
+            #[cfg(feature = "resumable")]
+            Self::Yield(Some(e), ..) => {
+                if !e.walk(path, on_node) {
+                    return false;
+                }
+            }
             #[cfg(not(feature = "no_module"))]
             Self::Import(x, ..) => {
                 if !x.0.walk(path, on_node) {
@@ -0,0 +1,887 @@
+use rhai::debugger::{BreakPoint, DebuggerCommand, DebuggerEvent};
+use rhai::{ASTNode, Dynamic, Engine, EvalAltResult, EvalContext, Position, Scope, AST};
+use serde_json::{json, Value};
+
+use std::{
+    convert::TryFrom,
+    env,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::Path,
+    process::exit,
+    sync::{
+        mpsc::{channel, Receiver, TryRecvError},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+/// The only thread of a Rhai script.
+const THREAD_ID: i64 = 1;
+
+/// Variables reference of the local variables scope.
+const LOCALS_REFERENCE: usize = 1;
+
+/// Maximum length of the text of a value.
+const MAX_VALUE_LEN: usize = 200;
+
+/// Maximum size of a DAP message, in bytes.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Read one DAP message from the input stream.
+///
+/// Returns `None` when the input stream is closed.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    let mut line = String::new();
+
+    // Read headers
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(len) = content_length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        ));
+    };
+
+    if len > MAX_MESSAGE_SIZE {
+        // Skip the message body to stay in sync with the input stream
+        io::copy(&mut input.by_ref().take(len as u64), &mut io::sink())?;
+
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message too large ({len} bytes, maximum is {MAX_MESSAGE_SIZE} bytes)"),
+        ));
+    }
+
+    let mut body = vec![0; len];
+    input.read_exact(&mut body)?;
+
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Write one DAP message to the output stream.
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+/// Canonicalize a file path, falling back to the original path.
+fn canonical_path(path: &str) -> String {
+    Path::new(path)
+        .canonicalize()
+        .map_or_else(|_| path.to_string(), |p| p.to_string_lossy().into_owned())
+}
+
+/// Get the text of a value, truncated to [`MAX_VALUE_LEN`] characters.
+fn value_text(value: &Dynamic) -> String {
+    let text = format!("{value:?}");
+
+    match text.char_indices().nth(MAX_VALUE_LEN) {
+        Some((n, ..)) => format!("{}...", &text[..n]),
+        None => text,
+    }
+}
+
+/// Get the child items of an array or object map value.
+fn children(value: &Dynamic) -> Vec<(String, Dynamic)> {
+    #[cfg(not(feature = "no_index"))]
+    if let Some(array) = value.read_lock::<rhai::Array>() {
+        return array
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (format!("[{i}]"), item.clone()))
+            .collect();
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    if let Some(map) = value.read_lock::<rhai::Map>() {
+        return map
+            .iter()
+            .map(|(key, item)| (key.to_string(), item.clone()))
+            .collect();
+    }
+
+    let _ = value;
+    Vec::new()
+}
+
+/// Add the condition, hit condition and log message of a DAP break-point to a [`BreakPoint`].
+///
+/// Unsupported hit conditions are reported in the break-point's response.
+fn with_options(mut bp: BreakPoint, options: &Value, response: &mut Value) -> BreakPoint {
+    if let Some(condition) = options["condition"].as_str().map(str::trim) {
+        if !condition.is_empty() {
+            bp = bp.with_condition(condition);
+        }
+    }
+
+    if let Some(hit_condition) = options["hitCondition"].as_str().map(str::trim) {
+        // Only "N", ">= N" and "> N" are supported
+        let count = match hit_condition.strip_prefix(">=") {
+            Some(n) => n.trim().parse().ok(),
+            None => match hit_condition.strip_prefix('>') {
+                Some(n) => n.trim().parse().ok().map(|n: usize| n + 1),
+                None => hit_condition.parse().ok(),
+            },
+        };
+
+        match count {
+            Some(n) => bp = bp.with_hit_count(n),
+            None => {
+                response["verified"] = json!(false);
+                response["message"] = json!(format!("Unsupported hit condition: {hit_condition}"));
+            }
+        }
+    }
+
+    // Expressions in log messages are enclosed in braces
+    if let Some(message) = options["logMessage"].as_str() {
+        bp = bp.with_log_message(message.replace('{', "${"));
+    }
+
+    bp
+}
+
+/// Why the script is stopped.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum StopReason {
+    Entry,
+    Step,
+    Pause,
+    BreakPoint(usize),
+}
+
+/// A debugging session.
+struct Session {
+    /// Output stream.
+    output: Box<dyn Write + Send>,
+    /// Incoming requests.
+    requests: Receiver<Value>,
+    /// Sequence number of the next message.
+    seq: i64,
+    /// Are lines 1-based in the client?
+    lines_start_at_1: bool,
+    /// Are columns 1-based in the client?
+    columns_start_at_1: bool,
+    /// Break-points set by `setBreakpoints`.
+    line_break_points: Vec<BreakPoint>,
+    /// Break-points set by `setFunctionBreakpoints`.
+    fn_break_points: Vec<BreakPoint>,
+    /// Stop at the beginning of the script?
+    stop_on_entry: bool,
+    /// Is the script running freely (i.e. not stepping)?
+    running: bool,
+    /// Has a `pause` request been received?
+    pause_requested: bool,
+    /// Command used to resume the script after the last stop.
+    command: DebuggerCommand,
+    /// Has the client disconnected?
+    disconnected: bool,
+    /// Line (and source) of the last stop, used to avoid stopping at the same line repeatedly.
+    last_stop: Option<(Option<String>, usize)>,
+    /// Values exposed via variables references while stopped.
+    handles: Vec<Dynamic>,
+}
+
+impl Session {
+    /// Create a new [`Session`].
+    fn new(output: Box<dyn Write + Send>, requests: Receiver<Value>) -> Self {
+        Self {
+            output,
+            requests,
+            seq: 1,
+            lines_start_at_1: true,
+            columns_start_at_1: true,
+            line_break_points: Vec::new(),
+            fn_break_points: Vec::new(),
+            stop_on_entry: false,
+            running: false,
+            pause_requested: false,
+            command: DebuggerCommand::Continue,
+            disconnected: false,
+            last_stop: None,
+            handles: Vec::new(),
+        }
+    }
+    /// Send a message to the client.
+    fn send(&mut self, mut message: Value) {
+        message["seq"] = json!(self.seq);
+        self.seq += 1;
+
+        if let Err(err) = write_message(&mut self.output, &message) {
+            eprintln!("Error writing DAP message: {err}");
+            exit(1);
+        }
+    }
+    /// Send a successful response to a request.
+    fn respond(&mut self, request: &Value, body: Value) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": true,
+            "command": request["command"],
+            "body": body,
+        }));
+    }
+    /// Send an error response to a request.
+    fn respond_error(&mut self, request: &Value, message: impl Into<String>) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "success": false,
+            "command": request["command"],
+            "message": message.into(),
+        }));
+    }
+    /// Send an event.
+    fn event(&mut self, event: &str, body: Value) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+    /// Send an `output` event.
+    fn output(&mut self, category: &str, text: impl Into<String>) {
+        self.event(
+            "output",
+            json!({ "category": category, "output": text.into() }),
+        );
+    }
+    /// Get all break-points, with line break-points first.
+    fn break_points(&self) -> Vec<BreakPoint> {
+        self.line_break_points
+            .iter()
+            .chain(&self.fn_break_points)
+            .cloned()
+            .collect()
+    }
+    /// Convert a Rhai line number into a client line number.
+    fn to_client_line(&self, line: usize) -> usize {
+        if self.lines_start_at_1 {
+            line
+        } else {
+            line.saturating_sub(1)
+        }
+    }
+    /// Convert a Rhai column number into a client column number.
+    fn to_client_column(&self, column: usize) -> usize {
+        if self.columns_start_at_1 {
+            column
+        } else {
+            column.saturating_sub(1)
+        }
+    }
+    /// Handle requests that are valid at any time.
+    fn handle_common(&mut self, request: &Value) {
+        let args = &request["arguments"];
+
+        match request["command"].as_str().unwrap_or_default() {
+            "initialize" => {
+                self.lines_start_at_1 = args["linesStartAt1"].as_bool().unwrap_or(true);
+                self.columns_start_at_1 = args["columnsStartAt1"].as_bool().unwrap_or(true);
+
+                self.respond(
+                    request,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsFunctionBreakpoints": true,
+                        "supportsConditionalBreakpoints": true,
+                        "supportsHitConditionalBreakpoints": true,
+                        "supportsLogPoints": true,
+                        "supportsEvaluateForHovers": true,
+                        "supportsSteppingGranularity": true,
+                        "supportsTerminateRequest": true,
+                    }),
+                );
+                self.event("initialized", json!({}));
+            }
+            "threads" => self.respond(
+                request,
+                json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+            ),
+            "setBreakpoints" => {
+                let source = args["source"]["path"].as_str().map(canonical_path);
+                let line_base = usize::from(!self.lines_start_at_1);
+                let column_base = usize::from(!self.columns_start_at_1);
+
+                let list = args["breakpoints"]
+                    .as_array()
+                    .map_or(&[][..], Vec::as_slice);
+                let mut break_points = Vec::with_capacity(list.len());
+
+                self.line_break_points.clear();
+
+                for (i, options) in list.iter().enumerate() {
+                    let line = options["line"].as_u64().unwrap_or(0) as usize + line_base;
+                    let column = options["column"]
+                        .as_u64()
+                        .map_or(0, |c| c as usize + column_base);
+
+                    let mut response = json!({
+                        "id": i,
+                        "verified": cfg!(not(feature = "no_position")),
+                        "line": self.to_client_line(line),
+                    });
+
+                    #[cfg(not(feature = "no_position"))]
+                    {
+                        let bp = BreakPoint::AtPosition {
+                            source: source.as_deref().map(Into::into),
+                            pos: Position::new(
+                                u16::try_from(line).unwrap_or(0),
+                                u16::try_from(column).unwrap_or(0),
+                            ),
+                            enabled: true,
+                        };
+                        self.line_break_points
+                            .push(with_options(bp, options, &mut response));
+                    }
+
+                    let _ = column;
+                    break_points.push(response);
+                }
+
+                let _ = source;
+
+                self.respond(request, json!({ "breakpoints": break_points }));
+            }
+            "setFunctionBreakpoints" => {
+                let list = args["breakpoints"]
+                    .as_array()
+                    .map_or(&[][..], Vec::as_slice);
+                let base = self.line_break_points.len();
+                let mut break_points = Vec::with_capacity(list.len());
+
+                self.fn_break_points.clear();
+
+                for (i, options) in list.iter().enumerate() {
+                    let mut response = json!({ "id": base + i, "verified": true });
+
+                    let bp = BreakPoint::AtFunctionName {
+                        name: options["name"].as_str().unwrap_or_default().trim().into(),
+                        enabled: true,
+                    };
+                    self.fn_break_points
+                        .push(with_options(bp, options, &mut response));
+
+                    break_points.push(response);
+                }
+
+                self.respond(request, json!({ "breakpoints": break_points }));
+            }
+            "setExceptionBreakpoints" => self.respond(request, json!({})),
+            "disconnect" | "terminate" => {
+                self.disconnected = true;
+                self.respond(request, json!({}));
+            }
+            command => self.respond_error(request, format!("Unsupported request: {command}")),
+        }
+    }
+    /// Create a DAP variable for a value.
+    fn variable(&mut self, name: &str, value: &Dynamic) -> Value {
+        let reference = if children(value).is_empty() {
+            0
+        } else {
+            self.handles.push(value.clone());
+            self.handles.len() + LOCALS_REFERENCE
+        };
+
+        json!({
+            "name": name,
+            "value": value_text(value),
+            "type": value.type_name(),
+            "variablesReference": reference,
+        })
+    }
+    /// Build the stack trace at the current position.
+    fn stack_trace(&self, context: &EvalContext, source: Option<&str>, pos: Position) -> Value {
+        let call_stack = context.global_runtime_state().debugger().call_stack();
+
+        let make_source = |source: Option<&str>| match source {
+            Some(path) if Path::new(path).is_file() => json!({
+                "name": Path::new(path).file_name().map(|s| s.to_string_lossy()),
+                "path": path,
+            }),
+            Some(name) => json!({ "name": name }),
+            None => Value::Null,
+        };
+
+        let make_frame = |id: usize, name: &str, source: Option<&str>, pos: Position| {
+            json!({
+                "id": id,
+                "name": name,
+                "source": make_source(source),
+                "line": self.to_client_line(pos.line().unwrap_or(0)),
+                "column": self.to_client_column(pos.position().unwrap_or(0)),
+            })
+        };
+
+        let mut frames = Vec::with_capacity(call_stack.len() + 1);
+        let mut pos = pos;
+        let mut source = source;
+
+        for frame in call_stack.iter().rev() {
+            frames.push(make_frame(frames.len(), &frame.fn_name, source, pos));
+            pos = frame.pos;
+            source = frame.source.as_deref().or(source);
+        }
+
+        frames.push(make_frame(frames.len(), "<main>", source, pos));
+
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+    /// Handle requests that arrive while the script is running.
+    ///
+    /// Returns an error if the script should be terminated.
+    fn poll(&mut self, context: &mut EvalContext, pos: Position) -> Result<(), Box<EvalAltResult>> {
+        loop {
+            let request = match self.requests.try_recv() {
+                Ok(request) => request,
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => {
+                    self.disconnected = true;
+                    return Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, pos).into());
+                }
+            };
+
+            match request["command"].as_str().unwrap_or_default() {
+                "pause" => {
+                    self.pause_requested = true;
+                    self.respond(&request, json!({}));
+                }
+                "continue" => self.respond(&request, json!({ "allThreadsContinued": true })),
+                "next" | "stepIn" | "stepOut" | "stackTrace" | "scopes" | "variables"
+                | "evaluate" => self.respond_error(&request, "Script is running"),
+                _ => {
+                    self.handle_common(&request);
+
+                    *context
+                        .global_runtime_state_mut()
+                        .debugger_mut()
+                        .break_points_mut() = self.break_points();
+
+                    if self.disconnected {
+                        return Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, pos).into());
+                    }
+                }
+            }
+        }
+    }
+    /// Main callback for debugging.
+    fn debug(
+        &mut self,
+        mut context: EvalContext,
+        event: DebuggerEvent,
+        node: ASTNode,
+        source: Option<&str>,
+        pos: Position,
+    ) -> Result<DebuggerCommand, Box<EvalAltResult>> {
+        let line = pos.line().unwrap_or(0);
+
+        // Forget the last stop when moving to another line
+        if let Some((ref src, n)) = self.last_stop {
+            if n != line || src.as_deref() != source {
+                self.last_stop = None;
+            }
+        }
+
+        // Keep stepping through the script while running, in order to poll for requests
+        let resume = |running: bool| {
+            Ok(if running {
+                DebuggerCommand::StepInto
+            } else {
+                DebuggerCommand::Continue
+            })
+        };
+
+        let reason = match event {
+            DebuggerEvent::Start if self.stop_on_entry => StopReason::Entry,
+            DebuggerEvent::Start => {
+                self.running = true;
+                return resume(true);
+            }
+            // Break-points within the statement of the last stop are ignored
+            DebuggerEvent::BreakPoint(n) if self.last_stop.is_none() || node.is_stmt() => {
+                StopReason::BreakPoint(n)
+            }
+            DebuggerEvent::Step
+            | DebuggerEvent::BreakPoint(..)
+            | DebuggerEvent::FunctionExitWithValue(..)
+            | DebuggerEvent::FunctionExitWithError(..)
+                if self.running =>
+            {
+                self.poll(&mut context, pos)?;
+
+                if self.pause_requested {
+                    StopReason::Pause
+                } else {
+                    return resume(true);
+                }
+            }
+            DebuggerEvent::Step
+            | DebuggerEvent::FunctionExitWithValue(..)
+            | DebuggerEvent::FunctionExitWithError(..) => StopReason::Step,
+            // Break-points take the place of steps, so keep stepping
+            DebuggerEvent::BreakPoint(..) if self.command == DebuggerCommand::StepInto => {
+                StopReason::Step
+            }
+            DebuggerEvent::BreakPoint(..) => return Ok(self.command),
+            _ => return resume(false),
+        };
+
+        self.stop(context, reason, source, pos)
+    }
+    /// Stop the script and handle requests until it is resumed.
+    fn stop(
+        &mut self,
+        mut context: EvalContext,
+        reason: StopReason,
+        source: Option<&str>,
+        pos: Position,
+    ) -> Result<DebuggerCommand, Box<EvalAltResult>> {
+        self.running = false;
+        self.pause_requested = false;
+        self.last_stop = Some((source.map(Into::into), pos.line().unwrap_or(0)));
+
+        let mut body = json!({ "threadId": THREAD_ID, "allThreadsStopped": true });
+
+        body["reason"] = json!(match reason {
+            StopReason::Entry => "entry",
+            StopReason::Step => "step",
+            StopReason::Pause => "pause",
+            StopReason::BreakPoint(n) if n < self.line_break_points.len() => "breakpoint",
+            StopReason::BreakPoint(..) => "function breakpoint",
+        });
+        if let StopReason::BreakPoint(n) = reason {
+            body["hitBreakpointIds"] = json!([n]);
+        }
+
+        self.event("stopped", body);
+
+        loop {
+            let Ok(request) = self.requests.recv() else {
+                self.disconnected = true;
+                return Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, pos).into());
+            };
+
+            let args = &request["arguments"];
+
+            let command = match request["command"].as_str().unwrap_or_default() {
+                "continue" => {
+                    self.respond(&request, json!({ "allThreadsContinued": true }));
+                    self.running = true;
+                    DebuggerCommand::StepInto
+                }
+                "next" => {
+                    self.respond(&request, json!({}));
+                    match args["granularity"].as_str() {
+                        Some("instruction") => DebuggerCommand::StepOver,
+                        _ => DebuggerCommand::Next,
+                    }
+                }
+                "stepIn" => {
+                    self.respond(&request, json!({}));
+                    DebuggerCommand::StepInto
+                }
+                "stepOut" => {
+                    self.respond(&request, json!({}));
+                    DebuggerCommand::FunctionExit
+                }
+                "pause" => {
+                    self.respond(&request, json!({}));
+                    continue;
+                }
+                "stackTrace" => {
+                    let body = self.stack_trace(&context, source, pos);
+                    self.respond(&request, body);
+                    continue;
+                }
+                "scopes" => {
+                    let scopes = json!([{
+                        "name": "Locals",
+                        "presentationHint": "locals",
+                        "variablesReference": LOCALS_REFERENCE,
+                        "expensive": false,
+                    }]);
+                    self.respond(&request, json!({ "scopes": scopes }));
+                    continue;
+                }
+                "variables" => {
+                    let reference = args["variablesReference"].as_u64().unwrap_or(0) as usize;
+
+                    let items: Vec<_> = if reference == LOCALS_REFERENCE {
+                        let scope = context.scope().clone_visible();
+                        let mut items: Vec<_> = scope
+                            .iter()
+                            .map(|(name, .., value)| (name.to_string(), value))
+                            .collect();
+                        if let Some(this) = context.this_ptr() {
+                            items.push(("this".into(), this.clone()));
+                        }
+                        items
+                    } else {
+                        match self
+                            .handles
+                            .get(reference.wrapping_sub(LOCALS_REFERENCE + 1))
+                        {
+                            Some(value) => children(value),
+                            None => Vec::new(),
+                        }
+                    };
+
+                    let variables: Vec<_> = items
+                        .iter()
+                        .map(|(name, value)| self.variable(name, value))
+                        .collect();
+
+                    self.respond(&request, json!({ "variables": variables }));
+                    continue;
+                }
+                "evaluate" => {
+                    let expr = args["expression"].as_str().unwrap_or_default();
+
+                    // Evaluate on a copy of the scope with a separate engine
+                    let mut engine = Engine::new();
+                    engine.on_print(|_| ()).on_debug(|_, _, _| ());
+                    let mut scope: Scope = context.scope().clone_visible();
+
+                    match engine.eval_with_scope::<Dynamic>(&mut scope, expr) {
+                        Ok(value) => {
+                            let variable = self.variable(expr, &value);
+                            self.respond(
+                                &request,
+                                json!({
+                                    "result": variable["value"],
+                                    "type": variable["type"],
+                                    "variablesReference": variable["variablesReference"],
+                                }),
+                            );
+                        }
+                        Err(err) => self.respond_error(&request, err.to_string()),
+                    }
+                    continue;
+                }
+                _ => {
+                    self.handle_common(&request);
+
+                    *context
+                        .global_runtime_state_mut()
+                        .debugger_mut()
+                        .break_points_mut() = self.break_points();
+
+                    if self.disconnected {
+                        return Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, pos).into());
+                    }
+                    continue;
+                }
+            };
+
+            // Values are only valid while stopped
+            self.handles.clear();
+
+            self.command = command;
+            break Ok(command);
+        }
+    }
+}
+
+/// Handle configuration requests until a script is launched and the configuration is done.
+///
+/// Returns `None` if the client disconnects before that.
+fn configure(session: &mut Session, engine: &Engine) -> Option<AST> {
+    let mut launched = None;
+    let mut configured = false;
+
+    while launched.is_none() || !configured {
+        let request = session.requests.recv().ok()?;
+        let args = &request["arguments"];
+
+        match request["command"].as_str().unwrap_or_default() {
+            "launch" => {
+                let Some(program) = args["program"].as_str() else {
+                    session.respond_error(&request, "Missing `program` argument");
+                    continue;
+                };
+
+                match engine.compile_file(canonical_path(program).into()) {
+                    Ok(ast) => {
+                        session.stop_on_entry = args["stopOnEntry"].as_bool().unwrap_or(false);
+                        session.respond(&request, json!({}));
+                        launched = Some(ast);
+                    }
+                    Err(err) => session.respond_error(&request, err.to_string()),
+                }
+            }
+            "configurationDone" => {
+                session.respond(&request, json!({}));
+                configured = true;
+            }
+            _ => {
+                session.handle_common(&request);
+
+                if session.disconnected {
+                    return None;
+                }
+            }
+        }
+    }
+
+    launched
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+
+    // Set up the transport
+    let (input, output): (Box<dyn BufRead + Send>, Box<dyn Write + Send>) = match args.next() {
+        None => (
+            Box::new(BufReader::new(io::stdin())),
+            Box::new(io::stdout()),
+        ),
+        Some(arg) if arg == "--port" => {
+            let port = args.next().and_then(|p| p.parse::<u16>().ok());
+            let Some(port) = port else {
+                eprintln!("Invalid port number");
+                exit(2);
+            };
+
+            let listener = TcpListener::bind(("127.0.0.1", port)).unwrap_or_else(|err| {
+                eprintln!("Cannot listen on port {port}: {err}");
+                exit(1);
+            });
+            eprintln!("Listening on port {port}...");
+
+            let (stream, ..) = listener.accept().unwrap_or_else(|err| {
+                eprintln!("Cannot accept connection: {err}");
+                exit(1);
+            });
+
+            (
+                Box::new(BufReader::new(stream.try_clone().unwrap())),
+                Box::new(stream),
+            )
+        }
+        Some(..) => {
+            eprintln!("Usage: rhai-dap [--port <port>]");
+            exit(2);
+        }
+    };
+
+    // Read requests in the background so that they can be polled while the script runs
+    let (tx, rx) = channel();
+
+    thread::spawn(move || {
+        let mut input = input;
+
+        loop {
+            match read_message(&mut input) {
+                Ok(Some(message)) => {
+                    if tx.send(message).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(err) => eprintln!("Error reading DAP message: {err}"),
+            }
+        }
+    });
+
+    // Callbacks must be `Send + Sync` under the `sync` feature
+    #[allow(clippy::arc_with_non_send_sync)]
+    let session = Arc::new(Mutex::new(Session::new(output, rx)));
+
+    // Initialize scripting engine
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "no_optimize"))]
+    engine.set_optimization_level(rhai::OptimizationLevel::None);
+
+    let Some(ast) = configure(&mut session.lock().unwrap(), &engine) else {
+        return;
+    };
+
+    // Redirect script output to the client
+    let s = session.clone();
+    engine.on_print(move |text| s.lock().unwrap().output("stdout", format!("{text}\n")));
+
+    let s = session.clone();
+    engine.on_debug(move |text, source, pos| {
+        let text = match source {
+            Some(source) => format!("{source} @ {pos:?} | {text}\n"),
+            None if pos.is_none() => format!("{text}\n"),
+            None => format!("{pos:?} | {text}\n"),
+        };
+        s.lock().unwrap().output("console", text);
+    });
+
+    // Hook up debugger
+    let s = session.clone();
+    let s2 = session.clone();
+
+    #[allow(deprecated)]
+    engine.register_debugger(
+        move |_, mut debugger| {
+            *debugger.break_points_mut() = s.lock().unwrap().break_points();
+            debugger
+        },
+        move |context, event, node, source, pos| {
+            s2.lock().unwrap().debug(context, event, node, source, pos)
+        },
+    );
+
+    let result = engine.run_ast(&ast);
+
+    let mut session = session.lock().unwrap();
+
+    let exit_code = match result {
+        Ok(()) => 0,
+        Err(err) if matches!(*err, EvalAltResult::ErrorTerminated(..)) => 1,
+        Err(err) => {
+            session.output("stderr", format!("{err}\n"));
+            1
+        }
+    };
+
+    if session.disconnected {
+        return;
+    }
+
+    session.event("exited", json!({ "exitCode": exit_code }));
+    session.event("terminated", json!({}));
+
+    // Wait for the client to disconnect
+    while let Ok(request) = session.requests.recv() {
+        match request["command"].as_str().unwrap_or_default() {
+            "disconnect" | "terminate" => {
+                session.respond(&request, json!({}));
+                break;
+            }
+            "initialize"
+            | "threads"
+            | "setBreakpoints"
+            | "setFunctionBreakpoints"
+            | "setExceptionBreakpoints" => session.handle_common(&request),
+            _ => session.respond_error(&request, "Script has terminated"),
+        }
+    }
+}
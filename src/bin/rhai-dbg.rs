@@ -148,6 +148,15 @@ fn print_debug_help() {
     println!(
         "break/b <func> <#args> => set a new break-point for a function call with #args arguments"
     );
+    println!(
+        "break/b <bp> if <expr> => set a new break-point that breaks only when <expr> is true"
+    );
+    println!("break/b <bp> hits <#>  => set a new break-point that breaks only from the #-th hit");
+    println!("log <bp> <message...>  => set a new log-point that prints an interpolated message");
+    println!("                          (<bp> is a line#, a .<prop> or a <func>)");
+    println!("watch <variable>       => set a new watch-point for assignments to a variable");
+    #[cfg(not(feature = "no_object"))]
+    println!("watch .<prop>          => set a new watch-point for assignments to a property");
     println!("throw                  => throw a runtime exception");
     println!("throw <message...>     => throw an exception with string data");
     println!("throw <#>              => throw an exception with numeric data");
@@ -160,6 +169,89 @@ fn print_debug_help() {
     println!();
 }
 
+/// Get the rest of a command after skipping a number of words.
+fn skip_words(input: &str, n: usize) -> &str {
+    let mut text = input.trim();
+
+    for _ in 0..n {
+        text = text
+            .split_once(char::is_whitespace)
+            .map_or("", |(.., rest)| rest.trim_start());
+    }
+
+    text
+}
+
+/// Create a break-point from a line number, a property (`.prop`) or a function name.
+fn make_break_point(
+    param: &str,
+    source: Option<&str>,
+    lines: &[String],
+) -> Result<BreakPoint, String> {
+    // Property name
+    #[cfg(not(feature = "no_object"))]
+    if param.starts_with('.') && param.len() > 1 {
+        return Ok(BreakPoint::AtProperty {
+            name: param[1..].into(),
+            enabled: true,
+        });
+    }
+
+    // Numeric parameter
+    #[cfg(not(feature = "no_position"))]
+    if let Ok(n) = param.parse::<usize>() {
+        let range = if source.is_none() {
+            1..=lines.len()
+        } else {
+            1..=(u16::MAX as usize)
+        };
+
+        if !range.contains(&n) {
+            return Err(format!("Invalid line number: '{n}'"));
+        }
+
+        return Ok(BreakPoint::AtPosition {
+            source: source.map(|s| s.into()),
+            pos: Position::new(n as u16, 0),
+            enabled: true,
+        });
+    }
+
+    let _ = (source, lines);
+
+    // Function name parameter
+    Ok(BreakPoint::AtFunctionName {
+        name: param.trim().into(),
+        enabled: true,
+    })
+}
+
+/// Add options (`hits <#>` and/or `if <condition>`) to a break-point.
+fn add_break_point_options(mut bp: BreakPoint, mut options: &str) -> Result<BreakPoint, String> {
+    while !options.is_empty() {
+        match options.split_once(char::is_whitespace) {
+            Some(("hits", rest)) => {
+                let count = skip_words(rest, 0)
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default();
+                match count.parse::<usize>() {
+                    Ok(n) if n > 0 => bp = bp.with_hit_count(n),
+                    _ => return Err(format!("Invalid hit count: '{count}'")),
+                }
+                options = skip_words(rest, 1);
+            }
+            Some(("if", condition)) if !condition.trim().is_empty() => {
+                bp = bp.with_condition(condition.trim());
+                options = "";
+            }
+            _ => return Err(format!("Invalid break-point option: '{options}'")),
+        }
+    }
+
+    Ok(bp)
+}
+
 // Load script to debug.
 fn load_script(engine: &Engine) -> (rhai::AST, String) {
     if let Some(filename) = env::args().nth(1) {
@@ -247,7 +339,13 @@ fn debug_callback(
         DebuggerEvent::End => println!("\x1b[31m! Script end\x1b[39m"),
         DebuggerEvent::Step => (),
         DebuggerEvent::BreakPoint(n) => {
-            match context.global_runtime_state().debugger().break_points()[n] {
+            let bp = match context.global_runtime_state().debugger().break_points()[n] {
+                BreakPoint::Conditional {
+                    ref break_point, ..
+                } => break_point,
+                ref bp => bp,
+            };
+            match bp {
                 #[cfg(not(feature = "no_position"))]
                 BreakPoint::AtPosition { .. } => (),
                 BreakPoint::AtFunctionName { ref name, .. }
@@ -261,6 +359,24 @@ fn debug_callback(
                 _ => unreachable!(),
             }
         }
+        DebuggerEvent::WatchPoint(n, old_value, new_value) => {
+            let bp = match context.global_runtime_state().debugger().break_points()[n] {
+                BreakPoint::Conditional {
+                    ref break_point, ..
+                } => break_point,
+                ref bp => bp,
+            };
+            match bp {
+                BreakPoint::OnVariableChange { ref name, .. } => {
+                    println!("! Variable {name} changed: {old_value:?} => {new_value:?}")
+                }
+                #[cfg(not(feature = "no_object"))]
+                BreakPoint::OnPropertyWrite { ref name, .. } => {
+                    println!("! Property {name} changed: {old_value:?} => {new_value:?}")
+                }
+                _ => unreachable!(),
+            }
+        }
         DebuggerEvent::FunctionExitWithValue(r) => {
             println!(
                 "! Return from function call '{}' => {:?}",
@@ -479,6 +595,33 @@ fn debug_callback(
                         .clear();
                     println!("All break-points deleted.");
                 }
+                ["break" | "b", param, "if" | "hits", ..] => {
+                    match make_break_point(param, source, lines)
+                        .and_then(|bp| add_break_point_options(bp, skip_words(&input, 2)))
+                    {
+                        Ok(bp) => {
+                            println!("Break-point added {bp}");
+                            context
+                                .global_runtime_state_mut()
+                                .debugger_mut()
+                                .break_points_mut()
+                                .push(bp);
+                        }
+                        Err(err) => eprintln!("\x1b[31m{err}\x1b[39m"),
+                    }
+                }
+                ["log", param, _, ..] => match make_break_point(param, source, lines) {
+                    Ok(bp) => {
+                        let bp = bp.with_log_message(skip_words(&input, 2));
+                        println!("Log-point added {bp}");
+                        context
+                            .global_runtime_state_mut()
+                            .debugger_mut()
+                            .break_points_mut()
+                            .push(bp);
+                    }
+                    Err(err) => eprintln!("\x1b[31m{err}\x1b[39m"),
+                },
                 ["break" | "b", fn_name, args] => {
                     if let Ok(args) = args.parse::<usize>() {
                         let bp = rhai::debugger::BreakPoint::AtFunctionCall {
@@ -563,6 +706,31 @@ fn debug_callback(
                         .break_points_mut()
                         .push(bp);
                 }
+                ["watch", param, ..] => {
+                    let bp = match param.strip_prefix('.') {
+                        #[cfg(not(feature = "no_object"))]
+                        Some(name) if !name.is_empty() => BreakPoint::OnPropertyWrite {
+                            name: name.into(),
+                            enabled: true,
+                        },
+                        _ => BreakPoint::OnVariableChange {
+                            name: param.trim().into(),
+                            enabled: true,
+                        },
+                    };
+
+                    match add_break_point_options(bp, skip_words(&input, 2)) {
+                        Ok(bp) => {
+                            println!("Watch-point added for {bp}");
+                            context
+                                .global_runtime_state_mut()
+                                .debugger_mut()
+                                .break_points_mut()
+                                .push(bp);
+                        }
+                        Err(err) => eprintln!("\x1b[31m{err}\x1b[39m"),
+                    }
+                }
                 ["throw"] => break Err(EvalAltResult::ErrorRuntime(Dynamic::UNIT, pos).into()),
                 ["throw", num] if num.trim().parse::<INT>().is_ok() => {
                     let value = num.trim().parse::<INT>().unwrap().into();
@@ -0,0 +1,152 @@
+#![cfg_attr(feature = "no_position", allow(unused_imports, dead_code))]
+
+use rhai::Engine;
+
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    process::exit,
+};
+
+#[cfg(not(feature = "no_position"))]
+use rhai::FormatOptions;
+
+/// Print help text.
+fn print_help() {
+    println!("Usage: rhai-fmt [options] [files...]");
+    println!();
+    println!("Formats Rhai scripts in place.");
+    println!("Without files, reads a script from stdin and writes the formatted script to stdout.");
+    println!();
+    println!("Options:");
+    println!(
+        "  --check      don't write anything, exit with code 1 if any script is not formatted"
+    );
+    println!("  --indent N   number of spaces per indentation level (default 4)");
+    println!("  --tabs       indent with tabs");
+    println!("  --width N    maximum line width (default 100)");
+    println!("  --help       print this help text");
+}
+
+/// Parse a numeric command-line argument.
+fn parse_number(name: &str, value: Option<String>) -> usize {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(n)) => n,
+        _ => {
+            eprintln!("Invalid value for {name}");
+            exit(2);
+        }
+    }
+}
+
+#[cfg(feature = "no_position")]
+fn main() {
+    eprintln!("rhai-fmt is not available under `no_position`.");
+    exit(2);
+}
+
+#[cfg(not(feature = "no_position"))]
+fn main() {
+    let mut options = FormatOptions::new();
+    let mut check = false;
+    let mut files = Vec::new();
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--tabs" => options.use_tabs = true,
+            "--indent" => options.indent_size = parse_number("--indent", args.next()),
+            "--width" => options.max_width = parse_number("--width", args.next()),
+            "-h" | "--help" => {
+                print_help();
+                return;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("Unknown option: {arg}");
+                print_help();
+                exit(2);
+            }
+            _ => files.push(arg),
+        }
+    }
+
+    let engine = Engine::new();
+
+    if files.is_empty() {
+        let mut contents = String::new();
+
+        if let Err(err) = io::stdin().read_to_string(&mut contents) {
+            eprintln!("Error reading stdin\n{err}");
+            exit(2);
+        }
+
+        match engine.format_script(&contents, options) {
+            Ok(formatted) if check => {
+                if formatted != contents {
+                    eprintln!("<stdin> is not formatted");
+                    exit(1);
+                }
+            }
+            Ok(formatted) => {
+                io::stdout().write_all(formatted.as_bytes()).unwrap();
+            }
+            Err(err) => {
+                eprintln!("<stdin>: {err}");
+                exit(2);
+            }
+        }
+
+        return;
+    }
+
+    let mut unformatted = false;
+    let mut failed = false;
+
+    for filename in files {
+        let contents = match fs::read_to_string(&filename) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("Error reading script file: {filename}\n{err}");
+                failed = true;
+                continue;
+            }
+        };
+
+        // Keep the shebang line as is
+        let (shebang, script) = if contents.starts_with("#!") {
+            contents.split_at(contents.find('\n').map_or(contents.len(), |n| n + 1))
+        } else {
+            ("", &*contents)
+        };
+
+        let formatted = match engine.format_script(script, options) {
+            Ok(formatted) => format!("{shebang}{formatted}"),
+            Err(err) => {
+                eprintln!("{filename}: {err}");
+                failed = true;
+                continue;
+            }
+        };
+
+        if formatted == contents {
+            continue;
+        }
+
+        if check {
+            println!("{filename}");
+            unformatted = true;
+        } else if let Err(err) = fs::write(&filename, formatted) {
+            eprintln!("Error writing script file: {filename}\n{err}");
+            failed = true;
+        }
+    }
+
+    if failed {
+        exit(2);
+    }
+    if unformatted {
+        exit(1);
+    }
+}
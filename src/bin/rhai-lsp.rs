@@ -0,0 +1,610 @@
+use rhai::{ASTNode, Engine, Expr, ParseError, Position, Stmt, AST};
+use serde_json::{json, Map, Value};
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io::{self, BufRead, Read, Write},
+    process::exit,
+};
+
+/// LSP error code: method not found.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Maximum size of an LSP message, in bytes.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// LSP diagnostic severity: error.
+const SEVERITY_ERROR: i64 = 1;
+
+/// LSP completion item kinds.
+const KIND_FUNCTION: i64 = 3;
+const KIND_CLASS: i64 = 7;
+const KIND_MODULE: i64 = 9;
+const KIND_KEYWORD: i64 = 14;
+
+/// Keywords offered for completion.
+const KEYWORDS: &[&str] = &[
+    "let",
+    "const",
+    "if",
+    "else",
+    "switch",
+    "match",
+    "do",
+    "while",
+    "until",
+    "loop",
+    "for",
+    "in",
+    "break",
+    "continue",
+    "return",
+    "throw",
+    "try",
+    "catch",
+    "fn",
+    "private",
+    "import",
+    "export",
+    "as",
+    "true",
+    "false",
+    "this",
+    "global",
+    "Fn",
+    "call",
+    "curry",
+    "is_def_fn",
+    "is_def_var",
+    "type_of",
+    "print",
+    "debug",
+    "eval",
+];
+
+/// Read one LSP message from the input stream.
+///
+/// Returns `None` when the input stream is closed.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    let mut line = String::new();
+
+    // Read headers
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(len) = content_length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        ));
+    };
+
+    if len > MAX_MESSAGE_SIZE {
+        // Skip the message body to stay in sync with the input stream
+        io::copy(&mut input.by_ref().take(len as u64), &mut io::sink())?;
+
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message too large ({len} bytes, maximum is {MAX_MESSAGE_SIZE} bytes)"),
+        ));
+    }
+
+    let mut body = vec![0; len];
+    input.read_exact(&mut body)?;
+
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Write one LSP message to the output stream.
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+/// Convert a Rhai [`Position`] into an LSP position.
+///
+/// Rhai positions are 1-based and count characters, while LSP positions are 0-based and count
+/// UTF-16 code units.
+fn to_lsp_position(text: &str, pos: Position) -> Value {
+    let line = pos.line().unwrap_or(1) - 1;
+    let column = pos.position().unwrap_or(1).saturating_sub(1);
+
+    let character: usize = text.split('\n').nth(line).map_or(column, |s| {
+        s.chars().take(column).map(char::len_utf16).sum()
+    });
+
+    json!({ "line": line, "character": character })
+}
+
+/// Create an LSP range covering the identifier (or single character) at a Rhai [`Position`].
+fn to_lsp_range(text: &str, pos: Position, len: usize) -> Value {
+    let start = to_lsp_position(text, pos);
+    let mut end = start.clone();
+    end["character"] = json!(start["character"].as_u64().unwrap_or(0) as usize + len);
+    json!({ "start": start, "end": end })
+}
+
+/// Find the identifier at an LSP position, together with the Rhai [`Position`] of its start.
+fn word_at(text: &str, position: &Value) -> Option<(String, Position)> {
+    let line_no = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+    let line = text.split('\n').nth(line_no)?;
+
+    // Convert UTF-16 offset to characters
+    let chars: Vec<_> = line.chars().collect();
+    let mut offset = 0;
+    let mut index = chars.len();
+
+    for (i, ch) in chars.iter().enumerate() {
+        if offset >= character {
+            index = i;
+            break;
+        }
+        offset += ch.len_utf16();
+    }
+
+    let is_id_char = |ch: &char| ch.is_alphanumeric() || *ch == '_';
+
+    let start = chars[..index]
+        .iter()
+        .rposition(|ch| !is_id_char(ch))
+        .map_or(0, |i| i + 1);
+    let end = chars[index..]
+        .iter()
+        .position(|ch| !is_id_char(ch))
+        .map_or(chars.len(), |i| index + i);
+
+    if start >= end {
+        return None;
+    }
+
+    let pos = Position::new(
+        u16::try_from(line_no + 1).ok()?,
+        u16::try_from(start + 1).ok()?,
+    );
+
+    Some((chars[start..end].iter().collect(), pos))
+}
+
+/// Strip doc-comment leaders from a list of doc-comments.
+fn format_doc_comments<'a>(comments: impl IntoIterator<Item = &'a str>) -> String {
+    comments
+        .into_iter()
+        .flat_map(|comment| comment.lines())
+        .map(|line| {
+            let line = line.trim();
+            let line = line
+                .strip_prefix("///")
+                .or_else(|| line.strip_prefix("/**"))
+                .unwrap_or(line);
+            let line = line.strip_suffix("*/").unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A document opened in the editor.
+struct Document {
+    /// Text of the document.
+    text: String,
+    /// Compiled [`AST`], if the document has no syntax errors.
+    ast: Option<AST>,
+}
+
+/// The language server.
+struct Server {
+    /// Scripting engine used to parse documents.
+    engine: Engine,
+    /// Opened documents, keyed by URI.
+    documents: HashMap<String, Document>,
+    /// Has a `shutdown` request been received?
+    shutdown: bool,
+}
+
+impl Server {
+    /// Create a new [`Server`].
+    fn new() -> Self {
+        let mut engine = Engine::new();
+
+        // Keep the AST close to the source text
+        #[cfg(not(feature = "no_optimize"))]
+        engine.set_optimization_level(rhai::OptimizationLevel::None);
+
+        Self {
+            engine,
+            documents: HashMap::new(),
+            shutdown: false,
+        }
+    }
+
+    /// Handle a request, returning its result.
+    ///
+    /// Returns `Err` with an error code and message if the request fails.
+    fn handle_request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "completionProvider": { "triggerCharacters": [":"] },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                },
+                "serverInfo": {
+                    "name": "rhai-lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/completion" => Ok(self.completion(params)),
+            "textDocument/hover" => Ok(self.hover(params)),
+            "textDocument/definition" => Ok(self.definition(params)),
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {method}"))),
+        }
+    }
+
+    /// Handle a notification, returning other notifications to send back.
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        match method {
+            "textDocument/didOpen" => {
+                let doc = &params["textDocument"];
+                let uri = doc["uri"].as_str().unwrap_or_default();
+                let text = doc["text"].as_str().unwrap_or_default();
+                vec![self.update_document(uri, text.into())]
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+                // Full text synchronization - the last change contains the entire text
+                match params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    Some(text) => vec![self.update_document(uri, text.into())],
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+
+                // Clear diagnostics
+                vec![json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                })]
+            }
+            "exit" => exit(if self.shutdown { 0 } else { 1 }),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Re-compile a document, returning a notification with its diagnostics.
+    fn update_document(&mut self, uri: &str, text: String) -> Value {
+        let (ast, diagnostics) = match self.engine.compile(&text) {
+            Ok(ast) => (Some(ast), Vec::new()),
+            Err(ParseError(err, pos)) => {
+                let diagnostic = json!({
+                    "range": to_lsp_range(&text, pos, 1),
+                    "severity": SEVERITY_ERROR,
+                    "source": "rhai",
+                    "message": err.to_string(),
+                });
+                (None, vec![diagnostic])
+            }
+        };
+
+        self.documents
+            .insert(uri.to_string(), Document { text, ast });
+
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    /// Get the document and the identifier (with its position) at the position of a request.
+    fn document_and_word<'a>(
+        &'a self,
+        params: &'a Value,
+    ) -> Option<(&'a str, &'a Document, String, Position)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let doc = self.documents.get(uri)?;
+        let (word, pos) = word_at(&doc.text, &params["position"])?;
+        Some((uri, doc, word, pos))
+    }
+
+    /// Generate functions metadata, including functions defined in the document (if any).
+    fn metadata(&self, doc: Option<&Document>) -> Value {
+        let json = match doc.and_then(|doc| doc.ast.as_ref()) {
+            Some(ast) => self.engine.gen_fn_metadata_with_ast_to_json(ast, true),
+            None => self.engine.gen_fn_metadata_to_json(true),
+        };
+
+        json.ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Handle `textDocument/completion`.
+    fn completion(&self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let metadata = self.metadata(self.documents.get(uri));
+
+        let mut items = Map::new();
+
+        fn add_module(items: &mut Map<String, Value>, prefix: &str, module: &Value) {
+            for f in module["functions"].as_array().into_iter().flatten() {
+                let Some(name) = f["name"].as_str() else {
+                    continue;
+                };
+                // Skip operators, property accessors and anonymous functions
+                if !rhai::is_valid_function_name(name) || f["isAnonymous"] == true {
+                    continue;
+                }
+
+                let label = format!("{prefix}{name}");
+
+                if items.contains_key(&label) {
+                    continue;
+                }
+
+                let docs = format_doc_comments(
+                    f["docComments"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str),
+                );
+
+                let item = json!({
+                    "label": label,
+                    "kind": KIND_FUNCTION,
+                    "detail": f["signature"],
+                    "documentation": { "kind": "markdown", "value": docs },
+                });
+                items.insert(label, item);
+            }
+
+            for t in module["customTypes"].as_array().into_iter().flatten() {
+                let Some(name) = t["displayName"].as_str() else {
+                    continue;
+                };
+
+                let docs = format_doc_comments(
+                    t["docComments"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str),
+                );
+
+                let item = json!({
+                    "label": name,
+                    "kind": KIND_CLASS,
+                    "detail": t["typeName"],
+                    "documentation": { "kind": "markdown", "value": docs },
+                });
+                items.insert(name.to_string(), item);
+            }
+
+            for (name, sub_module) in module["modules"].as_object().into_iter().flatten() {
+                let label = format!("{prefix}{name}");
+                let item = json!({ "label": label, "kind": KIND_MODULE });
+                items.insert(label, item);
+
+                add_module(items, &format!("{prefix}{name}::"), sub_module);
+            }
+        }
+
+        add_module(&mut items, "", &metadata);
+
+        for keyword in KEYWORDS {
+            items
+                .entry(keyword.to_string())
+                .or_insert_with(|| json!({ "label": keyword, "kind": KIND_KEYWORD }));
+        }
+
+        Value::Array(items.into_iter().map(|(.., item)| item).collect())
+    }
+
+    /// Handle `textDocument/hover`.
+    fn hover(&self, params: &Value) -> Value {
+        let Some((_, doc, word, _)) = self.document_and_word(params) else {
+            return Value::Null;
+        };
+
+        let mut sections = Vec::new();
+
+        // Script-defined functions
+        #[cfg(not(feature = "no_function"))]
+        if let Some(ref ast) = doc.ast {
+            for f in ast.iter_functions().filter(|f| f.name == word) {
+                let mut section = format!("```rhai\n{f}\n```");
+                let docs = format_doc_comments(f.comments.iter().copied());
+                if !docs.is_empty() {
+                    section.push_str("\n\n");
+                    section.push_str(&docs);
+                }
+                sections.push(section);
+            }
+        }
+
+        // Registered functions
+        if sections.is_empty() {
+            let metadata = self.metadata(None);
+
+            for f in metadata["functions"].as_array().into_iter().flatten() {
+                if f["name"].as_str() != Some(word.as_str()) {
+                    continue;
+                }
+                let Some(signature) = f["signature"].as_str() else {
+                    continue;
+                };
+                let mut section = format!("```rust\n{signature}\n```");
+                let docs = format_doc_comments(
+                    f["docComments"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str),
+                );
+                if !docs.is_empty() {
+                    section.push_str("\n\n");
+                    section.push_str(&docs);
+                }
+                sections.push(section);
+            }
+        }
+
+        if sections.is_empty() {
+            return Value::Null;
+        }
+
+        json!({
+            "contents": { "kind": "markdown", "value": sections.join("\n\n---\n\n") },
+        })
+    }
+
+    /// Handle `textDocument/definition`.
+    ///
+    /// Script-defined functions are looked up in the compiled [`AST`]. For a function call, only
+    /// functions with the same number of parameters are returned, so overloaded functions are told
+    /// apart. Qualified calls (e.g. `foo::bar()`) refer to functions in other modules and are
+    /// not resolved.
+    #[cfg(not(feature = "no_function"))]
+    fn definition(&self, params: &Value) -> Value {
+        let Some((uri, doc, word, pos)) = self.document_and_word(params) else {
+            return Value::Null;
+        };
+        let Some(ref ast) = doc.ast else {
+            return Value::Null;
+        };
+
+        // Find the function call at the position, if any
+        let mut call = None;
+
+        ast.walk(&mut |path| {
+            let x = match path.last() {
+                Some(ASTNode::Expr(Expr::FnCall(x, p) | Expr::MethodCall(x, p)))
+                | Some(ASTNode::Stmt(Stmt::FnCall(x, p))) => (*p == pos).then_some(x),
+                _ => None,
+            };
+
+            match x {
+                Some(x) if x.name == word => {
+                    call = Some((x.is_qualified(), x.args.len()));
+                    false
+                }
+                _ => true,
+            }
+        });
+
+        let locations: Vec<_> = match call {
+            Some((true, ..)) => Vec::new(),
+            _ => ast
+                .iter_fn_def()
+                .filter(|f| f.name == word)
+                .filter(|f| call.map_or(true, |(.., n)| f.params.len() == n))
+                .map(|f| {
+                    let span = f.body.span();
+                    let mut end = to_lsp_position(&doc.text, span.end());
+                    end["character"] = json!(end["character"].as_u64().unwrap_or(0) + 1);
+                    let start = to_lsp_position(&doc.text, span.start());
+                    json!({ "uri": uri, "range": { "start": start, "end": end } })
+                })
+                .collect(),
+        };
+
+        match locations.len() {
+            0 => Value::Null,
+            _ => Value::Array(locations),
+        }
+    }
+    /// Handle `textDocument/definition`.
+    ///
+    /// There are no script-defined functions under `no_function`.
+    #[cfg(feature = "no_function")]
+    fn definition(&self, _params: &Value) -> Value {
+        Value::Null
+    }
+}
+
+fn main() {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+
+    let mut server = Server::new();
+
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(err) => {
+                eprintln!("Error reading LSP message: {err}");
+                continue;
+            }
+        };
+
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        let replies = match message.get("id") {
+            // Response to a request from the server - ignore
+            Some(..) if method.is_empty() => continue,
+            // Request
+            Some(id) => {
+                let reply = match server.handle_request(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, msg)) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": code, "message": msg },
+                    }),
+                };
+                vec![reply]
+            }
+            // Notification
+            None => server.handle_notification(method, params),
+        };
+
+        for reply in replies {
+            if let Err(err) = write_message(&mut output, &reply) {
+                eprintln!("Error writing LSP message: {err}");
+                exit(1);
+            }
+        }
+    }
+
+    exit(if server.shutdown { 0 } else { 1 });
+}
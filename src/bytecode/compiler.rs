@@ -0,0 +1,668 @@
+//! Module implementing the compiler of statements and expressions into bytecode.
+
+use super::{CallSite, Chunk, Idx, Instr, Label, Loop, Reg};
+use crate::ast::{ASTFlags, ASTNode, BinaryExpr, Expr, FlowControl, FnCallExpr, Stmt, StmtBlock};
+use crate::engine::{
+    KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL, KEYWORD_FN_PTR_CURRY,
+    KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_TYPE_OF,
+};
+use crate::tokenizer::Token;
+use crate::{Dynamic, Engine, Position};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Compile a list of statements (the body of a script or a script-defined function) into a
+/// [`Chunk`].
+///
+/// Returns [`None`] if the statements contain anything that may change the
+/// [`Scope`][crate::Scope] or the imported modules behind the compiler's back, namely `eval`,
+/// `import`, `export` and custom syntax.
+#[must_use]
+pub(super) fn compile(engine: &Engine, statements: &[Stmt]) -> Option<Chunk> {
+    let path = &mut Vec::new();
+
+    if !statements
+        .iter()
+        .all(|stmt| stmt.walk(path, &mut is_compilable))
+    {
+        return None;
+    }
+
+    let mut compiler = Compiler {
+        chunk: Chunk::default(),
+        fast_operators: engine.fast_operators(),
+        next_reg: 0,
+        scope_len: 0,
+        depth: 0,
+        iters: 0,
+        loops: Vec::new(),
+    };
+
+    let dst = compiler.alloc();
+    compiler.compile_stmts(statements, Some(dst));
+    compiler.emit(Instr::End(dst));
+
+    Some(compiler.chunk)
+}
+
+/// Can a node be part of a compiled [`Chunk`]?
+fn is_compilable(path: &[ASTNode]) -> bool {
+    match path.last() {
+        Some(ASTNode::Stmt(Stmt::FnCall(x, ..)) | ASTNode::Expr(Expr::FnCall(x, ..))) => {
+            #[cfg(not(feature = "no_module"))]
+            if x.is_qualified() {
+                return true;
+            }
+            x.name != KEYWORD_EVAL
+        }
+
+        #[cfg(not(feature = "no_module"))]
+        Some(ASTNode::Stmt(Stmt::Import(..) | Stmt::Export(..))) => false,
+
+        #[cfg(not(feature = "no_custom_syntax"))]
+        Some(ASTNode::Expr(Expr::Custom(..))) => false,
+
+        _ => true,
+    }
+}
+
+/// Is an expression free of side effects when evaluated?
+#[inline]
+#[must_use]
+fn is_simple(expr: &Expr) -> bool {
+    matches!(expr, Expr::Variable(..) | Expr::ThisPtr(..)) || expr.get_literal_value().is_some()
+}
+
+/// A loop being compiled.
+struct LoopScope {
+    /// Number of variables defined in the chunk outside the loop body.
+    scope_len: usize,
+    /// Register holding the result of the loop.
+    dst: Reg,
+    /// Instruction to jump to on `continue`.
+    next: Label,
+    /// Jumps to the end of the loop, to be patched when the loop is done.
+    breaks: Vec<usize>,
+}
+
+/// State of the compiler.
+struct Compiler {
+    /// The chunk being compiled.
+    chunk: Chunk,
+    /// Is Fast Operators mode turned on?
+    fast_operators: bool,
+    /// Next free register.
+    next_reg: Reg,
+    /// Number of variables defined so far in the chunk that are still in scope.
+    scope_len: usize,
+    /// Nesting level of statements blocks.
+    depth: usize,
+    /// Number of running `for` loop iterators.
+    iters: usize,
+    /// Loops being compiled, innermost last.
+    loops: Vec<LoopScope>,
+}
+
+impl Compiler {
+    /// Index of the next instruction.
+    #[inline(always)]
+    #[must_use]
+    fn pc(&self) -> Label {
+        self.chunk.code.len() as Label
+    }
+    /// Add an instruction, returning its index.
+    #[inline(always)]
+    fn emit(&mut self, instr: Instr) -> usize {
+        self.chunk.code.push(instr);
+        self.chunk.code.len() - 1
+    }
+    /// Set the target of a jump instruction.
+    fn patch(&mut self, index: usize, target: Label) {
+        match self.chunk.code[index] {
+            Instr::Jump(ref mut label)
+            | Instr::JumpIf(_, _, ref mut label, _)
+            | Instr::JumpIfNotUnit(_, ref mut label)
+            | Instr::ForNext(_, _, ref mut label) => *label = target,
+            ref instr => unreachable!("jump instruction expected but gets {:?}", instr),
+        }
+    }
+    /// Allocate a new register.
+    #[inline]
+    #[must_use]
+    fn alloc(&mut self) -> Reg {
+        let reg = self.next_reg;
+        self.next_reg += 1;
+        self.chunk.num_regs = self.chunk.num_regs.max(self.next_reg as usize);
+        reg
+    }
+    /// Add a constant value.
+    #[inline]
+    fn add_constant(&mut self, value: Dynamic) -> Idx {
+        self.chunk.constants.push(value);
+        (self.chunk.constants.len() - 1) as Idx
+    }
+    /// Add an expression to be referred to by an instruction.
+    #[inline]
+    fn add_expr(&mut self, expr: &Expr) -> Idx {
+        self.chunk.exprs.push(expr.clone());
+        (self.chunk.exprs.len() - 1) as Idx
+    }
+    /// Add a statement to be referred to by an instruction.
+    #[inline]
+    fn add_stmt(&mut self, stmt: &Stmt) -> Idx {
+        self.chunk.stmts.push(stmt.clone());
+        (self.chunk.stmts.len() - 1) as Idx
+    }
+    /// Add a function call site.
+    fn add_call(&mut self, x: &FnCallExpr, first: Option<&Expr>, pos: Position) -> Idx {
+        let bindable = !matches!(
+            x.name.as_str(),
+            KEYWORD_TYPE_OF | KEYWORD_PRINT | KEYWORD_DEBUG
+        );
+
+        self.chunk.calls.push(CallSite {
+            name: x.name.clone(),
+            hashes: x.hashes,
+            op_token: x.op_token.clone(),
+            num_args: x.args.len(),
+            first: first.cloned(),
+            bindable,
+            pos,
+        });
+        (self.chunk.calls.len() - 1) as Idx
+    }
+
+    /// Set a register (if any) to `()`.
+    #[inline]
+    fn unit(&mut self, dst: Option<Reg>) {
+        if let Some(dst) = dst {
+            self.emit(Instr::Unit(dst));
+        }
+    }
+    /// Run a statement with the tree-walking interpreter.
+    #[inline]
+    fn exec(&mut self, stmt: &Stmt, dst: Option<Reg>) {
+        let index = self.add_stmt(stmt);
+        self.emit(Instr::Exec(dst, index, self.depth == 0));
+    }
+
+    /// Compile a list of statements, putting the value of the last one into a register (if any).
+    fn compile_stmts(&mut self, statements: &[Stmt], dst: Option<Reg>) {
+        if statements.is_empty() {
+            self.unit(dst);
+            return;
+        }
+
+        let last = statements.len() - 1;
+
+        for (i, stmt) in statements.iter().enumerate() {
+            self.compile_stmt(stmt, if i == last { dst } else { None });
+        }
+    }
+    /// Compile a statements block, putting its value into a register (if any).
+    fn compile_block(&mut self, statements: &[Stmt], dst: Option<Reg>) {
+        let scope_len = self.scope_len;
+
+        self.depth += 1;
+        self.compile_stmts(statements, dst);
+        self.depth -= 1;
+
+        if self.scope_len > scope_len {
+            self.emit(Instr::Rewind(scope_len));
+            self.scope_len = scope_len;
+        }
+    }
+    /// Compile the body of a loop, returning the index of the [`Loop`] and the jumps to the end
+    /// of the loop.
+    fn compile_loop_body(
+        &mut self,
+        body: &StmtBlock,
+        dst: Reg,
+        next: Label,
+    ) -> (usize, Vec<usize>) {
+        self.loops.push(LoopScope {
+            scope_len: self.scope_len,
+            dst,
+            next,
+            breaks: Vec::new(),
+        });
+
+        let start = self.pc();
+        self.compile_block(body.statements(), None);
+        let end = self.pc();
+
+        let LoopScope {
+            scope_len, breaks, ..
+        } = self.loops.pop().unwrap();
+
+        self.chunk.loops.push(Loop {
+            start,
+            end,
+            scope_len,
+            iters: self.iters,
+            dst,
+            next,
+            exit: 0,
+        });
+
+        (self.chunk.loops.len() - 1, breaks)
+    }
+    /// Set the exit of a loop and patch all jumps to it.
+    fn end_loop(&mut self, index: Option<usize>, breaks: Vec<usize>, exit: Label) {
+        if let Some(index) = index {
+            self.chunk.loops[index].exit = exit;
+        }
+        for jump in breaks {
+            self.patch(jump, exit);
+        }
+    }
+
+    /// Compile a statement, putting its value into a register (if any).
+    fn compile_stmt(&mut self, stmt: &Stmt, dst: Option<Reg>) {
+        let mark = self.next_reg;
+
+        match stmt {
+            Stmt::Noop(..) => {
+                self.emit(Instr::Track(stmt.position()));
+                self.unit(dst);
+            }
+
+            Stmt::Expr(expr) => {
+                self.emit(Instr::Track(stmt.position()));
+                let reg = dst.unwrap_or_else(|| self.alloc());
+                self.compile_expr(expr, reg);
+                if dst.is_some() {
+                    self.emit(Instr::Flatten(reg));
+                }
+            }
+
+            Stmt::Block(x) => {
+                self.emit(Instr::Track(stmt.position()));
+                self.compile_block(x.statements(), dst);
+            }
+
+            Stmt::FnCall(x, pos) if self.is_compilable_call(x) => {
+                self.emit(Instr::Track(stmt.position()));
+                let reg = dst.unwrap_or_else(|| self.alloc());
+                self.compile_call(x, *pos, reg);
+            }
+
+            Stmt::Assignment(x) if matches!(x.1.lhs, Expr::Variable(..)) => {
+                self.emit(Instr::Track(stmt.position()));
+                let reg = self.alloc();
+                self.compile_expr(&x.1.rhs, reg);
+                let index = self.add_stmt(stmt);
+                self.emit(Instr::Assign(reg, index));
+                self.unit(dst);
+            }
+
+            Stmt::Var(x, ..) => {
+                self.emit(Instr::Track(stmt.position()));
+                let index = self.add_stmt(stmt);
+                self.emit(Instr::CheckVar(index));
+                let reg = self.alloc();
+                self.compile_expr(&x.1, reg);
+                self.emit(Instr::DefineVar(reg, index, self.depth == 0));
+                if x.2.is_none() {
+                    self.scope_len += 1;
+                }
+                self.unit(dst);
+            }
+
+            Stmt::Destructure(x, ..) => {
+                self.emit(Instr::Track(stmt.position()));
+                let index = self.add_stmt(stmt);
+                self.emit(Instr::CheckVar(index));
+                let reg = self.alloc();
+                self.compile_expr(&x.2, reg);
+                self.emit(Instr::Destructure(reg, index, self.depth == 0));
+                self.scope_len += x.1.len();
+                self.unit(dst);
+            }
+
+            Stmt::If(x, ..) => {
+                let FlowControl { expr, body, branch } = &**x;
+
+                self.emit(Instr::Track(stmt.position()));
+                let reg = self.alloc();
+                self.compile_expr(expr, reg);
+                let to_else = self.emit(Instr::JumpIf(reg, false, 0, expr.position()));
+                self.compile_block(body.statements(), dst);
+                let to_end = self.emit(Instr::Jump(0));
+                self.patch(to_else, self.pc());
+                self.compile_block(branch.statements(), dst);
+                self.patch(to_end, self.pc());
+            }
+
+            // Infinite loop
+            Stmt::While(x, ..)
+                if matches!(x.expr, Expr::Unit(..) | Expr::BoolConstant(true, ..)) =>
+            {
+                let body = &x.body;
+
+                self.emit(Instr::Track(stmt.position()));
+                let reg = dst.unwrap_or_else(|| self.alloc());
+                let start = self.pc();
+
+                if body.is_empty() {
+                    self.emit(Instr::Track(body.position()));
+                    self.emit(Instr::Jump(start));
+                } else {
+                    let (index, breaks) = self.compile_loop_body(body, reg, start);
+                    self.emit(Instr::Jump(start));
+                    self.end_loop(Some(index), breaks, self.pc());
+                }
+            }
+
+            Stmt::While(x, ..) => {
+                let FlowControl { expr, body, .. } = &**x;
+
+                self.emit(Instr::Track(stmt.position()));
+                let reg = dst.unwrap_or_else(|| self.alloc());
+                self.emit(Instr::Unit(reg));
+                let start = self.pc();
+                let cond = self.alloc();
+                self.compile_expr(expr, cond);
+                let to_exit = self.emit(Instr::JumpIf(cond, false, 0, expr.position()));
+
+                let (index, mut breaks) = if body.is_empty() {
+                    (None, Vec::new())
+                } else {
+                    let (index, breaks) = self.compile_loop_body(body, reg, start);
+                    (Some(index), breaks)
+                };
+                self.emit(Instr::Jump(start));
+
+                breaks.push(to_exit);
+                self.end_loop(index, breaks, self.pc());
+            }
+
+            Stmt::Do(x, options, ..) => {
+                let FlowControl { expr, body, .. } = &**x;
+                let is_while = !options.intersects(ASTFlags::NEGATED);
+
+                self.emit(Instr::Track(stmt.position()));
+                let reg = dst.unwrap_or_else(|| self.alloc());
+                self.emit(Instr::Unit(reg));
+                let start = self.pc();
+
+                // `continue` skips the loop condition
+                let (index, breaks) = if body.is_empty() {
+                    (None, Vec::new())
+                } else {
+                    let (index, breaks) = self.compile_loop_body(body, reg, start);
+                    (Some(index), breaks)
+                };
+
+                let cond = self.alloc();
+                self.compile_expr(expr, cond);
+                self.emit(Instr::JumpIf(cond, is_while, start, expr.position()));
+
+                self.end_loop(index, breaks, self.pc());
+            }
+
+            Stmt::For(x, ..) => {
+                let (_, counter, FlowControl { expr, body, .. }) = &**x;
+
+                self.emit(Instr::Track(stmt.position()));
+                let reg = dst.unwrap_or_else(|| self.alloc());
+                let index = self.add_stmt(stmt);
+                self.emit(Instr::CheckFor(index));
+                let iter_obj = self.alloc();
+                self.compile_expr(expr, iter_obj);
+
+                let slot = self.iters;
+                let scope_len = self.scope_len;
+
+                self.emit(Instr::ForInit(iter_obj, index, slot));
+                self.emit(Instr::Unit(reg));
+                self.scope_len += 1 + usize::from(counter.is_some());
+                self.iters += 1;
+
+                let next = self.pc();
+                let to_exit = self.emit(Instr::ForNext(index, slot, 0));
+
+                let (loop_index, mut breaks) = if body.is_empty() {
+                    (None, Vec::new())
+                } else {
+                    let (index, breaks) = self.compile_loop_body(body, reg, next);
+                    (Some(index), breaks)
+                };
+                self.emit(Instr::Jump(next));
+
+                self.iters -= 1;
+                self.scope_len = scope_len;
+
+                breaks.push(to_exit);
+                self.end_loop(loop_index, breaks, self.pc());
+                self.emit(Instr::ForEnd(slot, scope_len));
+            }
+
+            Stmt::BreakLoop(expr, options, ..) if !self.loops.is_empty() => {
+                let is_break = options.intersects(ASTFlags::BREAK);
+                let LoopScope {
+                    scope_len,
+                    dst: loop_dst,
+                    next,
+                    ..
+                } = *self.loops.last().unwrap();
+
+                self.emit(Instr::Track(stmt.position()));
+
+                match expr {
+                    Some(expr) if is_break => self.compile_expr(expr, loop_dst),
+                    Some(expr) => {
+                        let reg = self.alloc();
+                        self.compile_expr(expr, reg);
+                    }
+                    None if is_break => {
+                        self.emit(Instr::Unit(loop_dst));
+                    }
+                    None => (),
+                }
+
+                if self.scope_len > scope_len {
+                    self.emit(Instr::Rewind(scope_len));
+                }
+
+                if is_break {
+                    let jump = self.emit(Instr::Jump(0));
+                    self.loops.last_mut().unwrap().breaks.push(jump);
+                } else {
+                    self.emit(Instr::Jump(next));
+                }
+            }
+
+            Stmt::Return(expr, options, pos) => {
+                self.emit(Instr::Track(stmt.position()));
+                let reg = self.alloc();
+                match expr {
+                    Some(expr) => self.compile_expr(expr, reg),
+                    None => {
+                        self.emit(Instr::Unit(reg));
+                    }
+                }
+                if options.intersects(ASTFlags::BREAK) {
+                    self.emit(Instr::Throw(reg, *pos));
+                } else {
+                    self.emit(Instr::Return(reg));
+                }
+            }
+
+            // Everything else is run by the tree-walking interpreter
+            _ => self.exec(stmt, dst),
+        }
+
+        self.next_reg = mark;
+    }
+
+    /// Can a function call be compiled?
+    #[must_use]
+    fn is_compilable_call(&self, x: &FnCallExpr) -> bool {
+        let op_token = x.op_token.as_ref();
+
+        // Fast operators
+        if self.fast_operators
+            && ((x.args.len() == 1 && op_token == Some(&Token::Bang))
+                || (x.args.len() == 2 && op_token.is_some()))
+        {
+            return true;
+        }
+
+        #[cfg(not(feature = "no_module"))]
+        if x.is_qualified() {
+            return false;
+        }
+
+        if x.capture_parent_scope || matches!(x.args.first(), Some(Expr::ThisPtr(..))) {
+            return false;
+        }
+
+        if op_token.is_some() {
+            return true;
+        }
+
+        match x.name.as_str() {
+            KEYWORD_FN_PTR_CALL | KEYWORD_FN_PTR | KEYWORD_FN_PTR_CURRY | KEYWORD_IS_DEF_VAR
+            | KEYWORD_EVAL => false,
+            #[cfg(not(feature = "no_closure"))]
+            crate::engine::KEYWORD_IS_SHARED => false,
+            #[cfg(not(feature = "no_function"))]
+            crate::engine::KEYWORD_IS_DEF_FN => false,
+            _ => true,
+        }
+    }
+    /// Compile the arguments of a function call into consecutive registers.
+    fn compile_args(&mut self, args: &[Expr], base: Reg) {
+        for (i, arg) in args.iter().enumerate() {
+            let reg = base + i as Reg;
+
+            if let Some(value) = arg.get_literal_value() {
+                let index = self.add_constant(value);
+                self.emit(Instr::Const(reg, index, arg.start_position()));
+                continue;
+            }
+
+            self.compile_expr(arg, reg);
+
+            // Arguments are flattened as soon as they are evaluated, so make sure that
+            // evaluating the rest of the arguments cannot change them
+            if args[i + 1..].iter().any(|arg| !is_simple(arg)) {
+                self.emit(Instr::Flatten(reg));
+            }
+        }
+    }
+    /// Compile a (compilable) function call.
+    fn compile_call(&mut self, x: &FnCallExpr, pos: Position, dst: Reg) {
+        let base = self.next_reg;
+        for _ in 0..x.args.len() {
+            let _ = self.alloc();
+        }
+
+        let op_token = x.op_token.as_ref();
+
+        if self.fast_operators && x.args.len() == 1 && op_token == Some(&Token::Bang) {
+            self.compile_args(&x.args, base);
+            let index = self.add_call(x, None, pos);
+            self.emit(Instr::Not(dst, base, index));
+        } else if self.fast_operators && x.args.len() == 2 && op_token.is_some() {
+            self.compile_args(&x.args, base);
+            let index = self.add_call(x, None, pos);
+            self.emit(Instr::Binary(dst, base, index));
+        } else if let Some(first @ Expr::Variable(..)) = x.args.first() {
+            // Turn it into a method call to avoid cloning the first argument
+            self.emit(Instr::Track(first.position()));
+            self.compile_args(&x.args[1..], base + 1);
+            let index = self.add_call(x, Some(first), pos);
+            self.emit(Instr::CallRef(dst, base, index));
+        } else {
+            self.compile_args(&x.args, base);
+            let index = self.add_call(x, None, pos);
+            self.emit(Instr::Call(dst, base, index));
+        }
+    }
+
+    /// Compile an expression, putting its value into a register.
+    fn compile_expr(&mut self, expr: &Expr, dst: Reg) {
+        let mark = self.next_reg;
+
+        match expr {
+            Expr::DynamicConstant(..)
+            | Expr::BoolConstant(..)
+            | Expr::IntegerConstant(..)
+            | Expr::CharConstant(..)
+            | Expr::StringConstant(..)
+            | Expr::Unit(..) => {
+                let index = self.add_constant(expr.get_literal_value().unwrap());
+                self.emit(Instr::Const(dst, index, expr.position()));
+            }
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(..) => {
+                let index = self.add_constant(expr.get_literal_value().unwrap());
+                self.emit(Instr::Const(dst, index, expr.position()));
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            Expr::Variable(x, ..) if !x.2.is_empty() => {
+                let index = self.add_expr(expr);
+                self.emit(Instr::Var(dst, index));
+            }
+            Expr::Variable(_, Some(offset), ..) => {
+                let index = self.add_expr(expr);
+                self.emit(Instr::Local(dst, *offset, index));
+            }
+            Expr::Variable(..) => {
+                let index = self.add_expr(expr);
+                self.emit(Instr::Var(dst, index));
+            }
+
+            Expr::ThisPtr(pos) => {
+                self.emit(Instr::This(dst, *pos));
+            }
+
+            Expr::FnCall(x, pos) if self.is_compilable_call(x) => {
+                self.emit(Instr::Track(expr.position()));
+                self.compile_call(x, *pos, dst);
+            }
+
+            Expr::And(x, ..) | Expr::Or(x, ..) => {
+                let BinaryExpr { lhs, rhs } = &**x;
+                // Short-circuit when the left-hand side is `false` for `&&`, `true` for `||`
+                let is_and = matches!(expr, Expr::And(..));
+
+                self.emit(Instr::Track(expr.position()));
+                self.compile_expr(lhs, dst);
+                let to_short = self.emit(Instr::JumpIf(dst, !is_and, 0, lhs.position()));
+                self.compile_expr(rhs, dst);
+                let to_short2 = self.emit(Instr::JumpIf(dst, !is_and, 0, rhs.position()));
+                self.emit(Instr::Bool(dst, is_and));
+                let to_end = self.emit(Instr::Jump(0));
+                self.patch(to_short, self.pc());
+                self.patch(to_short2, self.pc());
+                self.emit(Instr::Bool(dst, !is_and));
+                self.patch(to_end, self.pc());
+            }
+
+            Expr::Coalesce(x, ..) => {
+                self.emit(Instr::Track(expr.position()));
+                self.compile_expr(&x.lhs, dst);
+                let to_end = self.emit(Instr::JumpIfNotUnit(dst, 0));
+                self.compile_expr(&x.rhs, dst);
+                self.patch(to_end, self.pc());
+            }
+
+            Expr::Stmt(x) => {
+                self.emit(Instr::Track(expr.position()));
+                self.compile_block(x.statements(), Some(dst));
+            }
+
+            // Everything else is evaluated by the tree-walking interpreter
+            _ => {
+                let index = self.add_expr(expr);
+                self.emit(Instr::Eval(dst, index));
+            }
+        }
+
+        self.next_reg = mark;
+    }
+}
@@ -0,0 +1,287 @@
+//! Module implementing the bytecode compiler and register-based virtual machine.
+//!
+//! Script bodies compiled into [`Bytecode`] are run by the virtual machine instead of walking
+//! [`Stmt`] and [`Expr`] nodes.
+//!
+//! Variables still live in the same [`Scope`][crate::Scope] layout as under the tree-walking
+//! interpreter, so statements and expressions that are not compiled into instructions can be
+//! handed back to the interpreter at any point. The number of operations counted and the
+//! positions of errors are the same as when the [`AST`] is evaluated.
+
+mod compiler;
+mod vm;
+
+use crate::ast::{Expr, FnCallHashes, Stmt};
+use crate::tokenizer::Token;
+use crate::{Dynamic, Engine, ImmutableString, Position, Shared, AST};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{fmt, num::NonZeroU8};
+
+/// Index of a register in a [`Chunk`].
+type Reg = u32;
+
+/// Index of an instruction in a [`Chunk`].
+type Label = u32;
+
+/// Index into one of the tables of a [`Chunk`].
+type Idx = u32;
+
+/// An instruction of the virtual machine.
+///
+/// Instructions that count operations do so at the same positions as the tree-walking
+/// interpreter.
+#[derive(Debug, Clone)]
+pub(crate) enum Instr {
+    /// Count one operation at a position.
+    Track(Position),
+    /// Load a constant into a register, counting one operation.
+    Const(Reg, Idx, Position),
+    /// Set a register to a `bool` value.
+    Bool(Reg, bool),
+    /// Set a register to `()`.
+    Unit(Reg),
+    /// Load a variable at an offset from the end of the [`Scope`][crate::Scope], counting one
+    /// operation.
+    ///
+    /// The variable expression is kept for the position and in case the variable must be
+    /// searched by name.
+    Local(Reg, NonZeroU8, Idx),
+    /// Load a variable by searching for it, counting one operation.
+    Var(Reg, Idx),
+    /// Load the `this` pointer, counting one operation.
+    This(Reg, Position),
+    /// Evaluate an expression with the tree-walking interpreter.
+    Eval(Reg, Idx),
+    /// Run a statement with the tree-walking interpreter, keeping the result in a register (if
+    /// any).
+    ///
+    /// The flag is set if the statement is at the top level of the chunk.
+    Exec(Option<Reg>, Idx, bool),
+    /// Flatten any shared value in a register.
+    Flatten(Reg),
+    /// Call a binary operator on the two registers starting from a register, under Fast Operators
+    /// mode.
+    Binary(Reg, Reg, Idx),
+    /// Call the `!` operator under Fast Operators mode.
+    Not(Reg, Reg, Idx),
+    /// Call a function with arguments in consecutive registers starting from a register.
+    Call(Reg, Reg, Idx),
+    /// Call a function with the first argument being a variable, which is passed by reference if
+    /// possible.
+    ///
+    /// The register of the first argument is left empty.
+    CallRef(Reg, Reg, Idx),
+    /// Jump to an instruction.
+    Jump(Label),
+    /// Jump to an instruction if the `bool` value in a register matches.
+    ///
+    /// The position is for the error raised when the value is not `bool`.
+    JumpIf(Reg, bool, Label, Position),
+    /// Jump to an instruction if the value in a register is not `()`.
+    JumpIfNotUnit(Reg, Label),
+    /// Check that the variables of a `let`/`const` statement can be defined.
+    CheckVar(Idx),
+    /// Define the variable of a `let`/`const` statement with the value in a register.
+    ///
+    /// The flag is set if the statement is at the top level of the chunk.
+    DefineVar(Reg, Idx, bool),
+    /// Define the variables of a destructuring `let`/`const` statement with the value in a
+    /// register.
+    ///
+    /// The flag is set if the statement is at the top level of the chunk.
+    Destructure(Reg, Idx, bool),
+    /// Assign (or op-assign) the value in a register to a variable.
+    Assign(Reg, Idx),
+    /// Rewind the [`Scope`][crate::Scope] to a number of variables defined in the chunk.
+    Rewind(usize),
+    /// Check that the variables of a `for` statement can be defined.
+    CheckFor(Idx),
+    /// Start a `for` loop on the value in a register, with an iterator at a slot.
+    ForInit(Reg, Idx, usize),
+    /// Get the next value of the iterator at a slot, jumping to an instruction when it ends.
+    ForNext(Idx, usize, Label),
+    /// End a `for` loop, dropping its iterator at a slot and rewinding the
+    /// [`Scope`][crate::Scope] to a number of variables defined in the chunk.
+    ForEnd(usize, usize),
+    /// Return the (flattened) value in a register.
+    Return(Reg),
+    /// Throw the (flattened) value in a register as an exception.
+    Throw(Reg, Position),
+    /// End of the chunk, with the value in a register as result.
+    End(Reg),
+}
+
+/// A function call site in a [`Chunk`].
+#[derive(Debug, Clone)]
+pub(crate) struct CallSite {
+    /// Function name.
+    pub name: ImmutableString,
+    /// Pre-calculated hashes.
+    pub hashes: FnCallHashes,
+    /// Is this function call a native operator?
+    pub op_token: Option<Token>,
+    /// Number of arguments.
+    pub num_args: usize,
+    /// The first argument, if it is a variable that is passed by reference.
+    pub first: Option<Expr>,
+    /// Can the resolved function be bound to this call site?
+    pub bindable: bool,
+    /// Position of the function call.
+    pub pos: Position,
+}
+
+/// A loop in a [`Chunk`], for handling `break` and `continue` raised by the tree-walking
+/// interpreter.
+#[derive(Debug, Clone)]
+pub(crate) struct Loop {
+    /// First instruction of the loop body.
+    pub start: Label,
+    /// One past the last instruction of the loop body.
+    pub end: Label,
+    /// Number of variables defined in the chunk outside the loop body.
+    pub scope_len: usize,
+    /// Number of `for` loop iterators running outside the loop body.
+    pub iters: usize,
+    /// Register holding the result of the loop.
+    pub dst: Reg,
+    /// Instruction to jump to on `continue`.
+    pub next: Label,
+    /// Instruction to jump to on `break`.
+    pub exit: Label,
+}
+
+/// A sequence of instructions compiled from the body of a script or a script-defined function.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Chunk {
+    /// Instructions.
+    pub code: Vec<Instr>,
+    /// Constant values.
+    pub constants: Vec<Dynamic>,
+    /// Expressions referred to by instructions.
+    pub exprs: Vec<Expr>,
+    /// Statements referred to by instructions.
+    pub stmts: Vec<Stmt>,
+    /// Function call sites.
+    pub calls: Vec<CallSite>,
+    /// Loops, innermost first.
+    pub loops: Vec<Loop>,
+    /// Number of registers used.
+    pub num_regs: usize,
+}
+
+/// Bytecode compiled from an [`AST`].
+pub(crate) struct Program {
+    /// The [`AST`], also keeping the script-defined functions alive.
+    pub ast: AST,
+    /// Chunk compiled from the statements of the [`AST`], if possible.
+    pub main: Option<Chunk>,
+    /// Chunks compiled from script-defined functions, keyed by the addresses of their
+    /// definitions.
+    #[cfg(not(feature = "no_function"))]
+    pub functions: std::collections::BTreeMap<usize, Chunk>,
+    /// Is Fast Operators mode turned on when compiling?
+    pub fast_operators: bool,
+}
+
+impl Program {
+    /// Get the chunk compiled from a script-defined function, if any.
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub fn get_fn(&self, fn_def: &crate::ast::ScriptFuncDef) -> Option<&Chunk> {
+        self.functions.get(&(fn_def as *const _ as usize))
+    }
+}
+
+impl fmt::Debug for Program {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut f = f.debug_struct("Program");
+        f.field("main", &self.main);
+        #[cfg(not(feature = "no_function"))]
+        f.field("functions", &self.functions.values().collect::<Vec<_>>());
+        f.finish()
+    }
+}
+
+/// _(bytecode)_ A script compiled into bytecode that runs on a register-based virtual machine.
+/// Exported under the `bytecode` feature only.
+///
+/// Create via [`Engine::compile_bytecode`] and run via [`Engine::eval_bytecode`] or
+/// [`Engine::run_bytecode`].
+///
+/// Statements and expressions that have no bytecode equivalent (e.g. indexing, property access,
+/// `switch`, `try`) are evaluated by the tree-walking interpreter as part of the run. A script
+/// body or function containing `eval`, `import`, `export` or custom syntax is not compiled at
+/// all and is always evaluated by walking its [`AST`].
+///
+/// Bytecode is tied to the [`Engine`] settings in force when it is compiled. If Fast Operators
+/// mode is changed afterwards, or if a debugger, variable resolver or variable definition filter
+/// is registered, the [`AST`] is evaluated instead.
+#[derive(Debug, Clone)]
+pub struct Bytecode {
+    /// The compiled program.
+    program: Shared<Program>,
+}
+
+impl Bytecode {
+    /// Compile an [`AST`] into bytecode.
+    #[must_use]
+    pub(crate) fn compile(engine: &Engine, ast: &AST) -> Self {
+        let ast = ast.clone();
+        let main = compiler::compile(engine, ast.statements());
+
+        #[cfg(not(feature = "no_function"))]
+        let functions = ast
+            .shared_lib()
+            .iter_script_fn()
+            .filter_map(|(.., fn_def)| {
+                let chunk = compiler::compile(engine, fn_def.body.statements())?;
+                Some((Shared::as_ptr(fn_def) as usize, chunk))
+            })
+            .collect();
+
+        Self {
+            program: Program {
+                ast,
+                main,
+                #[cfg(not(feature = "no_function"))]
+                functions,
+                fast_operators: engine.fast_operators(),
+            }
+            .into(),
+        }
+    }
+    /// Get the [`AST`] that this bytecode is compiled from.
+    #[inline(always)]
+    #[must_use]
+    pub fn ast(&self) -> &AST {
+        &self.program.ast
+    }
+    /// Are the statements of the script and all its functions compiled into bytecode?
+    ///
+    /// If not, the parts that are not compiled are evaluated by walking the [`AST`].
+    #[inline]
+    #[must_use]
+    pub fn is_fully_compiled(&self) -> bool {
+        #[cfg(not(feature = "no_function"))]
+        let num_fn = self.program.ast.shared_lib().iter_script_fn().count();
+        #[cfg(feature = "no_function")]
+        let num_fn = 0;
+
+        #[cfg(not(feature = "no_function"))]
+        let num_compiled = self.program.functions.len();
+        #[cfg(feature = "no_function")]
+        let num_compiled = 0;
+
+        self.program.main.is_some() && num_compiled == num_fn
+    }
+    /// Get the compiled program.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) const fn program(&self) -> &Shared<Program> {
+        &self.program
+    }
+}
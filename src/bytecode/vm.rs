@@ -0,0 +1,818 @@
+//! Module implementing the virtual machine running bytecode.
+
+use super::{CallSite, Chunk, Instr, Label};
+use crate::ast::{ASTFlags, BinaryExpr, Expr, FlowControl, Stmt};
+use crate::eval::{Caches, FnResolutionCacheEntry, GlobalRuntimeState, Target};
+use crate::func::call::ArgBackup;
+use crate::func::{calc_fn_hash_full, FnCallArgs, RhaiFunc};
+use crate::types::dynamic::{AccessMode, Union};
+use crate::{Dynamic, Engine, FnArgsVec, Position, RhaiResult, RhaiResultOf, Scope, ERR, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{convert::TryFrom, iter::once, mem};
+
+/// Function returning the next value of a `for` loop.
+type NextFn<'e> = Box<dyn FnMut(&mut GlobalRuntimeState) -> Option<RhaiResultOf<Dynamic>> + 'e>;
+
+/// A running `for` loop.
+struct ForState<'e> {
+    /// Function returning the next value.
+    next: NextFn<'e>,
+    /// Index of the counter variable in the [`Scope`], if any.
+    counter: Option<usize>,
+    /// Index of the loop variable in the [`Scope`].
+    index: usize,
+    /// Number of iterations so far.
+    count: usize,
+}
+
+/// A function bound to a call site.
+enum Binding {
+    /// Not resolved yet.
+    Unbound,
+    /// Native function resolved for arguments with a particular combination of types (as a hash).
+    Native(u64, FnResolutionCacheEntry),
+    /// Resolved to something that cannot be bound, or with arguments of varying types.
+    None,
+}
+
+/// State of a running [`Chunk`].
+struct Frame<'e> {
+    /// Index of the next instruction.
+    pc: usize,
+    /// Registers.
+    regs: Vec<Dynamic>,
+    /// Running `for` loops.
+    iters: Vec<ForState<'e>>,
+    /// Functions bound to call sites (empty if the chunk has no loops).
+    bindings: Vec<Binding>,
+    /// Length of the [`Scope`] when the chunk starts.
+    base: usize,
+    /// Length of the [`Scope`] including all variables defined at the top level of the chunk.
+    top_len: usize,
+}
+
+/// Get the access mode of the variables defined by a `let`/`const` statement, and whether they are
+/// exported.
+#[inline]
+#[must_use]
+fn var_options(options: ASTFlags) -> (AccessMode, bool) {
+    let access = if options.intersects(ASTFlags::CONSTANT) {
+        AccessMode::ReadOnly
+    } else {
+        AccessMode::ReadWrite
+    };
+    (access, options.intersects(ASTFlags::EXPORTED))
+}
+
+impl Engine {
+    /// Run a [`Chunk`] compiled from a list of statements, with the same semantics as
+    /// [`eval_stmt_block`][Engine::eval_stmt_block].
+    ///
+    /// Statements blocks within the chunk do not keep track of their scope levels, which are only
+    /// observable by the variable definition filter (under which bytecode is never run).
+    pub(crate) fn run_chunk(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        chunk: &Chunk,
+        restore_orig_state: bool,
+    ) -> RhaiResult {
+        // Restore global state at end of chunk if necessary
+        let orig_always_search_scope = global.always_search_scope;
+        #[cfg(not(feature = "no_module"))]
+        let orig_imports_len = global.num_imports();
+
+        if restore_orig_state {
+            global.scope_level += 1;
+        }
+
+        defer! { global if restore_orig_state => move |g| {
+            g.scope_level -= 1;
+
+            #[cfg(not(feature = "no_module"))]
+            g.truncate_imports(orig_imports_len);
+
+            g.always_search_scope = orig_always_search_scope;
+        }}
+
+        // Pop new function resolution caches at end of chunk
+        defer! {
+            caches => rewind_fn_resolution_caches;
+            let orig_fn_resolution_caches_len = caches.fn_resolution_caches_len();
+        }
+
+        // Bind functions to call sites only if they may be called repeatedly
+        let bindings = if chunk.loops.is_empty() {
+            Vec::new()
+        } else {
+            chunk.calls.iter().map(|_| Binding::Unbound).collect()
+        };
+
+        let mut frame = Frame {
+            pc: 0,
+            regs: vec![Dynamic::UNIT; chunk.num_regs],
+            iters: Vec::new(),
+            bindings,
+            base: scope.len(),
+            top_len: scope.len(),
+        };
+
+        let result = loop {
+            let this_ptr = this_ptr.as_deref_mut();
+
+            let err = match self.run_frame(
+                global,
+                caches,
+                scope,
+                this_ptr,
+                chunk,
+                &mut frame,
+                restore_orig_state,
+            ) {
+                Ok(value) => break Ok(value),
+                Err(err) => err,
+            };
+
+            // Handle `break`/`continue` in code run by the tree-walking interpreter
+            let pc = (frame.pc - 1) as Label;
+            let lp = match *err {
+                ERR::LoopBreak(..) => chunk.loops.iter().find(|l| l.start <= pc && pc < l.end),
+                _ => None,
+            };
+            let Some(lp) = lp else {
+                break Err(err);
+            };
+
+            frame.iters.truncate(lp.iters);
+            scope.rewind(frame.base + lp.scope_len);
+
+            match *err {
+                ERR::LoopBreak(true, value, ..) => {
+                    frame.regs[lp.dst as usize] = value;
+                    frame.pc = lp.exit as usize;
+                }
+                _ => frame.pc = lp.next as usize,
+            }
+        };
+
+        // Remove all local variables, keeping those at the top level if the state is not restored
+        frame.iters.clear();
+        scope.rewind(if restore_orig_state {
+            frame.base
+        } else {
+            frame.top_len
+        });
+
+        result
+    }
+
+    /// Run the instructions of a [`Chunk`] until it ends or an error occurs.
+    fn run_frame<'e>(
+        &'e self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        chunk: &Chunk,
+        frame: &mut Frame<'e>,
+        restore_orig_state: bool,
+    ) -> RhaiResult {
+        let Frame {
+            pc,
+            regs,
+            iters,
+            bindings,
+            base,
+            top_len,
+        } = frame;
+
+        loop {
+            let instr = &chunk.code[*pc];
+            *pc += 1;
+
+            match *instr {
+                Instr::Track(pos) => self.track_operation(global, pos)?,
+
+                Instr::Const(dst, index, pos) => {
+                    self.track_operation(global, pos)?;
+                    regs[dst as usize] = chunk.constants[index as usize].clone();
+                }
+
+                Instr::Bool(dst, value) => regs[dst as usize] = value.into(),
+
+                Instr::Unit(dst) => regs[dst as usize] = Dynamic::UNIT,
+
+                Instr::Local(dst, offset, index) => {
+                    let expr = &chunk.exprs[index as usize];
+                    self.track_operation(global, expr.position())?;
+
+                    let target = if global.always_search_scope {
+                        let this_ptr = this_ptr.as_deref_mut();
+                        self.search_namespace(global, caches, scope, this_ptr, expr)?
+                    } else {
+                        let index = scope.len() - offset.get() as usize;
+                        Target::try_from(scope.get_mut_by_index(index))?
+                    };
+
+                    regs[dst as usize] = target.take_or_clone();
+                }
+
+                Instr::Var(dst, index) => {
+                    let expr = &chunk.exprs[index as usize];
+                    self.track_operation(global, expr.position())?;
+
+                    let this_ptr = this_ptr.as_deref_mut();
+                    regs[dst as usize] = self
+                        .search_namespace(global, caches, scope, this_ptr, expr)?
+                        .take_or_clone();
+                }
+
+                Instr::This(dst, pos) => {
+                    self.track_operation(global, pos)?;
+
+                    regs[dst as usize] = this_ptr
+                        .as_deref()
+                        .cloned()
+                        .ok_or_else(|| ERR::ErrorUnboundThis(pos))?;
+                }
+
+                Instr::Eval(dst, index) => {
+                    let expr = &chunk.exprs[index as usize];
+                    let this_ptr = this_ptr.as_deref_mut();
+                    regs[dst as usize] = self.eval_expr(global, caches, scope, this_ptr, expr)?;
+                }
+
+                Instr::Exec(dst, index, top) => {
+                    let stmt = &chunk.stmts[index as usize];
+                    let this_ptr = this_ptr.as_deref_mut();
+                    let rewind_scope = !top || restore_orig_state;
+                    let value =
+                        self.eval_stmt(global, caches, scope, this_ptr, stmt, rewind_scope)?;
+
+                    if let Some(dst) = dst {
+                        regs[dst as usize] = value;
+                    }
+                }
+
+                Instr::Flatten(reg) => {
+                    let value = &mut regs[reg as usize];
+                    *value = mem::take(value).flatten();
+                }
+
+                Instr::Binary(dst, args, call) => {
+                    let site = &chunk.calls[call as usize];
+                    let lhs = mem::take(&mut regs[args as usize]).flatten();
+                    let rhs = mem::take(&mut regs[args as usize + 1]).flatten();
+                    let (name, op_token) = (site.name.as_str(), site.op_token.as_ref().unwrap());
+
+                    regs[dst as usize] = self.eval_binary_op(
+                        global,
+                        caches,
+                        name,
+                        op_token,
+                        site.hashes,
+                        lhs,
+                        rhs,
+                        site.pos,
+                    )?;
+                }
+
+                Instr::Not(dst, arg, call) => {
+                    let mut value = mem::take(&mut regs[arg as usize]).flatten();
+
+                    regs[dst as usize] = if let Union::Bool(b, ..) = value.0 {
+                        (!b).into()
+                    } else {
+                        let site = &chunk.calls[call as usize];
+                        let (name, op_token) = (site.name.as_str(), site.op_token.as_ref());
+                        let operand = &mut [&mut value];
+
+                        self.exec_fn_call(
+                            global,
+                            caches,
+                            None,
+                            name,
+                            op_token,
+                            site.hashes,
+                            operand,
+                            false,
+                            false,
+                            site.pos,
+                        )?
+                        .0
+                    };
+                }
+
+                Instr::Call(dst, args, call) => {
+                    let site = &chunk.calls[call as usize];
+                    let args = &mut regs[args as usize..args as usize + site.num_args];
+
+                    for value in args.iter_mut() {
+                        *value = mem::take(value).flatten();
+                    }
+
+                    let binding = bindings.get_mut(call as usize);
+                    let value = {
+                        let args = &mut args.iter_mut().collect::<FnArgsVec<_>>();
+                        self.call_site_fn(global, caches, site, binding, args, false)?
+                    };
+
+                    regs[dst as usize] = value;
+                }
+
+                Instr::CallRef(dst, args, call) => {
+                    let site = &chunk.calls[call as usize];
+                    let args = &mut regs[args as usize..args as usize + site.num_args];
+                    let (first_arg, rest_args) = args.split_first_mut().unwrap();
+
+                    for value in rest_args.iter_mut() {
+                        *value = mem::take(value).flatten();
+                    }
+
+                    let first = site.first.as_ref().unwrap();
+                    let this_ptr = this_ptr.as_deref_mut();
+                    let mut target =
+                        self.search_namespace(global, caches, scope, this_ptr, first)?;
+
+                    if target.as_ref().is_read_only() {
+                        target = target.into_owned();
+                    }
+
+                    let binding = bindings.get_mut(call as usize);
+
+                    let value = if target.is_shared() || target.is_temp_value() {
+                        *first_arg = target.take_or_clone().flatten();
+                        let args = &mut once(first_arg)
+                            .chain(rest_args.iter_mut())
+                            .collect::<FnArgsVec<_>>();
+                        self.call_site_fn(global, caches, site, binding, args, false)?
+                    } else {
+                        // Pass the variable by reference
+                        let obj = target.take_ref().unwrap();
+                        let args = &mut once(obj)
+                            .chain(rest_args.iter_mut())
+                            .collect::<FnArgsVec<_>>();
+                        self.call_site_fn(global, caches, site, binding, args, true)?
+                    };
+
+                    regs[dst as usize] = value;
+                }
+
+                Instr::Jump(target) => *pc = target as usize,
+
+                Instr::JumpIf(reg, value, target, pos) => {
+                    let condition = regs[reg as usize]
+                        .as_bool()
+                        .map_err(|typ| self.make_type_mismatch_err::<bool>(typ, pos))?;
+
+                    if condition == value {
+                        *pc = target as usize;
+                    }
+                }
+
+                Instr::JumpIfNotUnit(reg, target) => {
+                    if !regs[reg as usize].is_unit() {
+                        *pc = target as usize;
+                    }
+                }
+
+                Instr::CheckVar(index) => {
+                    let this_ptr = this_ptr.as_deref_mut();
+
+                    match chunk.stmts[index as usize] {
+                        Stmt::Var(ref x, options, pos) => {
+                            let (var_name, _, _index) = &**x;
+                            let (access, _) = var_options(options);
+
+                            self.check_var_def(
+                                global, caches, scope, this_ptr, var_name, access, pos,
+                            )?;
+
+                            // Guard against too many variables
+                            #[cfg(not(feature = "unchecked"))]
+                            if _index.is_none() && scope.len() >= self.max_variables() {
+                                return Err(ERR::ErrorTooManyVariables(pos).into());
+                            }
+                        }
+                        Stmt::Destructure(ref x, options, pos) => {
+                            let (_, vars, _) = &**x;
+                            let (access, _) = var_options(options);
+                            let mut this_ptr = this_ptr;
+
+                            for var_name in vars {
+                                self.check_var_def(
+                                    global,
+                                    caches,
+                                    scope,
+                                    this_ptr.as_deref_mut(),
+                                    var_name,
+                                    access,
+                                    pos,
+                                )?;
+                            }
+
+                            // Guard against too many variables
+                            #[cfg(not(feature = "unchecked"))]
+                            if scope.len() + vars.len() > self.max_variables() {
+                                return Err(ERR::ErrorTooManyVariables(pos).into());
+                            }
+                        }
+                        ref stmt => unreachable!("Stmt::Var expected but gets {:?}", stmt),
+                    }
+                }
+
+                Instr::DefineVar(reg, index, top) => {
+                    let Stmt::Var(ref x, options, ..) = chunk.stmts[index as usize] else {
+                        unreachable!("Stmt::Var expected");
+                    };
+                    let (var_name, _, index) = &**x;
+                    let (access, export) = var_options(options);
+                    let value = mem::take(&mut regs[reg as usize]).flatten();
+                    let rewind_scope = !top || restore_orig_state;
+
+                    self.define_var(
+                        global,
+                        scope,
+                        var_name,
+                        value,
+                        access,
+                        export,
+                        rewind_scope,
+                        *index,
+                    );
+
+                    if top {
+                        *top_len = scope.len();
+                    }
+                }
+
+                Instr::Destructure(reg, index, top) => {
+                    let Stmt::Destructure(ref x, options, ..) = chunk.stmts[index as usize] else {
+                        unreachable!("Stmt::Destructure expected");
+                    };
+                    let (pattern, vars, _) = &**x;
+                    let (access, export) = var_options(options);
+                    let value = mem::take(&mut regs[reg as usize]).flatten();
+                    let rewind_scope = !top || restore_orig_state;
+
+                    let mut values = FnArgsVec::new_const();
+                    values.resize(vars.len(), Dynamic::UNIT);
+
+                    self.destructure_pattern(pattern, &value, &mut values)?;
+
+                    for (var_name, value) in vars.iter().zip(values) {
+                        self.define_var(
+                            global,
+                            scope,
+                            var_name,
+                            value,
+                            access,
+                            export,
+                            rewind_scope,
+                            None,
+                        );
+                    }
+
+                    if top {
+                        *top_len = scope.len();
+                    }
+                }
+
+                Instr::Assign(reg, index) => {
+                    let Stmt::Assignment(ref x) = chunk.stmts[index as usize] else {
+                        unreachable!("Stmt::Assignment expected");
+                    };
+                    let (op_info, BinaryExpr { lhs, .. }) = &**x;
+                    let rhs_val = mem::take(&mut regs[reg as usize]).flatten();
+
+                    self.track_operation(global, lhs.position())?;
+
+                    let this_ptr = this_ptr.as_deref_mut();
+                    let mut target = self.search_namespace(global, caches, scope, this_ptr, lhs)?;
+
+                    let is_temp_result = !target.is_ref();
+
+                    #[cfg(not(feature = "no_closure"))]
+                    // Also handle case where target is a `Dynamic` shared value
+                    let is_temp_result = is_temp_result && !target.is_shared();
+
+                    // Cannot assign to temp result from expression
+                    if is_temp_result {
+                        let Expr::Variable(ref v, ..) = lhs else {
+                            unreachable!("Expr::Variable expected");
+                        };
+                        return Err(ERR::ErrorAssignmentToConstant(
+                            v.1.to_string(),
+                            lhs.position(),
+                        )
+                        .into());
+                    }
+
+                    self.eval_op_assignment(global, caches, op_info, lhs, &mut target, rhs_val)?;
+                }
+
+                Instr::Rewind(len) => {
+                    scope.rewind(*base + len);
+                }
+
+                Instr::CheckFor(_index) => {
+                    // Guard against too many variables
+                    #[cfg(not(feature = "unchecked"))]
+                    {
+                        let Stmt::For(ref x, ..) = chunk.stmts[_index as usize] else {
+                            unreachable!("Stmt::For expected");
+                        };
+                        if scope.len() >= self.max_variables() - usize::from(x.1.is_some()) {
+                            return Err(ERR::ErrorTooManyVariables(x.0.pos).into());
+                        }
+                    }
+                }
+
+                Instr::ForInit(reg, index, slot) => {
+                    let Stmt::For(ref x, ..) = chunk.stmts[index as usize] else {
+                        unreachable!("Stmt::For expected");
+                    };
+                    let (var_name, counter, FlowControl { expr, .. }) = &**x;
+                    let iter_obj = mem::take(&mut regs[reg as usize]).flatten();
+
+                    let next = self.make_next_fn(global, iter_obj, expr)?;
+
+                    // Add the loop variables
+                    let counter = counter.as_ref().map(|counter| {
+                        scope.push(counter.name.clone(), 0 as INT);
+                        scope.len() - 1
+                    });
+
+                    scope.push(var_name.name.clone(), ());
+
+                    iters.truncate(slot);
+                    iters.push(ForState {
+                        next,
+                        counter,
+                        index: scope.len() - 1,
+                        count: 0,
+                    });
+                }
+
+                Instr::ForNext(index, slot, exit) => {
+                    let Stmt::For(ref x, ..) = chunk.stmts[index as usize] else {
+                        unreachable!("Stmt::For expected");
+                    };
+                    let (_, _counter, FlowControl { expr, body, .. }) = &**x;
+                    let state = &mut iters[slot];
+
+                    let Some(iter_value) = (state.next)(global) else {
+                        *pc = exit as usize;
+                        continue;
+                    };
+
+                    if body.is_empty() {
+                        if let Err(err) = iter_value {
+                            return Err(err.fill_position(expr.position()));
+                        }
+                        self.track_operation(global, body.position())?;
+                        continue;
+                    }
+
+                    // Increment counter
+                    if let Some(counter_index) = state.counter {
+                        // As the variable increments from 0, this should always work
+                        // since any overflow will first be caught below.
+                        let i = state.count;
+                        let index_value = i as INT;
+
+                        #[cfg(not(feature = "unchecked"))]
+                        #[allow(clippy::absurd_extreme_comparisons)]
+                        if index_value > crate::MAX_USIZE_INT {
+                            return Err(ERR::ErrorArithmetic(
+                                format!("for-loop counter overflow: {i}"),
+                                _counter.as_ref().unwrap().pos,
+                            )
+                            .into());
+                        }
+
+                        *scope.get_mut_by_index(counter_index).write_lock().unwrap() =
+                            Dynamic::from_int(index_value);
+                    }
+
+                    state.count += 1;
+
+                    // Set loop value
+                    let value = iter_value
+                        .map_err(|err| err.fill_position(expr.position()))?
+                        .flatten();
+
+                    *scope.get_mut_by_index(state.index).write_lock().unwrap() = value;
+                }
+
+                Instr::ForEnd(slot, len) => {
+                    iters.truncate(slot);
+                    scope.rewind(*base + len);
+                }
+
+                Instr::Return(reg) => return Ok(mem::take(&mut regs[reg as usize]).flatten()),
+
+                Instr::Throw(reg, pos) => {
+                    let value = mem::take(&mut regs[reg as usize]).flatten();
+                    return Err(ERR::ErrorRuntime(value, pos).into());
+                }
+
+                Instr::End(reg) => return Ok(mem::take(&mut regs[reg as usize])),
+            }
+        }
+    }
+
+    /// Make the function returning the next value of a `for` loop iterating over a value.
+    fn make_next_fn<'e>(
+        &'e self,
+        global: &GlobalRuntimeState,
+        iter_obj: Dynamic,
+        expr: &Expr,
+    ) -> RhaiResultOf<NextFn<'e>> {
+        // Generators are run lazily, keeping track of the number of operations
+        #[cfg(feature = "resumable")]
+        #[cfg(not(feature = "no_function"))]
+        let iter_obj = match iter_obj.try_cast_result::<crate::Generator>() {
+            Ok(generator) => {
+                let pos = expr.start_position();
+                let mut generator = self.start_generator(global, generator, pos)?;
+                return Ok(Box::new(move |global: &mut _| generator.next(global)));
+            }
+            Err(iter_obj) => iter_obj,
+        };
+
+        // Lazy iterators run adapters, which need a context to call functions
+        #[cfg(not(feature = "no_index"))]
+        let iter_obj = match iter_obj.try_cast_result::<crate::LazyIter>() {
+            Ok(iter) => {
+                let pos = expr.start_position();
+                let ctx = (self, "for", None, global, pos).into();
+                let mut state = iter.start(&ctx)?;
+                return Ok(Box::new(move |global: &mut GlobalRuntimeState| {
+                    let ctx = (self, "for", None, &*global, pos).into();
+                    state.next(&ctx)
+                }));
+            }
+            Err(iter_obj) => iter_obj,
+        };
+
+        let iter_func = self
+            .get_type_iterator(global, iter_obj.type_id())
+            .ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
+        let mut iter = iter_func(iter_obj);
+
+        Ok(Box::new(move |_: &mut _| iter.next()))
+    }
+
+    /// Call the function at a call site, binding it to the call site if possible.
+    fn call_site_fn(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        site: &CallSite,
+        binding: Option<&mut Binding>,
+        args: &mut FnCallArgs,
+        is_ref_mut: bool,
+    ) -> RhaiResult {
+        let name = site.name.as_str();
+        let op_token = site.op_token.as_ref();
+
+        if let Some(binding) = binding.filter(|_| site.bindable) {
+            let hash = calc_fn_hash_full(site.hashes.native(), args.iter().map(|a| a.type_id()));
+
+            match binding {
+                Binding::Native(h, entry) if *h == hash => {
+                    return self.call_bound_fn(global, name, entry, args, is_ref_mut, site.pos)
+                }
+                // Arguments of different types
+                Binding::Native(..) => *binding = Binding::None,
+                Binding::None => (),
+                Binding::Unbound => {
+                    // Script-defined functions take precedence
+                    #[cfg(not(feature = "no_function"))]
+                    let is_script = !site.hashes.is_native_only()
+                        && self
+                            .resolve_fn(
+                                global,
+                                caches,
+                                &mut None,
+                                None,
+                                site.hashes.script(),
+                                None,
+                                false,
+                            )
+                            .is_some();
+                    #[cfg(feature = "no_function")]
+                    let is_script = false;
+
+                    *binding = if is_script {
+                        Binding::None
+                    } else {
+                        let local_entry = &mut None;
+                        let hash_base = site.hashes.native();
+                        let a = Some(&mut *args);
+
+                        match self.resolve_fn(
+                            global,
+                            caches,
+                            local_entry,
+                            op_token,
+                            hash_base,
+                            a,
+                            true,
+                        ) {
+                            Some(entry) if entry.func.is_native() => {
+                                Binding::Native(hash, entry.clone())
+                            }
+                            _ => Binding::None,
+                        }
+                    };
+
+                    if let Binding::Native(_, ref entry) = binding {
+                        return self.call_bound_fn(global, name, entry, args, is_ref_mut, site.pos);
+                    }
+                }
+            }
+        }
+
+        self.exec_fn_call(
+            global,
+            caches,
+            None,
+            name,
+            op_token,
+            site.hashes,
+            args,
+            is_ref_mut,
+            false,
+            site.pos,
+        )
+        .map(|(v, ..)| v)
+    }
+
+    /// Call a native function bound to a call site, with the same semantics as
+    /// [`exec_fn_call`][Engine::exec_fn_call].
+    fn call_bound_fn(
+        &self,
+        global: &mut GlobalRuntimeState,
+        name: &str,
+        entry: &FnResolutionCacheEntry,
+        args: &mut FnCallArgs,
+        is_ref_mut: bool,
+        pos: Position,
+    ) -> RhaiResult {
+        // Check for data race.
+        #[cfg(not(feature = "no_closure"))]
+        crate::func::ensure_no_data_race(name, args, is_ref_mut)?;
+
+        defer! { let orig_level = global.level; global.level += 1 }
+
+        self.track_operation(global, pos)?;
+
+        let FnResolutionCacheEntry { func, source } = entry;
+
+        let backup = &mut ArgBackup::new();
+
+        // Calling non-method function but the first argument is a reference?
+        let swap = is_ref_mut && !func.is_method() && !args.is_empty();
+
+        if swap {
+            // Clone the first argument
+            backup.change_first_arg_to_copy(args);
+        }
+
+        // Run external function
+        let context = func
+            .has_context()
+            .then(|| (self, name, source.as_deref(), &*global, pos).into());
+
+        let result = match func {
+            // If function is not pure, there must be at least one argument
+            f if !f.is_pure() && !args.is_empty() && args[0].is_read_only() => {
+                Err(ERR::ErrorNonPureMethodCallOnConstant(name.to_string(), pos).into())
+            }
+            RhaiFunc::Plugin { func } => func.call(context, args),
+            RhaiFunc::Pure { func, .. } | RhaiFunc::Method { func, .. } => func(context, args),
+            _ => unreachable!("non-native function"),
+        }
+        .and_then(|r| self.check_data_size(r, pos))
+        .map_err(|err| err.fill_position(pos));
+
+        if swap {
+            backup.restore_first_arg(args);
+        }
+
+        let result = result?;
+
+        // Check the data size of any `&mut` object, which may be changed.
+        #[cfg(not(feature = "unchecked"))]
+        if is_ref_mut && !args.is_empty() {
+            self.check_data_size(&*args[0], pos)?;
+        }
+
+        Ok(result)
+    }
+}
@@ -92,12 +92,22 @@ pub struct Engine {
     #[cfg(not(feature = "no_module"))]
     pub(crate) global_sub_modules: std::collections::BTreeMap<Identifier, SharedModule>,
 
+    /// A stamp identifying the current set of registered functions and modules, renewed whenever
+    /// they change.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) fn_stamp: Option<crate::Shared<()>>,
+
     /// A module resolution service.
     #[cfg(not(feature = "no_module"))]
     pub(crate) module_resolver: Option<Box<dyn crate::ModuleResolver>>,
 
     /// Strings interner.
     pub(crate) interned_strings: Option<Locked<StringsInterner>>,
+    /// Cache of compiled regular expressions, keyed by pattern.
+    #[cfg(feature = "regex")]
+    pub(crate) regex_cache: Locked<std::collections::BTreeMap<ImmutableString, regex::Regex>>,
+    /// Pseudo-random number generator.
+    pub(crate) rng: Locked<crate::packages::random_basic::Rng>,
 
     /// A set of symbols to disable.
     pub(crate) disabled_symbols: BTreeSet<Identifier>,
@@ -132,6 +142,9 @@ pub struct Engine {
     /// Callback closure for progress reporting.
     #[cfg(not(feature = "unchecked"))]
     pub(crate) progress: Option<Box<crate::func::native::OnProgressCallback>>,
+    /// Callback closure for getting the current date/time.
+    #[cfg(not(feature = "no_time"))]
+    pub(crate) clock: Option<Box<crate::func::native::OnClockCallback>>,
 
     /// Language options.
     pub(crate) options: LangOptions,
@@ -142,11 +155,28 @@ pub struct Engine {
     /// Script optimization level.
     #[cfg(not(feature = "no_optimize"))]
     pub(crate) optimization_level: crate::OptimizationLevel,
+    /// Maximum size of a script-defined function body that can be inlined at call sites.
+    #[cfg(not(feature = "no_optimize"))]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) max_inline_size: usize,
+    /// User-defined optimization passes.
+    #[cfg(not(feature = "no_optimize"))]
+    #[cfg(feature = "internals")]
+    pub(crate) optimizer_passes: Vec<Box<dyn crate::OptimizerPass>>,
 
     /// Max limits.
     #[cfg(not(feature = "unchecked"))]
     pub(crate) limits: crate::api::limits::Limits,
 
+    /// Size of the stack of a resumable evaluation (or a running generator).
+    #[cfg(feature = "resumable")]
+    pub(crate) resumable_stack_size: usize,
+    /// Number of generators currently running, each on its own stack.
+    #[cfg(feature = "resumable")]
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) running_generators: std::sync::atomic::AtomicUsize,
+
     /// Callback closure for debugging.
     #[cfg(feature = "debugging")]
     pub(crate) debugger_interface: Option<(
@@ -184,16 +214,27 @@ impl fmt::Debug for Engine {
 
         #[cfg(not(feature = "unchecked"))]
         f.field("progress", &self.progress.is_some());
+        #[cfg(not(feature = "no_time"))]
+        f.field("clock", &self.clock.is_some());
 
         f.field("options", &self.options)
             .field("default_tag", &self.def_tag);
 
         #[cfg(not(feature = "no_optimize"))]
         f.field("optimization_level", &self.optimization_level);
+        #[cfg(not(feature = "no_optimize"))]
+        #[cfg(not(feature = "no_function"))]
+        f.field("max_inline_size", &self.max_inline_size);
+        #[cfg(not(feature = "no_optimize"))]
+        #[cfg(feature = "internals")]
+        f.field("optimizer_passes", &self.optimizer_passes.len());
 
         #[cfg(not(feature = "unchecked"))]
         f.field("limits", &self.limits);
 
+        #[cfg(feature = "resumable")]
+        f.field("resumable_stack_size", &self.resumable_stack_size);
+
         #[cfg(feature = "debugging")]
         f.field("debugger_interface", &self.debugger_interface.is_some());
 
@@ -233,16 +274,23 @@ pub fn make_setter(id: &str) -> Identifier {
 
 impl Engine {
     /// An empty raw [`Engine`].
+    #[allow(clippy::declare_interior_mutable_const)]
     pub const RAW: Self = Self {
         global_modules: Vec::new(),
 
         #[cfg(not(feature = "no_module"))]
         global_sub_modules: std::collections::BTreeMap::new(),
 
+        #[cfg(not(feature = "no_function"))]
+        fn_stamp: None,
+
         #[cfg(not(feature = "no_module"))]
         module_resolver: None,
 
         interned_strings: None,
+        #[cfg(feature = "regex")]
+        regex_cache: Locked::new(std::collections::BTreeMap::new()),
+        rng: Locked::new(crate::packages::random_basic::Rng::new()),
         disabled_symbols: BTreeSet::new(),
         #[cfg(not(feature = "no_custom_syntax"))]
         custom_keywords: std::collections::BTreeMap::new(),
@@ -265,6 +313,8 @@ impl Engine {
 
         #[cfg(not(feature = "unchecked"))]
         progress: None,
+        #[cfg(not(feature = "no_time"))]
+        clock: None,
 
         options: LangOptions::new(),
 
@@ -272,10 +322,23 @@ impl Engine {
 
         #[cfg(not(feature = "no_optimize"))]
         optimization_level: crate::OptimizationLevel::Simple,
+        #[cfg(not(feature = "no_optimize"))]
+        #[cfg(not(feature = "no_function"))]
+        max_inline_size: crate::optimizer::MAX_INLINE_SIZE,
+        #[cfg(not(feature = "no_optimize"))]
+        #[cfg(feature = "internals")]
+        optimizer_passes: Vec::new(),
 
         #[cfg(not(feature = "unchecked"))]
         limits: crate::api::limits::Limits::new(),
 
+        #[cfg(feature = "resumable")]
+        resumable_stack_size: crate::api::resumable::STACK_SIZE,
+        #[cfg(feature = "resumable")]
+        #[cfg(not(feature = "no_function"))]
+        #[cfg(not(feature = "unchecked"))]
+        running_generators: std::sync::atomic::AtomicUsize::new(0),
+
         #[cfg(feature = "debugging")]
         debugger_interface: None,
     };
@@ -396,6 +459,23 @@ impl Engine {
         self.get_interned_string("")
     }
 
+    /// Get the current date/time from the clock registered via
+    /// [`set_clock`][Engine::set_clock], or from the system clock.
+    ///
+    /// Returns [`None`] if there is no clock available.
+    #[cfg(not(feature = "no_time"))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn current_datetime(&self) -> Option<crate::DateTime> {
+        match self.clock {
+            Some(ref clock) => Some(clock()),
+            #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
+            None => Some(crate::DateTime::now()),
+            #[cfg(all(target_family = "wasm", target_os = "unknown"))]
+            None => None,
+        }
+    }
+
     /// Is there a debugger interface registered with this [`Engine`]?
     #[cfg(feature = "debugging")]
     #[inline(always)]
@@ -1,7 +1,11 @@
 //! System caches.
 
+#[cfg(not(feature = "no_function"))]
+use super::GlobalRuntimeState;
 use crate::func::{RhaiFunc, StraightHashMap};
 use crate::types::BloomFilterU64;
+#[cfg(not(feature = "no_function"))]
+use crate::{Engine, Shared, SharedModule, AST};
 use crate::{ImmutableString, StaticVec};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -87,3 +91,93 @@ impl Caches {
         self.fn_resolution.truncate(len);
     }
 }
+
+/// A cache of function resolution results that is kept across calls to script-defined functions
+/// via [`CallFnOptions::with_cache`][crate::CallFnOptions::with_cache].
+///
+/// Not available under `no_function`.
+///
+/// Cached results are only valid for a particular [`Engine`] and [`AST`]. The cache is
+/// automatically reset when it is used with a different [`AST`], or when functions or modules are
+/// registered into the [`Engine`] in the meantime.
+///
+/// The cache is not kept when a call imports a module containing global functions.
+#[cfg(not(feature = "no_function"))]
+#[derive(Debug, Clone, Default)]
+pub struct EngineCache {
+    /// The [`Engine`] stamp and the [`AST`] functions that the cached results are valid for.
+    key: Option<(Option<Shared<()>>, SharedModule)>,
+    /// Cached function resolution results.
+    fn_resolution: FnResolutionCache,
+}
+
+#[cfg(not(feature = "no_function"))]
+impl EngineCache {
+    /// Create an empty [`EngineCache`].
+    #[inline(always)]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Clear the [`EngineCache`].
+    #[inline]
+    pub fn clear(&mut self) {
+        self.key = None;
+        self.fn_resolution.clear();
+    }
+    /// Is the [`EngineCache`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fn_resolution.dict.is_empty()
+    }
+    /// Move the cached results into [`Caches`] as the current function resolution cache,
+    /// returning its position in the stack.
+    ///
+    /// The cache is reset first if it is not valid for the [`Engine`] and [`AST`].
+    #[must_use]
+    pub(crate) fn load(&mut self, engine: &Engine, ast: &AST, caches: &mut Caches) -> usize {
+        let is_valid = self.key.as_ref().map_or(false, |(stamp, lib)| {
+            let same_stamp = match (stamp, &engine.fn_stamp) {
+                (Some(s1), Some(s2)) => Shared::ptr_eq(s1, s2),
+                (None, None) => true,
+                _ => false,
+            };
+            same_stamp && Shared::ptr_eq(lib, ast.shared_lib())
+        });
+
+        if !is_valid {
+            self.fn_resolution.clear();
+            self.key = Some((engine.fn_stamp.clone(), ast.shared_lib().clone()));
+        }
+
+        let index = caches.fn_resolution.len();
+        caches
+            .fn_resolution
+            .push(std::mem::take(&mut self.fn_resolution));
+        index
+    }
+    /// Move the function resolution cache at a position in the stack of [`Caches`] back into
+    /// the [`EngineCache`] after a call.
+    pub(crate) fn store(
+        &mut self,
+        caches: &mut Caches,
+        index: usize,
+        _global: &GlobalRuntimeState,
+    ) {
+        // Results involving global functions in imported modules are only valid for this call
+        #[cfg(not(feature = "no_module"))]
+        if _global
+            .scan_imports_raw()
+            .any(|(.., m)| m.contains_indexed_global_functions())
+        {
+            self.clear();
+            return;
+        }
+
+        match caches.fn_resolution.get_mut(index) {
+            Some(cache) => self.fn_resolution = std::mem::take(cache),
+            None => self.clear(),
+        }
+    }
+}
@@ -912,6 +912,9 @@ impl Engine {
                         #[cfg(feature = "debugging")]
                         self.dbg(global, caches, x!(s, b), this_ptr.as_deref_mut(), rhs)?;
 
+                        #[cfg(feature = "debugging")]
+                        let watched;
+
                         let index = &mut x.2.clone().into();
                         {
                             let obj = target.as_mut();
@@ -929,12 +932,40 @@ impl Engine {
                             let new_scope = x!(s, b);
 
                             let item = &mut self.get_indexed_mut(
-                                global, caches, new_scope, this_ptr, obj, index, *pos, op_pos,
-                                true, false,
+                                global,
+                                caches,
+                                new_scope,
+                                this_ptr.as_deref_mut(),
+                                obj,
+                                index,
+                                *pos,
+                                op_pos,
+                                true,
+                                false,
                             )?;
+
+                            #[cfg(feature = "debugging")]
+                            let old_val = self
+                                .dbg_is_watched(global, rhs)
+                                .then(|| item.as_ref().flatten_clone());
+
                             self.eval_op_assignment(global, caches, op_info, root, item, new_val)?;
+
+                            #[cfg(feature = "debugging")]
+                            {
+                                watched = old_val.map(|v| (v, item.as_ref().flatten_clone()));
+                            }
                         }
                         self.check_data_size(target.source(), op_info.position())?;
+
+                        #[cfg(feature = "debugging")]
+                        if let Some((old_val, new_val)) = watched {
+                            let scope = x!(s, b);
+                            self.dbg_watch(
+                                global, caches, scope, this_ptr, rhs, &old_val, &new_val,
+                            )?;
+                        }
+
                         Ok((Dynamic::UNIT, true))
                     }
                     // {xxx:map}.id
@@ -966,10 +997,19 @@ impl Engine {
                     // xxx.id op= ???
                     (Expr::Property(x, pos), Some((mut new_val, op_info)), false) => {
                         #[cfg(feature = "debugging")]
-                        self.dbg(global, caches, x!(s, b), this_ptr, rhs)?;
+                        self.dbg(global, caches, x!(s, b), this_ptr.as_deref_mut(), rhs)?;
+
+                        #[cfg(feature = "debugging")]
+                        let is_watched = self.dbg_is_watched(global, rhs);
+                        #[cfg(not(feature = "debugging"))]
+                        let is_watched = false;
 
                         let ((getter, hash_get), (setter, hash_set), name) = &**x;
 
+                        // The getter is not called just for watch-points, so the original value
+                        // is only available for op-assignments
+                        let mut _old_val = is_watched.then_some(Dynamic::UNIT);
+
                         if op_info.is_op_assignment() {
                             let args = &mut [target.as_mut()];
 
@@ -997,6 +1037,10 @@ impl Engine {
                                     _ => Err(err),
                                 })?;
 
+                            if is_watched {
+                                _old_val = Some(orig_val.flatten_clone());
+                            }
+
                             {
                                 let orig_val = &mut (&mut orig_val).try_into()?;
 
@@ -1008,27 +1052,42 @@ impl Engine {
                             new_val = orig_val;
                         }
 
+                        // The setter consumes the new value
+                        let _watched = _old_val.map(|v| (v, new_val.flatten_clone()));
+
                         let args = &mut [target.as_mut(), &mut new_val];
 
-                        self.exec_native_fn_call(
-                            global, caches, setter, None, *hash_set, args, is_ref_mut, false, *pos,
-                        )
-                        .or_else(|err| match *err {
-                            // Try an indexer if property does not exist
-                            ERR::ErrorDotExpr(..) => {
-                                let target = target.as_mut();
-                                let idx = &mut name.into();
-                                let new_val = &mut new_val;
-                                self.call_indexer_set(
-                                    global, caches, target, idx, new_val, is_ref_mut, op_pos,
-                                )
-                                .map_err(|e| match *e {
-                                    ERR::ErrorIndexingType(..) => err,
-                                    _ => e,
-                                })
-                            }
-                            _ => Err(err),
-                        })
+                        let result = self
+                            .exec_native_fn_call(
+                                global, caches, setter, None, *hash_set, args, is_ref_mut, false,
+                                *pos,
+                            )
+                            .or_else(|err| match *err {
+                                // Try an indexer if property does not exist
+                                ERR::ErrorDotExpr(..) => {
+                                    let target = target.as_mut();
+                                    let idx = &mut name.into();
+                                    let new_val = &mut new_val;
+                                    self.call_indexer_set(
+                                        global, caches, target, idx, new_val, is_ref_mut, op_pos,
+                                    )
+                                    .map_err(|e| match *e {
+                                        ERR::ErrorIndexingType(..) => err,
+                                        _ => e,
+                                    })
+                                }
+                                _ => Err(err),
+                            })?;
+
+                        #[cfg(feature = "debugging")]
+                        if let Some((old_val, new_val)) = _watched {
+                            let scope = x!(s, b);
+                            self.dbg_watch(
+                                global, caches, scope, this_ptr, rhs, &old_val, &new_val,
+                            )?;
+                        }
+
+                        Ok(result)
                     }
                     // xxx.id
                     (Expr::Property(x, pos), None, false) => {
@@ -5,6 +5,8 @@ use super::GlobalRuntimeState;
 use crate::types::dynamic::Union;
 use crate::{Dynamic, Engine, Position, RhaiResultOf, ERR};
 use std::borrow::Borrow;
+#[cfg(feature = "bigint")]
+use std::convert::TryFrom;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -39,6 +41,11 @@ pub fn calc_array_sizes(array: &crate::Array) -> (usize, usize, usize) {
                 sx += s;
             }
             Union::Str(ref s, ..) => sx += s.len(),
+            Union::Variant(..) => {
+                let (a, _, s) = calc_variant_sizes(value);
+                ax += a;
+                sx += s;
+            }
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(..) => {
                 unreachable!("shared values discovered within data")
@@ -49,6 +56,47 @@ pub fn calc_array_sizes(array: &crate::Array) -> (usize, usize, usize) {
 
     (ax, mx, sx)
 }
+/// Calculate the sizes of a [`Set`][crate::Set].
+///
+/// Sizes returned are `(` [`Array`][crate::Array], [`Map`][crate::Map] and [`String`] `)`.
+#[cfg(not(feature = "no_index"))]
+#[inline]
+pub fn calc_set_sizes(set: &crate::Set) -> (usize, usize, usize) {
+    let sx = set
+        .iter()
+        .map(|value| match value.0 {
+            Union::Str(ref s, ..) => s.len(),
+            _ => 0,
+        })
+        .sum();
+
+    (set.len(), 0, sx)
+}
+/// Calculate the size of a [`BigInt`][crate::BigInt].
+///
+/// The number of bytes in its magnitude is counted towards the [`String`] size limit.
+#[cfg(feature = "bigint")]
+#[inline]
+#[must_use]
+pub fn calc_bigint_size(value: &crate::BigInt) -> usize {
+    usize::try_from((value.bits() + 7) / 8).unwrap_or(usize::MAX)
+}
+/// Calculate the sizes of a custom type value.
+///
+/// Sizes returned are `(` [`Array`][crate::Array], [`Map`][crate::Map] and [`String`] `)`.
+#[inline]
+fn calc_variant_sizes(_value: &Dynamic) -> (usize, usize, usize) {
+    #[cfg(not(feature = "no_index"))]
+    if let Some(set) = _value.downcast_ref::<crate::Set>() {
+        return calc_set_sizes(set);
+    }
+    #[cfg(feature = "bigint")]
+    if let Some(value) = _value.downcast_ref::<crate::BigInt>() {
+        return (0, 0, calc_bigint_size(value));
+    }
+
+    (0, 0, 0)
+}
 /// Recursively calculate the sizes of a map.
 ///
 /// Sizes returned are `(` [`Array`][crate::Array], [`Map`][crate::Map] and [`String`] `)`.
@@ -81,6 +129,11 @@ pub fn calc_map_sizes(map: &crate::Map) -> (usize, usize, usize) {
                 sx += s;
             }
             Union::Str(ref s, ..) => sx += s.len(),
+            Union::Variant(..) => {
+                let (a, _, s) = calc_variant_sizes(value);
+                ax += a;
+                sx += s;
+            }
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(..) => {
                 unreachable!("shared values discovered within data")
@@ -109,6 +162,7 @@ pub fn calc_data_sizes(value: &Dynamic, _top: bool) -> (usize, usize, usize) {
         #[cfg(not(feature = "no_object"))]
         Union::Map(ref map, ..) => calc_map_sizes(map),
         Union::Str(ref s, ..) => (0, 0, s.len()),
+        Union::Variant(..) => calc_variant_sizes(value),
         #[cfg(not(feature = "no_closure"))]
         Union::Shared(..) if _top => calc_data_sizes(&value.read_lock::<Dynamic>().unwrap(), true),
         #[cfg(not(feature = "no_closure"))]
@@ -80,6 +80,8 @@ pub enum DebuggerEvent<'a> {
     Step,
     /// Break on break-point.
     BreakPoint(usize),
+    /// Break on a watch-point - index of the break-point, old value and new value.
+    WatchPoint(usize, &'a Dynamic, &'a Dynamic),
     /// Return from a function with a value.
     FunctionExitWithValue(&'a Dynamic),
     /// Return from a function with a value.
@@ -130,6 +132,48 @@ pub enum BreakPoint {
         /// Is the break-point enabled?
         enabled: bool,
     },
+    /// Break when a variable is assigned to (including op-assignments).
+    OnVariableChange {
+        /// Variable name.
+        name: ImmutableString,
+        /// Is the break-point enabled?
+        enabled: bool,
+    },
+    /// Break when a property is assigned to (including op-assignments and property setters).
+    ///
+    /// The property getter is not called just to watch a property, so the old value of a plain
+    /// assignment via a property setter is reported as `()`.
+    ///
+    /// Not available under `no_object`.
+    #[cfg(not(feature = "no_object"))]
+    OnPropertyWrite {
+        /// Property name.
+        name: ImmutableString,
+        /// Is the break-point enabled?
+        enabled: bool,
+    },
+    /// Break at another break-point only when a condition holds and/or the number of hits reaches
+    /// a threshold.
+    ///
+    /// If a log message is specified, the message is printed via the `debug` callback instead of
+    /// breaking (i.e. a log-point).
+    ///
+    /// Break-points at a line are only checked at statements.
+    Conditional {
+        /// The underlying break-point.
+        break_point: Box<BreakPoint>,
+        /// Condition (a Rhai expression evaluated in the current scope), if any.
+        condition: Option<ImmutableString>,
+        /// Only break when the number of hits reaches this threshold (zero to break on every hit).
+        hit_count: usize,
+        /// Message to print instead of breaking, if any.
+        ///
+        /// The message is evaluated as an interpolated string, so `${x}` is replaced by the value
+        /// of `x`.
+        log_message: Option<ImmutableString>,
+        /// Number of times that this break-point has been hit.
+        hits: usize,
+    },
 }
 
 impl fmt::Display for BreakPoint {
@@ -180,6 +224,40 @@ impl fmt::Display for BreakPoint {
                 }
                 Ok(())
             }
+            Self::OnVariableChange { name, enabled } => {
+                write!(f, "watch {name}")?;
+                if !*enabled {
+                    f.write_str(" (disabled)")?;
+                }
+                Ok(())
+            }
+            #[cfg(not(feature = "no_object"))]
+            Self::OnPropertyWrite { name, enabled } => {
+                write!(f, "watch .{name}")?;
+                if !*enabled {
+                    f.write_str(" (disabled)")?;
+                }
+                Ok(())
+            }
+            Self::Conditional {
+                break_point,
+                condition,
+                hit_count,
+                log_message,
+                hits,
+            } => {
+                write!(f, "{break_point}")?;
+                if let Some(condition) = condition {
+                    write!(f, " if {condition}")?;
+                }
+                if *hit_count > 0 {
+                    write!(f, " (hits {hits}/{hit_count})")?;
+                }
+                if let Some(message) = log_message {
+                    write!(f, " => log {message:?}")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -195,6 +273,10 @@ impl BreakPoint {
             Self::AtFunctionName { enabled, .. } | Self::AtFunctionCall { enabled, .. } => *enabled,
             #[cfg(not(feature = "no_object"))]
             Self::AtProperty { enabled, .. } => *enabled,
+            Self::OnVariableChange { enabled, .. } => *enabled,
+            #[cfg(not(feature = "no_object"))]
+            Self::OnPropertyWrite { enabled, .. } => *enabled,
+            Self::Conditional { break_point, .. } => break_point.is_enabled(),
         }
     }
     /// Enable/disable this [`BreakPoint`].
@@ -208,6 +290,151 @@ impl BreakPoint {
             }
             #[cfg(not(feature = "no_object"))]
             Self::AtProperty { enabled, .. } => *enabled = value,
+            Self::OnVariableChange { enabled, .. } => *enabled = value,
+            #[cfg(not(feature = "no_object"))]
+            Self::OnPropertyWrite { enabled, .. } => *enabled = value,
+            Self::Conditional { break_point, .. } => break_point.enable(value),
+        }
+    }
+    /// Make this [`BreakPoint`] conditional, if it is not already.
+    #[must_use]
+    fn into_conditional(self) -> Self {
+        match self {
+            Self::Conditional { .. } => self,
+            _ => Self::Conditional {
+                break_point: self.into(),
+                condition: None,
+                hit_count: 0,
+                log_message: None,
+                hits: 0,
+            },
+        }
+    }
+    /// Only break when a condition (a Rhai expression evaluated in the current scope) holds.
+    #[must_use]
+    pub fn with_condition(self, condition: impl Into<ImmutableString>) -> Self {
+        let mut bp = self.into_conditional();
+        if let Self::Conditional {
+            condition: ref mut value,
+            ..
+        } = bp
+        {
+            *value = Some(condition.into());
+        }
+        bp
+    }
+    /// Only break when the number of hits reaches a threshold.
+    #[must_use]
+    pub fn with_hit_count(self, count: usize) -> Self {
+        let mut bp = self.into_conditional();
+        if let Self::Conditional {
+            ref mut hit_count, ..
+        } = bp
+        {
+            *hit_count = count;
+        }
+        bp
+    }
+    /// Print a message instead of breaking (i.e. turn this [`BreakPoint`] into a log-point).
+    ///
+    /// The message is evaluated as an interpolated string, so `${x}` is replaced by the value of `x`.
+    #[must_use]
+    pub fn with_log_message(self, message: impl Into<ImmutableString>) -> Self {
+        let mut bp = self.into_conditional();
+        if let Self::Conditional {
+            ref mut log_message,
+            ..
+        } = bp
+        {
+            *log_message = Some(message.into());
+        }
+        bp
+    }
+    /// Is this [`BreakPoint`] at the beginning of a line?
+    #[must_use]
+    fn is_at_line(&self) -> bool {
+        match self {
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { pos, .. } => pos.is_beginning_of_line(),
+            Self::Conditional { break_point, .. } => break_point.is_at_line(),
+            _ => false,
+        }
+    }
+    /// Is this [`BreakPoint`] triggered by a particular [`AST` Node][ASTNode]?
+    ///
+    /// Conditions are not checked.
+    #[must_use]
+    fn is_triggered_by(&self, src: Option<&str>, node: ASTNode) -> bool {
+        let _src = src;
+
+        match self {
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { pos, .. } if pos.is_none() => false,
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { source, pos, .. } if pos.is_beginning_of_line() => {
+                node.position().line().unwrap_or(0) == pos.line().unwrap()
+                    && _src == source.as_deref()
+            }
+            #[cfg(not(feature = "no_position"))]
+            Self::AtPosition { source, pos, .. } => {
+                node.position() == *pos && _src == source.as_deref()
+            }
+            Self::AtFunctionName { name, .. } => match node {
+                ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
+                    x.name == *name
+                }
+                ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
+                    Expr::FnCall(x, ..) => x.name == *name,
+                    _ => false,
+                },
+                _ => false,
+            },
+            Self::AtFunctionCall { name, args, .. } => match node {
+                ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
+                    x.args.len() == *args && x.name == *name
+                }
+                ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
+                    Expr::FnCall(x, ..) => x.args.len() == *args && x.name == *name,
+                    _ => false,
+                },
+                _ => false,
+            },
+            #[cfg(not(feature = "no_object"))]
+            Self::AtProperty { name, .. } => match node {
+                ASTNode::Expr(Expr::Property(x, ..)) => x.2 == *name,
+                _ => false,
+            },
+            // Watch-points are only triggered by assignments
+            Self::OnVariableChange { .. } => false,
+            #[cfg(not(feature = "no_object"))]
+            Self::OnPropertyWrite { .. } => false,
+            // Only check line break-points at statements to avoid multiple hits per line
+            Self::Conditional { break_point, .. } => {
+                let valid = match node {
+                    ASTNode::Stmt(Stmt::Expr(..)) => break_point.is_at_line(),
+                    ASTNode::Stmt(..) => true,
+                    ASTNode::Expr(..) => !break_point.is_at_line(),
+                };
+                valid && break_point.is_triggered_by(_src, node)
+            }
+        }
+    }
+    /// Is this [`BreakPoint`] a watch-point on the variable or property that is the target of an
+    /// assignment?
+    ///
+    /// Conditions are not checked.
+    #[must_use]
+    fn is_watching(&self, target: ASTNode) -> bool {
+        match (self, target) {
+            (Self::OnVariableChange { name, .. }, ASTNode::Expr(Expr::Variable(x, ..))) => {
+                x.1 == *name
+            }
+            #[cfg(not(feature = "no_object"))]
+            (Self::OnPropertyWrite { name, .. }, ASTNode::Expr(Expr::Property(x, ..))) => {
+                x.2 == *name
+            }
+            (Self::Conditional { break_point, .. }, _) => break_point.is_watching(target),
+            _ => false,
         }
     }
 }
@@ -318,53 +545,13 @@ impl Debugger {
         }
     }
     /// Returns the first break-point triggered by a particular [`AST` Node][ASTNode].
+    ///
+    /// Conditions of [conditional break-points][BreakPoint::Conditional] are not checked.
     #[must_use]
     pub fn is_break_point(&self, src: Option<&str>, node: ASTNode) -> Option<usize> {
-        let _src = src;
-
         self.break_points()
             .iter()
-            .enumerate()
-            .filter(|(.., bp)| bp.is_enabled())
-            .find(|(.., bp)| match bp {
-                #[cfg(not(feature = "no_position"))]
-                BreakPoint::AtPosition { pos, .. } if pos.is_none() => false,
-                #[cfg(not(feature = "no_position"))]
-                BreakPoint::AtPosition { source, pos, .. } if pos.is_beginning_of_line() => {
-                    node.position().line().unwrap_or(0) == pos.line().unwrap()
-                        && _src == source.as_deref()
-                }
-                #[cfg(not(feature = "no_position"))]
-                BreakPoint::AtPosition { source, pos, .. } => {
-                    node.position() == *pos && _src == source.as_deref()
-                }
-                BreakPoint::AtFunctionName { name, .. } => match node {
-                    ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
-                        x.name == *name
-                    }
-                    ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
-                        Expr::FnCall(x, ..) => x.name == *name,
-                        _ => false,
-                    },
-                    _ => false,
-                },
-                BreakPoint::AtFunctionCall { name, args, .. } => match node {
-                    ASTNode::Expr(Expr::FnCall(x, ..)) | ASTNode::Stmt(Stmt::FnCall(x, ..)) => {
-                        x.args.len() == *args && x.name == *name
-                    }
-                    ASTNode::Stmt(Stmt::Expr(e)) => match &**e {
-                        Expr::FnCall(x, ..) => x.args.len() == *args && x.name == *name,
-                        _ => false,
-                    },
-                    _ => false,
-                },
-                #[cfg(not(feature = "no_object"))]
-                BreakPoint::AtProperty { name, .. } => match node {
-                    ASTNode::Expr(Expr::Property(x, ..)) => x.2 == *name,
-                    _ => false,
-                },
-            })
-            .map(|(i, ..)| i)
+            .position(|bp| bp.is_enabled() && bp.is_triggered_by(src, node))
     }
     /// Get a slice of all [`BreakPoint`]'s.
     #[inline(always)]
@@ -452,35 +639,186 @@ impl Engine {
     ) -> RhaiResultOf<Option<DebuggerStatus>> {
         let node = node.into();
 
-        // Skip transitive nodes
-        match node {
-            ASTNode::Expr(Expr::Stmt(..)) | ASTNode::Stmt(Stmt::Expr(..)) => return Ok(None),
-            _ => (),
-        }
+        // Skip transitive nodes, but expression statements still trigger conditional line break-points
+        let transitive = match node {
+            ASTNode::Expr(Expr::Stmt(..)) => return Ok(None),
+            ASTNode::Stmt(Stmt::Expr(..)) => true,
+            _ => false,
+        };
 
-        match global.debugger {
-            Some(ref dbg) => {
-                let event = match dbg.status {
-                    DebuggerStatus::Init => Some(DebuggerEvent::Start),
-                    DebuggerStatus::NEXT if node.is_stmt() => Some(DebuggerEvent::Step),
-                    DebuggerStatus::INTO if node.is_expr() => Some(DebuggerEvent::Step),
-                    DebuggerStatus::STEP => Some(DebuggerEvent::Step),
-                    DebuggerStatus::Terminate => Some(DebuggerEvent::End),
-                    _ => None,
-                };
+        let status = match global.debugger {
+            Some(ref dbg) => dbg.status,
+            None => return Ok(None),
+        };
 
-                let event = match event {
-                    Some(e) => e,
-                    None => match dbg.is_break_point(global.source(), node) {
-                        Some(bp) => DebuggerEvent::BreakPoint(bp),
-                        None => return Ok(None),
+        let event = match status {
+            DebuggerStatus::Init => DebuggerEvent::Start,
+            DebuggerStatus::Terminate => DebuggerEvent::End,
+            // Break-points are checked even when stepping, so that hit counts and log-points work
+            _ => {
+                match self.find_break_point(global, caches, scope, node.position(), |bp, src| {
+                    // Plain break-points are not triggered by transitive nodes
+                    (!transitive || matches!(bp, BreakPoint::Conditional { .. }))
+                        && bp.is_triggered_by(src, node)
+                })? {
+                    Some(bp) => DebuggerEvent::BreakPoint(bp),
+                    None if transitive => return Ok(None),
+                    None => match status {
+                        DebuggerStatus::NEXT if node.is_stmt() => DebuggerEvent::Step,
+                        DebuggerStatus::INTO if node.is_expr() => DebuggerEvent::Step,
+                        DebuggerStatus::STEP => DebuggerEvent::Step,
+                        _ => return Ok(None),
                     },
-                };
+                }
+            }
+        };
+
+        self.dbg_raw(global, caches, scope, this_ptr, node, event)
+    }
+    /// Find the first triggered break-point, checking the conditions of
+    /// [conditional break-points][BreakPoint::Conditional].
+    ///
+    /// All triggered break-points are checked, so that hit counts and log-points are updated.
+    /// Log-points print their messages and never break.
+    fn find_break_point(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        pos: Position,
+        is_triggered: impl Fn(&BreakPoint, Option<&str>) -> bool,
+    ) -> RhaiResultOf<Option<usize>> {
+        let mut triggered = None;
+
+        for index in 0..global.debugger().break_points().len() {
+            let bp = &global.debugger().break_points()[index];
 
-                self.dbg_raw(global, caches, scope, this_ptr, node, event)
+            if !bp.is_enabled() || !is_triggered(bp, global.source()) {
+                continue;
+            }
+
+            let (condition, log_message) = match bp {
+                BreakPoint::Conditional {
+                    condition,
+                    log_message,
+                    ..
+                } => (condition.clone(), log_message.clone()),
+                _ => {
+                    triggered = triggered.or(Some(index));
+                    continue;
+                }
+            };
+
+            // Errors in the condition (or a non-boolean result) break, so that they can be examined
+            if let Some(condition) = condition {
+                let result = self.eval_in_debugger(global, caches, scope, &condition, pos);
+
+                if let Ok(false) = result.map(|v| v.as_bool().unwrap_or(true)) {
+                    continue;
+                }
+            }
+
+            if let BreakPoint::Conditional {
+                hit_count, hits, ..
+            } = &mut global.debugger_mut().break_points_mut()[index]
+            {
+                *hits += 1;
+
+                if *hits < *hit_count {
+                    continue;
+                }
+            }
+
+            match log_message {
+                Some(message) => {
+                    let script = format!("`{}`", message.replace('`', "``"));
+
+                    let text = match self.eval_in_debugger(global, caches, scope, &script, pos) {
+                        Ok(value) => value.to_string(),
+                        Err(err) => err.to_string(),
+                    };
+
+                    if let Some(ref debug) = self.debug {
+                        debug(&text, global.source(), pos);
+                    }
+                }
+                None => triggered = triggered.or(Some(index)),
             }
-            None => Ok(None),
         }
+
+        Ok(triggered)
+    }
+    /// Is the target of an assignment (a variable or a property) watched by a
+    /// [watch-point][BreakPoint::OnVariableChange]?
+    #[inline]
+    #[must_use]
+    pub(crate) fn dbg_is_watched<'a>(
+        &self,
+        global: &GlobalRuntimeState,
+        target: impl Into<ASTNode<'a>>,
+    ) -> bool {
+        let target = target.into();
+
+        global.debugger.as_ref().map_or(false, |dbg| {
+            dbg.break_points()
+                .iter()
+                .any(|bp| bp.is_enabled() && bp.is_watching(target))
+        })
+    }
+    /// Run the debugger callback if the target of an assignment (a variable or a property) is
+    /// watched by a [watch-point][BreakPoint::OnVariableChange].
+    pub(crate) fn dbg_watch<'a>(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        target: impl Into<ASTNode<'a>>,
+        old_value: &Dynamic,
+        new_value: &Dynamic,
+    ) -> RhaiResultOf<()> {
+        let target = target.into();
+
+        let index =
+            match self.find_break_point(global, caches, scope, target.position(), |bp, _| {
+                bp.is_watching(target)
+            })? {
+                Some(index) => index,
+                None => return Ok(()),
+            };
+
+        let event = DebuggerEvent::WatchPoint(index, old_value, new_value);
+
+        if let Some(cmd) = self.dbg_raw(global, caches, scope, this_ptr, target, event)? {
+            global.debugger_mut().status = cmd;
+        }
+
+        Ok(())
+    }
+    /// Evaluate a script in the current scope with the debugger suspended.
+    ///
+    /// Variables defined by the script are removed afterwards.
+    fn eval_in_debugger(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        script: &str,
+        pos: Position,
+    ) -> crate::RhaiResult {
+        let dbg = global.debugger_mut();
+        let status = mem::replace(&mut dbg.status, DebuggerStatus::CONTINUE);
+        let break_points = mem::take(&mut dbg.break_points);
+        let orig_scope_len = scope.len();
+
+        let result = self.eval_script_expr_in_place(global, caches, scope, script, pos);
+
+        scope.rewind(orig_scope_len);
+        let dbg = global.debugger_mut();
+        dbg.status = status;
+        dbg.break_points = break_points;
+
+        result
     }
     /// Run the debugger callback unconditionally.
     ///
@@ -396,6 +396,28 @@ impl Engine {
                 self.eval_stmt_block(global, caches, scope, this_ptr, x.statements(), true)
             }
 
+            // Errors are wrapped just like a call to the function
+            #[cfg(not(feature = "no_function"))]
+            Expr::InlinedFn(x, pos) => self
+                .eval_stmt_block(global, caches, scope, this_ptr, x.1.statements(), true)
+                .map_err(|err| match *err {
+                    mut err @ ERR::Exit(..) => {
+                        err.set_position(*pos);
+                        err.into()
+                    }
+                    mut err if err.is_system_exception() => {
+                        err.set_position(*pos);
+                        err.into()
+                    }
+                    _ => ERR::ErrorInFunctionCall(
+                        x.0.to_string(),
+                        global.source().unwrap_or("").to_string(),
+                        err,
+                        *pos,
+                    )
+                    .into(),
+                }),
+
             #[cfg(not(feature = "no_index"))]
             Expr::Index(..) => {
                 self.eval_dot_index_chain(global, caches, scope, this_ptr, expr, None)
@@ -73,6 +73,10 @@ pub struct GlobalRuntimeState {
     /// Debugging interface.
     #[cfg(feature = "debugging")]
     pub(crate) debugger: Option<Box<super::Debugger>>,
+    /// Bytecode compiled from the script functions being run, if any.
+    #[cfg(feature = "bytecode")]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) bytecode: Option<crate::Shared<crate::bytecode::Program>>,
 }
 
 impl Engine {
@@ -109,6 +113,10 @@ impl Engine {
                 let dbg = crate::eval::Debugger::new(crate::eval::DebuggerStatus::Init);
                 (x.0)(self, dbg).into()
             }),
+
+            #[cfg(feature = "bytecode")]
+            #[cfg(not(feature = "no_function"))]
+            bytecode: None,
         }
     }
 }
@@ -8,6 +8,8 @@ mod global_state;
 mod stmt;
 mod target;
 
+#[cfg(not(feature = "no_function"))]
+pub use cache::EngineCache;
 #[allow(unused_imports)]
 pub use cache::FnResolutionCache;
 pub use cache::{Caches, FnResolutionCacheEntry};
@@ -15,6 +17,9 @@ pub use cache::{Caches, FnResolutionCacheEntry};
 #[cfg(not(feature = "no_index"))]
 pub use data_check::calc_array_sizes;
 #[cfg(not(feature = "unchecked"))]
+#[cfg(feature = "bigint")]
+pub use data_check::calc_bigint_size;
+#[cfg(not(feature = "unchecked"))]
 #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
 pub use data_check::calc_data_sizes;
 #[cfg(feature = "debugging")]
@@ -2,13 +2,18 @@
 
 use super::{Caches, EvalContext, GlobalRuntimeState, Target};
 use crate::ast::{
-    ASTFlags, BinaryExpr, Expr, FlowControl, OpAssignment, Stmt, SwitchCasesCollection,
+    ASTFlags, BinaryExpr, Expr, FlowControl, Ident, MatchArm, OpAssignment, Pattern, RangeCase,
+    Stmt, SwitchCasesCollection,
 };
-use crate::func::{get_builtin_op_assignment_fn, get_hasher};
+use crate::func::{get_builtin_op_assignment_fn, get_hasher, FnIterator};
 use crate::tokenizer::Token;
 use crate::types::dynamic::{AccessMode, Union};
-use crate::{Dynamic, Engine, RhaiResult, RhaiResultOf, Scope, VarDefInfo, ERR, INT};
+use crate::{
+    Dynamic, Engine, ExclusiveRange, FnArgsVec, InclusiveRange, Position, RhaiResult, RhaiResultOf,
+    Scope, StaticVec, VarDefInfo, ERR, INT,
+};
 use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -22,6 +27,517 @@ impl Engine {
         }
     }
 
+    /// Find the type iterator for a type.
+    #[must_use]
+    pub(crate) fn get_type_iterator<'a>(
+        &'a self,
+        global: &'a GlobalRuntimeState,
+        iter_type: std::any::TypeId,
+    ) -> Option<&'a FnIterator> {
+        // lib should only contain scripts, so technically they cannot have iterators
+
+        // Search order:
+        // 1) Global namespace - functions registered via Engine::register_XXX
+        // 2) Global modules - packages
+        // 3) Imported modules - functions marked with global namespace
+        // 4) Global sub-modules - functions marked with global namespace
+        let iter_func = self
+            .global_modules
+            .iter()
+            .find_map(|m| m.get_iter(iter_type));
+
+        #[cfg(not(feature = "no_module"))]
+        let iter_func = iter_func
+            .or_else(|| global.get_iter(iter_type))
+            .or_else(|| {
+                self.global_sub_modules
+                    .values()
+                    .find_map(|m| m.get_qualified_iter(iter_type))
+            });
+
+        #[cfg(feature = "no_module")]
+        let _ = global;
+
+        iter_func
+    }
+
+    /// Evaluate the body of a `for` loop for each value returned by `next`.
+    fn eval_for_loop(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        x: &(Ident, Option<Ident>, FlowControl),
+        mut next: impl FnMut(&mut GlobalRuntimeState) -> Option<RhaiResultOf<Dynamic>>,
+    ) -> RhaiResult {
+        let (var_name, counter, FlowControl { expr, body, .. }) = x;
+
+        // Restore scope at end of statement
+        defer! { scope => rewind; let orig_scope_len = scope.len(); }
+
+        // Add the loop variables
+        let counter_index = counter.as_ref().map(|counter| {
+            scope.push(counter.name.clone(), 0 as INT);
+            scope.len() - 1
+        });
+
+        scope.push(var_name.name.clone(), ());
+        let index = scope.len() - 1;
+
+        let mut result = Dynamic::UNIT;
+
+        if body.is_empty() {
+            while let Some(iter_value) = next(global) {
+                if let Err(err) = iter_value {
+                    return Err(err.fill_position(expr.position()));
+                }
+                self.track_operation(global, body.position())?;
+            }
+        } else {
+            for i in 0_usize.. {
+                let Some(iter_value) = next(global) else {
+                    break;
+                };
+
+                // Increment counter
+                if let Some(counter_index) = counter_index {
+                    // As the variable increments from 0, this should always work
+                    // since any overflow will first be caught below.
+                    let index_value = i as INT;
+
+                    #[cfg(not(feature = "unchecked"))]
+                    #[allow(clippy::absurd_extreme_comparisons)]
+                    if index_value > crate::MAX_USIZE_INT {
+                        return Err(ERR::ErrorArithmetic(
+                            format!("for-loop counter overflow: {i}"),
+                            counter.as_ref().unwrap().pos,
+                        )
+                        .into());
+                    }
+
+                    *scope.get_mut_by_index(counter_index).write_lock().unwrap() =
+                        Dynamic::from_int(index_value);
+                }
+
+                // Set loop value
+                let value = iter_value
+                    .map_err(|err| err.fill_position(expr.position()))?
+                    .flatten();
+
+                *scope.get_mut_by_index(index).write_lock().unwrap() = value;
+
+                // Run block
+                let this_ptr = this_ptr.as_deref_mut();
+                let statements = body.statements();
+
+                match self.eval_stmt_block(global, caches, scope, this_ptr, statements, true) {
+                    Ok(_) => (),
+                    Err(err) => match *err {
+                        ERR::LoopBreak(false, ..) => (),
+                        ERR::LoopBreak(true, value, ..) => {
+                            result = value;
+                            break;
+                        }
+                        _ => return Err(err),
+                    },
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Evaluate a `match` expression.
+    fn eval_match(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        x: &(Expr, StaticVec<MatchArm>),
+    ) -> RhaiResult {
+        let (expr, arms) = x;
+
+        let value = self
+            .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
+            .flatten();
+
+        // Restore scope at end of statement
+        defer! { scope => rewind; let orig_scope_len = scope.len(); }
+
+        let mut values = FnArgsVec::new_const();
+
+        for MatchArm {
+            pattern,
+            vars,
+            condition,
+            expr,
+        } in arms
+        {
+            values.clear();
+            values.resize(vars.len(), Dynamic::UNIT);
+
+            if !self.match_pattern(pattern, &value, &mut values) {
+                continue;
+            }
+
+            // Add the bound variables
+            for (var, value) in vars.iter().zip(values.drain(..)) {
+                scope.push(var.name.clone(), value);
+            }
+
+            let cond_result = match condition {
+                Expr::BoolConstant(b, ..) => *b,
+                c => self
+                    .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), c)?
+                    .as_bool()
+                    .map_err(|typ| self.make_type_mismatch_err::<bool>(typ, c.position()))?,
+            };
+
+            if cond_result {
+                return self.eval_expr(global, caches, scope, this_ptr, expr);
+            }
+
+            scope.rewind(orig_scope_len);
+        }
+
+        Ok(Dynamic::UNIT)
+    }
+
+    /// Match a value against a [`Pattern`].
+    ///
+    /// The values of variables bound by the pattern are stored into `values`, indexed by their
+    /// positions in the list of variables bound by the containing arm.
+    pub(crate) fn match_pattern(
+        &self,
+        pattern: &Pattern,
+        value: &Dynamic,
+        values: &mut [Dynamic],
+    ) -> bool {
+        #[cfg(not(feature = "no_closure"))]
+        if value.is_shared() {
+            return self.match_pattern(pattern, &value.flatten_clone(), values);
+        }
+
+        match pattern {
+            Pattern::Wildcard(..) => true,
+
+            Pattern::Value(x, ..) => {
+                if let Some(range) = x.read_lock::<ExclusiveRange>() {
+                    return RangeCase::from(range.clone()).contains(value);
+                }
+                if let Some(range) = x.read_lock::<InclusiveRange>() {
+                    return RangeCase::from(range.clone()).contains(value);
+                }
+
+                match (&x.0, &value.0) {
+                    (Union::Unit(..), Union::Unit(..)) => true,
+                    (Union::Bool(a, ..), Union::Bool(b, ..)) => a == b,
+                    (Union::Int(a, ..), Union::Int(b, ..)) => a == b,
+                    #[cfg(not(feature = "no_float"))]
+                    (Union::Float(a, ..), Union::Float(b, ..)) => **a == **b,
+                    #[cfg(feature = "decimal")]
+                    (Union::Decimal(a, ..), Union::Decimal(b, ..)) => **a == **b,
+                    (Union::Char(a, ..), Union::Char(b, ..)) => a == b,
+                    (Union::Str(a, ..), Union::Str(b, ..)) => a == b,
+                    _ => false,
+                }
+            }
+
+            Pattern::Bind(_, index) => {
+                values[*index] = value.clone();
+                true
+            }
+
+            Pattern::Typed(x) => {
+                let (pattern, typ) = &**x;
+
+                let is_type = match typ.as_str() {
+                    "int" => value.is_int(),
+                    #[cfg(not(feature = "no_float"))]
+                    "float" => value.is_float(),
+                    typ => self.map_type_name(value.type_name()) == typ,
+                };
+
+                is_type && self.match_pattern(pattern, value, values)
+            }
+
+            #[cfg(not(feature = "no_index"))]
+            Pattern::Array(items, ..) => {
+                let Union::Array(ref array, ..) = value.0 else {
+                    return false;
+                };
+
+                let Some(n) = items.iter().position(|p| matches!(p, Pattern::Rest(..))) else {
+                    return array.len() == items.len()
+                        && items
+                            .iter()
+                            .zip(array.iter())
+                            .all(|(p, v)| self.match_pattern(p, v, values));
+                };
+
+                // [ head .., .. rest, tail .. ]
+                let tail_len = items.len() - n - 1;
+
+                if array.len() < n + tail_len {
+                    return false;
+                }
+
+                let tail = array.len() - tail_len;
+
+                items[..n]
+                    .iter()
+                    .zip(array[..n].iter())
+                    .chain(items[n + 1..].iter().zip(array[tail..].iter()))
+                    .all(|(p, v)| self.match_pattern(p, v, values))
+                    && match items[n] {
+                        Pattern::Rest(Some((_, index)), ..) => {
+                            values[index] = array[n..tail].to_vec().into();
+                            true
+                        }
+                        _ => true,
+                    }
+            }
+            #[cfg(not(feature = "no_index"))]
+            Pattern::Rest(..) => true,
+
+            #[cfg(not(feature = "no_object"))]
+            Pattern::Map(items, ..) => {
+                let Union::Map(ref map, ..) = value.0 else {
+                    return false;
+                };
+
+                items.iter().all(|(key, p)| {
+                    map.get(key.as_str())
+                        .map_or(false, |v| self.match_pattern(p, v, values))
+                })
+            }
+
+            Pattern::Or(x) => x.iter().any(|p| self.match_pattern(p, value, values)),
+        }
+    }
+
+    /// Destructure a value according to a [`Pattern`] in a `let` or `const` statement.
+    ///
+    /// The values of variables bound by the pattern are stored into `values`, indexed by their
+    /// positions in the list of variables bound by the statement.
+    ///
+    /// Unlike [`match_pattern`][Engine::match_pattern], a value that does not fit the shape of the
+    /// pattern is an error.
+    pub(crate) fn destructure_pattern(
+        &self,
+        pattern: &Pattern,
+        value: &Dynamic,
+        values: &mut [Dynamic],
+    ) -> RhaiResultOf<()> {
+        #[cfg(not(feature = "no_closure"))]
+        if value.is_shared() {
+            return self.destructure_pattern(pattern, &value.flatten_clone(), values);
+        }
+
+        match pattern {
+            Pattern::Wildcard(..) => Ok(()),
+
+            Pattern::Bind(_, index) => {
+                values[*index] = value.clone();
+                Ok(())
+            }
+
+            Pattern::Typed(x) => {
+                let (pattern, typ) = &**x;
+
+                let is_type = match typ.as_str() {
+                    "int" => value.is_int(),
+                    #[cfg(not(feature = "no_float"))]
+                    "float" => value.is_float(),
+                    typ => self.map_type_name(value.type_name()) == typ,
+                };
+
+                if !is_type {
+                    let actual = self.map_type_name(value.type_name());
+                    return Err(ERR::ErrorMismatchDataType(
+                        typ.as_str().into(),
+                        actual.into(),
+                        typ.pos,
+                    )
+                    .into());
+                }
+
+                self.destructure_pattern(pattern, value, values)
+            }
+
+            #[cfg(not(feature = "no_index"))]
+            Pattern::Array(items, pos) => {
+                let Union::Array(ref array, ..) = value.0 else {
+                    let typ = self.map_type_name(value.type_name());
+                    return Err(self.make_type_mismatch_err::<crate::Array>(typ, *pos));
+                };
+
+                let rest = items.iter().position(|p| matches!(p, Pattern::Rest(..)));
+                let min_len = rest.map_or(items.len(), |_| items.len() - 1);
+
+                if array.len() < min_len {
+                    return Err(ERR::ErrorIndexNotFound((array.len() as INT).into(), *pos).into());
+                }
+                if rest.is_none() && array.len() > min_len {
+                    return Err(ERR::ErrorMismatchDataType(
+                        format!("array of length {}", items.len()),
+                        format!("array of length {}", array.len()),
+                        *pos,
+                    )
+                    .into());
+                }
+
+                let n = rest.unwrap_or(items.len());
+                let tail = array.len() - (items.len() - n).saturating_sub(1);
+
+                for (p, v) in items[..n].iter().zip(array[..n].iter()) {
+                    self.destructure_pattern(p, v, values)?;
+                }
+
+                if let Some(Pattern::Rest(var, ..)) = items.get(n) {
+                    if let Some((_, index)) = var {
+                        values[*index] = array[n..tail].to_vec().into();
+                    }
+                    for (p, v) in items[n + 1..].iter().zip(array[tail..].iter()) {
+                        self.destructure_pattern(p, v, values)?;
+                    }
+                }
+
+                Ok(())
+            }
+            #[cfg(not(feature = "no_index"))]
+            Pattern::Rest(..) => Ok(()),
+
+            #[cfg(not(feature = "no_object"))]
+            Pattern::Map(items, pos) => {
+                let Union::Map(ref map, ..) = value.0 else {
+                    let typ = self.map_type_name(value.type_name());
+                    return Err(self.make_type_mismatch_err::<crate::Map>(typ, *pos));
+                };
+
+                for (key, p) in items.iter() {
+                    let Some(v) = map.get(key.as_str()) else {
+                        return Err(
+                            ERR::ErrorIndexNotFound(key.name.clone().into(), key.pos).into()
+                        );
+                    };
+                    self.destructure_pattern(p, v, values)?;
+                }
+
+                Ok(())
+            }
+
+            // Literals and alternatives are not produced by the parser for a destructuring
+            // statement, but may still come from a deserialized `AST`
+            Pattern::Value(..) | Pattern::Or(..) => {
+                if self.match_pattern(pattern, value, values) {
+                    Ok(())
+                } else {
+                    let typ = self.map_type_name(value.type_name());
+                    Err(
+                        ERR::ErrorMismatchDataType(String::new(), typ.into(), pattern.position())
+                            .into(),
+                    )
+                }
+            }
+        }
+    }
+
+    /// Check whether a variable can be defined, running the variable definition filter (if any).
+    pub(crate) fn check_var_def(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        var_name: &Ident,
+        access: AccessMode,
+        pos: Position,
+    ) -> RhaiResultOf<()> {
+        if !self.allow_shadowing() && scope.contains(var_name.as_str()) {
+            return Err(ERR::ErrorVariableExists(var_name.as_str().to_string(), pos).into());
+        }
+
+        // Check variable definition filter
+        if let Some(ref filter) = self.def_var_filter {
+            let will_shadow = scope.contains(var_name.as_str());
+            let is_const = access == AccessMode::ReadOnly;
+            let info =
+                VarDefInfo::new(var_name.as_str(), is_const, global.scope_level, will_shadow);
+            let orig_scope_len = scope.len();
+            let context = EvalContext::new(self, global, caches, scope, this_ptr);
+            let filter_result = filter(true, info, context);
+
+            if orig_scope_len != scope.len() {
+                // The scope is changed, always search from now on
+                global.always_search_scope = true;
+            }
+
+            if !filter_result? {
+                return Err(ERR::ErrorForbiddenVariable(var_name.as_str().to_string(), pos).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Put the value of a newly-defined variable into the [`Scope`].
+    ///
+    /// If `index` is [`Some`], the variable reuses an existing entry in the [`Scope`] at that
+    /// offset from the end.
+    pub(crate) fn define_var(
+        &self,
+        _global: &mut GlobalRuntimeState,
+        scope: &mut Scope,
+        var_name: &Ident,
+        value: Dynamic,
+        access: AccessMode,
+        export: bool,
+        rewind_scope: bool,
+        index: Option<NonZeroUsize>,
+    ) {
+        let mut value = self.intern_string(value);
+
+        let _alias = if !rewind_scope {
+            // Put global constants into global module
+            #[cfg(not(feature = "no_function"))]
+            #[cfg(not(feature = "no_module"))]
+            if _global.scope_level == 0
+                && access == AccessMode::ReadOnly
+                && _global.lib.iter().any(|m| !m.is_empty())
+            {
+                crate::func::locked_write(_global.constants.get_or_insert_with(|| {
+                    crate::Shared::new(crate::Locked::new(std::collections::BTreeMap::new()))
+                }))
+                .unwrap()
+                .insert(var_name.name.clone(), value.clone());
+            }
+
+            export.then_some(var_name)
+        } else if !export {
+            None
+        } else {
+            unreachable!("exported variable not on global level");
+        };
+
+        match index {
+            Some(index) => {
+                value.set_access_mode(access);
+                *scope.get_mut_by_index(scope.len() - index.get()) = value;
+            }
+            _ => {
+                scope.push_entry(var_name.name.clone(), access, value);
+            }
+        }
+
+        #[cfg(not(feature = "no_module"))]
+        if let Some(alias) = _alias {
+            scope.add_alias_by_index(scope.len() - 1, alias.as_str().into());
+        }
+    }
+
     /// Evaluate a statements block.
     pub(crate) fn eval_stmt_block(
         &self,
@@ -325,7 +841,8 @@ impl Engine {
 
                     self.track_operation(global, lhs.position())?;
 
-                    let mut target = self.search_namespace(global, caches, scope, this_ptr, lhs)?;
+                    let mut target =
+                        self.search_namespace(global, caches, scope, this_ptr.as_deref_mut(), lhs)?;
 
                     let is_temp_result = !target.is_ref();
 
@@ -343,7 +860,19 @@ impl Engine {
                         .into());
                     }
 
+                    #[cfg(feature = "debugging")]
+                    let old_val = self
+                        .dbg_is_watched(global, lhs)
+                        .then(|| target.as_ref().flatten_clone());
+
                     self.eval_op_assignment(global, caches, op_info, lhs, &mut target, rhs_val)?;
+
+                    #[cfg(feature = "debugging")]
+                    if let Some(old_val) = old_val {
+                        let new_val = target.as_ref().flatten_clone();
+                        drop(target);
+                        self.dbg_watch(global, caches, scope, this_ptr, lhs, &old_val, &new_val)?;
+                    }
                 } else {
                     #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
                     {
@@ -383,10 +912,6 @@ impl Engine {
 
             // Variable definition
             Stmt::Var(x, options, pos) => {
-                if !self.allow_shadowing() && scope.contains(x.0.as_str()) {
-                    return Err(ERR::ErrorVariableExists(x.0.as_str().to_string(), *pos).into());
-                }
-
                 // Let/const statement
                 let (var_name, expr, index) = &**x;
 
@@ -397,34 +922,15 @@ impl Engine {
                 };
                 let export = options.intersects(ASTFlags::EXPORTED);
 
-                // Check variable definition filter
-                if let Some(ref filter) = self.def_var_filter {
-                    let will_shadow = scope.contains(var_name.as_str());
-                    let is_const = access == AccessMode::ReadOnly;
-                    let info = VarDefInfo::new(
-                        var_name.as_str(),
-                        is_const,
-                        global.scope_level,
-                        will_shadow,
-                    );
-                    let orig_scope_len = scope.len();
-                    let context =
-                        EvalContext::new(self, global, caches, scope, this_ptr.as_deref_mut());
-                    let filter_result = filter(true, info, context);
-
-                    if orig_scope_len != scope.len() {
-                        // The scope is changed, always search from now on
-                        global.always_search_scope = true;
-                    }
-
-                    if !filter_result? {
-                        return Err(ERR::ErrorForbiddenVariable(
-                            var_name.as_str().to_string(),
-                            *pos,
-                        )
-                        .into());
-                    }
-                }
+                self.check_var_def(
+                    global,
+                    caches,
+                    scope,
+                    this_ptr.as_deref_mut(),
+                    var_name,
+                    access,
+                    *pos,
+                )?;
 
                 // Guard against too many variables
                 #[cfg(not(feature = "unchecked"))]
@@ -436,45 +942,72 @@ impl Engine {
                 let value = self
                     .eval_expr(global, caches, scope, this_ptr, expr)?
                     .flatten();
-                let mut value = self.intern_string(value);
 
-                let _alias = if !rewind_scope {
-                    // Put global constants into global module
-                    #[cfg(not(feature = "no_function"))]
-                    #[cfg(not(feature = "no_module"))]
-                    if global.scope_level == 0
-                        && access == AccessMode::ReadOnly
-                        && global.lib.iter().any(|m| !m.is_empty())
-                    {
-                        crate::func::locked_write(global.constants.get_or_insert_with(|| {
-                            crate::Shared::new(
-                                crate::Locked::new(std::collections::BTreeMap::new()),
-                            )
-                        }))
-                        .unwrap()
-                        .insert(var_name.name.clone(), value.clone());
-                    }
+                self.define_var(
+                    global,
+                    scope,
+                    var_name,
+                    value,
+                    access,
+                    export,
+                    rewind_scope,
+                    *index,
+                );
+
+                Ok(Dynamic::UNIT)
+            }
+
+            // Destructuring variable definition
+            Stmt::Destructure(x, options, pos) => {
+                // Let/const statement
+                let (pattern, vars, expr) = &**x;
 
-                    export.then_some(var_name)
-                } else if !export {
-                    None
+                let access = if options.intersects(ASTFlags::CONSTANT) {
+                    AccessMode::ReadOnly
                 } else {
-                    unreachable!("exported variable not on global level");
+                    AccessMode::ReadWrite
                 };
+                let export = options.intersects(ASTFlags::EXPORTED);
 
-                match index {
-                    Some(index) => {
-                        value.set_access_mode(access);
-                        *scope.get_mut_by_index(scope.len() - index.get()) = value;
-                    }
-                    _ => {
-                        scope.push_entry(var_name.name.clone(), access, value);
-                    }
+                for var_name in vars {
+                    self.check_var_def(
+                        global,
+                        caches,
+                        scope,
+                        this_ptr.as_deref_mut(),
+                        var_name,
+                        access,
+                        *pos,
+                    )?;
                 }
 
-                #[cfg(not(feature = "no_module"))]
-                if let Some(alias) = _alias {
-                    scope.add_alias_by_index(scope.len() - 1, alias.as_str().into());
+                // Guard against too many variables
+                #[cfg(not(feature = "unchecked"))]
+                if scope.len() + vars.len() > self.max_variables() {
+                    return Err(ERR::ErrorTooManyVariables(*pos).into());
+                }
+
+                // Evaluate initial value
+                let value = self
+                    .eval_expr(global, caches, scope, this_ptr, expr)?
+                    .flatten();
+
+                let mut values = FnArgsVec::new_const();
+                values.resize(vars.len(), Dynamic::UNIT);
+
+                self.destructure_pattern(pattern, &value, &mut values)?;
+
+                for (var_name, value) in vars.iter().zip(values) {
+                    self.define_var(
+                        global,
+                        scope,
+                        var_name,
+                        value,
+                        access,
+                        export,
+                        rewind_scope,
+                        None,
+                    );
                 }
 
                 Ok(Dynamic::UNIT)
@@ -507,6 +1040,7 @@ impl Engine {
                         cases,
                         def_case,
                         ranges,
+                        ..
                     },
                 ) = &**x;
 
@@ -571,6 +1105,9 @@ impl Engine {
                     })
             }
 
+            // Match expression
+            Stmt::Match(x, ..) => self.eval_match(global, caches, scope, this_ptr, x),
+
             // Loop
             Stmt::While(x, ..)
                 if matches!(x.expr, Expr::Unit(..) | Expr::BoolConstant(true, ..)) =>
@@ -665,111 +1202,53 @@ impl Engine {
 
             // For loop
             Stmt::For(x, ..) => {
-                let (var_name, counter, FlowControl { expr, body, .. }) = &**x;
+                let FlowControl { expr, .. } = &x.2;
 
                 // Guard against too many variables
                 #[cfg(not(feature = "unchecked"))]
-                if scope.len() >= self.max_variables() - usize::from(counter.is_some()) {
-                    return Err(ERR::ErrorTooManyVariables(var_name.pos).into());
+                if scope.len() >= self.max_variables() - usize::from(x.1.is_some()) {
+                    return Err(ERR::ErrorTooManyVariables(x.0.pos).into());
                 }
 
                 let iter_obj = self
                     .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
                     .flatten();
 
-                let iter_type = iter_obj.type_id();
-
-                // lib should only contain scripts, so technically they cannot have iterators
-
-                // Search order:
-                // 1) Global namespace - functions registered via Engine::register_XXX
-                // 2) Global modules - packages
-                // 3) Imported modules - functions marked with global namespace
-                // 4) Global sub-modules - functions marked with global namespace
-                let iter_func = self
-                    .global_modules
-                    .iter()
-                    .find_map(|m| m.get_iter(iter_type));
-
-                #[cfg(not(feature = "no_module"))]
-                let iter_func = iter_func
-                    .or_else(|| global.get_iter(iter_type))
-                    .or_else(|| {
-                        self.global_sub_modules
-                            .values()
-                            .find_map(|m| m.get_qualified_iter(iter_type))
-                    });
-
-                let iter_func = iter_func.ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
-
-                // Restore scope at end of statement
-                defer! { scope => rewind; let orig_scope_len = scope.len(); }
-
-                // Add the loop variables
-                let counter_index = counter.as_ref().map(|counter| {
-                    scope.push(counter.name.clone(), 0 as INT);
-                    scope.len() - 1
-                });
-
-                scope.push(var_name.name.clone(), ());
-                let index = scope.len() - 1;
-
-                let mut result = Dynamic::UNIT;
-
-                if body.is_empty() {
-                    for _ in iter_func(iter_obj) {
-                        self.track_operation(global, body.position())?;
+                // Generators are run lazily, keeping track of the number of operations
+                #[cfg(feature = "resumable")]
+                #[cfg(not(feature = "no_function"))]
+                let iter_obj = match iter_obj.try_cast_result::<crate::Generator>() {
+                    Ok(generator) => {
+                        let pos = expr.start_position();
+                        let mut generator = self.start_generator(global, generator, pos)?;
+                        let next = |global: &mut _| generator.next(global);
+                        return self.eval_for_loop(global, caches, scope, this_ptr, x, next);
                     }
-                } else {
-                    for (i, iter_value) in iter_func(iter_obj).enumerate() {
-                        // Increment counter
-                        if let Some(counter_index) = counter_index {
-                            // As the variable increments from 0, this should always work
-                            // since any overflow will first be caught below.
-                            let index_value = i as INT;
-
-                            #[cfg(not(feature = "unchecked"))]
-                            #[allow(clippy::absurd_extreme_comparisons)]
-                            if index_value > crate::MAX_USIZE_INT {
-                                return Err(ERR::ErrorArithmetic(
-                                    format!("for-loop counter overflow: {i}"),
-                                    counter.as_ref().unwrap().pos,
-                                )
-                                .into());
-                            }
-
-                            *scope.get_mut_by_index(counter_index).write_lock().unwrap() =
-                                Dynamic::from_int(index_value);
-                        }
-
-                        // Set loop value
-                        let value = iter_value
-                            .map_err(|err| err.fill_position(expr.position()))?
-                            .flatten();
-
-                        *scope.get_mut_by_index(index).write_lock().unwrap() = value;
-
-                        // Run block
-                        let this_ptr = this_ptr.as_deref_mut();
-                        let statements = body.statements();
+                    Err(iter_obj) => iter_obj,
+                };
 
-                        match self
-                            .eval_stmt_block(global, caches, scope, this_ptr, statements, true)
-                        {
-                            Ok(_) => (),
-                            Err(err) => match *err {
-                                ERR::LoopBreak(false, ..) => (),
-                                ERR::LoopBreak(true, value, ..) => {
-                                    result = value;
-                                    break;
-                                }
-                                _ => return Err(err),
-                            },
-                        }
+                // Lazy iterators run adapters, which need a context to call functions
+                #[cfg(not(feature = "no_index"))]
+                let iter_obj = match iter_obj.try_cast_result::<crate::LazyIter>() {
+                    Ok(iter) => {
+                        let pos = expr.start_position();
+                        let ctx = (self, "for", None, &*global, pos).into();
+                        let mut state = iter.start(&ctx)?;
+                        let next = |global: &mut GlobalRuntimeState| {
+                            let ctx = (self, "for", None, &*global, pos).into();
+                            state.next(&ctx)
+                        };
+                        return self.eval_for_loop(global, caches, scope, this_ptr, x, next);
                     }
-                }
+                    Err(iter_obj) => iter_obj,
+                };
+
+                let iter_func = self
+                    .get_type_iterator(global, iter_obj.type_id())
+                    .ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
+                let mut iter = iter_func(iter_obj);
 
-                Ok(result)
+                self.eval_for_loop(global, caches, scope, this_ptr, x, |_| iter.next())
             }
 
             // Continue/Break statement
@@ -889,6 +1368,24 @@ impl Engine {
             // Empty return
             Stmt::Return(None, .., pos) => Err(ERR::Return(Dynamic::UNIT, *pos).into()),
 
+            // Yield value to the `for` loop running the generator
+            #[cfg(feature = "resumable")]
+            Stmt::Yield(expr, pos) => {
+                let _value = match expr {
+                    Some(expr) => self
+                        .eval_expr(global, caches, scope, this_ptr, expr)?
+                        .flatten(),
+                    None => Dynamic::UNIT,
+                };
+
+                #[cfg(not(feature = "no_function"))]
+                if crate::types::generator::yield_value(global, _value) {
+                    return Ok(Dynamic::UNIT);
+                }
+
+                Err(ERR::ErrorRuntime("yield outside of a generator".into(), *pos).into())
+            }
+
             // Import statement
             #[cfg(not(feature = "no_module"))]
             Stmt::Import(x, _pos) => {
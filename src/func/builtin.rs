@@ -39,6 +39,46 @@ fn const_true_fn(_: Option<NativeCallContext>, _: &mut [&mut Dynamic]) -> RhaiRe
 fn const_false_fn(_: Option<NativeCallContext>, _: &mut [&mut Dynamic]) -> RhaiResult {
     Ok(Dynamic::FALSE)
 }
+/// Returns true if the types are a [`BigInt`][crate::BigInt] and a [`BigInt`][crate::BigInt] or an
+/// integer, in either order.
+#[cfg(feature = "bigint")]
+#[inline(always)]
+fn is_bigint_pair(type1: TypeId, type2: TypeId) -> bool {
+    let (big, int) = (TypeId::of::<crate::BigInt>(), TypeId::of::<INT>());
+
+    (type1 == big && (type2 == big || type2 == int)) || (type1 == int && type2 == big)
+}
+/// Get an operand (either a [`BigInt`][crate::BigInt] or an integer) as a [`BigInt`][crate::BigInt].
+#[cfg(feature = "bigint")]
+#[inline]
+fn bigint_arg(value: &Dynamic) -> crate::BigInt {
+    value.as_int().map_or_else(
+        |_| value.read_lock::<crate::BigInt>().unwrap().clone(),
+        Into::into,
+    )
+}
+/// Get an operand (either a [`BigInt`][crate::BigInt] or an integer) as an integer.
+#[cfg(feature = "bigint")]
+#[inline]
+fn bigint_int_arg(value: &Dynamic) -> crate::RhaiResultOf<INT> {
+    use num_traits::ToPrimitive;
+
+    value.as_int().or_else(|_| {
+        let x = value.read_lock::<crate::BigInt>().unwrap();
+
+        #[cfg(feature = "only_i32")]
+        let n = x.to_i32();
+        #[cfg(not(feature = "only_i32"))]
+        let n = x.to_i64();
+
+        n.ok_or_else(|| {
+            crate::packages::arithmetic::make_err(format!(
+                "Integer overflow: {} does not fit into an integer",
+                *x
+            ))
+        })
+    })
+}
 /// Returns true if the type is numeric.
 #[inline(always)]
 fn is_numeric(typ: TypeId) -> bool {
@@ -56,6 +96,11 @@ fn is_numeric(typ: TypeId) -> bool {
         return true;
     }
 
+    #[cfg(feature = "bigint")]
+    if typ == TypeId::of::<crate::BigInt>() {
+        return true;
+    }
+
     #[cfg(not(feature = "only_i32"))]
     #[cfg(not(feature = "only_i64"))]
     if typ == TypeId::of::<u8>()
@@ -448,6 +493,49 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
         impl_decimal!(INT, as_int, Decimal, as_decimal);
     }
 
+    #[cfg(feature = "bigint")]
+    if is_bigint_pair(type1, type2) {
+        #[allow(clippy::wildcard_imports)]
+        use crate::packages::arithmetic::bigint_functions::*;
+
+        macro_rules! impl_bigint {
+            ($func:ident) => { Some((|_, args| {
+                Ok(Dynamic::from($func(bigint_arg(args[0]), bigint_arg(args[1]))))
+            }, false)) };
+            (Ok($func:ident)) => { Some((|_, args| {
+                $func(bigint_arg(args[0]), bigint_arg(args[1])).map(Dynamic::from)
+            }, false)) };
+            (ctx $func:ident) => { Some((|ctx, args| {
+                let y = bigint_int_arg(args[1])?;
+                $func(ctx.unwrap(), bigint_arg(args[0]), y).map(Dynamic::from)
+            }, true)) };
+            ($op:tt) => { Some((|_, args| {
+                Ok((bigint_arg(args[0]) $op bigint_arg(args[1])).into())
+            }, false)) };
+        }
+
+        return match op {
+            Plus => impl_bigint!(add),
+            Minus => impl_bigint!(subtract),
+            Multiply => impl_bigint!(multiply),
+            Divide => impl_bigint!(Ok(divide)),
+            Modulo => impl_bigint!(Ok(modulo)),
+            PowerOf => impl_bigint!(ctx power),
+            LeftShift => impl_bigint!(ctx shift_left),
+            RightShift => impl_bigint!(ctx shift_right),
+            Ampersand => impl_bigint!(binary_and),
+            Pipe => impl_bigint!(binary_or),
+            XOr => impl_bigint!(binary_xor),
+            EqualsTo => impl_bigint!(==),
+            NotEqualsTo => impl_bigint!(!=),
+            GreaterThan => impl_bigint!(>),
+            GreaterThanEqualsTo => impl_bigint!(>=),
+            LessThan => impl_bigint!(<),
+            LessThanEqualsTo => impl_bigint!(<=),
+            _ => None,
+        };
+    }
+
     // Ranges
     if *op == ExclusiveRange && type1 == TypeId::of::<INT>() && type2 == TypeId::of::<()>() {
         return Some((
@@ -947,6 +1035,64 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
         impl_decimal!(Decimal, as_decimal, INT, as_int);
     }
 
+    #[cfg(feature = "bigint")]
+    if type1 == TypeId::of::<crate::BigInt>() && is_bigint_pair(type1, type2) {
+        #[allow(clippy::wildcard_imports)]
+        use crate::packages::arithmetic::bigint_functions::*;
+
+        macro_rules! impl_bigint {
+            ($func:ident) => {
+                Some((
+                    |_ctx, args| {
+                        let v = Dynamic::from($func(bigint_arg(args[0]), bigint_arg(args[1])));
+
+                        #[cfg(not(feature = "unchecked"))]
+                        _ctx.unwrap()
+                            .engine()
+                            .check_data_size(&v, crate::Position::NONE)?;
+
+                        Ok((*args[0].write_lock().unwrap() = v).into())
+                    },
+                    CHECKED_BUILD,
+                ))
+            };
+            (Ok($func:ident)) => {
+                Some((
+                    |_, args| {
+                        let v = Dynamic::from($func(bigint_arg(args[0]), bigint_arg(args[1]))?);
+                        Ok((*args[0].write_lock().unwrap() = v).into())
+                    },
+                    false,
+                ))
+            };
+            (ctx $func:ident) => {
+                Some((
+                    |ctx, args| {
+                        let y = bigint_int_arg(args[1])?;
+                        let v = Dynamic::from($func(ctx.unwrap(), bigint_arg(args[0]), y)?);
+                        Ok((*args[0].write_lock().unwrap() = v).into())
+                    },
+                    true,
+                ))
+            };
+        }
+
+        return match op {
+            PlusAssign => impl_bigint!(add),
+            MinusAssign => impl_bigint!(subtract),
+            MultiplyAssign => impl_bigint!(multiply),
+            DivideAssign => impl_bigint!(Ok(divide)),
+            ModuloAssign => impl_bigint!(Ok(modulo)),
+            PowerOfAssign => impl_bigint!(ctx power),
+            LeftShiftAssign => impl_bigint!(ctx shift_left),
+            RightShiftAssign => impl_bigint!(ctx shift_right),
+            AndAssign => impl_bigint!(binary_and),
+            OrAssign => impl_bigint!(binary_or),
+            XOrAssign => impl_bigint!(binary_xor),
+            _ => None,
+        };
+    }
+
     // string op= char
     if (type1, type2) == (TypeId::of::<ImmutableString>(), TypeId::of::<char>()) {
         return match op {
@@ -39,7 +39,7 @@ pub type FnCallArgs<'a> = [&'a mut Dynamic];
 /// A type that temporarily stores a mutable reference to a `Dynamic`,
 /// replacing it with a cloned copy.
 #[derive(Debug)]
-struct ArgBackup<'a> {
+pub(crate) struct ArgBackup<'a> {
     orig_mut: Option<&'a mut Dynamic>,
     value_copy: Dynamic,
 }
@@ -163,7 +163,7 @@ impl Engine {
     /// 4) Imported modules - functions marked with global namespace
     /// 5) Static registered modules
     #[must_use]
-    fn resolve_fn<'s>(
+    pub(crate) fn resolve_fn<'s>(
         &self,
         _global: &GlobalRuntimeState,
         caches: &'s mut Caches,
@@ -630,7 +630,6 @@ impl Engine {
                     unreachable!("Script function expected");
                 };
 
-                let fn_def = &*fn_def;
                 let env = env.as_deref();
 
                 if fn_def.body.is_empty() {
@@ -653,7 +652,7 @@ impl Engine {
                     let (first_arg, args) = args.split_first_mut().unwrap();
                     let this_ptr = Some(&mut **first_arg);
                     self.call_script_fn(
-                        global, caches, scope, this_ptr, env, fn_def, args, true, pos,
+                        global, caches, scope, this_ptr, env, &fn_def, args, true, pos,
                     )
                 } else {
                     // Normal call of script function
@@ -668,7 +667,7 @@ impl Engine {
 
                     defer! { args = (args) if swap => move |a| backup.restore_first_arg(a) }
 
-                    self.call_script_fn(global, caches, scope, None, env, fn_def, args, true, pos)
+                    self.call_script_fn(global, caches, scope, None, env, &fn_def, args, true, pos)
                 }
                 .map(|r| (r, false));
             }
@@ -753,7 +752,7 @@ impl Engine {
                         defer! { let orig_level = global.level; global.level += 1 }
 
                         self.call_script_fn(
-                            global, caches, scope, None, env, &*fn_def, &mut args, true, pos,
+                            global, caches, scope, None, env, fn_def, &mut args, true, pos,
                         )
                         .map(|v| (v, false))
                     }
@@ -1759,15 +1758,12 @@ impl Engine {
 
         // Short-circuit native binary operator call if under Fast Operators mode
         if self.fast_operators() && args.len() == 2 && op_token.is_some() {
-            #[allow(clippy::wildcard_imports)]
-            use Token::*;
-
-            let mut lhs = self
+            let lhs = self
                 .get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), &args[0])?
                 .0
                 .flatten();
 
-            let mut rhs = self
+            let rhs = self
                 .get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), &args[1])?
                 .0
                 .flatten();
@@ -1775,185 +1771,7 @@ impl Engine {
             #[allow(clippy::unnecessary_unwrap)]
             let op_token = op_token.unwrap();
 
-            // For extremely simple primary data operations, do it directly
-            // to avoid the overhead of calling a function.
-            match (&lhs.0, &rhs.0) {
-                (Union::Unit(..), Union::Unit(..)) => match op_token {
-                    EqualsTo => return Ok(Dynamic::TRUE),
-                    NotEqualsTo | GreaterThan | GreaterThanEqualsTo | LessThan
-                    | LessThanEqualsTo => return Ok(Dynamic::FALSE),
-                    _ => (),
-                },
-                (Union::Bool(b1, ..), Union::Bool(b2, ..)) => match op_token {
-                    EqualsTo => return Ok((b1 == b2).into()),
-                    NotEqualsTo => return Ok((b1 != b2).into()),
-                    GreaterThan | GreaterThanEqualsTo | LessThan | LessThanEqualsTo => {
-                        return Ok(Dynamic::FALSE)
-                    }
-                    Pipe => return Ok((*b1 || *b2).into()),
-                    Ampersand => return Ok((*b1 && *b2).into()),
-                    _ => (),
-                },
-                (Union::Int(n1, ..), Union::Int(n2, ..)) => {
-                    #[cfg(not(feature = "unchecked"))]
-                    #[allow(clippy::wildcard_imports)]
-                    use crate::packages::arithmetic::arith_basic::INT::functions::*;
-
-                    #[cfg(not(feature = "unchecked"))]
-                    match op_token {
-                        EqualsTo => return Ok((n1 == n2).into()),
-                        NotEqualsTo => return Ok((n1 != n2).into()),
-                        GreaterThan => return Ok((n1 > n2).into()),
-                        GreaterThanEqualsTo => return Ok((n1 >= n2).into()),
-                        LessThan => return Ok((n1 < n2).into()),
-                        LessThanEqualsTo => return Ok((n1 <= n2).into()),
-                        Plus => return add(*n1, *n2).map(Into::into),
-                        Minus => return subtract(*n1, *n2).map(Into::into),
-                        Multiply => return multiply(*n1, *n2).map(Into::into),
-                        Divide => return divide(*n1, *n2).map(Into::into),
-                        Modulo => return modulo(*n1, *n2).map(Into::into),
-                        _ => (),
-                    }
-                    #[cfg(feature = "unchecked")]
-                    match op_token {
-                        EqualsTo => return Ok((n1 == n2).into()),
-                        NotEqualsTo => return Ok((n1 != n2).into()),
-                        GreaterThan => return Ok((n1 > n2).into()),
-                        GreaterThanEqualsTo => return Ok((n1 >= n2).into()),
-                        LessThan => return Ok((n1 < n2).into()),
-                        LessThanEqualsTo => return Ok((n1 <= n2).into()),
-                        Plus => return Ok((n1 + n2).into()),
-                        Minus => return Ok((n1 - n2).into()),
-                        Multiply => return Ok((n1 * n2).into()),
-                        Divide => return Ok((n1 / n2).into()),
-                        Modulo => return Ok((n1 % n2).into()),
-                        _ => (),
-                    }
-                }
-                #[cfg(not(feature = "no_float"))]
-                (Union::Float(f1, ..), Union::Float(f2, ..)) => match op_token {
-                    #[cfg(feature = "unchecked")]
-                    EqualsTo => return Ok((**f1 == **f2).into()),
-                    #[cfg(not(feature = "unchecked"))]
-                    EqualsTo => return Ok(((**f1 - **f2).abs() <= FLOAT::EPSILON).into()),
-                    #[cfg(feature = "unchecked")]
-                    NotEqualsTo => return Ok((**f1 != **f2).into()),
-                    #[cfg(not(feature = "unchecked"))]
-                    NotEqualsTo => return Ok(((**f1 - **f2).abs() > FLOAT::EPSILON).into()),
-                    GreaterThan => return Ok((**f1 > **f2).into()),
-                    GreaterThanEqualsTo => return Ok((**f1 >= **f2).into()),
-                    LessThan => return Ok((**f1 < **f2).into()),
-                    LessThanEqualsTo => return Ok((**f1 <= **f2).into()),
-                    Plus => return Ok((**f1 + **f2).into()),
-                    Minus => return Ok((**f1 - **f2).into()),
-                    Multiply => return Ok((**f1 * **f2).into()),
-                    Divide => return Ok((**f1 / **f2).into()),
-                    Modulo => return Ok((**f1 % **f2).into()),
-                    _ => (),
-                },
-                #[cfg(not(feature = "no_float"))]
-                (Union::Float(f1, ..), Union::Int(n2, ..)) => match op_token {
-                    #[cfg(feature = "unchecked")]
-                    EqualsTo => return Ok((**f1 == (*n2 as FLOAT)).into()),
-                    #[cfg(not(feature = "unchecked"))]
-                    EqualsTo => return Ok(((**f1 - (*n2 as FLOAT)).abs() <= FLOAT::EPSILON).into()),
-                    #[cfg(feature = "unchecked")]
-                    NotEqualsTo => return Ok((**f1 != (*n2 as FLOAT)).into()),
-                    #[cfg(not(feature = "unchecked"))]
-                    NotEqualsTo => {
-                        return Ok(((**f1 - (*n2 as FLOAT)).abs() > FLOAT::EPSILON).into())
-                    }
-                    GreaterThan => return Ok((**f1 > (*n2 as FLOAT)).into()),
-                    GreaterThanEqualsTo => return Ok((**f1 >= (*n2 as FLOAT)).into()),
-                    LessThan => return Ok((**f1 < (*n2 as FLOAT)).into()),
-                    LessThanEqualsTo => return Ok((**f1 <= (*n2 as FLOAT)).into()),
-                    Plus => return Ok((**f1 + (*n2 as FLOAT)).into()),
-                    Minus => return Ok((**f1 - (*n2 as FLOAT)).into()),
-                    Multiply => return Ok((**f1 * (*n2 as FLOAT)).into()),
-                    Divide => return Ok((**f1 / (*n2 as FLOAT)).into()),
-                    Modulo => return Ok((**f1 % (*n2 as FLOAT)).into()),
-                    _ => (),
-                },
-                #[cfg(not(feature = "no_float"))]
-                (Union::Int(n1, ..), Union::Float(f2, ..)) => match op_token {
-                    #[cfg(feature = "unchecked")]
-                    EqualsTo => return Ok(((*n1 as FLOAT) == **f2).into()),
-                    #[cfg(not(feature = "unchecked"))]
-                    EqualsTo => return Ok((((*n1 as FLOAT) - **f2).abs() <= FLOAT::EPSILON).into()),
-                    #[cfg(feature = "unchecked")]
-                    NotEqualsTo => return Ok(((*n1 as FLOAT) != **f2).into()),
-                    #[cfg(not(feature = "unchecked"))]
-                    NotEqualsTo => {
-                        return Ok((((*n1 as FLOAT) - **f2).abs() > FLOAT::EPSILON).into())
-                    }
-                    GreaterThan => return Ok(((*n1 as FLOAT) > **f2).into()),
-                    GreaterThanEqualsTo => return Ok(((*n1 as FLOAT) >= **f2).into()),
-                    LessThan => return Ok(((*n1 as FLOAT) < **f2).into()),
-                    LessThanEqualsTo => return Ok(((*n1 as FLOAT) <= **f2).into()),
-                    Plus => return Ok(((*n1 as FLOAT) + **f2).into()),
-                    Minus => return Ok(((*n1 as FLOAT) - **f2).into()),
-                    Multiply => return Ok(((*n1 as FLOAT) * **f2).into()),
-                    Divide => return Ok(((*n1 as FLOAT) / **f2).into()),
-                    Modulo => return Ok(((*n1 as FLOAT) % **f2).into()),
-                    _ => (),
-                },
-                (Union::Str(s1, ..), Union::Str(s2, ..)) => match op_token {
-                    EqualsTo => return Ok((s1 == s2).into()),
-                    NotEqualsTo => return Ok((s1 != s2).into()),
-                    GreaterThan => return Ok((s1 > s2).into()),
-                    GreaterThanEqualsTo => return Ok((s1 >= s2).into()),
-                    LessThan => return Ok((s1 < s2).into()),
-                    LessThanEqualsTo => return Ok((s1 <= s2).into()),
-                    Plus => {
-                        #[cfg(not(feature = "unchecked"))]
-                        self.throw_on_size((0, 0, s1.len() + s2.len()))?;
-                        return Ok((s1 + s2).into());
-                    }
-                    Minus => return Ok((s1 - s2).into()),
-                    _ => (),
-                },
-                (Union::Char(c1, ..), Union::Char(c2, ..)) => match op_token {
-                    EqualsTo => return Ok((c1 == c2).into()),
-                    NotEqualsTo => return Ok((c1 != c2).into()),
-                    GreaterThan => return Ok((c1 > c2).into()),
-                    GreaterThanEqualsTo => return Ok((c1 >= c2).into()),
-                    LessThan => return Ok((c1 < c2).into()),
-                    LessThanEqualsTo => return Ok((c1 <= c2).into()),
-                    Plus => {
-                        let mut result = SmartString::new_const();
-                        result.push(*c1);
-                        result.push(*c2);
-
-                        #[cfg(not(feature = "unchecked"))]
-                        self.throw_on_size((0, 0, result.len()))?;
-
-                        return Ok(result.into());
-                    }
-                    _ => (),
-                },
-                (Union::Variant(..), _) | (_, Union::Variant(..)) => (),
-                _ => {
-                    if let Some((func, need_context)) =
-                        get_builtin_binary_op_fn(op_token, &lhs, &rhs)
-                    {
-                        // We may not need to bump the level because built-in's do not need it.
-                        //defer! { let orig_level = global.level; global.level += 1 }
-
-                        let context =
-                            need_context.then(|| (self, name.as_str(), None, &*global, pos).into());
-                        return func(context, &mut [&mut lhs, &mut rhs]);
-                    }
-                }
-            }
-
-            let operands = &mut [&mut lhs, &mut rhs];
-            let op_token = Some(op_token);
-
-            return self
-                .exec_fn_call(
-                    global, caches, None, name, op_token, *hashes, operands, false, false, pos,
-                )
-                .map(|(v, ..)| v);
+            return self.eval_binary_op(global, caches, name, op_token, *hashes, lhs, rhs, pos);
         }
 
         #[cfg(not(feature = "no_module"))]
@@ -1977,4 +1795,197 @@ impl Engine {
             *capture, pos,
         )
     }
+
+    /// Evaluate a binary operator call on two (flattened) operand values under Fast Operators mode.
+    ///
+    /// Simple operations on primary data types are done directly, falling back to calling the
+    /// operator function.
+    #[inline]
+    pub(crate) fn eval_binary_op(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        name: &str,
+        op_token: &Token,
+        hashes: FnCallHashes,
+        mut lhs: Dynamic,
+        mut rhs: Dynamic,
+        pos: Position,
+    ) -> RhaiResult {
+        #[allow(clippy::wildcard_imports)]
+        use Token::*;
+
+        // For extremely simple primary data operations, do it directly
+        // to avoid the overhead of calling a function.
+        match (&lhs.0, &rhs.0) {
+            (Union::Unit(..), Union::Unit(..)) => match op_token {
+                EqualsTo => return Ok(Dynamic::TRUE),
+                NotEqualsTo | GreaterThan | GreaterThanEqualsTo | LessThan | LessThanEqualsTo => {
+                    return Ok(Dynamic::FALSE)
+                }
+                _ => (),
+            },
+            (Union::Bool(b1, ..), Union::Bool(b2, ..)) => match op_token {
+                EqualsTo => return Ok((b1 == b2).into()),
+                NotEqualsTo => return Ok((b1 != b2).into()),
+                GreaterThan | GreaterThanEqualsTo | LessThan | LessThanEqualsTo => {
+                    return Ok(Dynamic::FALSE)
+                }
+                Pipe => return Ok((*b1 || *b2).into()),
+                Ampersand => return Ok((*b1 && *b2).into()),
+                _ => (),
+            },
+            (Union::Int(n1, ..), Union::Int(n2, ..)) => {
+                #[cfg(not(feature = "unchecked"))]
+                #[allow(clippy::wildcard_imports)]
+                use crate::packages::arithmetic::arith_basic::INT::functions::*;
+
+                #[cfg(not(feature = "unchecked"))]
+                match op_token {
+                    EqualsTo => return Ok((n1 == n2).into()),
+                    NotEqualsTo => return Ok((n1 != n2).into()),
+                    GreaterThan => return Ok((n1 > n2).into()),
+                    GreaterThanEqualsTo => return Ok((n1 >= n2).into()),
+                    LessThan => return Ok((n1 < n2).into()),
+                    LessThanEqualsTo => return Ok((n1 <= n2).into()),
+                    Plus => return add(*n1, *n2).map(Into::into),
+                    Minus => return subtract(*n1, *n2).map(Into::into),
+                    Multiply => return multiply(*n1, *n2).map(Into::into),
+                    Divide => return divide(*n1, *n2).map(Into::into),
+                    Modulo => return modulo(*n1, *n2).map(Into::into),
+                    _ => (),
+                }
+                #[cfg(feature = "unchecked")]
+                match op_token {
+                    EqualsTo => return Ok((n1 == n2).into()),
+                    NotEqualsTo => return Ok((n1 != n2).into()),
+                    GreaterThan => return Ok((n1 > n2).into()),
+                    GreaterThanEqualsTo => return Ok((n1 >= n2).into()),
+                    LessThan => return Ok((n1 < n2).into()),
+                    LessThanEqualsTo => return Ok((n1 <= n2).into()),
+                    Plus => return Ok((n1 + n2).into()),
+                    Minus => return Ok((n1 - n2).into()),
+                    Multiply => return Ok((n1 * n2).into()),
+                    Divide => return Ok((n1 / n2).into()),
+                    Modulo => return Ok((n1 % n2).into()),
+                    _ => (),
+                }
+            }
+            #[cfg(not(feature = "no_float"))]
+            (Union::Float(f1, ..), Union::Float(f2, ..)) => match op_token {
+                #[cfg(feature = "unchecked")]
+                EqualsTo => return Ok((**f1 == **f2).into()),
+                #[cfg(not(feature = "unchecked"))]
+                EqualsTo => return Ok(((**f1 - **f2).abs() <= FLOAT::EPSILON).into()),
+                #[cfg(feature = "unchecked")]
+                NotEqualsTo => return Ok((**f1 != **f2).into()),
+                #[cfg(not(feature = "unchecked"))]
+                NotEqualsTo => return Ok(((**f1 - **f2).abs() > FLOAT::EPSILON).into()),
+                GreaterThan => return Ok((**f1 > **f2).into()),
+                GreaterThanEqualsTo => return Ok((**f1 >= **f2).into()),
+                LessThan => return Ok((**f1 < **f2).into()),
+                LessThanEqualsTo => return Ok((**f1 <= **f2).into()),
+                Plus => return Ok((**f1 + **f2).into()),
+                Minus => return Ok((**f1 - **f2).into()),
+                Multiply => return Ok((**f1 * **f2).into()),
+                Divide => return Ok((**f1 / **f2).into()),
+                Modulo => return Ok((**f1 % **f2).into()),
+                _ => (),
+            },
+            #[cfg(not(feature = "no_float"))]
+            (Union::Float(f1, ..), Union::Int(n2, ..)) => match op_token {
+                #[cfg(feature = "unchecked")]
+                EqualsTo => return Ok((**f1 == (*n2 as FLOAT)).into()),
+                #[cfg(not(feature = "unchecked"))]
+                EqualsTo => return Ok(((**f1 - (*n2 as FLOAT)).abs() <= FLOAT::EPSILON).into()),
+                #[cfg(feature = "unchecked")]
+                NotEqualsTo => return Ok((**f1 != (*n2 as FLOAT)).into()),
+                #[cfg(not(feature = "unchecked"))]
+                NotEqualsTo => return Ok(((**f1 - (*n2 as FLOAT)).abs() > FLOAT::EPSILON).into()),
+                GreaterThan => return Ok((**f1 > (*n2 as FLOAT)).into()),
+                GreaterThanEqualsTo => return Ok((**f1 >= (*n2 as FLOAT)).into()),
+                LessThan => return Ok((**f1 < (*n2 as FLOAT)).into()),
+                LessThanEqualsTo => return Ok((**f1 <= (*n2 as FLOAT)).into()),
+                Plus => return Ok((**f1 + (*n2 as FLOAT)).into()),
+                Minus => return Ok((**f1 - (*n2 as FLOAT)).into()),
+                Multiply => return Ok((**f1 * (*n2 as FLOAT)).into()),
+                Divide => return Ok((**f1 / (*n2 as FLOAT)).into()),
+                Modulo => return Ok((**f1 % (*n2 as FLOAT)).into()),
+                _ => (),
+            },
+            #[cfg(not(feature = "no_float"))]
+            (Union::Int(n1, ..), Union::Float(f2, ..)) => match op_token {
+                #[cfg(feature = "unchecked")]
+                EqualsTo => return Ok(((*n1 as FLOAT) == **f2).into()),
+                #[cfg(not(feature = "unchecked"))]
+                EqualsTo => return Ok((((*n1 as FLOAT) - **f2).abs() <= FLOAT::EPSILON).into()),
+                #[cfg(feature = "unchecked")]
+                NotEqualsTo => return Ok(((*n1 as FLOAT) != **f2).into()),
+                #[cfg(not(feature = "unchecked"))]
+                NotEqualsTo => return Ok((((*n1 as FLOAT) - **f2).abs() > FLOAT::EPSILON).into()),
+                GreaterThan => return Ok(((*n1 as FLOAT) > **f2).into()),
+                GreaterThanEqualsTo => return Ok(((*n1 as FLOAT) >= **f2).into()),
+                LessThan => return Ok(((*n1 as FLOAT) < **f2).into()),
+                LessThanEqualsTo => return Ok(((*n1 as FLOAT) <= **f2).into()),
+                Plus => return Ok(((*n1 as FLOAT) + **f2).into()),
+                Minus => return Ok(((*n1 as FLOAT) - **f2).into()),
+                Multiply => return Ok(((*n1 as FLOAT) * **f2).into()),
+                Divide => return Ok(((*n1 as FLOAT) / **f2).into()),
+                Modulo => return Ok(((*n1 as FLOAT) % **f2).into()),
+                _ => (),
+            },
+            (Union::Str(s1, ..), Union::Str(s2, ..)) => match op_token {
+                EqualsTo => return Ok((s1 == s2).into()),
+                NotEqualsTo => return Ok((s1 != s2).into()),
+                GreaterThan => return Ok((s1 > s2).into()),
+                GreaterThanEqualsTo => return Ok((s1 >= s2).into()),
+                LessThan => return Ok((s1 < s2).into()),
+                LessThanEqualsTo => return Ok((s1 <= s2).into()),
+                Plus => {
+                    #[cfg(not(feature = "unchecked"))]
+                    self.throw_on_size((0, 0, s1.len() + s2.len()))?;
+                    return Ok((s1 + s2).into());
+                }
+                Minus => return Ok((s1 - s2).into()),
+                _ => (),
+            },
+            (Union::Char(c1, ..), Union::Char(c2, ..)) => match op_token {
+                EqualsTo => return Ok((c1 == c2).into()),
+                NotEqualsTo => return Ok((c1 != c2).into()),
+                GreaterThan => return Ok((c1 > c2).into()),
+                GreaterThanEqualsTo => return Ok((c1 >= c2).into()),
+                LessThan => return Ok((c1 < c2).into()),
+                LessThanEqualsTo => return Ok((c1 <= c2).into()),
+                Plus => {
+                    let mut result = SmartString::new_const();
+                    result.push(*c1);
+                    result.push(*c2);
+
+                    #[cfg(not(feature = "unchecked"))]
+                    self.throw_on_size((0, 0, result.len()))?;
+
+                    return Ok(result.into());
+                }
+                _ => (),
+            },
+            (Union::Variant(..), _) | (_, Union::Variant(..)) => (),
+            _ => {
+                if let Some((func, need_context)) = get_builtin_binary_op_fn(op_token, &lhs, &rhs) {
+                    // We may not need to bump the level because built-in's do not need it.
+                    //defer! { let orig_level = global.level; global.level += 1 }
+
+                    let context = need_context.then(|| (self, name, None, &*global, pos).into());
+                    return func(context, &mut [&mut lhs, &mut rhs]);
+                }
+            }
+        }
+
+        let operands = &mut [&mut lhs, &mut rhs];
+        let op_token = Some(op_token);
+
+        self.exec_fn_call(
+            global, caches, None, name, op_token, hashes, operands, false, false, pos,
+        )
+        .map(|(v, ..)| v)
+    }
 }
@@ -647,6 +647,15 @@ pub type OnProgressCallback = dyn Fn(u64) -> Option<Dynamic>;
 #[cfg(feature = "sync")]
 pub type OnProgressCallback = dyn Fn(u64) -> Option<Dynamic> + Send + Sync;
 
+/// Callback function for getting the current date/time.
+#[cfg(not(feature = "no_time"))]
+#[cfg(not(feature = "sync"))]
+pub type OnClockCallback = dyn Fn() -> crate::DateTime;
+/// Callback function for getting the current date/time.
+#[cfg(not(feature = "no_time"))]
+#[cfg(feature = "sync")]
+pub type OnClockCallback = dyn Fn() -> crate::DateTime + Send + Sync;
+
 /// Callback function for printing.
 #[cfg(not(feature = "sync"))]
 pub type OnPrintCallback = dyn Fn(&str);
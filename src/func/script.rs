@@ -4,7 +4,7 @@
 use super::call::FnCallArgs;
 use crate::ast::{EncapsulatedEnviron, ScriptFuncDef};
 use crate::eval::{Caches, GlobalRuntimeState};
-use crate::{Dynamic, Engine, Position, RhaiResult, Scope, ERR};
+use crate::{Dynamic, Engine, Position, RhaiResult, Scope, Shared, ERR};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -21,7 +21,47 @@ impl Engine {
     /// All function arguments not in the first position are always passed by value and thus consumed.
     ///
     /// **DO NOT** reuse the argument values except for the first `&mut` argument - all others are silently replaced by `()`!
+    #[inline]
     pub(crate) fn call_script_fn(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        env: Option<&EncapsulatedEnviron>,
+        fn_def: &Shared<ScriptFuncDef>,
+        args: &mut FnCallArgs,
+        rewind_scope: bool,
+        pos: Position,
+    ) -> RhaiResult {
+        // Calling a generator function only creates the generator
+        #[cfg(feature = "resumable")]
+        if fn_def.is_generator {
+            let generator = self.make_generator(global, this_ptr, env, fn_def, args);
+            return Ok(Dynamic::from(generator));
+        }
+
+        self.run_script_fn(
+            global,
+            caches,
+            scope,
+            this_ptr,
+            env,
+            fn_def,
+            args,
+            rewind_scope,
+            pos,
+        )
+    }
+    /// Run the body of a script-defined function.
+    ///
+    /// Unlike [`call_script_fn`][Engine::call_script_fn], the body of a generator function is run
+    /// directly.
+    ///
+    /// # WARNING
+    ///
+    /// Function call arguments may be _consumed_, same as [`call_script_fn`][Engine::call_script_fn].
+    pub(crate) fn run_script_fn(
         &self,
         global: &mut GlobalRuntimeState,
         caches: &mut Caches,
@@ -117,43 +157,50 @@ impl Engine {
             self.dbg(global, caches, scope, this_ptr.as_deref_mut(), &node)?;
         }
 
+        // Run the compiled bytecode of the function instead, if any
+        #[cfg(feature = "bytecode")]
+        let program = global.bytecode.clone();
+        #[cfg(feature = "bytecode")]
+        let chunk = program.as_deref().and_then(|p| p.get_fn(fn_def));
+        #[cfg(not(feature = "bytecode"))]
+        let chunk = None::<()>;
+
         // Evaluate the function
-        let mut _result: RhaiResult = self
-            .eval_stmt_block(
-                global,
-                caches,
-                scope,
-                this_ptr.as_deref_mut(),
-                fn_def.body.statements(),
-                rewind_scope,
+        let this = this_ptr.as_deref_mut();
+        let statements = fn_def.body.statements();
+
+        let mut _result: RhaiResult = match chunk {
+            #[cfg(feature = "bytecode")]
+            Some(chunk) => self.run_chunk(global, caches, scope, this, chunk, rewind_scope),
+            _ => self.eval_stmt_block(global, caches, scope, this, statements, rewind_scope),
+        }
+        .or_else(|err| match *err {
+            // Convert return statement to return value
+            ERR::Return(x, ..) => Ok(x),
+            // Exit value is passed straight-through
+            mut err @ ERR::Exit(..) => {
+                err.set_position(pos);
+                Err(err.into())
+            }
+            // System errors are passed straight-through
+            mut err if err.is_system_exception() => {
+                err.set_position(pos);
+                Err(err.into())
+            }
+            // Other errors are wrapped in `ErrorInFunctionCall`
+            _ => Err(ERR::ErrorInFunctionCall(
+                fn_def.name.to_string(),
+                #[cfg(not(feature = "no_module"))]
+                _env.and_then(|env| env.lib.id())
+                    .unwrap_or_else(|| global.source().unwrap_or(""))
+                    .to_string(),
+                #[cfg(feature = "no_module")]
+                global.source().unwrap_or("").to_string(),
+                err,
+                pos,
             )
-            .or_else(|err| match *err {
-                // Convert return statement to return value
-                ERR::Return(x, ..) => Ok(x),
-                // Exit value is passed straight-through
-                mut err @ ERR::Exit(..) => {
-                    err.set_position(pos);
-                    Err(err.into())
-                }
-                // System errors are passed straight-through
-                mut err if err.is_system_exception() => {
-                    err.set_position(pos);
-                    Err(err.into())
-                }
-                // Other errors are wrapped in `ErrorInFunctionCall`
-                _ => Err(ERR::ErrorInFunctionCall(
-                    fn_def.name.to_string(),
-                    #[cfg(not(feature = "no_module"))]
-                    _env.and_then(|env| env.lib.id())
-                        .unwrap_or_else(|| global.source().unwrap_or(""))
-                        .to_string(),
-                    #[cfg(feature = "no_module")]
-                    global.source().unwrap_or("").to_string(),
-                    err,
-                    pos,
-                )
-                .into()),
-            });
+            .into()),
+        });
 
         #[cfg(feature = "debugging")]
         if self.is_debugger_registered() {
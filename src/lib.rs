@@ -114,6 +114,8 @@ mod defer;
 
 mod api;
 mod ast;
+#[cfg(feature = "bytecode")]
+mod bytecode;
 pub mod config;
 mod engine;
 mod eval;
@@ -236,8 +238,12 @@ pub use api::custom_syntax::Expression;
 #[cfg(not(feature = "no_std"))]
 #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
 pub use api::files::{eval_file, run_file};
+#[cfg(feature = "resumable")]
+pub use api::resumable::{suspend, Resumable, Suspended};
 pub use api::{eval::eval, run::run};
 pub use ast::{FnAccess, AST};
+#[cfg(feature = "bytecode")]
+pub use bytecode::Bytecode;
 use defer::Deferred;
 pub use engine::{Engine, OP_CONTAINS, OP_EQUALS};
 pub use eval::EvalContext;
@@ -249,12 +255,22 @@ pub use func::{plugin, FuncArgs, NativeCallContext, RhaiNativeFunc};
 pub use module::{FnNamespace, FuncRegistration, Module};
 pub use packages::string_basic::{FUNC_TO_DEBUG, FUNC_TO_STRING};
 pub use rhai_codegen::*;
+#[cfg(feature = "resumable")]
+#[cfg(not(feature = "no_function"))]
+pub use types::Generator;
 #[cfg(not(feature = "no_time"))]
-pub use types::Instant;
+pub use types::{DateTime, Instant};
 pub use types::{
     Dynamic, EvalAltResult, FnPtr, ImmutableString, LexError, ParseError, ParseErrorType, Position,
     Scope, VarDefInfo,
 };
+#[cfg(not(feature = "no_index"))]
+pub use types::{LazyIter, Set};
+
+/// _(bigint)_ An arbitrary-precision integer.
+/// Exported under the `bigint` feature only.
+#[cfg(feature = "bigint")]
+pub use num_bigint::BigInt;
 
 /// _(debugging)_ Module containing types for debugging.
 /// Exported under the `debugging` feature only.
@@ -293,6 +309,12 @@ pub use ast::ScriptFnMetadata;
 #[cfg(not(feature = "no_function"))]
 pub use api::call_fn::CallFnOptions;
 
+#[cfg(not(feature = "no_function"))]
+pub use eval::EngineCache;
+
+#[cfg(not(feature = "no_position"))]
+pub use api::formatter::FormatOptions;
+
 /// Variable-sized array of [`Dynamic`] values.
 ///
 /// Not available under `no_index`.
@@ -358,8 +380,8 @@ pub use api::default_limits;
 #[cfg(feature = "internals")]
 pub use ast::{
     ASTFlags, ASTNode, BinaryExpr, EncapsulatedEnviron, Expr, FlowControl, FnCallExpr,
-    FnCallHashes, Ident, OpAssignment, RangeCase, ScriptFuncDef, Stmt, StmtBlock,
-    SwitchCasesCollection,
+    FnCallHashes, Ident, MatchArm, OpAssignment, Pattern, RangeCase, ScriptFuncDef, Stmt,
+    StmtBlock, SwitchCasesCollection,
 };
 
 #[cfg(feature = "internals")]
@@ -370,6 +392,10 @@ pub use ast::CustomExpr;
 #[cfg(not(feature = "no_module"))]
 pub use ast::Namespace;
 
+#[cfg(feature = "internals")]
+#[cfg(not(feature = "no_optimize"))]
+pub use optimizer::{OptimizerContext, OptimizerPass};
+
 #[cfg(feature = "internals")]
 pub use eval::{Caches, FnResolutionCache, FnResolutionCacheEntry, GlobalRuntimeState, Target};
 
@@ -496,3 +522,11 @@ compile_error!("`stdweb` cannot be used non-WASM target");
 #[cfg(feature = "wasm-bindgen")]
 #[cfg(feature = "stdweb")]
 compile_error!("`wasm-bindgen` and `stdweb` cannot be used together");
+
+#[cfg(feature = "no_std")]
+#[cfg(feature = "resumable")]
+compile_error!("`resumable` cannot be used with `no-std`");
+
+#[cfg(target_family = "wasm")]
+#[cfg(feature = "resumable")]
+compile_error!("`resumable` cannot be used for WASM target");
@@ -2,17 +2,22 @@
 #![cfg(not(feature = "no_optimize"))]
 
 use crate::ast::{
-    ASTFlags, Expr, FlowControl, OpAssignment, Stmt, StmtBlock, StmtBlockContainer,
-    SwitchCasesCollection,
+    ASTFlags, BinaryExpr, Expr, FlowControl, FnCallExpr, Ident, OpAssignment, Stmt, StmtBlock,
+    StmtBlockContainer, SwitchCasesCollection,
 };
 use crate::engine::{
-    KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CURRY, KEYWORD_PRINT,
-    KEYWORD_TYPE_OF, OP_NOT,
+    KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL, KEYWORD_FN_PTR_CURRY,
+    KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_TYPE_OF, OP_NOT,
 };
 use crate::eval::{Caches, GlobalRuntimeState};
 use crate::func::builtin::get_builtin_binary_op_fn;
 use crate::func::hashing::get_hasher;
 use crate::tokenizer::Token;
+#[cfg(not(feature = "no_function"))]
+use crate::{
+    ast::{ASTNode, ScriptFuncDef},
+    Shared,
+};
 use crate::{
     calc_fn_hash, calc_fn_hash_full, Dynamic, Engine, FnArgsVec, FnPtr, ImmutableString, Position,
     Scope, AST,
@@ -22,9 +27,11 @@ use std::prelude::v1::*;
 use std::{
     any::TypeId,
     borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
     convert::TryFrom,
     hash::{Hash, Hasher},
     mem,
+    num::{NonZeroU8, NonZeroUsize},
 };
 
 /// Level of optimization performed.
@@ -44,6 +51,11 @@ pub enum OptimizationLevel {
     Full,
 }
 
+/// Default maximum size (in number of statements and expressions) of a script-defined function
+/// body that can be inlined at call sites.
+#[cfg(not(feature = "no_function"))]
+pub const MAX_INLINE_SIZE: usize = 32;
+
 /// Mutable state throughout an optimization pass.
 #[derive(Debug, Clone)]
 struct OptimizerState<'a> {
@@ -63,95 +75,1559 @@ struct OptimizerState<'a> {
     caches: Caches,
     /// Optimization level.
     optimization_level: OptimizationLevel,
+    /// Number of temporary variables created.
+    temp_vars: usize,
+    /// Is a function body being optimized?
+    ///
+    /// New variables defined at the top level of a function body do not leak into the caller.
+    is_fn_body: bool,
+    /// Cached effects of calling registered functions, by name and number of parameters.
+    fn_effects: BTreeMap<(ImmutableString, usize), Option<FnEffects>>,
+}
+
+impl<'a> OptimizerState<'a> {
+    /// Create a new [`OptimizerState`].
+    #[inline(always)]
+    pub fn new(
+        engine: &'a Engine,
+        lib: &'a [crate::SharedModule],
+        scope: Option<&'a Scope<'a>>,
+        optimization_level: OptimizationLevel,
+    ) -> Self {
+        let mut _global = engine.new_global_runtime_state();
+        let _lib = lib;
+
+        #[cfg(not(feature = "no_function"))]
+        {
+            _global.lib = _lib.into();
+        }
+
+        Self {
+            is_dirty: false,
+            variables: Vec::new(),
+            propagate_constants: true,
+            engine,
+            scope,
+            global: _global,
+            caches: Caches::new(),
+            optimization_level,
+            temp_vars: 0,
+            is_fn_body: false,
+            fn_effects: BTreeMap::new(),
+        }
+    }
+    /// Set the [`AST`] state to be dirty (i.e. changed).
+    #[inline(always)]
+    pub fn set_dirty(&mut self) {
+        self.is_dirty = true;
+    }
+    /// Set the [`AST`] state to be not dirty (i.e. unchanged).
+    #[inline(always)]
+    pub fn clear_dirty(&mut self) {
+        self.is_dirty = false;
+    }
+    /// Is the [`AST`] dirty (i.e. changed)?
+    #[inline(always)]
+    pub const fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+    /// Rewind the variables stack back to a specified size.
+    #[inline(always)]
+    pub fn rewind_var(&mut self, len: usize) {
+        self.variables.truncate(len);
+    }
+    /// Add a new variable to the stack.
+    ///
+    /// `Some(value)` if literal constant (which can be used for constants propagation), `None` otherwise.
+    #[inline(always)]
+    pub fn push_var<'x: 'a>(&mut self, name: ImmutableString, value: Option<Cow<'x, Dynamic>>) {
+        self.variables.push((name, value));
+    }
+    /// Look up a literal constant from the variables stack.
+    #[inline]
+    pub fn find_literal_constant(&self, name: &str) -> Option<&Dynamic> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+    /// Call a registered function
+    #[inline]
+    pub fn call_fn_with_const_args(
+        &mut self,
+        fn_name: &str,
+        op_token: Option<&Token>,
+        arg_values: &mut [Dynamic],
+    ) -> Option<Dynamic> {
+        self.engine
+            .exec_native_fn_call(
+                &mut self.global,
+                &mut self.caches,
+                fn_name,
+                op_token,
+                calc_fn_hash(None, fn_name, arg_values.len()),
+                &mut arg_values.iter_mut().collect::<FnArgsVec<_>>(),
+                false,
+                true,
+                Position::NONE,
+            )
+            .ok()
+            .map(|(v, ..)| v)
+    }
+    /// Find a script-defined function that can be inlined at a call site.
+    ///
+    /// Only small, non-recursive functions that do not access `this`, the calling scope or other
+    /// script-defined functions, and only call pure functions, are inlined.
+    #[cfg(not(feature = "no_function"))]
+    pub fn find_inline_fn(&mut self, x: &FnCallExpr) -> Option<Shared<ScriptFuncDef>> {
+        let max_size = self.engine.max_inline_size();
+
+        if max_size == 0
+            || x.hashes.is_native_only()
+            || x.is_operator_call()
+            || x.capture_parent_scope
+            // Parameters are bound to new variables in the calling scope
+            || !self.engine.allow_shadowing()
+            || self.engine.def_var_filter.is_some()
+        {
+            return None;
+        }
+
+        #[cfg(not(feature = "no_module"))]
+        if x.is_qualified() {
+            return None;
+        }
+
+        let lib = &*self.global.lib;
+        let fn_def = lib
+            .iter()
+            .find_map(|m| m.get_script_fn(&x.name, x.args.len()))?
+            .clone();
+
+        #[cfg(not(feature = "no_object"))]
+        if fn_def.this_type.is_some() {
+            return None;
+        }
+        #[cfg(feature = "resumable")]
+        if fn_def.is_generator {
+            return None;
+        }
+
+        let statements = fn_def.body.statements();
+        let mut size = 0;
+        let mut calls = Vec::new();
+
+        let ok = statements.iter().enumerate().all(|(i, stmt)| match stmt {
+            // `return` is only allowed at the end of the function body
+            Stmt::Return(expr, options, ..)
+                if i == statements.len() - 1 && !options.intersects(ASTFlags::BREAK) =>
+            {
+                size += 1;
+                expr.as_ref().map_or(true, |e| {
+                    e.walk(&mut Vec::new(), &mut |path| {
+                        is_inlinable_node(path, lib, &mut size, max_size, &mut calls)
+                    })
+                })
+            }
+            _ => stmt.walk(&mut Vec::new(), &mut |path| {
+                is_inlinable_node(path, lib, &mut size, max_size, &mut calls)
+            }),
+        });
+
+        // Calls to functions with side effects are not inlined
+        let ok = ok
+            && calls
+                .iter()
+                .all(|(x, is_method)| self.call_effects(x, *is_method) == FnEffects::Pure);
+
+        ok.then_some(fn_def)
+    }
+    /// Find the effects of calling a function with a particular name and number of parameters,
+    /// based on the purity and volatility of all registered functions that may match.
+    ///
+    /// Returns `None` if there is no such function.
+    pub fn fn_effects(&mut self, name: &str, num_params: usize) -> Option<FnEffects> {
+        let key = (name.into(), num_params);
+
+        if let Some(effects) = self.fn_effects.get(&key) {
+            return *effects;
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        let is_script = self
+            .global
+            .lib
+            .iter()
+            .any(|m| m.get_script_fn(name, num_params).is_some());
+        #[cfg(feature = "no_function")]
+        let is_script = false;
+
+        let engine = self.engine;
+        let modules = engine.global_modules.iter();
+        #[cfg(not(feature = "no_module"))]
+        let modules = modules.chain(engine.global_sub_modules.values());
+
+        let effects = if is_script {
+            Some(FnEffects::Unknown)
+        } else {
+            modules
+                .flat_map(|m| m.iter_fn())
+                .filter(|(_, m)| m.num_params == num_params && m.name == name)
+                .map(|(f, m)| match f {
+                    _ if f.is_script() => FnEffects::Unknown,
+                    // Native functions can only call back into scripts via function pointers
+                    _ if f.has_context()
+                        && m.param_types.iter().any(|&t| t == TypeId::of::<FnPtr>()) =>
+                    {
+                        FnEffects::Unknown
+                    }
+                    _ if !f.is_pure() => FnEffects::Mutating,
+                    _ if f.is_volatile() => FnEffects::Volatile,
+                    _ => FnEffects::Pure,
+                })
+                .max()
+        };
+
+        self.fn_effects.insert(key, effects);
+        effects
+    }
+    /// Find the effects of a function call (or a method call if `is_method` is `true`).
+    pub fn call_effects(&mut self, x: &FnCallExpr, is_method: bool) -> FnEffects {
+        #[cfg(not(feature = "no_module"))]
+        if x.is_qualified() {
+            return FnEffects::Unknown;
+        }
+
+        match x.name.as_str() {
+            _ if x.capture_parent_scope => FnEffects::Unknown,
+            KEYWORD_EVAL | KEYWORD_FN_PTR | KEYWORD_FN_PTR_CALL | KEYWORD_FN_PTR_CURRY
+            | KEYWORD_IS_DEF_VAR => FnEffects::Unknown,
+            KEYWORD_PRINT | KEYWORD_DEBUG if !is_method && x.args.len() == 1 => FnEffects::Volatile,
+            KEYWORD_TYPE_OF if !is_method && x.args.len() == 1 => FnEffects::Pure,
+            _ => {
+                let num_params = x.args.len() + usize::from(is_method);
+
+                match self.fn_effects(&x.name, num_params) {
+                    Some(effects) => effects,
+                    // Built-in operators
+                    None if x.is_operator_call() => FnEffects::Pure,
+                    None => FnEffects::Unknown,
+                }
+            }
+        }
+    }
+    /// Create a new temporary variable name that is not in a list of names.
+    pub fn new_temp_var(&mut self, names: &BTreeSet<ImmutableString>) -> ImmutableString {
+        loop {
+            let name: ImmutableString = format!("$temp{}", self.temp_vars).into();
+            self.temp_vars += 1;
+
+            if !names.contains(&name) {
+                return name;
+            }
+        }
+    }
+}
+
+/// _(internals)_ A user-defined optimization pass, run as part of the [`AST`] optimizer.
+/// Exported under the `internals` feature only.
+///
+/// Not available under `no_optimize`.
+///
+/// # WARNING - Unstable API
+///
+/// This API is volatile and may change in the future.
+///
+/// # Running Passes
+///
+/// Unless the optimization level is [`OptimizationLevel::None`], all passes registered via
+/// [`Engine::register_optimizer_pass`] are called, in order of registration, on every
+/// [statement][Stmt] and [expression][Expr] just before it is optimized by the built-in optimizer.
+///
+/// Notice that function calls made as statements are [`Stmt::FnCall`] while function calls within
+/// expressions are [`Expr::FnCall`].
+///
+/// The optimizer keeps going over a block of statements until nothing is changed, so a pass must
+/// return `true` only when it has actually changed a node; otherwise optimization never ends.
+#[cfg(feature = "internals")]
+pub trait OptimizerPass: crate::func::SendSync {
+    /// Rewrite a [statement][Stmt].
+    ///
+    /// Returns `true` if the statement is changed.
+    #[allow(unused_variables)]
+    fn optimize_stmt(&self, stmt: &mut Stmt, context: &mut OptimizerContext) -> bool {
+        false
+    }
+    /// Rewrite an [expression][Expr].
+    ///
+    /// Returns `true` if the expression is changed.
+    #[allow(unused_variables)]
+    fn optimize_expr(&self, expr: &mut Expr, context: &mut OptimizerContext) -> bool {
+        false
+    }
+}
+
+/// _(internals)_ Context of a user-defined [optimization pass][OptimizerPass].
+/// Exported under the `internals` feature only.
+///
+/// Not available under `no_optimize`.
+#[cfg(feature = "internals")]
+pub struct OptimizerContext<'s, 'a> {
+    /// Current state of the optimizer.
+    state: &'s mut OptimizerState<'a>,
+}
+
+#[cfg(feature = "internals")]
+impl OptimizerContext<'_, '_> {
+    /// The current [`Engine`].
+    #[inline(always)]
+    #[must_use]
+    pub fn engine(&self) -> &Engine {
+        self.state.engine
+    }
+    /// The current optimization level.
+    #[inline(always)]
+    #[must_use]
+    pub fn optimization_level(&self) -> OptimizationLevel {
+        self.state.optimization_level
+    }
+    /// Get the value of a constant visible at the current position, if known.
+    ///
+    /// Constants include those defined in the script, in the [`Scope`] passed to the optimizer
+    /// and in global modules registered into the [`Engine`].
+    #[inline]
+    #[must_use]
+    pub fn find_constant(&self, name: &str) -> Option<&Dynamic> {
+        if self.state.propagate_constants {
+            self.state.find_literal_constant(name)
+        } else {
+            None
+        }
+    }
+    /// Are all the functions registered into the [`Engine`] with a particular name and number of
+    /// parameters _pure_ and _non-volatile_?
+    ///
+    /// Such functions do not change their arguments and always return the same result for the
+    /// same inputs, so calls to them can be freely removed, reordered or evaluated ahead of time.
+    ///
+    /// Returns `false` if there is no such function or if a script-defined function of the same
+    /// name and number of parameters exists.
+    #[inline]
+    #[must_use]
+    pub fn is_pure_fn(&mut self, name: &str, num_params: usize) -> bool {
+        self.state.fn_effects(name, num_params) == Some(FnEffects::Pure)
+    }
+    /// Is a function call free of side effects, always returning the same result for the same
+    /// arguments?
+    ///
+    /// The arguments themselves are not checked.
+    #[inline]
+    #[must_use]
+    pub fn is_pure_call(&mut self, x: &FnCallExpr) -> bool {
+        self.state.call_effects(x, false) == FnEffects::Pure
+    }
+    /// Calculate the [hashes][crate::ast::FnCallHashes] of a non-qualified call to a function
+    /// with a particular name and number of arguments.
+    ///
+    /// This is useful when changing the name or number of arguments of a [`FnCallExpr`].
+    #[inline]
+    #[must_use]
+    pub fn calc_fn_call_hashes(&self, name: &str, num_args: usize) -> crate::ast::FnCallHashes {
+        let hash = calc_fn_hash(None, name, num_args);
+
+        if crate::tokenizer::is_valid_function_name(name) {
+            crate::ast::FnCallHashes::from_hash(hash)
+        } else {
+            crate::ast::FnCallHashes::from_native_only(hash)
+        }
+    }
+}
+
+/// Run user-defined optimization passes on a statement.
+#[cfg(feature = "internals")]
+fn run_stmt_passes(stmt: &mut Stmt, state: &mut OptimizerState) {
+    let engine = state.engine;
+    let context = &mut OptimizerContext { state };
+
+    for pass in &engine.optimizer_passes {
+        if pass.optimize_stmt(stmt, context) {
+            context.state.set_dirty();
+        }
+    }
+}
+
+/// Run user-defined optimization passes on an expression.
+#[cfg(feature = "internals")]
+fn run_expr_passes(expr: &mut Expr, state: &mut OptimizerState) {
+    let engine = state.engine;
+    let context = &mut OptimizerContext { state };
+
+    for pass in &engine.optimizer_passes {
+        if pass.optimize_expr(expr, context) {
+            context.state.set_dirty();
+        }
+    }
+}
+
+/// Can a node in the body of a script-defined function be inlined at a call site?
+///
+/// `size` keeps count of the number of nodes visited, and function calls (together with whether
+/// they are method calls) are collected into `calls` in order to check their effects.
+#[cfg(not(feature = "no_function"))]
+fn is_inlinable_node(
+    path: &[ASTNode],
+    lib: &[crate::SharedModule],
+    size: &mut usize,
+    max_size: usize,
+    calls: &mut Vec<(FnCallExpr, bool)>,
+) -> bool {
+    // Calls to script-defined functions (including recursive calls) are never inlined
+    fn is_inlinable_call(x: &FnCallExpr, lib: &[crate::SharedModule]) -> bool {
+        #[cfg(not(feature = "no_module"))]
+        if x.is_qualified() {
+            return false;
+        }
+
+        !x.capture_parent_scope
+            && !matches!(
+                x.name.as_str(),
+                KEYWORD_EVAL
+                    | KEYWORD_FN_PTR
+                    | KEYWORD_FN_PTR_CALL
+                    | KEYWORD_FN_PTR_CURRY
+                    | KEYWORD_IS_DEF_VAR
+            )
+            && !lib
+                .iter()
+                .flat_map(|m| m.iter_script_fn())
+                .any(|(.., name, _, _)| name == x.name)
+    }
+
+    *size += 1;
+
+    if *size > max_size {
+        return false;
+    }
+
+    match path.last().unwrap() {
+        ASTNode::Stmt(stmt) => match stmt {
+            Stmt::FnCall(x, ..) => {
+                calls.push(((**x).clone(), false));
+                is_inlinable_call(x, lib)
+            }
+            // Control flow must not escape the inlined function body
+            Stmt::Return(..) => false,
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(..) | Stmt::Export(..) => false,
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(..) => false,
+            _ => true,
+        },
+        ASTNode::Expr(expr) => match expr {
+            // Variables must be parameters or local variables
+            #[cfg(not(feature = "no_module"))]
+            Expr::Variable(x, ..) if !x.2.is_empty() => false,
+            Expr::Variable(x, ..) => x.0.is_some(),
+            Expr::ThisPtr(..) => false,
+            Expr::FnCall(x, ..) => {
+                calls.push(((**x).clone(), false));
+                is_inlinable_call(x, lib)
+            }
+            // Arguments of method calls are not walked
+            Expr::MethodCall(x, ..) => {
+                calls.push(((**x).clone(), true));
+                is_inlinable_call(x, lib)
+                    && x.args.iter().all(|arg| {
+                        arg.walk(&mut Vec::new(), &mut |path| {
+                            is_inlinable_node(path, lib, size, max_size, calls)
+                        })
+                    })
+            }
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(..) => false,
+            _ => true,
+        },
+    }
+}
+
+/// Is a function parameter only ever read in the body of a script-defined function,
+/// so that it can be bound to a constant?
+#[cfg(not(feature = "no_function"))]
+fn is_read_only_param(body: &[Stmt], param: &str) -> bool {
+    fn check(path: &[ASTNode], param: &str) -> bool {
+        match path {
+            [.., parent, ASTNode::Expr(Expr::Variable(x, ..))] if x.1 == param => match parent {
+                ASTNode::Stmt(
+                    Stmt::Expr(..)
+                    | Stmt::Var(..)
+                    | Stmt::If(..)
+                    | Stmt::While(..)
+                    | Stmt::Do(..)
+                    | Stmt::Switch(..)
+                    | Stmt::Return(..),
+                )
+                | ASTNode::Expr(
+                    Expr::And(..)
+                    | Expr::Or(..)
+                    | Expr::Coalesce(..)
+                    | Expr::Array(..)
+                    | Expr::Map(..)
+                    | Expr::InterpolatedString(..),
+                ) => true,
+                // The first argument of a function call may be passed by reference
+                ASTNode::Stmt(Stmt::FnCall(f, ..)) | ASTNode::Expr(Expr::FnCall(f, ..)) => {
+                    f.is_operator_call()
+                        || !matches!(f.args[0], Expr::Variable(ref v, ..) if v.1 == param)
+                }
+                _ => false,
+            },
+            // The parameter must not be re-defined
+            [.., ASTNode::Stmt(Stmt::Var(x, ..))] => x.0.name != param,
+            [.., ASTNode::Stmt(Stmt::Destructure(x, ..))] => x.1.iter().all(|v| v.name != param),
+            [.., ASTNode::Stmt(Stmt::For(x, ..))] => {
+                x.0.name != param && x.1.as_ref().map_or(true, |v| v.name != param)
+            }
+            [.., ASTNode::Stmt(Stmt::Match(x, ..))] => {
+                x.1.iter()
+                    .flat_map(|arm| arm.vars.iter())
+                    .all(|v| v.name != param)
+            }
+            // Arguments of method calls are not walked
+            [.., ASTNode::Expr(Expr::MethodCall(x, ..))] => x
+                .args
+                .iter()
+                .all(|arg| arg.walk(&mut Vec::new(), &mut |path| check(path, param))),
+            _ => true,
+        }
+    }
+
+    body.iter()
+        .all(|stmt| stmt.walk(&mut Vec::new(), &mut |path| check(path, param)))
+}
+
+/// Shift the offsets of all variables in an argument expression that is moved past `shadowed`
+/// newly-defined variables.
+///
+/// Returns `false` if the expression cannot be moved.
+#[cfg(not(feature = "no_function"))]
+fn shift_var_offsets(expr: &mut Expr, shadowed: &[ImmutableString]) -> bool {
+    match expr {
+        _ if expr.is_constant() => true,
+
+        #[cfg(not(feature = "no_module"))]
+        Expr::Variable(x, ..) if !x.2.is_empty() => false,
+        // Variables may always be searched by name, so they must not be shadowed
+        Expr::Variable(x, ..) if shadowed.contains(&x.1) => false,
+        Expr::Variable(x, short_index, ..) => {
+            if let Some(index) = x.0 {
+                let index = index.get() + shadowed.len();
+                x.0 = NonZeroUsize::new(index);
+                if short_index.is_some() {
+                    *short_index = u8::try_from(index).ok().and_then(NonZeroU8::new);
+                }
+            }
+            true
+        }
+
+        Expr::ThisPtr(..) | Expr::Property(..) => true,
+
+        Expr::FnCall(x, ..) | Expr::MethodCall(x, ..) => {
+            !x.capture_parent_scope
+                && x.name != KEYWORD_EVAL
+                && x.name != KEYWORD_IS_DEF_VAR
+                && x.args.iter_mut().all(|e| shift_var_offsets(e, shadowed))
+        }
+
+        Expr::Dot(x, ..)
+        | Expr::Index(x, ..)
+        | Expr::And(x, ..)
+        | Expr::Or(x, ..)
+        | Expr::Coalesce(x, ..) => {
+            shift_var_offsets(&mut x.lhs, shadowed) && shift_var_offsets(&mut x.rhs, shadowed)
+        }
+
+        Expr::InterpolatedString(x, ..) | Expr::Array(x, ..) => {
+            x.iter_mut().all(|e| shift_var_offsets(e, shadowed))
+        }
+        Expr::Map(x, ..) => x.0.iter_mut().all(|(.., e)| shift_var_offsets(e, shadowed)),
+
+        _ => false,
+    }
+}
+
+/// Inline a call to a script-defined function.
+///
+/// The function body is turned into a statements block with the parameters bound to the
+/// argument values, which is then subject to further constants propagation.
+///
+/// Errors raised by the body (but not by the arguments) are still wrapped in
+/// [`ErrorInFunctionCall`][crate::EvalAltResult::ErrorInFunctionCall] with the position of the
+/// call.
+#[cfg(not(feature = "no_function"))]
+fn inline_fn_call(fn_def: &ScriptFuncDef, args: &[Expr], pos: Position) -> Option<Expr> {
+    let body = fn_def.body.statements();
+    let mut statements = StmtBlockContainer::new_const();
+
+    for (i, (param, arg)) in fn_def.params.iter().zip(args).enumerate() {
+        let mut arg = arg.clone();
+
+        // Arguments are evaluated after the parameters before them have been defined
+        if i > 0 && !shift_var_offsets(&mut arg, &fn_def.params[..i]) {
+            return None;
+        }
+
+        let flags = if arg.is_constant() && is_read_only_param(body, param) {
+            ASTFlags::CONSTANT
+        } else {
+            ASTFlags::empty()
+        };
+        let ident = Ident {
+            name: param.clone(),
+            pos: arg.start_position(),
+        };
+
+        statements.push(Stmt::Var((ident, arg, None).into(), flags, pos));
+    }
+
+    let mut body = body.iter().cloned().collect::<StmtBlockContainer>();
+
+    if let Some(Stmt::Return(expr, ..)) = body.last_mut() {
+        let expr = expr.take().map_or(Expr::Unit(pos), |e| *e);
+        *body.last_mut().unwrap() = Stmt::Expr(expr.into());
+    }
+
+    let body = StmtBlock::new(body, fn_def.body.position(), Position::NONE);
+    let expr = Expr::InlinedFn((fn_def.name.clone(), body).into(), pos);
+    statements.push(Stmt::Expr(expr.into()));
+
+    Some(Expr::Stmt(
+        StmtBlock::new(statements, pos, Position::NONE).into(),
+    ))
+}
+
+/// Can a statement never fail when evaluated (barring resource limits)?
+///
+/// An inlined function body that cannot fail need not have its errors wrapped.
+#[cfg(not(feature = "no_function"))]
+fn is_infallible(stmt: &Stmt) -> bool {
+    fn is_infallible_expr(expr: &Expr) -> bool {
+        match expr {
+            _ if expr.is_constant() => true,
+
+            #[cfg(not(feature = "no_module"))]
+            Expr::Variable(x, ..) if !x.2.is_empty() => false,
+            Expr::Variable(..) => true,
+
+            Expr::Stmt(x) => x.iter().all(is_infallible),
+
+            _ => false,
+        }
+    }
+
+    match stmt {
+        Stmt::Noop(..) => true,
+        Stmt::Var(x, ..) => is_infallible_expr(&x.1),
+        Stmt::Expr(e) => is_infallible_expr(e),
+        _ => false,
+    }
+}
+
+/// Effects of calling a function, as far as the optimizer can tell.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+enum FnEffects {
+    /// Does not mutate its first argument, and always returns the same result for the same inputs.
+    Pure,
+    /// Does not mutate its first argument, but may not return the same result for the same inputs.
+    Volatile,
+    /// May mutate its first argument.
+    Mutating,
+    /// May do anything, including changing shared variables (e.g. a script-defined function or a
+    /// native function that can call back into scripts).
+    Unknown,
+}
+
+/// Is the right-hand side of a chain an index?
+///
+/// Returns `(is_index, is_end)` where `is_end` is `true` if the right-hand side is the final index
+/// value instead of the rest of the chain.
+#[inline]
+fn chain_step(expr: &Expr) -> (bool, bool) {
+    match expr {
+        Expr::Index(_, options, ..) => (true, options.intersects(ASTFlags::BREAK)),
+        _ => (false, false),
+    }
+}
+
+/// Get the name of the variable at the root of an expression, if any.
+fn root_var(expr: &Expr) -> Option<&ImmutableString> {
+    match expr {
+        #[cfg(not(feature = "no_module"))]
+        Expr::Variable(x, ..) if !x.2.is_empty() => None,
+        Expr::Variable(x, ..) => Some(&x.1),
+        Expr::Dot(x, ..) | Expr::Index(x, ..) => root_var(&x.lhs),
+        _ => None,
+    }
+}
+
+/// Are two expressions the same, disregarding positions and variable offsets?
+fn is_same_expr(expr1: &Expr, expr2: &Expr) -> bool {
+    match (expr1, expr2) {
+        (Expr::Unit(..), Expr::Unit(..)) => true,
+        (Expr::BoolConstant(x, ..), Expr::BoolConstant(y, ..)) => x == y,
+        (Expr::IntegerConstant(x, ..), Expr::IntegerConstant(y, ..)) => x == y,
+        (Expr::CharConstant(x, ..), Expr::CharConstant(y, ..)) => x == y,
+        (Expr::StringConstant(x, ..), Expr::StringConstant(y, ..)) => x == y,
+        #[cfg(not(feature = "no_module"))]
+        (Expr::Variable(x, ..), Expr::Variable(y, ..)) if !x.2.is_empty() || !y.2.is_empty() => {
+            false
+        }
+        (Expr::Variable(x, ..), Expr::Variable(y, ..)) => x.1 == y.1,
+        (Expr::Property(x, ..), Expr::Property(y, ..)) => x.2 == y.2,
+        (Expr::FnCall(x, ..), Expr::FnCall(y, ..))
+        | (Expr::MethodCall(x, ..), Expr::MethodCall(y, ..)) => {
+            #[cfg(not(feature = "no_module"))]
+            if x.is_qualified() || y.is_qualified() {
+                return false;
+            }
+
+            x.name == y.name
+                && x.op_token == y.op_token
+                && x.capture_parent_scope == y.capture_parent_scope
+                && x.args.len() == y.args.len()
+                && x.args.iter().zip(&y.args).all(|(a, b)| is_same_expr(a, b))
+        }
+        (Expr::Dot(x, options1, ..), Expr::Dot(y, options2, ..))
+        | (Expr::Index(x, options1, ..), Expr::Index(y, options2, ..)) => {
+            options1 == options2 && is_same_expr(&x.lhs, &y.lhs) && is_same_expr(&x.rhs, &y.rhs)
+        }
+        _ => false,
+    }
+}
+
+/// Variables that may be changed by a piece of code.
+#[derive(Debug, Clone, Default)]
+struct WriteSet {
+    /// Names of variables that may be defined, assigned to or mutated.
+    names: BTreeSet<ImmutableString>,
+    /// Names of all variables referenced.
+    used: BTreeSet<ImmutableString>,
+    /// Can variables be changed in ways that cannot be tracked?
+    is_opaque: bool,
+}
+
+impl WriteSet {
+    /// Scan a list of statements.
+    fn scan_block(&mut self, statements: &[Stmt], state: &mut OptimizerState) {
+        statements
+            .iter()
+            .for_each(|stmt| self.scan_stmt(stmt, state));
+    }
+    /// Scan a statement.
+    fn scan_stmt(&mut self, stmt: &Stmt, state: &mut OptimizerState) {
+        match stmt {
+            Stmt::Noop(..) | Stmt::BreakLoop(None, ..) | Stmt::Return(None, ..) => (),
+
+            Stmt::If(x, ..) | Stmt::While(x, ..) | Stmt::Do(x, ..) => {
+                self.scan_expr(&x.expr, state);
+                self.scan_block(x.body.statements(), state);
+                self.scan_block(x.branch.statements(), state);
+            }
+            Stmt::TryCatch(x, ..) => {
+                if let Expr::Variable(ref v, ..) = x.expr {
+                    self.names.insert(v.1.clone());
+                }
+                self.scan_block(x.body.statements(), state);
+                self.scan_block(x.branch.statements(), state);
+            }
+            Stmt::Switch(x, ..) => {
+                self.scan_expr(&x.0, state);
+                for expr in &x.1.expressions {
+                    self.scan_expr(&expr.lhs, state);
+                    self.scan_expr(&expr.rhs, state);
+                }
+            }
+            Stmt::Match(x, ..) => {
+                self.scan_expr(&x.0, state);
+                for arm in &x.1 {
+                    self.names.extend(arm.vars.iter().map(|v| v.name.clone()));
+                    self.scan_expr(&arm.condition, state);
+                    self.scan_expr(&arm.expr, state);
+                }
+            }
+            Stmt::For(x, ..) => {
+                self.names.insert(x.0.name.clone());
+                if let Some(ref counter) = x.1 {
+                    self.names.insert(counter.name.clone());
+                }
+                self.scan_expr(&x.2.expr, state);
+                self.scan_block(x.2.body.statements(), state);
+            }
+            Stmt::Var(x, ..) => {
+                self.names.insert(x.0.name.clone());
+                self.scan_expr(&x.1, state);
+            }
+            Stmt::Destructure(x, ..) => {
+                self.names.extend(x.1.iter().map(|v| v.name.clone()));
+                self.scan_expr(&x.2, state);
+            }
+            Stmt::Assignment(x) => {
+                if let Some(name) = root_var(&x.1.lhs) {
+                    self.names.insert(name.clone());
+                }
+                self.scan_expr(&x.1.lhs, state);
+                self.scan_expr(&x.1.rhs, state);
+            }
+            Stmt::FnCall(x, ..) => self.scan_call(x, state),
+            Stmt::Block(x) => self.scan_block(x.statements(), state),
+            Stmt::Expr(e) | Stmt::BreakLoop(Some(e), ..) | Stmt::Return(Some(e), ..) => {
+                self.scan_expr(e, state);
+            }
+            // The scope may be accessed from outside while the generator is suspended
+            #[cfg(feature = "resumable")]
+            Stmt::Yield(..) => self.is_opaque = true,
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, ..) => self.scan_expr(&x.0, state),
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(..) => (),
+            // Shared variables may be changed by any closure
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(..) => self.is_opaque = true,
+        }
+    }
+    /// Scan an expression.
+    fn scan_expr(&mut self, expr: &Expr, state: &mut OptimizerState) {
+        match expr {
+            Expr::Variable(x, ..) => {
+                self.used.insert(x.1.clone());
+            }
+            Expr::FnCall(x, ..) => self.scan_call(x, state),
+            Expr::MethodCall(x, ..) => {
+                x.args.iter().for_each(|arg| self.scan_expr(arg, state));
+                if state.call_effects(x, true) == FnEffects::Unknown {
+                    self.is_opaque = true;
+                }
+            }
+            Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+                let (is_index, is_end) = chain_step(expr);
+
+                // Method calls in a chain may mutate the object at the root of the chain
+                if self.scan_chain(&x.rhs, is_index, is_end, state) {
+                    if let Some(name) = root_var(&x.lhs) {
+                        self.names.insert(name.clone());
+                    }
+                }
+                self.scan_expr(&x.lhs, state);
+            }
+            Expr::And(x, ..) | Expr::Or(x, ..) | Expr::Coalesce(x, ..) => {
+                self.scan_expr(&x.lhs, state);
+                self.scan_expr(&x.rhs, state);
+            }
+            Expr::InterpolatedString(x, ..) | Expr::Array(x, ..) => {
+                x.iter().for_each(|e| self.scan_expr(e, state));
+            }
+            Expr::Map(x, ..) => x.0.iter().for_each(|(_, e)| self.scan_expr(e, state)),
+            Expr::Stmt(x) => self.scan_block(x.statements(), state),
+            #[cfg(not(feature = "no_function"))]
+            Expr::InlinedFn(x, ..) => self.scan_block(x.1.statements(), state),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(..) => self.is_opaque = true,
+            _ => (),
+        }
+    }
+    /// Scan the rest of a chain.
+    ///
+    /// Returns `true` if the object at the root of the chain may be mutated.
+    fn scan_chain(
+        &mut self,
+        expr: &Expr,
+        is_index: bool,
+        is_end: bool,
+        state: &mut OptimizerState,
+    ) -> bool {
+        match expr {
+            _ if is_end => {
+                self.scan_expr(expr, state);
+                false
+            }
+            Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+                let (next_is_index, next_is_end) = chain_step(expr);
+                let mutated = self.scan_chain(&x.lhs, is_index, is_index, state);
+                self.scan_chain(&x.rhs, next_is_index, next_is_end, state) || mutated
+            }
+            _ if is_index => {
+                self.scan_expr(expr, state);
+                false
+            }
+            Expr::MethodCall(x, ..) => {
+                x.args.iter().for_each(|arg| self.scan_expr(arg, state));
+
+                match state.call_effects(x, true) {
+                    FnEffects::Pure | FnEffects::Volatile => false,
+                    FnEffects::Mutating => true,
+                    FnEffects::Unknown => {
+                        self.is_opaque = true;
+                        true
+                    }
+                }
+            }
+            _ => false,
+        }
+    }
+    /// Scan a function call.
+    fn scan_call(&mut self, x: &FnCallExpr, state: &mut OptimizerState) {
+        x.args.iter().for_each(|arg| self.scan_expr(arg, state));
+
+        match state.call_effects(x, false) {
+            FnEffects::Pure | FnEffects::Volatile => (),
+            // The first argument may be passed by reference
+            FnEffects::Mutating => {
+                if let Some(name) = x.args.first().and_then(root_var) {
+                    self.names.insert(name.clone());
+                }
+            }
+            FnEffects::Unknown => self.is_opaque = true,
+        }
+    }
+}
+
+/// Is an expression invariant, i.e. always evaluating to the same value without side effects as
+/// long as none of the variables in a [`WriteSet`] are changed?
+fn is_invariant(expr: &Expr, state: &mut OptimizerState, ws: &WriteSet) -> bool {
+    match expr {
+        _ if expr.is_constant() => true,
+
+        #[cfg(not(feature = "no_module"))]
+        Expr::Variable(x, ..) if !x.2.is_empty() => false,
+        Expr::Variable(x, ..) => !ws.names.contains(&x.1),
+
+        Expr::FnCall(x, ..) => {
+            state.call_effects(x, false) == FnEffects::Pure
+                && x.args.iter().all(|arg| is_invariant(arg, state, ws))
+        }
+        Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+            let (is_index, is_end) = chain_step(expr);
+            is_invariant(&x.lhs, state, ws)
+                && is_invariant_chain(&x.rhs, is_index, is_end, state, ws)
+        }
+
+        _ => false,
+    }
+}
+
+/// Is the rest of a chain invariant?
+fn is_invariant_chain(
+    expr: &Expr,
+    is_index: bool,
+    is_end: bool,
+    state: &mut OptimizerState,
+    ws: &WriteSet,
+) -> bool {
+    match expr {
+        _ if is_index => {
+            // Indexers must be pure
+            #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+            if state
+                .fn_effects(crate::engine::FN_IDX_GET, 2)
+                .map_or(false, |effects| effects != FnEffects::Pure)
+            {
+                return false;
+            }
+
+            match expr {
+                Expr::Dot(x, ..) | Expr::Index(x, ..) if !is_end => {
+                    let (next_is_index, next_is_end) = chain_step(expr);
+                    is_invariant(&x.lhs, state, ws)
+                        && is_invariant_chain(&x.rhs, next_is_index, next_is_end, state, ws)
+                }
+                _ => is_invariant(expr, state, ws),
+            }
+        }
+        Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+            let (next_is_index, next_is_end) = chain_step(expr);
+            is_invariant_chain(&x.lhs, false, false, state, ws)
+                && is_invariant_chain(&x.rhs, next_is_index, next_is_end, state, ws)
+        }
+        // Property getters must be pure (there are none for object maps)
+        Expr::Property(x, ..) => state
+            .fn_effects(&x.0 .0, 1)
+            .map_or(true, |effects| effects == FnEffects::Pure),
+        Expr::MethodCall(x, ..) => {
+            state.call_effects(x, true) == FnEffects::Pure
+                && x.args.iter().all(|arg| is_invariant(arg, state, ws))
+        }
+        _ => false,
+    }
+}
+
+/// Invariant sub-expressions collected from a piece of code.
+///
+/// Only sub-expressions that are evaluated before anything with side effects are collected, in
+/// order of evaluation, so that they can be evaluated ahead of the code.
+struct Invariants<'w> {
+    /// Variables that may be changed by the code.
+    ws: &'w WriteSet,
+    /// Collect only property/index chains?
+    chains_only: bool,
+    /// Sub-expressions collected, with the offsets of variables relative to the start of the code.
+    list: Vec<Expr>,
+}
+
+impl<'w> Invariants<'w> {
+    /// Create a new [`Invariants`].
+    #[inline(always)]
+    pub const fn new(ws: &'w WriteSet, chains_only: bool) -> Self {
+        Self {
+            ws,
+            chains_only,
+            list: Vec::new(),
+        }
+    }
+    /// Collect from an expression.
+    ///
+    /// `depth` is the number of variables defined since the start of the code.
+    ///
+    /// Returns `false` if the expression may have side effects, so nothing after it can be
+    /// collected.
+    pub fn collect(&mut self, expr: &Expr, state: &mut OptimizerState, depth: usize) -> bool {
+        match expr {
+            Expr::FnCall(..) if self.chains_only => (),
+            Expr::Dot(..) | Expr::Index(..) | Expr::FnCall(..)
+                if !expr.is_constant() && is_invariant(expr, state, self.ws) =>
+            {
+                if !self.list.iter().any(|e| is_same_expr(e, expr)) {
+                    let mut expr = expr.clone();
+                    #[allow(clippy::cast_possible_wrap)]
+                    VarRewriter::new(state, -(depth as isize), &[])
+                        .rewrite_expr(&mut expr, 0, false);
+                    self.list.push(expr);
+                }
+                return true;
+            }
+            _ => (),
+        }
+
+        match expr {
+            _ if expr.is_constant() => true,
+            Expr::Variable(..) | Expr::ThisPtr(..) => true,
+
+            Expr::FnCall(x, ..) => self.collect_call(x, state, depth),
+            // The right-hand side is only evaluated conditionally
+            Expr::And(x, ..) | Expr::Or(x, ..) | Expr::Coalesce(x, ..) => {
+                self.collect(&x.lhs, state, depth);
+                false
+            }
+            Expr::Array(x, ..) => x.iter().all(|e| self.collect(e, state, depth)),
+
+            _ => false,
+        }
+    }
+    /// Collect from the arguments of a function call.
+    ///
+    /// Returns `false` if the function call may have side effects.
+    fn collect_call(&mut self, x: &FnCallExpr, state: &mut OptimizerState, depth: usize) -> bool {
+        let effects = state.call_effects(x, false);
+
+        effects != FnEffects::Unknown
+            && x.args.iter().enumerate().all(|(i, arg)| match arg {
+                // The first argument may be passed by reference
+                _ if i == 0 && effects == FnEffects::Mutating => {
+                    arg.is_constant() || matches!(arg, Expr::Variable(..))
+                }
+                _ => self.collect(arg, state, depth),
+            })
+            && effects == FnEffects::Pure
+    }
+    /// Collect from a statement, adding the number of new variables it defines to `depth`.
+    ///
+    /// Returns `false` if the statement may have side effects, so nothing after it can be
+    /// collected.
+    pub fn collect_stmt(
+        &mut self,
+        stmt: &Stmt,
+        state: &mut OptimizerState,
+        depth: &mut usize,
+    ) -> bool {
+        match stmt {
+            Stmt::Expr(e) => self.collect(e, state, *depth),
+            Stmt::FnCall(x, ..) => self.collect_call(x, state, *depth),
+            Stmt::Var(x, ..) => {
+                let ok = self.collect(&x.1, state, *depth);
+                if x.2.is_none() {
+                    *depth += 1;
+                }
+                ok
+            }
+            _ => false,
+        }
+    }
 }
 
-impl<'a> OptimizerState<'a> {
-    /// Create a new [`OptimizerState`].
+/// Rewrites code in front of which new variables are defined, optionally replacing expressions by
+/// those variables.
+struct VarRewriter<'s, 'a> {
+    /// Optimizer state.
+    state: &'s mut OptimizerState<'a>,
+    /// Change to the offsets of all variables defined outside the code.
+    shift: isize,
+    /// Expressions to replace, together with the names of the new variables holding their values
+    /// (in the order that they are defined).
+    targets: &'s [(Expr, ImmutableString)],
+    /// Number of expressions replaced.
+    count: usize,
+}
+
+impl<'s, 'a> VarRewriter<'s, 'a> {
+    /// Create a new [`VarRewriter`].
     #[inline(always)]
     pub fn new(
-        engine: &'a Engine,
-        lib: &'a [crate::SharedModule],
-        scope: Option<&'a Scope<'a>>,
-        optimization_level: OptimizationLevel,
+        state: &'s mut OptimizerState<'a>,
+        shift: isize,
+        targets: &'s [(Expr, ImmutableString)],
     ) -> Self {
-        let mut _global = engine.new_global_runtime_state();
-        let _lib = lib;
+        Self {
+            state,
+            shift,
+            targets,
+            count: 0,
+        }
+    }
+    /// Shift the offset of a variable defined outside the code.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    fn shift_index(&self, index: &mut Option<NonZeroUsize>, depth: usize) {
+        if let Some(n) = index.filter(|n| n.get() > depth) {
+            *index = NonZeroUsize::new((n.get() as isize + self.shift) as usize);
+        }
+    }
+    /// Rewrite a list of statements.
+    pub fn rewrite_block(&mut self, statements: &mut [Stmt], depth: usize) {
+        let mut depth = depth;
+        statements
+            .iter_mut()
+            .for_each(|stmt| self.rewrite_stmt(stmt, &mut depth));
+    }
+    /// Rewrite a statement, adding the number of new variables it defines to `depth`.
+    pub fn rewrite_stmt(&mut self, stmt: &mut Stmt, depth: &mut usize) {
+        let d = *depth;
 
-        #[cfg(not(feature = "no_function"))]
-        {
-            _global.lib = _lib.into();
+        match stmt {
+            Stmt::Noop(..) | Stmt::BreakLoop(None, ..) | Stmt::Return(None, ..) => (),
+
+            Stmt::If(x, ..) | Stmt::While(x, ..) | Stmt::Do(x, ..) => {
+                self.rewrite_expr(&mut x.expr, d, false);
+                self.rewrite_block(x.body.statements_mut(), d);
+                self.rewrite_block(x.branch.statements_mut(), d);
+            }
+            Stmt::TryCatch(x, ..) => {
+                self.rewrite_block(x.body.statements_mut(), d);
+                let n = usize::from(!x.expr.is_unit());
+                self.rewrite_block(x.branch.statements_mut(), d + n);
+            }
+            Stmt::Switch(x, ..) => {
+                self.rewrite_expr(&mut x.0, d, false);
+                for expr in &mut x.1.expressions {
+                    self.rewrite_expr(&mut expr.lhs, d, false);
+                    self.rewrite_expr(&mut expr.rhs, d, false);
+                }
+            }
+            Stmt::Match(x, ..) => {
+                self.rewrite_expr(&mut x.0, d, false);
+                for arm in &mut x.1 {
+                    let d = d + arm.vars.len();
+                    self.rewrite_expr(&mut arm.condition, d, false);
+                    self.rewrite_expr(&mut arm.expr, d, false);
+                }
+            }
+            Stmt::For(x, ..) => {
+                self.rewrite_expr(&mut x.2.expr, d, false);
+                let n = 1 + usize::from(x.1.is_some());
+                self.rewrite_block(x.2.body.statements_mut(), d + n);
+            }
+            Stmt::Var(x, ..) => {
+                self.rewrite_expr(&mut x.1, d, false);
+                if x.2.is_none() {
+                    *depth += 1;
+                } else {
+                    self.shift_index(&mut x.2, d);
+                }
+            }
+            Stmt::Destructure(x, ..) => {
+                self.rewrite_expr(&mut x.2, d, false);
+                *depth += x.1.len();
+            }
+            Stmt::Assignment(x) => {
+                self.rewrite_expr(&mut x.1.lhs, d, true);
+                self.rewrite_expr(&mut x.1.rhs, d, false);
+            }
+            Stmt::FnCall(x, ..) => self.rewrite_call(x, d),
+            Stmt::Block(x) => self.rewrite_block(x.statements_mut(), d),
+            Stmt::Expr(e) | Stmt::BreakLoop(Some(e), ..) | Stmt::Return(Some(e), ..) => {
+                self.rewrite_expr(e, d, false);
+            }
+            #[cfg(feature = "resumable")]
+            Stmt::Yield(e, ..) => {
+                if let Some(e) = e {
+                    self.rewrite_expr(e, d, false);
+                }
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, ..) => self.rewrite_expr(&mut x.0, d, false),
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(..) => (),
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(x) => x.iter_mut().for_each(|(_, index)| {
+                self.shift_index(index, d);
+            }),
+        }
+    }
+    /// Rewrite an expression.
+    ///
+    /// If `by_ref` is `true`, the expression may be passed by reference and is never replaced.
+    pub fn rewrite_expr(&mut self, expr: &mut Expr, depth: usize, by_ref: bool) {
+        if !by_ref {
+            if let Some(n) = self.targets.iter().position(|(e, _)| is_same_expr(e, expr)) {
+                let index = depth + self.targets.len() - n;
+                *expr = new_var_expr(self.targets[n].1.clone(), index, expr.start_position());
+                self.count += 1;
+                return;
+            }
         }
 
-        Self {
-            is_dirty: false,
-            variables: Vec::new(),
-            propagate_constants: true,
-            engine,
-            scope,
-            global: _global,
-            caches: Caches::new(),
-            optimization_level,
+        let (is_index, is_end) = chain_step(expr);
+
+        match expr {
+            Expr::Variable(x, short_index, ..) => {
+                self.shift_index(&mut x.0, depth);
+                // The short index is a copy of the index, if it fits
+                if short_index.is_some() {
+                    *short_index =
+                        x.0.and_then(|n| u8::try_from(n.get()).ok())
+                            .and_then(NonZeroU8::new);
+                }
+            }
+            Expr::Stmt(x) => self.rewrite_block(x.statements_mut(), depth),
+            #[cfg(not(feature = "no_function"))]
+            Expr::InlinedFn(x, ..) => self.rewrite_block(x.1.statements_mut(), depth),
+            Expr::FnCall(x, ..) => self.rewrite_call(x, depth),
+            Expr::MethodCall(x, ..) => x
+                .args
+                .iter_mut()
+                .for_each(|arg| self.rewrite_expr(arg, depth, false)),
+            Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+                self.rewrite_expr(&mut x.lhs, depth, true);
+                self.rewrite_chain(&mut x.rhs, is_index, is_end, depth);
+            }
+            Expr::And(x, ..) | Expr::Or(x, ..) | Expr::Coalesce(x, ..) => {
+                self.rewrite_expr(&mut x.lhs, depth, false);
+                self.rewrite_expr(&mut x.rhs, depth, false);
+            }
+            Expr::InterpolatedString(x, ..) | Expr::Array(x, ..) => x
+                .iter_mut()
+                .for_each(|e| self.rewrite_expr(e, depth, false)),
+            Expr::Map(x, ..) => {
+                x.0.iter_mut()
+                    .for_each(|(_, e)| self.rewrite_expr(e, depth, false))
+            }
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, ..) => x
+                .inputs
+                .iter_mut()
+                .for_each(|e| self.rewrite_expr(e, depth, false)),
+            _ => (),
         }
     }
-    /// Set the [`AST`] state to be dirty (i.e. changed).
-    #[inline(always)]
-    pub fn set_dirty(&mut self) {
-        self.is_dirty = true;
+    /// Rewrite the rest of a chain.
+    fn rewrite_chain(&mut self, expr: &mut Expr, is_index: bool, is_end: bool, depth: usize) {
+        let (next_is_index, next_is_end) = chain_step(expr);
+
+        match expr {
+            _ if is_end => self.rewrite_expr(expr, depth, false),
+            Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+                self.rewrite_chain(&mut x.lhs, is_index, is_index, depth);
+                self.rewrite_chain(&mut x.rhs, next_is_index, next_is_end, depth);
+            }
+            _ if is_index => self.rewrite_expr(expr, depth, false),
+            Expr::MethodCall(x, ..) => x
+                .args
+                .iter_mut()
+                .for_each(|arg| self.rewrite_expr(arg, depth, false)),
+            _ => (),
+        }
     }
-    /// Set the [`AST`] state to be not dirty (i.e. unchanged).
-    #[inline(always)]
-    pub fn clear_dirty(&mut self) {
-        self.is_dirty = false;
+    /// Rewrite a function call.
+    fn rewrite_call(&mut self, x: &mut FnCallExpr, depth: usize) {
+        // The first argument may be passed by reference
+        let by_ref = matches!(
+            self.state.call_effects(x, false),
+            FnEffects::Mutating | FnEffects::Unknown
+        );
+
+        x.args
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, arg)| self.rewrite_expr(arg, depth, by_ref && i == 0));
     }
-    /// Is the [`AST`] dirty (i.e. changed)?
-    #[inline(always)]
-    pub const fn is_dirty(&self) -> bool {
-        self.is_dirty
+}
+
+/// Create a new [variable][Expr::Variable] expression at a particular offset.
+fn new_var_expr(name: ImmutableString, index: usize, pos: Position) -> Expr {
+    let index = NonZeroUsize::new(index);
+    let short_index = index
+        .and_then(|n| u8::try_from(n.get()).ok())
+        .and_then(NonZeroU8::new);
+
+    Expr::Variable(
+        #[cfg(not(feature = "no_module"))]
+        (index, name, crate::ast::Namespace::NONE, 0).into(),
+        #[cfg(feature = "no_module")]
+        (index, name).into(),
+        short_index,
+        pos,
+    )
+}
+
+/// Can new variables be defined by the optimizer?
+fn can_define_temp_vars(engine: &Engine) -> bool {
+    engine.def_var_filter.is_none() && engine.resolve_var.is_none()
+}
+
+/// Hoist invariant expressions out of a loop, binding their values to new variables defined before
+/// the loop.
+///
+/// Only expressions that are evaluated at the start of the first iteration, ahead of anything with
+/// side effects, are hoisted. They are then evaluated just once.
+///
+/// The bodies of `while` and `for` loops may not run at all, so invariants in them are only
+/// assigned to their variables on the first iteration, guarded by a flag.
+fn hoist_loop_invariants(stmt: &mut Stmt, state: &mut OptimizerState) {
+    if !can_define_temp_vars(state.engine) {
+        return;
     }
-    /// Rewind the variables stack back to a specified size.
-    #[inline(always)]
-    pub fn rewind_var(&mut self, len: usize) {
-        self.variables.truncate(len);
+
+    let mut ws = WriteSet::default();
+    ws.scan_stmt(stmt, state);
+
+    if ws.is_opaque {
+        return;
     }
-    /// Add a new variable to the stack.
-    ///
-    /// `Some(value)` if literal constant (which can be used for constants propagation), `None` otherwise.
-    #[inline(always)]
-    pub fn push_var<'x: 'a>(&mut self, name: ImmutableString, value: Option<Cow<'x, Dynamic>>) {
-        self.variables.push((name, value));
+
+    let mut invariants = Invariants::new(&ws, false);
+
+    // Number of loop variables defined for the body, and whether the body may not run
+    let (body, depth, is_guarded) = match stmt {
+        // while expr { ... }
+        Stmt::While(x, ..) if !x.expr.is_unit() => {
+            invariants.collect(&x.expr, state, 0);
+            (&x.body, 0, true)
+        }
+        // loop { ... } | do { ... } while expr
+        Stmt::While(x, ..) | Stmt::Do(x, ..) => (&x.body, 0, false),
+        // for id in expr { ... }
+        Stmt::For(x, ..) => (&x.2.body, 1 + usize::from(x.1.is_some()), true),
+        _ => return,
+    };
+
+    // The condition of a `while` loop is always evaluated
+    let unguarded = if is_guarded {
+        invariants.list.len()
+    } else {
+        usize::MAX
+    };
+
+    let mut d = depth;
+    for s in body.iter() {
+        if !invariants.collect_stmt(s, state, &mut d) {
+            break;
+        }
     }
-    /// Look up a literal constant from the variables stack.
-    #[inline]
-    pub fn find_literal_constant(&self, name: &str) -> Option<&Dynamic> {
-        self.variables
-            .iter()
-            .rev()
-            .find(|(n, _)| n == name)
-            .and_then(|(_, value)| value.as_deref())
+
+    if invariants.list.is_empty() {
+        return;
     }
-    /// Call a registered function
-    #[inline]
-    pub fn call_fn_with_const_args(
-        &mut self,
-        fn_name: &str,
-        op_token: Option<&Token>,
-        arg_values: &mut [Dynamic],
-    ) -> Option<Dynamic> {
-        self.engine
-            .exec_native_fn_call(
-                &mut self.global,
-                &mut self.caches,
-                fn_name,
-                op_token,
-                calc_fn_hash(None, fn_name, arg_values.len()),
-                &mut arg_values.iter_mut().collect::<FnArgsVec<_>>(),
-                false,
-                true,
-                Position::NONE,
-            )
-            .ok()
-            .map(|(v, ..)| v)
+
+    let flag = (invariants.list.len() > unguarded).then(|| state.new_temp_var(&ws.used));
+
+    let targets = invariants
+        .list
+        .into_iter()
+        .map(|expr| (expr, state.new_temp_var(&ws.used)))
+        .collect::<Vec<_>>();
+
+    // The flag is defined before all the other new variables
+    let len = targets.len();
+    let shift = len + usize::from(flag.is_some());
+
+    #[allow(clippy::cast_possible_wrap)]
+    VarRewriter::new(state, shift as isize, &targets).rewrite_stmt(stmt, &mut 0);
+
+    let pos = stmt.position();
+    let mut statements = StmtBlockContainer::new_const();
+    let mut guard = StmtBlockContainer::new_const();
+
+    if let Some(ref name) = flag {
+        let ident = Ident {
+            name: name.clone(),
+            pos,
+        };
+        statements.push(Stmt::Var(
+            (ident, Expr::BoolConstant(true, pos), None).into(),
+            ASTFlags::empty(),
+            pos,
+        ));
+        let lhs = new_var_expr(name.clone(), depth + len + 1, pos);
+        let rhs = Expr::BoolConstant(false, pos);
+        guard.push(Stmt::Assignment(
+            (OpAssignment::new_assignment(pos), BinaryExpr { lhs, rhs }).into(),
+        ));
+    }
+
+    for (i, (mut expr, name)) in targets.into_iter().enumerate() {
+        let var_pos = expr.start_position();
+
+        let value = if i < unguarded {
+            // Each new variable is defined after the ones before it
+            #[allow(clippy::cast_possible_wrap)]
+            VarRewriter::new(state, (shift - len + i) as isize, &[])
+                .rewrite_expr(&mut expr, 0, false);
+            expr
+        } else {
+            // Assigned at the start of the body, after all the new variables and loop variables
+            #[allow(clippy::cast_possible_wrap)]
+            VarRewriter::new(state, (shift + depth) as isize, &[])
+                .rewrite_expr(&mut expr, 0, false);
+            let lhs = new_var_expr(name.clone(), depth + len - i, var_pos);
+            guard.push(Stmt::Assignment(
+                (
+                    OpAssignment::new_assignment(var_pos),
+                    BinaryExpr { lhs, rhs: expr },
+                )
+                    .into(),
+            ));
+            Expr::Unit(var_pos)
+        };
+
+        let ident = Ident { name, pos: var_pos };
+        statements.push(Stmt::Var(
+            (ident, value, None).into(),
+            ASTFlags::empty(),
+            pos,
+        ));
+    }
+
+    if let Some(name) = flag {
+        let body = match stmt {
+            Stmt::While(x, ..) => &mut x.body,
+            Stmt::For(x, ..) => &mut x.2.body,
+            _ => unreachable!("`Stmt::While` or `Stmt::For`"),
+        };
+        let expr = new_var_expr(name, depth + len + 1, pos);
+        let body_pos = body.position();
+        let guard = StmtBlock::new(guard, body_pos, Position::NONE);
+
+        body.statements_mut().insert(
+            0,
+            Stmt::If(
+                FlowControl {
+                    expr,
+                    body: guard,
+                    branch: StmtBlock::NONE,
+                }
+                .into(),
+                body_pos,
+            ),
+        );
+    }
+
+    statements.push(stmt.take());
+
+    *stmt = Stmt::Block(StmtBlock::new(statements, pos, Position::NONE).into());
+    state.set_dirty();
+}
+
+/// Eliminate a common sub-expression in a statements block.
+///
+/// A property/index chain that is evaluated in a statement ahead of anything with side effects,
+/// and then repeated before any of its variables may change, is bound to a new variable.
+///
+/// Returns `true` if the block is changed.
+fn eliminate_common_subexpr(
+    statements: &mut StmtBlockContainer,
+    state: &mut OptimizerState,
+) -> bool {
+    if !can_define_temp_vars(state.engine) {
+        return false;
+    }
+
+    for i in 0..statements.len() {
+        let mut ws = WriteSet::default();
+        ws.scan_stmt(&statements[i], state);
+
+        if ws.is_opaque {
+            continue;
+        }
+
+        let mut invariants = Invariants::new(&ws, true);
+        invariants.collect_stmt(&statements[i], state, &mut 0);
+
+        for expr in invariants.list {
+            let mut vars = WriteSet::default();
+            vars.scan_expr(&expr, state);
+
+            // Find the statements before any of the variables may change
+            let mut used = ws.used.clone();
+            let mut end = i + 1;
+
+            while end < statements.len() {
+                let mut ws = WriteSet::default();
+                ws.scan_stmt(&statements[end], state);
+
+                if ws.is_opaque || ws.names.iter().any(|name| vars.used.contains(name)) {
+                    break;
+                }
+                used.extend(ws.used);
+                end += 1;
+            }
+            for stmt in &statements[end..] {
+                let mut ws = WriteSet::default();
+                ws.scan_stmt(stmt, state);
+                used.extend(ws.used);
+            }
+
+            let targets = [(expr, state.new_temp_var(&used))];
+
+            // The expression must be repeated
+            let mut block = statements[i..end].to_vec();
+            let mut depth = 0;
+            let mut rewriter = VarRewriter::new(state, 1, &targets);
+            block
+                .iter_mut()
+                .for_each(|stmt| rewriter.rewrite_stmt(stmt, &mut depth));
+
+            if rewriter.count < 2 {
+                continue;
+            }
+
+            VarRewriter::new(state, 1, &[]).rewrite_block(&mut statements[end..], depth);
+
+            for (stmt, new_stmt) in statements[i..end].iter_mut().zip(block) {
+                *stmt = new_stmt;
+            }
+
+            let [(expr, name)] = targets;
+            let pos = statements[i].position();
+            let ident = Ident {
+                name,
+                pos: expr.start_position(),
+            };
+            statements.insert(
+                i,
+                Stmt::Var((ident, expr, None).into(), ASTFlags::empty(), pos),
+            );
+            return true;
+        }
     }
+
+    false
 }
 
 /// Optimize a block of [statements][Stmt].
@@ -231,6 +1707,13 @@ fn optimize_stmt_block(
                     };
                     state.push_var(x.0.name.clone(), value);
                 }
+                Stmt::Destructure(x, ..) => {
+                    optimize_expr(&mut x.2, state, false);
+
+                    for var in &x.1 {
+                        state.push_var(var.name.clone(), None);
+                    }
+                }
                 // Optimize the statement
                 _ => optimize_stmt(stmt, state, preserve_result),
             }
@@ -361,6 +1844,14 @@ fn optimize_stmt_block(
             }
         }
 
+        // Eliminate common sub-expressions
+        if (is_internal || state.is_fn_body)
+            && state.optimization_level == OptimizationLevel::Full
+            && eliminate_common_subexpr(&mut statements, state)
+        {
+            state.set_dirty();
+        }
+
         // Pop the stack and remove all the local constants
         state.rewind_var(orig_constants_len);
         state.propagate_constants = orig_propagate_constants;
@@ -400,6 +1891,9 @@ fn is_hashable_constant(expr: &Expr) -> bool {
 
 /// Optimize a [statement][Stmt].
 fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: bool) {
+    #[cfg(feature = "internals")]
+    run_stmt_passes(stmt, state);
+
     #[inline(always)]
     #[must_use]
     fn is_variable_access(expr: &Expr, _non_qualified: bool) -> bool {
@@ -522,6 +2016,7 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                     cases,
                     ranges,
                     def_case,
+                    ..
                 },
             ) = &mut **x;
 
@@ -739,6 +2234,36 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
             });
         }
 
+        // match expr { ... }
+        Stmt::Match(x, ..) => {
+            let (match_expr, arms) = &mut **x;
+
+            optimize_expr(match_expr, state, false);
+
+            for arm in &mut *arms {
+                // Variables bound by the pattern shadow any constants
+                let orig_len = state.variables.len();
+                for var in &arm.vars {
+                    state.push_var(var.name.clone(), None);
+                }
+
+                optimize_expr(&mut arm.condition, state, false);
+                optimize_expr(&mut arm.expr, state, false);
+
+                state.rewind_var(orig_len);
+            }
+
+            // Remove arms with false conditions
+            arms.retain(|arm| {
+                if matches!(arm.condition, Expr::BoolConstant(false, ..)) {
+                    state.set_dirty();
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
         // while false { block } -> Noop
         Stmt::While(x, ..) if matches!(x.expr, Expr::BoolConstant(false, ..)) => match x.expr {
             Expr::BoolConstant(false, pos) => {
@@ -756,23 +2281,37 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
             }
             *body.statements_mut() =
                 optimize_stmt_block(body.take_statements(), state, false, true, false);
+
+            if state.optimization_level == OptimizationLevel::Full {
+                hoist_loop_invariants(stmt, state);
+            }
         }
         // do { block } while|until expr
         Stmt::Do(x, ..) => {
             optimize_expr(&mut x.expr, state, false);
             *x.body.statements_mut() =
                 optimize_stmt_block(x.body.take_statements(), state, false, true, false);
+
+            if state.optimization_level == OptimizationLevel::Full {
+                hoist_loop_invariants(stmt, state);
+            }
         }
         // for id in expr { block }
         Stmt::For(x, ..) => {
             optimize_expr(&mut x.2.expr, state, false);
             *x.2.body.statements_mut() =
                 optimize_stmt_block(x.2.body.take_statements(), state, false, true, false);
+
+            if state.optimization_level == OptimizationLevel::Full {
+                hoist_loop_invariants(stmt, state);
+            }
         }
         // let id = expr;
         Stmt::Var(x, options, ..) if !options.intersects(ASTFlags::CONSTANT) => {
             optimize_expr(&mut x.1, state, false);
         }
+        // let pattern = expr;
+        Stmt::Destructure(x, ..) => optimize_expr(&mut x.2, state, false),
         // import expr as var;
         #[cfg(not(feature = "no_module"))]
         Stmt::Import(x, ..) => optimize_expr(&mut x.0, state, false),
@@ -915,6 +2454,24 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         KEYWORD_EVAL,  // arbitrary scripts
     ];
 
+    #[cfg(feature = "internals")]
+    run_expr_passes(expr, state);
+
+    // Inline script-defined functions
+    #[cfg(not(feature = "no_function"))]
+    if state.optimization_level == OptimizationLevel::Full {
+        if let Expr::FnCall(x, pos) = expr {
+            if let Some(fn_def) = state.find_inline_fn(x) {
+                if let Some(block) = inline_fn_call(&fn_def, &x.args, *pos) {
+                    state.set_dirty();
+                    *expr = block;
+                    optimize_expr(expr, state, false);
+                    return;
+                }
+            }
+        }
+    }
+
     match expr {
         // {}
         Expr::Stmt(x) if x.is_empty() => { state.set_dirty(); *expr = Expr::Unit(x.position()) }
@@ -935,6 +2492,14 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
             // { Stmt(Expr) } - promote
             if let [ Stmt::Expr(e) ] = x.statements_mut().as_mut() { state.set_dirty(); *expr = e.take(); }
         }
+        // inlined function body
+        #[cfg(not(feature = "no_function"))]
+        Expr::InlinedFn(x, ..) => {
+            *x.1.statements_mut() = optimize_stmt_block(x.1.take_statements(), state, true, true, false);
+
+            // A body that cannot fail is just a statements block
+            if x.1.iter().all(is_infallible) { state.set_dirty(); *expr = Expr::Stmt(mem::take(&mut x.1).into()); }
+        }
         // ()?.rhs
         #[cfg(not(feature = "no_object"))]
         Expr::Dot(x, options, ..) if options.intersects(ASTFlags::NEGATED) && matches!(x.lhs, Expr::Unit(..)) => {
@@ -1330,6 +2895,7 @@ impl Engine {
         scope: Option<&Scope>,
         lib: &[crate::SharedModule],
         optimization_level: OptimizationLevel,
+        is_fn_body: bool,
     ) -> StmtBlockContainer {
         let mut statements = statements;
 
@@ -1341,6 +2907,7 @@ impl Engine {
 
         // Set up the state
         let mut state = OptimizerState::new(self, lib, scope, optimization_level);
+        state.is_fn_body = is_fn_body;
 
         // Add constants from global modules
         self.global_modules
@@ -1383,13 +2950,19 @@ impl Engine {
         let lib: crate::Shared<_> = if optimization_level == OptimizationLevel::None {
             crate::Module::from(functions).into()
         } else {
-            // We only need the script library's signatures for optimization purposes
-            let lib2 = crate::Module::from(
-                functions
-                    .as_ref()
-                    .iter()
-                    .map(|fn_def| fn_def.clone_function_signatures().into()),
-            );
+            // We only need the script library's signatures for optimization purposes,
+            // unless functions are to be inlined
+            let lib2 = if optimization_level == OptimizationLevel::Full && self.max_inline_size > 0
+            {
+                crate::Module::from(functions.as_ref().iter().cloned())
+            } else {
+                crate::Module::from(
+                    functions
+                        .as_ref()
+                        .iter()
+                        .map(|fn_def| fn_def.clone_function_signatures().into()),
+                )
+            };
 
             let lib2 = &[lib2.into()];
 
@@ -1398,7 +2971,7 @@ impl Engine {
                 let mut fn_def = crate::func::shared_take_or_clone(fn_def);
                 let statements = fn_def.body.take_statements();
                 *fn_def.body.statements_mut() =
-                    self.optimize_top_level(statements, scope, lib2, optimization_level);
+                    self.optimize_top_level(statements, scope, lib2, optimization_level, true);
                 fn_def.into()
             }))
             .into()
@@ -1411,9 +2984,13 @@ impl Engine {
         AST::new(
             match optimization_level {
                 OptimizationLevel::None => statements,
-                OptimizationLevel::Simple | OptimizationLevel::Full => {
-                    self.optimize_top_level(statements, scope, &[lib.clone()], optimization_level)
-                }
+                OptimizationLevel::Simple | OptimizationLevel::Full => self.optimize_top_level(
+                    statements,
+                    scope,
+                    &[lib.clone()],
+                    optimization_level,
+                    false,
+                ),
             },
             #[cfg(not(feature = "no_function"))]
             lib,
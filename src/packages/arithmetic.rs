@@ -13,6 +13,20 @@ pub fn make_err(msg: impl Into<String>) -> RhaiError {
     ERR::ErrorArithmetic(msg.into(), Position::NONE).into()
 }
 
+/// Raise an error if the size (in bytes) of a [`BigInt`][crate::BigInt] would exceed the limit.
+///
+/// The size of a [`BigInt`][crate::BigInt] counts towards the [`String`] size limit.
+#[cfg(feature = "bigint")]
+#[cfg(not(feature = "unchecked"))]
+fn check_bigint_size(engine: &crate::Engine, size: usize) -> RhaiResultOf<()> {
+    match engine.max_string_size() {
+        max if max > 0 && size > max => {
+            Err(ERR::ErrorDataTooLarge("Size of number".to_string(), Position::NONE).into())
+        }
+        _ => Ok(()),
+    }
+}
+
 macro_rules! gen_arithmetic_functions {
     ($root:ident => $($arg_type:ident),+) => {
         #[allow(non_snake_case)]
@@ -228,6 +242,10 @@ def_package! {
         // Decimal functions
         #[cfg(feature = "decimal")]
         combine_with_exported_module!(lib, "decimal", decimal_functions);
+
+        // BigInt functions
+        #[cfg(feature = "bigint")]
+        combine_with_exported_module!(lib, "bigint", bigint_functions);
     }
 }
 
@@ -555,3 +573,144 @@ pub mod decimal_functions {
         x.is_zero()
     }
 }
+
+#[cfg(feature = "bigint")]
+#[export_module]
+pub mod bigint_functions {
+    use crate::BigInt;
+    use num_traits::{Signed, Zero};
+    use std::convert::TryFrom;
+
+    #[rhai_fn(name = "+")]
+    pub fn add(x: BigInt, y: BigInt) -> BigInt {
+        x + y
+    }
+    #[rhai_fn(name = "-")]
+    pub fn subtract(x: BigInt, y: BigInt) -> BigInt {
+        x - y
+    }
+    #[rhai_fn(name = "*")]
+    pub fn multiply(x: BigInt, y: BigInt) -> BigInt {
+        x * y
+    }
+    #[rhai_fn(name = "/", return_raw)]
+    pub fn divide(x: BigInt, y: BigInt) -> RhaiResultOf<BigInt> {
+        // Detect division by zero
+        if y.is_zero() {
+            Err(make_err(format!("Division by zero: {x} / {y}")))
+        } else {
+            Ok(x / y)
+        }
+    }
+    #[rhai_fn(name = "%", return_raw)]
+    pub fn modulo(x: BigInt, y: BigInt) -> RhaiResultOf<BigInt> {
+        // Detect division by zero
+        if y.is_zero() {
+            Err(make_err(format!("Modulo division by zero: {x} % {y}")))
+        } else {
+            Ok(x % y)
+        }
+    }
+    #[rhai_fn(name = "**", return_raw)]
+    pub fn power(_ctx: NativeCallContext, x: BigInt, y: INT) -> RhaiResultOf<BigInt> {
+        if y < 0 {
+            return Err(make_err(format!(
+                "Integer raised to a negative power: {x} ** {y}"
+            )));
+        }
+        let exp =
+            u32::try_from(y).map_err(|_| make_err(format!("Exponential overflow: {x} ** {y}")))?;
+
+        // Check the minimum size of the result before calculating it
+        #[cfg(not(feature = "unchecked"))]
+        if x.bits() > 1 {
+            let size = (x.bits() - 1).saturating_mul(u64::from(exp)) / 8;
+            let size = usize::try_from(size).unwrap_or(usize::MAX);
+            super::check_bigint_size(_ctx.engine(), size)?;
+        }
+
+        Ok(x.pow(exp))
+    }
+    #[rhai_fn(name = "<<", return_raw)]
+    pub fn shift_left(_ctx: NativeCallContext, x: BigInt, y: INT) -> RhaiResultOf<BigInt> {
+        if y < 0 {
+            return shift_right(_ctx, x, y.checked_abs().unwrap_or(INT::MAX));
+        }
+        let bits =
+            usize::try_from(y).map_err(|_| make_err(format!("Left-shift overflow: {x} << {y}")))?;
+
+        // Check the size of the result before calculating it
+        #[cfg(not(feature = "unchecked"))]
+        if !x.is_zero() {
+            let size = crate::eval::calc_bigint_size(&x).saturating_add(bits / 8);
+            super::check_bigint_size(_ctx.engine(), size)?;
+        }
+
+        Ok(x << bits)
+    }
+    #[rhai_fn(name = ">>", return_raw)]
+    pub fn shift_right(_ctx: NativeCallContext, x: BigInt, y: INT) -> RhaiResultOf<BigInt> {
+        if y < 0 {
+            return shift_left(_ctx, x, y.checked_abs().unwrap_or(INT::MAX));
+        }
+        Ok(x >> usize::try_from(y).unwrap_or(usize::MAX))
+    }
+    #[rhai_fn(name = "&")]
+    pub fn binary_and(x: BigInt, y: BigInt) -> BigInt {
+        x & y
+    }
+    #[rhai_fn(name = "|")]
+    pub fn binary_or(x: BigInt, y: BigInt) -> BigInt {
+        x | y
+    }
+    #[rhai_fn(name = "^")]
+    pub fn binary_xor(x: BigInt, y: BigInt) -> BigInt {
+        x ^ y
+    }
+    #[rhai_fn(name = "-")]
+    pub fn neg(x: BigInt) -> BigInt {
+        -x
+    }
+    #[rhai_fn(name = "+")]
+    pub fn plus(x: BigInt) -> BigInt {
+        x
+    }
+    /// Return the absolute value of the big integer.
+    pub fn abs(x: BigInt) -> BigInt {
+        x.abs()
+    }
+    /// Return the sign (as an integer) of the big integer according to the following:
+    ///
+    /// * `0` if the number is zero
+    /// * `1` if the number is positive
+    /// * `-1` if the number is negative
+    pub fn sign(x: BigInt) -> INT {
+        if x.is_zero() {
+            0
+        } else if x.is_negative() {
+            -1
+        } else {
+            1
+        }
+    }
+    /// Return true if the big integer is zero.
+    #[rhai_fn(get = "is_zero", name = "is_zero")]
+    pub fn is_zero(x: BigInt) -> bool {
+        x.is_zero()
+    }
+    /// Return true if the big integer is odd.
+    #[rhai_fn(get = "is_odd", name = "is_odd")]
+    pub fn is_odd(x: BigInt) -> bool {
+        x.bit(0)
+    }
+    /// Return true if the big integer is even.
+    #[rhai_fn(get = "is_even", name = "is_even")]
+    pub fn is_even(x: BigInt) -> bool {
+        !x.bit(0)
+    }
+    /// Return the number of bits required to represent the absolute value of the big integer.
+    #[rhai_fn(get = "bits", name = "bits")]
+    pub fn bits(x: BigInt) -> INT {
+        INT::try_from(x.bits()).unwrap_or(INT::MAX)
+    }
+}
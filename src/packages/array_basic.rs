@@ -3,22 +3,41 @@
 use crate::api::deprecated::deprecated_array_functions;
 use crate::engine::OP_EQUALS;
 use crate::eval::{calc_index, calc_offset_len};
+use crate::packages::arithmetic::make_err;
 use crate::plugin::*;
 use crate::types::fn_ptr::FnPtrType;
 use crate::{
     def_package, Array, Dynamic, ExclusiveRange, FnPtr, InclusiveRange, NativeCallContext,
     Position, RhaiResultOf, ERR, INT, MAX_USIZE_INT,
 };
+#[cfg(not(feature = "no_object"))]
+use crate::{Identifier, Map};
+#[cfg(not(feature = "no_object"))]
+use std::collections::BTreeMap;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{any::TypeId, cmp::Ordering, mem};
 
+#[cfg(not(feature = "no_float"))]
+use crate::FLOAT;
+
+#[cfg(feature = "no_std")]
+#[cfg(not(feature = "no_float"))]
+use num_traits::Float;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::{prelude::ToPrimitive, Decimal, MathematicalOps};
+#[cfg(feature = "decimal")]
+#[cfg(not(feature = "no_float"))]
+use std::convert::TryFrom;
+
 def_package! {
     /// Package of basic array utilities.
     pub BasicArrayPackage(lib) {
         lib.set_standard_lib(true);
 
         combine_with_exported_module!(lib, "array", array_functions);
+        combine_with_exported_module!(lib, "array_stats", array_stats_functions);
         combine_with_exported_module!(lib, "deprecated_array", deprecated_array_functions);
 
         // Register array iterator
@@ -1965,6 +1984,388 @@ pub mod array_functions {
 
         drained
     }
+    /// Return the element with the minimum value, as determined by the `comparer` function.
+    ///
+    /// If there are multiple minimum elements, the first one is returned.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element1`: copy of the first array element to compare
+    /// * `element2`: copy of the second array element to compare
+    ///
+    /// ## Return Value
+    ///
+    /// An integer number or boolean value, in the same manner as for the `sort` function.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = ["hello", "a", "world", "!"];
+    ///
+    /// let y = x.min_by(|a, b| a.len() - b.len());
+    ///
+    /// print(y);       // prints "a"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn min_by(ctx: NativeCallContext, array: &mut Array, comparer: FnPtr) -> RhaiResult {
+        if array.is_empty() {
+            return Ok(Dynamic::UNIT);
+        }
+
+        let mut index = 0;
+
+        for i in 1..array.len() {
+            if call_comparer(&ctx, &comparer, &array[i], &array[index])? == Ordering::Less {
+                index = i;
+            }
+        }
+
+        Ok(array[index].clone())
+    }
+    /// Return the element with the maximum value, as determined by the `comparer` function.
+    ///
+    /// If there are multiple maximum elements, the last one is returned.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element1`: copy of the first array element to compare
+    /// * `element2`: copy of the second array element to compare
+    ///
+    /// ## Return Value
+    ///
+    /// An integer number or boolean value, in the same manner as for the `sort` function.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = ["hello", "a", "world", "!"];
+    ///
+    /// let y = x.max_by(|a, b| a.len() - b.len());
+    ///
+    /// print(y);       // prints "world"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn max_by(ctx: NativeCallContext, array: &mut Array, comparer: FnPtr) -> RhaiResult {
+        if array.is_empty() {
+            return Ok(Dynamic::UNIT);
+        }
+
+        let mut index = 0;
+
+        for i in 1..array.len() {
+            if call_comparer(&ctx, &comparer, &array[i], &array[index])? != Ordering::Less {
+                index = i;
+            }
+        }
+
+        Ok(array[index].clone())
+    }
+    /// Sort the array based on the keys returned by applying the `key` function to each element.
+    ///
+    /// The `key` function is called only once per element. The sort is stable.
+    ///
+    /// Keys that are integers, floating-point numbers, characters or strings are compared directly;
+    /// otherwise the operator `<` is used to compare them and must be defined.
+    ///
+    /// # No Function Parameter
+    ///
+    /// Array element (mutable) is bound to `this`.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element`: copy of array element
+    /// * `index` _(optional)_: current index in the array
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = ["hello", "a", "world", "!!"];
+    ///
+    /// x.sort_by_key(|v| v.len());
+    ///
+    /// print(x);       // prints "[\"a\", \"!!\", \"hello\", \"world\"]"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn sort_by_key(ctx: NativeCallContext, array: &mut Array, key: FnPtr) -> RhaiResultOf<()> {
+        if array.len() <= 1 {
+            return Ok(());
+        }
+
+        let mut keys = Vec::with_capacity(array.len());
+
+        for (i, item) in array.iter_mut().enumerate() {
+            let ex = [(i as INT).into()];
+            keys.push(key.call_raw_with_extra_args(
+                "sort_by_key",
+                &ctx,
+                Some(item),
+                [],
+                ex,
+                Some(0),
+            )?);
+        }
+
+        let mut indices: Vec<_> = (0..array.len()).collect();
+        let mut error = None;
+
+        indices.sort_by(|&a, &b| {
+            compare_values(&ctx, &keys[a], &keys[b]).unwrap_or_else(|err| {
+                error.get_or_insert(err);
+                Ordering::Equal
+            })
+        });
+
+        if let Some(err) = error {
+            return Err(err);
+        }
+
+        let mut items: Vec<_> = mem::take(array).into_iter().map(Some).collect();
+        *array = indices
+            .into_iter()
+            .map(|i| items[i].take().unwrap())
+            .collect();
+
+        Ok(())
+    }
+    /// Group the elements of the array by the keys returned by applying the `key` function to each
+    /// element, and return an object map of arrays.
+    ///
+    /// Keys that are not strings are converted into strings.
+    ///
+    /// # No Function Parameter
+    ///
+    /// Array element (mutable) is bound to `this`.
+    ///
+    /// This method is marked _pure_; the `key` function should not mutate array elements.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element`: copy of array element
+    /// * `index` _(optional)_: current index in the array
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// let y = x.group_by(|v| if v % 2 == 0 { "even" } else { "odd" });
+    ///
+    /// print(y);       // prints "#{\"even\": [2, 4], \"odd\": [1, 3, 5]}"
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn group_by(ctx: NativeCallContext, array: &mut Array, key: FnPtr) -> RhaiResultOf<Map> {
+        let mut groups = BTreeMap::<Identifier, Array>::new();
+
+        for (i, item) in array.iter_mut().enumerate() {
+            let ex = [(i as INT).into()];
+            let k = key.call_raw_with_extra_args("group_by", &ctx, Some(item), [], ex, Some(0))?;
+
+            let k = match k.as_immutable_string_ref() {
+                Ok(s) => Identifier::from(s.as_str()),
+                Err(_) => k.to_string().into(),
+            };
+
+            groups.entry(k).or_default().push(item.clone());
+        }
+
+        Ok(groups.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+    /// Split the array into two arrays, with all elements that return `true` when applied the
+    /// `filter` function in the first, and all other elements in the second.
+    ///
+    /// An array containing the two arrays is returned.
+    ///
+    /// # No Function Parameter
+    ///
+    /// Array element (mutable) is bound to `this`.
+    ///
+    /// This method is marked _pure_; the `filter` function should not mutate array elements.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element`: copy of array element
+    /// * `index` _(optional)_: current index in the array
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// let [small, large] = x.partition(|v| v < 3);
+    ///
+    /// print(small);   // prints "[1, 2]"
+    ///
+    /// print(large);   // prints "[3, 4, 5]"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn partition(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        filter: FnPtr,
+    ) -> RhaiResultOf<Array> {
+        let mut matched = Array::new();
+        let mut rest = Array::new();
+
+        for (i, item) in array.iter_mut().enumerate() {
+            let ex = [(i as INT).into()];
+
+            if filter
+                .call_raw_with_extra_args("partition", &ctx, Some(item), [], ex, Some(0))?
+                .as_bool()
+                .unwrap_or(false)
+            {
+                matched.push(item.clone());
+            } else {
+                rest.push(item.clone());
+            }
+        }
+
+        Ok(vec![matched.into(), rest.into()])
+    }
+    /// Split the array into consecutive chunks of `size` elements and return them as an array of arrays.
+    ///
+    /// The last chunk may be shorter than `size`.
+    ///
+    /// An error is raised if `size` ≤ 0.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x.chunks(2));     // prints "[[1, 2], [3, 4], [5]]"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn chunks(array: &mut Array, size: INT) -> RhaiResultOf<Array> {
+        if size <= 0 {
+            return Err(ERR::ErrorArithmetic(
+                format!("Invalid chunk size: {size}"),
+                Position::NONE,
+            )
+            .into());
+        }
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let size = size.clamp(1, MAX_USIZE_INT) as usize;
+
+        Ok(array.chunks(size).map(|c| c.to_vec().into()).collect())
+    }
+    /// Return all overlapping windows of `size` consecutive elements in the array, as an array of arrays.
+    ///
+    /// If the array is shorter than `size`, an empty array is returned.
+    ///
+    /// An error is raised if `size` ≤ 0.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4];
+    ///
+    /// print(x.windows(2));    // prints "[[1, 2], [2, 3], [3, 4]]"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn windows(ctx: NativeCallContext, array: &mut Array, size: INT) -> RhaiResultOf<Array> {
+        if size <= 0 {
+            return Err(ERR::ErrorArithmetic(
+                format!("Invalid window size: {size}"),
+                Position::NONE,
+            )
+            .into());
+        }
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let size = size.clamp(1, MAX_USIZE_INT) as usize;
+
+        if size > array.len() {
+            return Ok(Array::new());
+        }
+
+        let _ctx = ctx;
+
+        // Check if the result will be over max size limit
+        #[cfg(not(feature = "unchecked"))]
+        if _ctx.engine().max_array_size() > 0 {
+            let count = array.len() - size + 1;
+            let (a, m, s) = crate::eval::calc_array_sizes(array);
+
+            _ctx.engine().throw_on_size((
+                count.saturating_add(a.saturating_mul(size)),
+                m.saturating_mul(size),
+                s.saturating_mul(size),
+            ))?;
+        }
+
+        Ok(array.windows(size).map(|w| w.to_vec().into()).collect())
+    }
+    /// Flatten one level of nesting in the array, returning a new array with the elements of all
+    /// inner arrays spliced in.
+    ///
+    /// Elements that are not arrays are kept as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, [2, 3], [[4], 5], 6];
+    ///
+    /// print(x.flatten());     // prints "[1, 2, 3, [4], 5, 6]"
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn flatten(array: &mut Array) -> Array {
+        let mut result = Array::with_capacity(array.len());
+
+        for item in array.iter() {
+            match item.read_lock::<Array>() {
+                Some(inner) => result.extend(inner.iter().cloned()),
+                None => result.push(item.clone()),
+            }
+        }
+
+        result
+    }
+    /// Search a sorted array for `value` using binary search.
+    ///
+    /// If found, the index of a matching element is returned. Otherwise, `-(index + 1)` is
+    /// returned, where `index` is the position at which `value` could be inserted to keep the
+    /// array sorted.
+    ///
+    /// Values that are integers, floating-point numbers, characters or strings are compared
+    /// directly; otherwise the operator `<` is used to compare them and must be defined.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 3, 5, 7, 9];
+    ///
+    /// print(x.binary_search(7));      // prints 3
+    ///
+    /// print(x.binary_search(4));      // prints -3
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn binary_search(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        value: Dynamic,
+    ) -> RhaiResultOf<INT> {
+        let mut lo = 0;
+        let mut hi = array.len();
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+
+            match compare_values(&ctx, &array[mid], &value)? {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return Ok(mid as INT),
+            }
+        }
+
+        Ok(-(lo as INT) - 1)
+    }
     /// Return `true` if two arrays are equal (i.e. all elements are equal and in the same order).
     ///
     /// The operator `==` is used to compare elements and must be defined,
@@ -2040,3 +2441,565 @@ pub mod array_functions {
         equals(ctx, array1, array2).map(|r| !r)
     }
 }
+
+#[export_module]
+pub mod array_stats_functions {
+    /// Return the sum of all the numbers in the array.
+    ///
+    /// The result is an integer if all elements are integers, a decimal number if any element is
+    /// a decimal number, and a floating-point number otherwise.
+    ///
+    /// An error is raised if any element is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x.sum());         // prints 15
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn sum(ctx: NativeCallContext, array: &mut Array) -> RhaiResult {
+        match number_kind(&ctx, array)? {
+            NumberKind::Int => fold_numbers::<INT>(array, 0, ArrayNumber::checked_add),
+            #[cfg(not(feature = "no_float"))]
+            NumberKind::Float => fold_numbers::<FLOAT>(array, 0, ArrayNumber::checked_add),
+            #[cfg(feature = "decimal")]
+            NumberKind::Decimal => fold_numbers::<Decimal>(array, 0, ArrayNumber::checked_add),
+        }
+    }
+    /// Return the product of all the numbers in the array.
+    ///
+    /// The result is an integer if all elements are integers, a decimal number if any element is
+    /// a decimal number, and a floating-point number otherwise.
+    ///
+    /// An error is raised if any element is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x.product());     // prints 120
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn product(ctx: NativeCallContext, array: &mut Array) -> RhaiResult {
+        match number_kind(&ctx, array)? {
+            NumberKind::Int => fold_numbers::<INT>(array, 1, ArrayNumber::checked_mul),
+            #[cfg(not(feature = "no_float"))]
+            NumberKind::Float => fold_numbers::<FLOAT>(array, 1, ArrayNumber::checked_mul),
+            #[cfg(feature = "decimal")]
+            NumberKind::Decimal => fold_numbers::<Decimal>(array, 1, ArrayNumber::checked_mul),
+        }
+    }
+    /// Return the arithmetic mean (average) of all the numbers in the array.
+    ///
+    /// The result is a decimal number if any element is a decimal number, and a floating-point
+    /// number otherwise.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// An error is raised if any element is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4];
+    ///
+    /// print(x.mean());        // prints 2.5
+    /// ```
+    #[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn mean(ctx: NativeCallContext, array: &mut Array) -> RhaiResult {
+        calc_stat(&ctx, array, &Stat::Mean)
+    }
+    /// Return the median of all the numbers in the array.
+    ///
+    /// If the array has an even number of elements, the mean of the two middle elements is returned.
+    ///
+    /// The result is a decimal number if any element is a decimal number, and a floating-point
+    /// number otherwise.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// An error is raised if any element is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [5, 1, 4, 2];
+    ///
+    /// print(x.median());      // prints 3.0
+    /// ```
+    #[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn median(ctx: NativeCallContext, array: &mut Array) -> RhaiResult {
+        calc_stat(&ctx, array, &Stat::Median)
+    }
+    /// Return the population variance of all the numbers in the array.
+    ///
+    /// The result is a decimal number if any element is a decimal number, and a floating-point
+    /// number otherwise.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// An error is raised if any element is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [2, 4, 4, 4, 5, 5, 7, 9];
+    ///
+    /// print(x.variance());    // prints 4.0
+    /// ```
+    #[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn variance(ctx: NativeCallContext, array: &mut Array) -> RhaiResult {
+        calc_stat(&ctx, array, &Stat::Variance)
+    }
+    /// Return the population standard deviation of all the numbers in the array.
+    ///
+    /// The result is a decimal number if any element is a decimal number, and a floating-point
+    /// number otherwise.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// An error is raised if any element is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [2, 4, 4, 4, 5, 5, 7, 9];
+    ///
+    /// print(x.std_dev());     // prints 2.0
+    /// ```
+    #[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn std_dev(ctx: NativeCallContext, array: &mut Array) -> RhaiResult {
+        calc_stat(&ctx, array, &Stat::StdDev)
+    }
+    /// Return the `p`-th percentile (between 0 and 100) of all the numbers in the array,
+    /// interpolating linearly between the two closest elements.
+    ///
+    /// The result is a decimal number if any element is a decimal number, and a floating-point
+    /// number otherwise.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// An error is raised if any element is not a number, or if `p` is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x.percentile(25));    // prints 2.0
+    ///
+    /// print(x.percentile(90));    // prints 4.6
+    /// ```
+    #[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+    #[rhai_fn(name = "percentile", return_raw, pure)]
+    pub fn percentile(ctx: NativeCallContext, array: &mut Array, p: INT) -> RhaiResult {
+        calc_stat(&ctx, array, &Stat::Percentile(p.into()))
+    }
+    /// Return the `p`-th percentile (between 0 and 100) of all the numbers in the array,
+    /// interpolating linearly between the two closest elements.
+    ///
+    /// The result is a decimal number if any element is a decimal number, and a floating-point
+    /// number otherwise.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// An error is raised if any element is not a number, or if `p` is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x.percentile(62.5));  // prints 3.5
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "percentile", return_raw, pure)]
+    pub fn percentile_float(ctx: NativeCallContext, array: &mut Array, p: FLOAT) -> RhaiResult {
+        calc_stat(&ctx, array, &Stat::Percentile(p.into()))
+    }
+    /// Return the `p`-th percentile (between 0 and 100) of all the numbers in the array,
+    /// interpolating linearly between the two closest elements.
+    ///
+    /// The result is a decimal number if any element is a decimal number, and a floating-point
+    /// number otherwise.
+    ///
+    /// If the array is empty, `()` is returned.
+    ///
+    /// An error is raised if any element is not a number, or if `p` is out of range.
+    #[cfg(feature = "decimal")]
+    #[rhai_fn(name = "percentile", return_raw, pure)]
+    pub fn percentile_decimal(ctx: NativeCallContext, array: &mut Array, p: Decimal) -> RhaiResult {
+        calc_stat(&ctx, array, &Stat::Percentile(p.into()))
+    }
+}
+
+/// Compare two values with a `comparer` function, which returns an integer number or a boolean
+/// value in the same manner as for `sort`.
+///
+/// Any other return value type is treated as equal.
+fn call_comparer(
+    ctx: &NativeCallContext,
+    comparer: &FnPtr,
+    x: &Dynamic,
+    y: &Dynamic,
+) -> RhaiResultOf<Ordering> {
+    let v = comparer.call_raw(ctx, None, [x.clone(), y.clone()])?;
+
+    Ok(v.as_int()
+        .map(|v| v.cmp(&0))
+        .or_else(|_| {
+            v.as_bool()
+                .map(|v| if v { Ordering::Less } else { Ordering::Greater })
+        })
+        .unwrap_or(Ordering::Equal))
+}
+
+/// Compare two values, directly for integers, floating-point numbers, characters and strings,
+/// and via the operator `<` otherwise.
+fn compare_values(ctx: &NativeCallContext, x: &Dynamic, y: &Dynamic) -> RhaiResultOf<Ordering> {
+    if let (Ok(a), Ok(b)) = (x.as_int(), y.as_int()) {
+        return Ok(a.cmp(&b));
+    }
+    #[cfg(not(feature = "no_float"))]
+    if let (Ok(a), Ok(b)) = (x.as_float(), y.as_float()) {
+        return Ok(a.partial_cmp(&b).unwrap_or(Ordering::Equal));
+    }
+    if let (Ok(a), Ok(b)) = (x.as_char(), y.as_char()) {
+        return Ok(a.cmp(&b));
+    }
+    if let (Ok(a), Ok(b)) = (x.as_immutable_string_ref(), y.as_immutable_string_ref()) {
+        return Ok(a.as_str().cmp(b.as_str()));
+    }
+
+    let less = |a: &Dynamic, b: &Dynamic| {
+        ctx.call_native_fn_raw("<", false, &mut [&mut a.clone(), &mut b.clone()])
+            .map(|v| v.as_bool().unwrap_or(false))
+    };
+
+    Ok(if less(x, y)? {
+        Ordering::Less
+    } else if less(y, x)? {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    })
+}
+
+/// Type of numbers held in an array.
+enum NumberKind {
+    /// All elements are integers.
+    Int,
+    /// Some elements are floating-point numbers.
+    #[cfg(not(feature = "no_float"))]
+    Float,
+    /// Some elements are decimal numbers.
+    #[cfg(feature = "decimal")]
+    Decimal,
+}
+
+/// Find the type of numbers held in an array, raising an error if any element is not a number.
+fn number_kind(ctx: &NativeCallContext, array: &Array) -> RhaiResultOf<NumberKind> {
+    #[allow(unused_mut)]
+    let mut kind = NumberKind::Int;
+
+    for item in array {
+        #[cfg(feature = "decimal")]
+        if item.is_decimal() {
+            kind = NumberKind::Decimal;
+            continue;
+        }
+        #[cfg(not(feature = "no_float"))]
+        if item.is_float() {
+            #[cfg(feature = "decimal")]
+            if matches!(kind, NumberKind::Decimal) {
+                continue;
+            }
+            kind = NumberKind::Float;
+            continue;
+        }
+        if !item.is_int() {
+            return Err(ERR::ErrorMismatchDataType(
+                "number".into(),
+                ctx.engine().map_type_name(item.type_name()).into(),
+                Position::NONE,
+            )
+            .into());
+        }
+    }
+
+    Ok(kind)
+}
+
+/// A number type used in array calculations.
+trait ArrayNumber: Copy + PartialOrd + Into<Dynamic> {
+    /// Convert a [`Dynamic`] number into this type.
+    fn from_dynamic(value: &Dynamic) -> Option<Self>;
+    /// Convert an integer into this type.
+    fn from_int(value: INT) -> Self;
+    fn checked_add(self, y: Self) -> Option<Self>;
+    fn checked_mul(self, y: Self) -> Option<Self>;
+}
+
+impl ArrayNumber for INT {
+    #[inline(always)]
+    fn from_dynamic(value: &Dynamic) -> Option<Self> {
+        value.as_int().ok()
+    }
+    #[inline(always)]
+    fn from_int(value: INT) -> Self {
+        value
+    }
+    #[inline(always)]
+    fn checked_add(self, y: Self) -> Option<Self> {
+        #[cfg(not(feature = "unchecked"))]
+        return INT::checked_add(self, y);
+        #[cfg(feature = "unchecked")]
+        return Some(self.wrapping_add(y));
+    }
+    #[inline(always)]
+    fn checked_mul(self, y: Self) -> Option<Self> {
+        #[cfg(not(feature = "unchecked"))]
+        return INT::checked_mul(self, y);
+        #[cfg(feature = "unchecked")]
+        return Some(self.wrapping_mul(y));
+    }
+}
+
+#[cfg(not(feature = "no_float"))]
+impl ArrayNumber for FLOAT {
+    #[inline]
+    fn from_dynamic(value: &Dynamic) -> Option<Self> {
+        #[allow(clippy::cast_precision_loss)]
+        value
+            .as_float()
+            .ok()
+            .or_else(|| value.as_int().ok().map(|v| v as FLOAT))
+    }
+    #[inline(always)]
+    #[allow(clippy::cast_precision_loss)]
+    fn from_int(value: INT) -> Self {
+        value as FLOAT
+    }
+    #[inline(always)]
+    fn checked_add(self, y: Self) -> Option<Self> {
+        Some(self + y)
+    }
+    #[inline(always)]
+    fn checked_mul(self, y: Self) -> Option<Self> {
+        Some(self * y)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl ArrayNumber for Decimal {
+    #[inline]
+    fn from_dynamic(value: &Dynamic) -> Option<Self> {
+        if let Ok(v) = value.as_decimal() {
+            return Some(v);
+        }
+        if let Ok(v) = value.as_int() {
+            return Some(v.into());
+        }
+        #[cfg(not(feature = "no_float"))]
+        if let Ok(v) = value.as_float() {
+            return Decimal::try_from(v).ok();
+        }
+        None
+    }
+    #[inline(always)]
+    fn from_int(value: INT) -> Self {
+        value.into()
+    }
+    #[inline(always)]
+    fn checked_add(self, y: Self) -> Option<Self> {
+        Decimal::checked_add(self, y)
+    }
+    #[inline(always)]
+    fn checked_mul(self, y: Self) -> Option<Self> {
+        Decimal::checked_mul(self, y)
+    }
+}
+
+/// Convert all elements of an array into numbers of a particular type.
+fn to_numbers<T: ArrayNumber>(array: &Array) -> RhaiResultOf<Vec<T>> {
+    array
+        .iter()
+        .map(|v| T::from_dynamic(v).ok_or_else(|| make_err(format!("Invalid number: {v}"))))
+        .collect()
+}
+
+/// Fold all elements of an array of numbers with an arithmetic operation.
+fn fold_numbers<T: ArrayNumber>(array: &Array, init: INT, op: fn(T, T) -> Option<T>) -> RhaiResult {
+    to_numbers::<T>(array)?
+        .into_iter()
+        .try_fold(T::from_int(init), op)
+        .map(Into::into)
+        .ok_or_else(|| make_err("Arithmetic overflow"))
+}
+
+/// A number type with a fractional part, used in statistics calculations.
+#[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+trait RealNumber: ArrayNumber {
+    fn checked_sub(self, y: Self) -> Option<Self>;
+    fn checked_div(self, y: Self) -> Option<Self>;
+    fn floor(self) -> Self;
+    fn sqrt(self) -> Option<Self>;
+    /// Convert a non-negative number into an index.
+    fn to_index(self) -> usize;
+}
+
+#[cfg(not(feature = "no_float"))]
+impl RealNumber for FLOAT {
+    #[inline(always)]
+    fn checked_sub(self, y: Self) -> Option<Self> {
+        Some(self - y)
+    }
+    #[inline(always)]
+    fn checked_div(self, y: Self) -> Option<Self> {
+        Some(self / y)
+    }
+    #[inline(always)]
+    fn floor(self) -> Self {
+        FLOAT::floor(self)
+    }
+    #[inline(always)]
+    fn sqrt(self) -> Option<Self> {
+        Some(FLOAT::sqrt(self))
+    }
+    #[inline(always)]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    fn to_index(self) -> usize {
+        self as usize
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl RealNumber for Decimal {
+    #[inline(always)]
+    fn checked_sub(self, y: Self) -> Option<Self> {
+        Decimal::checked_sub(self, y)
+    }
+    #[inline(always)]
+    fn checked_div(self, y: Self) -> Option<Self> {
+        Decimal::checked_div(self, y)
+    }
+    #[inline(always)]
+    fn floor(self) -> Self {
+        Decimal::floor(&self)
+    }
+    #[inline(always)]
+    fn sqrt(self) -> Option<Self> {
+        MathematicalOps::sqrt(&self)
+    }
+    #[inline(always)]
+    fn to_index(self) -> usize {
+        ToPrimitive::to_usize(&self).unwrap_or(0)
+    }
+}
+
+/// Statistics calculation on an array of numbers.
+#[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+enum Stat {
+    Mean,
+    Median,
+    Variance,
+    StdDev,
+    Percentile(Dynamic),
+}
+
+#[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+impl Stat {
+    /// Calculate the statistic on a non-empty array of numbers.
+    fn calc<T: RealNumber>(&self, array: &Array) -> RhaiResult {
+        let mut values = to_numbers::<T>(array)?;
+        let len = values.len();
+        let zero = T::from_int(0);
+
+        let mean = |values: &[T]| {
+            values
+                .iter()
+                .try_fold(zero, |sum, &x| sum.checked_add(x))?
+                .checked_div(T::from_int(len as INT))
+        };
+        let variance = |values: &[T]| {
+            let m = mean(values)?;
+            values
+                .iter()
+                .try_fold(zero, |sum, &x| {
+                    let d = x.checked_sub(m)?;
+                    sum.checked_add(d.checked_mul(d)?)
+                })?
+                .checked_div(T::from_int(len as INT))
+        };
+        let sort = |values: &mut [T]| {
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        };
+
+        let result = match self {
+            Self::Mean => mean(&values),
+            Self::Median => {
+                sort(&mut values);
+                let mid = len / 2;
+
+                if len % 2 == 1 {
+                    Some(values[mid])
+                } else {
+                    values[mid - 1]
+                        .checked_add(values[mid])
+                        .and_then(|sum| sum.checked_div(T::from_int(2)))
+                }
+            }
+            Self::Variance => variance(&values),
+            Self::StdDev => variance(&values).and_then(RealNumber::sqrt),
+            Self::Percentile(p) => {
+                let p = T::from_dynamic(p)
+                    .filter(|v| *v >= zero && *v <= T::from_int(100))
+                    .ok_or_else(|| make_err(format!("Invalid percentile: {p}")))?;
+
+                sort(&mut values);
+
+                p.checked_mul(T::from_int((len - 1) as INT))
+                    .and_then(|v| v.checked_div(T::from_int(100)))
+                    .and_then(|rank| {
+                        let lower = rank.floor();
+                        let i = lower.to_index();
+
+                        if i + 1 >= len {
+                            return Some(values[len - 1]);
+                        }
+
+                        let d = values[i + 1].checked_sub(values[i])?;
+                        values[i].checked_add(d.checked_mul(rank.checked_sub(lower)?)?)
+                    })
+            }
+        };
+
+        result
+            .map(Into::into)
+            .ok_or_else(|| make_err("Arithmetic overflow"))
+    }
+}
+
+/// Calculate a statistic on an array of numbers, returning `()` if the array is empty.
+#[cfg(any(not(feature = "no_float"), feature = "decimal"))]
+fn calc_stat(ctx: &NativeCallContext, array: &Array, stat: &Stat) -> RhaiResult {
+    if array.is_empty() {
+        return Ok(Dynamic::UNIT);
+    }
+
+    match number_kind(ctx, array)? {
+        #[cfg(feature = "decimal")]
+        NumberKind::Decimal => stat.calc::<Decimal>(array),
+        #[cfg(not(feature = "no_float"))]
+        _ => stat.calc::<FLOAT>(array),
+        #[cfg(feature = "no_float")]
+        _ => stat.calc::<Decimal>(array),
+    }
+}
@@ -3,12 +3,12 @@
 use crate::eval::{calc_index, calc_offset_len};
 use crate::plugin::*;
 use crate::{
-    def_package, Array, Blob, Dynamic, ExclusiveRange, InclusiveRange, NativeCallContext,
-    RhaiResultOf, INT, INT_BYTES, MAX_USIZE_INT,
+    def_package, Array, Blob, Dynamic, ExclusiveRange, InclusiveRange, NativeCallContext, Position,
+    RhaiError, RhaiResult, RhaiResultOf, ERR, INT, INT_BYTES, MAX_USIZE_INT,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{any::TypeId, borrow::Cow, mem};
+use std::{any::TypeId, borrow::Cow, convert::TryFrom, mem};
 
 #[cfg(not(feature = "no_float"))]
 use crate::{FLOAT, FLOAT_BYTES};
@@ -22,6 +22,8 @@ def_package! {
         combine_with_exported_module!(lib, "parse_int", parse_int_functions);
         combine_with_exported_module!(lib, "write_int", write_int_functions);
         combine_with_exported_module!(lib, "write_string", write_string_functions);
+        combine_with_exported_module!(lib, "pack", pack_functions);
+        combine_with_exported_module!(lib, "encoding", encoding_functions);
 
         #[cfg(not(feature = "no_float"))]
         {
@@ -1640,3 +1642,861 @@ mod write_string_functions {
         write_string(blob, start, len, string, true);
     }
 }
+
+/// A field in a [`pack`][pack_functions::pack] format string.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum PackField {
+    /// Padding byte (`x`).
+    Pad,
+    /// Boolean (`?`).
+    Bool,
+    /// Integer with the number of bytes, signed or unsigned.
+    Int(usize, bool),
+    /// 32-bit floating-point number (`f`).
+    #[cfg(not(feature = "no_float"))]
+    Float32,
+    /// 64-bit floating-point number (`d`).
+    #[cfg(not(feature = "no_float"))]
+    Float64,
+    /// Byte string (`s`).
+    Bytes,
+}
+
+impl PackField {
+    /// Number of bytes taken up by one such field.
+    #[must_use]
+    const fn size(self) -> usize {
+        match self {
+            Self::Pad | Self::Bool | Self::Bytes => 1,
+            Self::Int(size, ..) => size,
+            #[cfg(not(feature = "no_float"))]
+            Self::Float32 => 4,
+            #[cfg(not(feature = "no_float"))]
+            Self::Float64 => 8,
+        }
+    }
+}
+
+/// A parsed [`pack`][pack_functions::pack] format string.
+#[derive(Debug, Clone)]
+struct PackFormat {
+    /// Little-endian byte order?
+    is_le: bool,
+    /// Fields together with their repeat counts (or lengths for byte strings).
+    fields: Vec<(PackField, usize)>,
+}
+
+impl PackFormat {
+    /// Parse a format string.
+    fn parse(format: &str) -> RhaiResultOf<Self> {
+        let make_err =
+            |msg: String| -> RhaiError { ERR::ErrorArithmetic(msg, Position::NONE).into() };
+
+        let mut chars = format.chars().filter(|ch| !ch.is_whitespace()).peekable();
+
+        let is_le = match chars.peek() {
+            Some('<') => true,
+            Some('>' | '!') => false,
+            _ => cfg!(target_endian = "little"),
+        };
+        if matches!(chars.peek(), Some('<' | '>' | '!' | '=' | '@')) {
+            chars.next();
+        }
+
+        let mut fields = Vec::new();
+
+        while let Some(mut ch) = chars.next() {
+            let mut count = None::<usize>;
+
+            while let Some(digit) = ch.to_digit(10) {
+                count = count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(digit as usize));
+
+                if count.is_none() {
+                    return Err(make_err(format!(
+                        "Repeat count too large in format: '{format}'"
+                    )));
+                }
+
+                ch = chars.next().ok_or_else(|| {
+                    make_err(format!("Repeat count without format character: '{format}'"))
+                })?;
+            }
+
+            let field = match ch {
+                'x' => PackField::Pad,
+                '?' => PackField::Bool,
+                'b' => PackField::Int(1, true),
+                'B' => PackField::Int(1, false),
+                'h' => PackField::Int(2, true),
+                'H' => PackField::Int(2, false),
+                'i' | 'l' => PackField::Int(4, true),
+                'I' | 'L' => PackField::Int(4, false),
+                'q' => PackField::Int(8, true),
+                'Q' => PackField::Int(8, false),
+                #[cfg(not(feature = "no_float"))]
+                'f' => PackField::Float32,
+                #[cfg(not(feature = "no_float"))]
+                'd' => PackField::Float64,
+                's' => PackField::Bytes,
+                _ => {
+                    return Err(make_err(format!(
+                        "Invalid format character '{ch}' in format: '{format}'"
+                    )))
+                }
+            };
+
+            fields.push((field, count.unwrap_or(1)));
+        }
+
+        Ok(Self { is_le, fields })
+    }
+    /// Total number of bytes, or [`None`] if it overflows.
+    #[must_use]
+    fn size(&self) -> Option<usize> {
+        self.fields
+            .iter()
+            .try_fold(0_usize, |total, &(field, count)| {
+                field
+                    .size()
+                    .checked_mul(count)
+                    .and_then(|n| total.checked_add(n))
+            })
+    }
+    /// Number of values consumed by packing, or produced by unpacking.
+    #[must_use]
+    fn num_values(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|&(field, count)| match field {
+                PackField::Pad => 0,
+                PackField::Bytes => 1,
+                _ => count,
+            })
+            .sum()
+    }
+}
+
+/// Create an error for a value with the wrong type.
+fn make_mismatch_err(ctx: &NativeCallContext, expected: &str, value: &Dynamic) -> RhaiError {
+    let engine = ctx.engine();
+
+    ERR::ErrorMismatchDataType(
+        engine.map_type_name(expected).into(),
+        engine.map_type_name(value.type_name()).into(),
+        Position::NONE,
+    )
+    .into()
+}
+
+/// Pack values into a BLOB according to a format string.
+fn pack_values(ctx: &NativeCallContext, format: &str, values: Array) -> RhaiResultOf<Blob> {
+    let fmt = PackFormat::parse(format)?;
+
+    let size = fmt
+        .size()
+        .ok_or_else(|| ERR::ErrorDataTooLarge("Size of BLOB".to_string(), Position::NONE))?;
+
+    // Check if blob will be over max size limit
+    #[cfg(not(feature = "unchecked"))]
+    ctx.engine().throw_on_size((size, 0, 0))?;
+
+    let num_values = fmt.num_values();
+
+    if values.len() != num_values {
+        return Err(ERR::ErrorArithmetic(
+            format!(
+                "Format '{format}' requires {num_values} value(s) but {} given",
+                values.len()
+            ),
+            Position::NONE,
+        )
+        .into());
+    }
+
+    let mut blob = Blob::with_capacity(size);
+    let mut values = values.into_iter();
+
+    for (field, count) in fmt.fields {
+        match field {
+            PackField::Pad => blob.resize(blob.len() + count, 0),
+            PackField::Bytes => {
+                let value = values.next().unwrap();
+                let start = blob.len();
+
+                if value.is_string() {
+                    let s = value.into_immutable_string().unwrap();
+                    blob.extend(s.as_bytes().iter().take(count));
+                } else if value.is_blob() {
+                    blob.extend(value.into_blob().unwrap().into_iter().take(count));
+                } else {
+                    return Err(make_mismatch_err(ctx, "string or blob", &value));
+                }
+
+                blob.resize(start + count, 0);
+            }
+            _ => {
+                for value in values.by_ref().take(count) {
+                    pack_value(ctx, &mut blob, field, &value, fmt.is_le)?;
+                }
+            }
+        }
+    }
+
+    Ok(blob)
+}
+
+/// Pack a single value into a BLOB.
+fn pack_value(
+    ctx: &NativeCallContext,
+    blob: &mut Blob,
+    field: PackField,
+    value: &Dynamic,
+    is_le: bool,
+) -> RhaiResultOf<()> {
+    let mut push_bytes = |bytes: &[u8]| {
+        if is_le {
+            blob.extend_from_slice(bytes);
+        } else {
+            blob.extend(bytes.iter().rev());
+        }
+    };
+
+    match field {
+        PackField::Pad | PackField::Bytes => unreachable!("{:?} does not pack a value", field),
+        PackField::Bool => {
+            let x = value
+                .as_bool()
+                .map_err(|_| make_mismatch_err(ctx, "bool", value))?;
+            push_bytes(&[u8::from(x)]);
+        }
+        PackField::Int(size, signed) => {
+            let x = i128::from(
+                value
+                    .as_int()
+                    .map_err(|_| make_mismatch_err(ctx, std::any::type_name::<INT>(), value))?,
+            );
+            let bits = size * 8;
+            let (min, max) = if signed {
+                (-(1_i128 << (bits - 1)), (1_i128 << (bits - 1)) - 1)
+            } else {
+                (0, (1_i128 << bits) - 1)
+            };
+
+            if x < min || x > max {
+                return Err(ERR::ErrorArithmetic(
+                    format!(
+                        "Integer overflow: {x} does not fit into a {size}-byte {} integer",
+                        if signed { "signed" } else { "unsigned" }
+                    ),
+                    Position::NONE,
+                )
+                .into());
+            }
+
+            push_bytes(&x.to_le_bytes()[..size]);
+        }
+        #[cfg(not(feature = "no_float"))]
+        PackField::Float32 | PackField::Float64 => {
+            #[allow(clippy::cast_precision_loss)]
+            let x = value
+                .as_float()
+                .or_else(|_| value.as_int().map(|n| n as FLOAT))
+                .map_err(|_| make_mismatch_err(ctx, std::any::type_name::<FLOAT>(), value))?;
+
+            #[allow(clippy::unnecessary_cast, clippy::cast_possible_truncation)]
+            if field == PackField::Float32 {
+                push_bytes(&(x as f32).to_le_bytes());
+            } else {
+                push_bytes(&(x as f64).to_le_bytes());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Unpack values from a BLOB, starting at an offset, according to a format string.
+fn unpack_values(blob: &[u8], start: usize, format: &str) -> RhaiResultOf<Array> {
+    let fmt = PackFormat::parse(format)?;
+
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    match fmt.size().and_then(|size| start.checked_add(size)) {
+        Some(end) if end <= blob.len() => (),
+        _ => {
+            return Err(ERR::ErrorArrayBounds(blob.len(), blob.len() as INT, Position::NONE).into())
+        }
+    }
+
+    let mut result = Array::with_capacity(fmt.num_values());
+    let mut pos = start;
+
+    for (field, count) in fmt.fields {
+        match field {
+            PackField::Pad => pos += count,
+            PackField::Bytes => {
+                result.push(Dynamic::from_blob(blob[pos..][..count].to_vec()));
+                pos += count;
+            }
+            _ => {
+                for _ in 0..count {
+                    let size = field.size();
+                    let mut buf = [0_u8; 8];
+                    buf[..size].copy_from_slice(&blob[pos..][..size]);
+                    if !fmt.is_le {
+                        buf[..size].reverse();
+                    }
+                    pos += size;
+
+                    result.push(unpack_value(field, buf)?);
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Unpack a single value from little-endian bytes.
+fn unpack_value(field: PackField, buf: [u8; 8]) -> RhaiResult {
+    match field {
+        PackField::Pad | PackField::Bytes => unreachable!("{:?} does not unpack a value", field),
+        PackField::Bool => Ok((buf[0] != 0).into()),
+        PackField::Int(size, signed) => {
+            let bits = size * 8;
+            let mut x = i128::from(u64::from_le_bytes(buf));
+
+            // Sign-extend
+            if signed && x >> (bits - 1) & 1 == 1 {
+                x -= 1_i128 << bits;
+            }
+
+            INT::try_from(x).map(Into::into).map_err(|_| {
+                ERR::ErrorArithmetic(
+                    format!("Integer overflow: {x} does not fit into an integer"),
+                    Position::NONE,
+                )
+                .into()
+            })
+        }
+        #[cfg(not(feature = "no_float"))]
+        #[allow(clippy::unnecessary_cast, clippy::cast_possible_truncation)]
+        PackField::Float32 => {
+            let mut bytes = [0_u8; 4];
+            bytes.copy_from_slice(&buf[..4]);
+            Ok((f32::from_le_bytes(bytes) as FLOAT).into())
+        }
+        #[cfg(not(feature = "no_float"))]
+        #[allow(clippy::unnecessary_cast, clippy::cast_possible_truncation)]
+        PackField::Float64 => Ok((f64::from_le_bytes(buf) as FLOAT).into()),
+    }
+}
+
+#[export_module]
+mod pack_functions {
+    /// Pack an array of values into a new BLOB according to a format string,
+    /// similar to Python's `struct.pack`.
+    ///
+    /// The format string may start with a byte order character: `<` (little-endian),
+    /// `>` or `!` (big-endian), `=` or `@` (native, the default).
+    /// No alignment padding is ever added.
+    ///
+    /// Each format character may be preceded by a repeat count (e.g. `4B`):
+    ///
+    /// | Character | Value                 | Bytes |
+    /// |:---------:|-----------------------|:-----:|
+    /// |    `x`    | padding (no value)    |   1   |
+    /// |    `?`    | boolean               |   1   |
+    /// | `b` / `B` | signed/unsigned int   |   1   |
+    /// | `h` / `H` | signed/unsigned int   |   2   |
+    /// | `i` / `I` | signed/unsigned int   |   4   |
+    /// | `l` / `L` | signed/unsigned int   |   4   |
+    /// | `q` / `Q` | signed/unsigned int   |   8   |
+    /// |    `f`    | floating-point number |   4   |
+    /// |    `d`    | floating-point number |   8   |
+    /// |    `s`    | string or BLOB        | count |
+    ///
+    /// For `s`, the count is the length of the field (not a repeat count);
+    /// longer values are truncated and shorter ones are padded with zeros.
+    ///
+    /// An error is raised if the format string is invalid, the number of values does not match,
+    /// a value has the wrong type, or an integer does not fit into its field.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = pack(">HBx4s", [0x1234, 42, "abc"]);
+    ///
+    /// print(b);       // prints "[12342a0061626300]"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn pack(ctx: NativeCallContext, format: &str, values: Array) -> RhaiResultOf<Blob> {
+        pack_values(&ctx, format, values)
+    }
+    /// Return the number of bytes taken up by values packed according to a format string.
+    ///
+    /// See `pack` for the syntax of format strings.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// print(pack_size("<HBx4s"));    // prints 8
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn pack_size(format: &str) -> RhaiResultOf<INT> {
+        let fmt = PackFormat::parse(format)?;
+
+        fmt.size()
+            .and_then(|size| INT::try_from(size).ok())
+            .ok_or_else(|| {
+                ERR::ErrorArithmetic(
+                    format!("Integer overflow: pack_size('{format}')"),
+                    Position::NONE,
+                )
+                .into()
+            })
+    }
+    /// Unpack values from the BLOB into an array according to a format string,
+    /// similar to Python's `struct.unpack`.
+    ///
+    /// See `pack` for the syntax of format strings.
+    /// Byte strings (`s`) are unpacked as BLOB's.
+    ///
+    /// Extra bytes at the end of the BLOB are ignored.
+    /// An error is raised if the BLOB is too short, or if an unsigned integer does not fit into
+    /// an integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = pack(">HBx4s", [0x1234, 42, "abc"]);
+    ///
+    /// let x = b.unpack(">HBx4s");
+    ///
+    /// print(x);       // prints "[4660, 42, [61626300]]"
+    /// ```
+    #[rhai_fn(pure, return_raw)]
+    pub fn unpack(blob: &mut Blob, format: &str) -> RhaiResultOf<Array> {
+        unpack_values(blob, 0, format)
+    }
+    /// Unpack values from the BLOB, beginning at the `start` position, into an array according
+    /// to a format string.
+    ///
+    /// * If `start` < 0, position counts from the end of the BLOB (`-1` is the last byte).
+    ///
+    /// See `pack` for the syntax of format strings.
+    /// An error is raised if `start` is out of bounds or there are not enough bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b += 0xff;
+    /// b += pack("<hh", [-1, 2]);
+    ///
+    /// print(b.unpack("<hh", 1));     // prints "[-1, 2]"
+    /// ```
+    #[rhai_fn(name = "unpack", pure, return_raw)]
+    pub fn unpack_from(blob: &mut Blob, format: &str, start: INT) -> RhaiResultOf<Array> {
+        let len = blob.len();
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let start = if start >= 0 && start as u64 == len as u64 {
+            len
+        } else {
+            calc_read_start(blob, start)?
+        };
+
+        unpack_values(blob, start, format)
+    }
+}
+
+/// Alphabet for standard Base64 encoding (RFC 4648).
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Lookup table for CRC-32 (IEEE 802.3, reflected polynomial `0xEDB88320`).
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0_u32; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+};
+
+/// Calculate the starting position for reading from a BLOB.
+fn calc_read_start(blob: &[u8], start: INT) -> RhaiResultOf<usize> {
+    let len = blob.len();
+
+    calc_index(len, start, true, || {
+        Err(ERR::ErrorArrayBounds(len, start, Position::NONE).into())
+    })
+}
+
+/// Read a LEB128-encoded number from a BLOB, returning the raw bits, the number of bits
+/// and the position after the number.
+fn read_leb128(blob: &[u8], start: INT, signed: bool) -> RhaiResultOf<(u64, u32, usize)> {
+    let mut pos = calc_read_start(blob, start)?;
+    let mut value = 0_u64;
+    let mut shift = 0_u32;
+
+    loop {
+        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+        let byte = *blob
+            .get(pos)
+            .ok_or_else(|| ERR::ErrorArrayBounds(blob.len(), pos as INT, Position::NONE))?;
+        pos += 1;
+
+        // The tenth byte can only hold the highest bit (or the sign extension)
+        let overflow = match shift {
+            0..=62 => false,
+            63 if signed => byte & 0x7f != 0 && byte & 0x7f != 0x7f,
+            63 => byte & 0x7f > 1,
+            _ => true,
+        };
+
+        if overflow {
+            return Err(ERR::ErrorArithmetic(
+                "Integer overflow: LEB128 number too large".to_string(),
+                Position::NONE,
+            )
+            .into());
+        }
+
+        value |= u64::from(byte & 0x7f) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok((value, shift.min(64), pos));
+        }
+    }
+}
+
+#[export_module]
+mod encoding_functions {
+    /// Convert the BLOB into a string of hexadecimal digits, two for each byte.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob(3, 0xab);
+    ///
+    /// print(b.to_hex());      // prints "ababab"
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn to_hex(blob: &mut Blob) -> String {
+        const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        let mut result = String::with_capacity(blob.len() * 2);
+
+        for &byte in blob.iter() {
+            result.push(HEX_DIGITS[usize::from(byte >> 4)] as char);
+            result.push(HEX_DIGITS[usize::from(byte & 0x0f)] as char);
+        }
+
+        result
+    }
+    /// Convert a string of hexadecimal digits (two for each byte) into a BLOB.
+    ///
+    /// Both upper-case and lower-case digits are accepted.
+    /// An error is raised if the string is not valid hexadecimal.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = from_hex("DEADbeef");
+    ///
+    /// print(b);       // prints "[deadbeef]"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn from_hex(string: &str) -> RhaiResultOf<Blob> {
+        let make_err = || -> RhaiError {
+            ERR::ErrorArithmetic(
+                format!("Invalid hexadecimal string: '{string}'"),
+                Position::NONE,
+            )
+            .into()
+        };
+
+        if string.len() % 2 != 0 {
+            return Err(make_err());
+        }
+
+        string
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| {
+                let hi = char::from(pair[0]).to_digit(16).ok_or_else(make_err)?;
+                let lo = char::from(pair[1]).to_digit(16).ok_or_else(make_err)?;
+                #[allow(clippy::cast_possible_truncation)]
+                Ok((hi * 16 + lo) as u8)
+            })
+            .collect()
+    }
+    /// Convert the BLOB into a string in standard Base64 encoding (with padding).
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b.append("hello");
+    ///
+    /// print(b.to_base64());       // prints "aGVsbG8="
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn to_base64(blob: &mut Blob) -> String {
+        let mut result = String::with_capacity((blob.len() + 2) / 3 * 4);
+
+        for chunk in blob.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0_u32, |n, (i, &byte)| n | u32::from(byte) << (16 - i * 8));
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    result.push(BASE64_CHARS[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+                } else {
+                    result.push('=');
+                }
+            }
+        }
+
+        result
+    }
+    /// Convert a string in standard Base64 encoding into a BLOB.
+    ///
+    /// Trailing padding (`=`) is optional, but if present it must make the length of the string
+    /// a multiple of four.
+    /// An error is raised if the string is not valid Base64.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = from_base64("aGVsbG8=");
+    ///
+    /// print(b.as_string());       // prints "hello"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn from_base64(string: &str) -> RhaiResultOf<Blob> {
+        let make_err = || -> RhaiError {
+            ERR::ErrorArithmetic(format!("Invalid Base64 string: '{string}'"), Position::NONE)
+                .into()
+        };
+
+        let data = string.trim_end_matches('=').as_bytes();
+        let padding = string.len() - data.len();
+
+        if data.len() % 4 == 1 || padding > 2 || (padding > 0 && string.len() % 4 != 0) {
+            return Err(make_err());
+        }
+
+        let mut blob = Blob::with_capacity(data.len() * 3 / 4);
+
+        for chunk in data.chunks(4) {
+            let mut n = 0_u32;
+
+            for (i, &ch) in chunk.iter().enumerate() {
+                let digit = BASE64_CHARS
+                    .iter()
+                    .position(|&c| c == ch)
+                    .ok_or_else(make_err)?;
+                #[allow(clippy::cast_possible_truncation)]
+                let digit = digit as u32;
+                n |= digit << (18 - i * 6);
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            blob.extend((0..chunk.len() - 1).map(|i| (n >> (16 - i * 8)) as u8));
+        }
+
+        Ok(blob)
+    }
+    /// Calculate the CRC-32 checksum (as used by zlib, PNG, Ethernet etc.) of the BLOB.
+    ///
+    /// Under `only_i32`, checksums ≥ 2<sup>31</sup> are returned as negative integers.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b.append("123456789");
+    ///
+    /// print(b.crc32().to_hex());      // prints "cbf43926"
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn crc32(blob: &mut Blob) -> INT {
+        let crc = blob.iter().fold(0xFFFF_FFFF_u32, |crc, &byte| {
+            CRC32_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8)
+        }) ^ 0xFFFF_FFFF;
+
+        #[allow(clippy::cast_possible_wrap, clippy::cast_lossless)]
+        let crc = crc as INT;
+
+        crc
+    }
+
+    /// Append an integer to the end of the BLOB in unsigned LEB128 (variable-length) encoding.
+    ///
+    /// An error is raised if the integer is negative.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b.append_uleb128(624485);
+    ///
+    /// print(b);       // prints "[e58e26]"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn append_uleb128(blob: &mut Blob, value: INT) -> RhaiResultOf<()> {
+        if value < 0 {
+            return Err(ERR::ErrorArithmetic(
+                format!("Cannot encode negative number {value} as unsigned LEB128"),
+                Position::NONE,
+            )
+            .into());
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        let mut value = value as u64;
+
+        loop {
+            #[allow(clippy::cast_possible_truncation)]
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                blob.push(byte);
+                return Ok(());
+            }
+            blob.push(byte | 0x80);
+        }
+    }
+    /// Append an integer to the end of the BLOB in signed LEB128 (variable-length) encoding.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b.append_sleb128(-123456);
+    ///
+    /// print(b);       // prints "[c0bb78]"
+    /// ```
+    pub fn append_sleb128(blob: &mut Blob, value: INT) {
+        let mut value = value;
+
+        loop {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                blob.push(byte);
+                return;
+            }
+            blob.push(byte | 0x80);
+        }
+    }
+    /// Read an integer in unsigned LEB128 (variable-length) encoding from the BLOB,
+    /// beginning at the `start` position.
+    ///
+    /// * If `start` < 0, position counts from the end of the BLOB (`-1` is the last byte).
+    ///
+    /// Return an array of two elements: the integer and the position right after it.
+    ///
+    /// An error is raised if `start` is out of bounds, the encoding is truncated, or the number
+    /// does not fit into an integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b.append_uleb128(624485);
+    /// b.append_uleb128(42);
+    ///
+    /// let x = b.read_uleb128(0);
+    ///
+    /// print(x);                   // prints "[624485, 3]"
+    ///
+    /// print(b.read_uleb128(x[1]));    // prints "[42, 4]"
+    /// ```
+    #[rhai_fn(pure, return_raw)]
+    pub fn read_uleb128(blob: &mut Blob, start: INT) -> RhaiResultOf<Array> {
+        let (value, _, pos) = read_leb128(blob, start, false)?;
+
+        let value = INT::try_from(value).map_err(|_| {
+            ERR::ErrorArithmetic(
+                format!("Integer overflow: {value} does not fit into an integer"),
+                Position::NONE,
+            )
+        })?;
+
+        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+        Ok(vec![value.into(), (pos as INT).into()])
+    }
+    /// Read an integer in signed LEB128 (variable-length) encoding from the BLOB,
+    /// beginning at the `start` position.
+    ///
+    /// * If `start` < 0, position counts from the end of the BLOB (`-1` is the last byte).
+    ///
+    /// Return an array of two elements: the integer and the position right after it.
+    ///
+    /// An error is raised if `start` is out of bounds, the encoding is truncated, or the number
+    /// does not fit into an integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b.append_sleb128(-123456);
+    ///
+    /// print(b.read_sleb128(0));   // prints "[-123456, 3]"
+    /// ```
+    #[rhai_fn(pure, return_raw)]
+    pub fn read_sleb128(blob: &mut Blob, start: INT) -> RhaiResultOf<Array> {
+        let (value, bits, pos) = read_leb128(blob, start, true)?;
+
+        #[allow(clippy::cast_possible_wrap)]
+        let mut value = i128::from(value as i64);
+
+        // Sign-extend
+        if bits < 64 && value >> (bits - 1) & 1 == 1 {
+            value -= 1_i128 << bits;
+        }
+
+        let value = INT::try_from(value).map_err(|_| {
+            ERR::ErrorArithmetic(
+                format!("Integer overflow: {value} does not fit into an integer"),
+                Position::NONE,
+            )
+        })?;
+
+        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+        Ok(vec![value.into(), (pos as INT).into()])
+    }
+}
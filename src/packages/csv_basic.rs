@@ -0,0 +1,182 @@
+#![cfg(feature = "csv")]
+#![cfg(not(feature = "no_index"))]
+
+use super::make_data_parse_err;
+use crate::plugin::*;
+use crate::{def_package, Array, Dynamic, Position, RhaiError, RhaiResultOf, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_object"))]
+use crate::{Identifier, Map};
+
+def_package! {
+    /// Package of CSV utilities.
+    pub CsvPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "csv", csv_functions);
+    }
+}
+
+/// Create an error for a CSV text that fails to parse.
+fn make_parse_err(err: csv::Error) -> RhaiError {
+    #[allow(clippy::cast_possible_truncation)]
+    let line = err.position().map_or(0, |pos| pos.line() as usize);
+    make_data_parse_err("parse_csv", format!("Invalid CSV: {err}"), line, 0)
+}
+
+/// Create an error for values that cannot be converted into CSV.
+fn make_write_err(err: impl std::fmt::Display) -> RhaiError {
+    ERR::ErrorRuntime(
+        format!("Cannot convert to CSV: {err}").into(),
+        Position::NONE,
+    )
+    .into()
+}
+
+#[export_module]
+mod csv_functions {
+    /// Parse a CSV text with a header row into an array of object maps, one per record, keyed by
+    /// the column names in the header.
+    ///
+    /// Fields that look like numbers or booleans are converted; all other fields are strings.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let rows = parse_csv("name,age\nfoo,42\nbar,7");
+    ///
+    /// print(rows[0].name);        // prints "foo"
+    ///
+    /// print(rows[1].age);         // prints 7
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    #[rhai_fn(return_raw)]
+    pub fn parse_csv(text: &str) -> RhaiResultOf<Array> {
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+
+        let headers: Vec<Identifier> = reader
+            .headers()
+            .map_err(make_parse_err)?
+            .iter()
+            .map(Into::into)
+            .collect();
+
+        reader
+            .deserialize::<Array>()
+            .map(|record| {
+                record
+                    .map(|fields| headers.iter().cloned().zip(fields).collect::<Map>().into())
+                    .map_err(make_parse_err)
+            })
+            .collect()
+    }
+    /// Parse a CSV text into an array of records.
+    ///
+    /// If `has_header` is `true`, the first row is a header row and each record is an object map
+    /// keyed by the column names in the header. Otherwise, each record is an array of fields.
+    ///
+    /// Fields that look like numbers or booleans are converted; all other fields are strings.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let rows = parse_csv("foo,42\nbar,7", false);
+    ///
+    /// print(rows);                // prints [["foo", 42], ["bar", 7]]
+    /// ```
+    #[rhai_fn(name = "parse_csv", return_raw)]
+    pub fn parse_csv_with_header(text: &str, has_header: bool) -> RhaiResultOf<Array> {
+        #[cfg(not(feature = "no_object"))]
+        if has_header {
+            return parse_csv(text);
+        }
+
+        csv::ReaderBuilder::new()
+            .has_headers(has_header)
+            .from_reader(text.as_bytes())
+            .deserialize::<Array>()
+            .map(|record| record.map(Into::into).map_err(make_parse_err))
+            .collect()
+    }
+    /// Return the CSV representation of an array of records.
+    ///
+    /// Records can be either arrays of fields, or object maps. For object maps, a header row is
+    /// written with the property names of the first record, and missing properties in other
+    /// records are written as empty fields.
+    ///
+    /// Fields that are arrays are flattened into the record. An error is raised if a field is an
+    /// object map.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let rows = [#{name: "foo", age: 42}, #{name: "bar", age: 7}];
+    ///
+    /// print(rows.to_csv());       // prints "age,name\n42,foo\n7,bar\n"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn to_csv(ctx: NativeCallContext, array: &mut Array) -> RhaiResultOf<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        #[cfg(not(feature = "no_object"))]
+        let mut headers: Option<Vec<Identifier>> = None;
+        #[cfg(not(feature = "no_object"))]
+        let mut has_arrays = false;
+
+        for record in array.iter() {
+            #[cfg(not(feature = "no_object"))]
+            if let Some(map) = record.read_lock::<Map>() {
+                if has_arrays {
+                    return Err(make_write_err("records must be all arrays or all maps"));
+                }
+
+                let keys = match headers {
+                    Some(ref keys) => keys,
+                    None => {
+                        let keys: Vec<_> = map.keys().cloned().collect();
+                        writer
+                            .write_record(keys.iter().map(Identifier::as_str))
+                            .map_err(make_write_err)?;
+                        headers.get_or_insert(keys)
+                    }
+                };
+
+                let fields: Array = keys
+                    .iter()
+                    .map(|k| map.get(k).cloned().unwrap_or(Dynamic::UNIT))
+                    .collect();
+
+                writer.serialize(fields).map_err(make_write_err)?;
+                continue;
+            }
+
+            #[cfg(not(feature = "no_object"))]
+            if headers.is_some() {
+                return Err(make_write_err("records must be all arrays or all maps"));
+            }
+
+            match record.read_lock::<Array>() {
+                Some(fields) => writer.serialize(&*fields).map_err(make_write_err)?,
+                None => {
+                    return Err(ERR::ErrorMismatchDataType(
+                        "array or map".into(),
+                        ctx.engine().map_type_name(record.type_name()).into(),
+                        Position::NONE,
+                    )
+                    .into())
+                }
+            }
+
+            #[cfg(not(feature = "no_object"))]
+            {
+                has_arrays = true;
+            }
+        }
+
+        let bytes = writer.into_inner().map_err(make_write_err)?;
+
+        String::from_utf8(bytes).map_err(make_write_err)
+    }
+}
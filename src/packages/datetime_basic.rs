@@ -0,0 +1,507 @@
+#![cfg(not(feature = "no_time"))]
+
+use super::arithmetic::make_err as make_arithmetic_err;
+use crate::plugin::*;
+use crate::types::datetime::parse_offset;
+use crate::{def_package, DateTime, ImmutableString, RhaiResult, RhaiResultOf, INT};
+use std::convert::TryFrom;
+
+#[cfg(not(feature = "no_float"))]
+use crate::FLOAT;
+
+def_package! {
+    /// Package of wall-clock date/time utilities.
+    pub DateTimePackage(lib) {
+        lib.set_standard_lib(true);
+
+        // Register date/time functions
+        combine_with_exported_module!(lib, "datetime", datetime_functions);
+    }
+}
+
+#[export_module]
+mod datetime_functions {
+    /// Return the current date/time in UTC.
+    ///
+    /// The clock can be overridden by the host via `Engine::set_clock`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let now = now();
+    ///
+    /// print(now);             // prints 2024-02-29T12:30:00.123Z
+    /// ```
+    #[rhai_fn(volatile, return_raw)]
+    pub fn now(ctx: NativeCallContext) -> RhaiResultOf<DateTime> {
+        ctx.engine().current_datetime().ok_or_else(|| {
+            make_arithmetic_err("No clock is available to get the current date/time")
+        })
+    }
+
+    /// Create a date/time in UTC at midnight of the specified date.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 2, 29);
+    ///
+    /// print(d);               // prints 2024-02-29T00:00:00Z
+    /// ```
+    #[rhai_fn(name = "datetime", return_raw)]
+    pub fn from_ymd(year: INT, month: INT, day: INT) -> RhaiResultOf<DateTime> {
+        from_ymd_hms(year, month, day, 0, 0, 0)
+    }
+    /// Create a date/time in UTC with the specified date and time.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 2, 29, 12, 30, 0);
+    ///
+    /// print(d);               // prints 2024-02-29T12:30:00Z
+    /// ```
+    #[rhai_fn(name = "datetime", return_raw)]
+    #[allow(clippy::useless_conversion)]
+    pub fn from_ymd_hms(
+        year: INT,
+        month: INT,
+        day: INT,
+        hour: INT,
+        minute: INT,
+        second: INT,
+    ) -> RhaiResultOf<DateTime> {
+        let to_u32 = |v: INT| u32::try_from(v).unwrap_or(u32::MAX);
+
+        DateTime::from_ymd_hms(
+            year.into(),
+            to_u32(month),
+            to_u32(day),
+            to_u32(hour),
+            to_u32(minute),
+            to_u32(second),
+        )
+        .ok_or_else(|| {
+            make_arithmetic_err(format!(
+                "Invalid date/time: {year}-{month}-{day} {hour}:{minute}:{second}"
+            ))
+        })
+    }
+    /// Parse a date/time in RFC 3339 format.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = parse_datetime("2024-02-29T20:30:00+08:00");
+    ///
+    /// print(d.hour);          // prints 20
+    ///
+    /// print(d.to_utc());      // prints 2024-02-29T12:30:00Z
+    /// ```
+    #[rhai_fn(name = "parse_datetime", return_raw)]
+    pub fn parse_rfc3339(string: &str) -> RhaiResultOf<DateTime> {
+        DateTime::parse_rfc3339(string)
+    }
+    /// Parse a date/time according to a `strftime`-like `pattern`.
+    ///
+    /// If the pattern does not contain an offset from UTC, the date/time is assumed to be in UTC.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = parse_datetime("29/02/2024 08:30 PM", "%d/%m/%Y %I:%M %p");
+    ///
+    /// print(d);               // prints 2024-02-29T20:30:00Z
+    /// ```
+    #[rhai_fn(name = "parse_datetime", return_raw)]
+    pub fn parse_from_str(string: &str, pattern: &str) -> RhaiResultOf<DateTime> {
+        DateTime::parse_from_str(string, pattern)
+    }
+    /// Create a date/time in UTC from the number of seconds since the Unix epoch.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = from_unix_timestamp(1709209800);
+    ///
+    /// print(d);               // prints 2024-02-29T12:30:00Z
+    /// ```
+    #[rhai_fn(return_raw)]
+    #[allow(clippy::useless_conversion)]
+    pub fn from_unix_timestamp(seconds: INT) -> RhaiResultOf<DateTime> {
+        DateTime::from_unix_timestamp(seconds.into(), 0)
+            .ok_or_else(|| make_arithmetic_err(format!("Unix timestamp out of range: {seconds}")))
+    }
+    /// Create a date/time in UTC from the number of seconds (with fractions) since the Unix epoch.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = from_unix_timestamp(1709209800.5);
+    ///
+    /// print(d);               // prints 2024-02-29T12:30:00.500Z
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "from_unix_timestamp", return_raw)]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn from_unix_timestamp_float(seconds: FLOAT) -> RhaiResultOf<DateTime> {
+        let secs = seconds.floor();
+        let nanos = ((seconds - secs) * 1e9).round().min(999_999_999.0);
+
+        if secs.is_finite() && secs >= (i64::MIN as FLOAT) && secs <= (i64::MAX as FLOAT) {
+            if let Some(dt) = DateTime::from_unix_timestamp(secs as i64, nanos as u32) {
+                return Ok(dt);
+            }
+        }
+
+        Err(make_arithmetic_err(format!(
+            "Unix timestamp out of range: {seconds}"
+        )))
+    }
+
+    /// Return the number of whole seconds since the Unix epoch.
+    #[rhai_fn(get = "unix_timestamp", pure, return_raw)]
+    pub fn unix_timestamp(dt: &mut DateTime) -> RhaiResultOf<INT> {
+        INT::try_from(dt.unix_timestamp()).map_err(|_| {
+            make_arithmetic_err(format!(
+                "Integer overflow for Unix timestamp: {}",
+                dt.unix_timestamp()
+            ))
+        })
+    }
+    /// Return the year of the date/time.
+    #[rhai_fn(get = "year", pure)]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn year(dt: &mut DateTime) -> INT {
+        dt.year() as INT
+    }
+    /// Return the month (1-12) of the date/time.
+    #[rhai_fn(get = "month", pure)]
+    pub fn month(dt: &mut DateTime) -> INT {
+        dt.month() as INT
+    }
+    /// Return the day of the month (starting from 1) of the date/time.
+    #[rhai_fn(get = "day", pure)]
+    pub fn day(dt: &mut DateTime) -> INT {
+        dt.day() as INT
+    }
+    /// Return the hour (0-23) of the date/time.
+    #[rhai_fn(get = "hour", pure)]
+    pub fn hour(dt: &mut DateTime) -> INT {
+        dt.hour() as INT
+    }
+    /// Return the minute (0-59) of the date/time.
+    #[rhai_fn(get = "minute", pure)]
+    pub fn minute(dt: &mut DateTime) -> INT {
+        dt.minute() as INT
+    }
+    /// Return the second (0-59) of the date/time.
+    #[rhai_fn(get = "second", pure)]
+    pub fn second(dt: &mut DateTime) -> INT {
+        dt.second() as INT
+    }
+    /// Return the nanoseconds within the second of the date/time.
+    #[rhai_fn(get = "nanosecond", pure)]
+    pub fn nanosecond(dt: &mut DateTime) -> INT {
+        dt.nanosecond() as INT
+    }
+    /// Return the day of the week of the date/time, from 1 (Monday) to 7 (Sunday).
+    #[rhai_fn(get = "weekday", pure)]
+    pub fn weekday(dt: &mut DateTime) -> INT {
+        dt.weekday() as INT
+    }
+    /// Return the day of the year (starting from 1) of the date/time.
+    #[rhai_fn(get = "day_of_year", pure)]
+    pub fn day_of_year(dt: &mut DateTime) -> INT {
+        dt.day_of_year() as INT
+    }
+    /// Return the offset from UTC of the date/time, in seconds.
+    #[rhai_fn(get = "offset", pure)]
+    pub fn offset(dt: &mut DateTime) -> INT {
+        dt.offset() as INT
+    }
+
+    /// Convert the date/time into UTC.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = parse_datetime("2024-03-01T01:00:00+02:00");
+    ///
+    /// print(d.to_utc());      // prints 2024-02-29T23:00:00Z
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn to_utc(dt: &mut DateTime) -> DateTime {
+        dt.to_utc()
+    }
+    /// Convert the date/time into one with a different offset from UTC, in seconds.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 2, 29, 12, 30, 0);
+    ///
+    /// print(d.to_offset(-5 * 3600));  // prints 2024-02-29T07:30:00-05:00
+    /// ```
+    #[rhai_fn(pure, return_raw)]
+    #[allow(clippy::useless_conversion)]
+    pub fn to_offset(dt: &mut DateTime, seconds: INT) -> RhaiResultOf<DateTime> {
+        i32::try_from(seconds)
+            .ok()
+            .and_then(|offset| dt.with_offset(offset))
+            .ok_or_else(|| make_arithmetic_err(format!("Invalid offset from UTC: {seconds}")))
+    }
+    /// Convert the date/time into one with a different offset from UTC, in the format
+    /// `+HH:MM`, `-HHMM` or `Z`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 2, 29, 12, 30, 0);
+    ///
+    /// print(d.to_offset("+08:00"));   // prints 2024-02-29T20:30:00+08:00
+    /// ```
+    #[rhai_fn(name = "to_offset", pure, return_raw)]
+    pub fn to_offset_str(dt: &mut DateTime, offset: &str) -> RhaiResultOf<DateTime> {
+        dt.with_offset(parse_offset(offset)?)
+            .ok_or_else(|| make_arithmetic_err(format!("Invalid offset from UTC: {offset}")))
+    }
+
+    /// Format the date/time according to a `strftime`-like `pattern`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 2, 29, 12, 30, 0);
+    ///
+    /// print(d.format("%A, %d %B %Y %H:%M"));  // prints "Thursday, 29 February 2024 12:30"
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn format(dt: &mut DateTime, pattern: &str) -> ImmutableString {
+        dt.format(pattern).into()
+    }
+    /// Convert the date/time into a string in RFC 3339 format.
+    #[rhai_fn(name = "print", name = "to_string", name = "to_rfc3339", pure)]
+    pub fn to_rfc3339(dt: &mut DateTime) -> ImmutableString {
+        dt.to_string().into()
+    }
+    /// Convert the date/time into a string for debugging.
+    #[rhai_fn(name = "debug", name = "to_debug", pure)]
+    pub fn to_debug(dt: &mut DateTime) -> ImmutableString {
+        format!("{dt:?}").into()
+    }
+
+    #[inline]
+    #[allow(clippy::useless_conversion)]
+    fn add_impl(dt: DateTime, seconds: INT) -> RhaiResultOf<DateTime> {
+        dt.checked_add(seconds.into(), 0).ok_or_else(|| {
+            make_arithmetic_err(format!(
+                "Date/time overflow when adding {seconds} second(s)"
+            ))
+        })
+    }
+    #[inline]
+    #[allow(clippy::useless_conversion)]
+    fn subtract_impl(dt: DateTime, seconds: INT) -> RhaiResultOf<DateTime> {
+        i64::from(seconds)
+            .checked_neg()
+            .and_then(|seconds| dt.checked_add(seconds, 0))
+            .ok_or_else(|| {
+                make_arithmetic_err(format!(
+                    "Date/time overflow when subtracting {seconds} second(s)"
+                ))
+            })
+    }
+
+    /// Add the specified number of `seconds` to the date/time and return it as a new date/time.
+    #[rhai_fn(return_raw, name = "+")]
+    pub fn add(dt: DateTime, seconds: INT) -> RhaiResultOf<DateTime> {
+        add_impl(dt, seconds)
+    }
+    /// Add the specified number of `seconds` to the date/time.
+    #[rhai_fn(return_raw, name = "+=")]
+    pub fn add_assign(dt: &mut DateTime, seconds: INT) -> RhaiResultOf<()> {
+        *dt = add_impl(*dt, seconds)?;
+        Ok(())
+    }
+    /// Subtract the specified number of `seconds` from the date/time and return it as a new date/time.
+    #[rhai_fn(return_raw, name = "-")]
+    pub fn subtract(dt: DateTime, seconds: INT) -> RhaiResultOf<DateTime> {
+        subtract_impl(dt, seconds)
+    }
+    /// Subtract the specified number of `seconds` from the date/time.
+    #[rhai_fn(return_raw, name = "-=")]
+    pub fn subtract_assign(dt: &mut DateTime, seconds: INT) -> RhaiResultOf<()> {
+        *dt = subtract_impl(*dt, seconds)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    pub mod float_functions {
+        #[allow(clippy::cast_possible_truncation)]
+        fn add_impl(dt: DateTime, seconds: FLOAT) -> RhaiResultOf<DateTime> {
+            let nanos = (seconds * 1e9).round();
+
+            if nanos.is_finite() && nanos.abs() <= (i64::MAX as FLOAT) {
+                if let Some(dt) = dt.checked_add(0, nanos as i64) {
+                    return Ok(dt);
+                }
+            }
+
+            Err(make_arithmetic_err(format!(
+                "Date/time overflow when adding {seconds} second(s)"
+            )))
+        }
+
+        /// Add the specified number of `seconds` to the date/time and return it as a new date/time.
+        #[rhai_fn(return_raw, name = "+")]
+        pub fn add(dt: DateTime, seconds: FLOAT) -> RhaiResultOf<DateTime> {
+            add_impl(dt, seconds)
+        }
+        /// Add the specified number of `seconds` to the date/time.
+        #[rhai_fn(return_raw, name = "+=")]
+        pub fn add_assign(dt: &mut DateTime, seconds: FLOAT) -> RhaiResultOf<()> {
+            *dt = add_impl(*dt, seconds)?;
+            Ok(())
+        }
+        /// Subtract the specified number of `seconds` from the date/time and return it as a new date/time.
+        #[rhai_fn(return_raw, name = "-")]
+        pub fn subtract(dt: DateTime, seconds: FLOAT) -> RhaiResultOf<DateTime> {
+            add_impl(dt, -seconds)
+        }
+        /// Subtract the specified number of `seconds` from the date/time.
+        #[rhai_fn(return_raw, name = "-=")]
+        pub fn subtract_assign(dt: &mut DateTime, seconds: FLOAT) -> RhaiResultOf<()> {
+            *dt = add_impl(*dt, -seconds)?;
+            Ok(())
+        }
+    }
+
+    /// Return the number of seconds between two date/times.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d1 = datetime(2024, 3, 1);
+    /// let d2 = datetime(2024, 2, 28);
+    ///
+    /// print(d1 - d2);         // prints 172800.0
+    /// ```
+    #[rhai_fn(return_raw, name = "-")]
+    #[allow(clippy::cast_precision_loss, clippy::unnecessary_wraps)]
+    pub fn datetime_diff(dt1: DateTime, dt2: DateTime) -> RhaiResult {
+        let seconds = dt1.unix_timestamp() - dt2.unix_timestamp();
+        let nanos = i64::from(dt1.nanosecond()) - i64::from(dt2.nanosecond());
+
+        #[cfg(not(feature = "no_float"))]
+        return Ok(((seconds as FLOAT) + (nanos as FLOAT) / 1e9).into());
+
+        #[cfg(feature = "no_float")]
+        {
+            // Truncate towards zero
+            let seconds = match nanos {
+                n if n < 0 && seconds > 0 => seconds - 1,
+                n if n > 0 && seconds < 0 => seconds + 1,
+                _ => seconds,
+            };
+
+            INT::try_from(seconds).map(Into::into).map_err(|_| {
+                make_arithmetic_err(format!(
+                    "Integer overflow for date/time difference: {seconds}"
+                ))
+            })
+        }
+    }
+
+    /// Add the specified number of `days` to the date/time and return it as a new date/time.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 2, 28);
+    ///
+    /// print(d.add_days(2));   // prints 2024-03-01T00:00:00Z
+    /// ```
+    #[rhai_fn(pure, return_raw)]
+    #[allow(clippy::useless_conversion)]
+    pub fn add_days(dt: &mut DateTime, days: INT) -> RhaiResultOf<DateTime> {
+        i64::from(days)
+            .checked_mul(86_400)
+            .and_then(|seconds| dt.checked_add(seconds, 0))
+            .ok_or_else(|| {
+                make_arithmetic_err(format!("Date/time overflow when adding {days} day(s)"))
+            })
+    }
+    /// Add the specified number of calendar `months` to the date/time and return it as a new
+    /// date/time.
+    ///
+    /// If the day does not exist in the resulting month, the last day of the month is used.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 1, 31);
+    ///
+    /// print(d.add_months(1)); // prints 2024-02-29T00:00:00Z
+    /// ```
+    #[rhai_fn(pure, return_raw)]
+    #[allow(clippy::useless_conversion)]
+    pub fn add_months(dt: &mut DateTime, months: INT) -> RhaiResultOf<DateTime> {
+        dt.checked_add_months(months.into()).ok_or_else(|| {
+            make_arithmetic_err(format!("Date/time overflow when adding {months} month(s)"))
+        })
+    }
+    /// Add the specified number of calendar `years` to the date/time and return it as a new
+    /// date/time.
+    ///
+    /// If the day does not exist in the resulting year (i.e. February 29), February 28 is used.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let d = datetime(2024, 2, 29);
+    ///
+    /// print(d.add_years(1));  // prints 2025-02-28T00:00:00Z
+    /// ```
+    #[rhai_fn(pure, return_raw)]
+    #[allow(clippy::useless_conversion)]
+    pub fn add_years(dt: &mut DateTime, years: INT) -> RhaiResultOf<DateTime> {
+        i64::from(years)
+            .checked_mul(12)
+            .and_then(|months| dt.checked_add_months(months))
+            .ok_or_else(|| {
+                make_arithmetic_err(format!("Date/time overflow when adding {years} year(s)"))
+            })
+    }
+
+    /// Return `true` if two date/times represent the same instant in time.
+    #[rhai_fn(name = "==")]
+    pub fn eq(dt1: DateTime, dt2: DateTime) -> bool {
+        dt1 == dt2
+    }
+    /// Return `true` if two date/times do not represent the same instant in time.
+    #[rhai_fn(name = "!=")]
+    pub fn ne(dt1: DateTime, dt2: DateTime) -> bool {
+        dt1 != dt2
+    }
+    /// Return `true` if the first date/time is earlier than the second.
+    #[rhai_fn(name = "<")]
+    pub fn lt(dt1: DateTime, dt2: DateTime) -> bool {
+        dt1 < dt2
+    }
+    /// Return `true` if the first date/time is earlier than or equals to the second.
+    #[rhai_fn(name = "<=")]
+    pub fn lte(dt1: DateTime, dt2: DateTime) -> bool {
+        dt1 <= dt2
+    }
+    /// Return `true` if the first date/time is later than the second.
+    #[rhai_fn(name = ">")]
+    pub fn gt(dt1: DateTime, dt2: DateTime) -> bool {
+        dt1 > dt2
+    }
+    /// Return `true` if the first date/time is later than or equals to the second.
+    #[rhai_fn(name = ">=")]
+    pub fn gte(dt1: DateTime, dt2: DateTime) -> bool {
+        dt1 >= dt2
+    }
+}
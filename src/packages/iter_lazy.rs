@@ -0,0 +1,247 @@
+#![cfg(not(feature = "no_index"))]
+
+use crate::plugin::*;
+use crate::{
+    def_package, Array, Dynamic, ExclusiveRange, FnPtr, InclusiveRange, LazyIter, Position,
+    RhaiResultOf, ERR, INT, MAX_USIZE_INT,
+};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Convert a number of elements into a [`usize`], treating negative numbers as zero.
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+fn to_count(n: INT) -> usize {
+    n.clamp(0, MAX_USIZE_INT) as usize
+}
+
+macro_rules! reg_adapters {
+    ($lib:ident => $( $arg_type:ty ),*) => {
+        $({
+            #[export_module]
+            mod adapter_functions {
+                /// Return a lazy iterator that transforms each element via the `mapper` function.
+                ///
+                /// The `mapper` function is only called when the iterator is run.
+                ///
+                /// # No Function Parameter
+                ///
+                /// The element is bound to `this`.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (1..=5).map(|v| v * v);
+                ///
+                /// print(x.collect());     // prints "[1, 4, 9, 16, 25]"
+                /// ```
+                pub fn map(iter: $arg_type, mapper: FnPtr) -> LazyIter {
+                    LazyIter::from(iter).map(mapper)
+                }
+                /// Return a lazy iterator that keeps only the elements for which the `filter`
+                /// function returns `true`.
+                ///
+                /// The `filter` function is only called when the iterator is run.
+                ///
+                /// # No Function Parameter
+                ///
+                /// The element is bound to `this`.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (0..1_000_000).filter(|v| v % 3 == 0).take(4);
+                ///
+                /// print(x.collect());     // prints "[0, 3, 6, 9]"
+                /// ```
+                pub fn filter(iter: $arg_type, filter: FnPtr) -> LazyIter {
+                    LazyIter::from(iter).filter(filter)
+                }
+                /// Return a lazy iterator that keeps elements for as long as the `filter` function
+                /// returns `true`, and then stops.
+                ///
+                /// # No Function Parameter
+                ///
+                /// The element is bound to `this`.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (1..100).take_while(|v| v * v < 20);
+                ///
+                /// print(x.collect());     // prints "[1, 2, 3, 4]"
+                /// ```
+                pub fn take_while(iter: $arg_type, filter: FnPtr) -> LazyIter {
+                    LazyIter::from(iter).take_while(filter)
+                }
+                /// Return a lazy iterator that keeps only the first `n` elements.
+                ///
+                /// If `n` ≤ 0, the iterator is empty.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (10..100).take(3);
+                ///
+                /// print(x.collect());     // prints "[10, 11, 12]"
+                /// ```
+                pub fn take(iter: $arg_type, n: INT) -> LazyIter {
+                    LazyIter::from(iter).take(to_count(n))
+                }
+                /// Return a lazy iterator that skips the first `n` elements.
+                ///
+                /// If `n` ≤ 0, no elements are skipped.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (1..=5).skip(3);
+                ///
+                /// print(x.collect());     // prints "[4, 5]"
+                /// ```
+                pub fn skip(iter: $arg_type, n: INT) -> LazyIter {
+                    LazyIter::from(iter).skip(to_count(n))
+                }
+                /// Return a lazy iterator that keeps the first element and then every `step`-th
+                /// element.
+                ///
+                /// An error is raised if `step` ≤ 0.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (0..10).step_by(3);
+                ///
+                /// print(x.collect());     // prints "[0, 3, 6, 9]"
+                /// ```
+                #[rhai_fn(return_raw)]
+                pub fn step_by(iter: $arg_type, step: INT) -> RhaiResultOf<LazyIter> {
+                    if step <= 0 {
+                        return Err(ERR::ErrorArithmetic(
+                            format!("Invalid step: {step}"),
+                            Position::NONE,
+                        )
+                        .into());
+                    }
+                    Ok(LazyIter::from(iter).step_by(to_count(step)))
+                }
+                /// Return a lazy iterator that turns each element into an array of
+                /// `[index, element]`.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// for [i, v] in (5..8).enumerate() {
+                ///     print(`${i}: ${v}`);    // prints "0: 5", "1: 6", "2: 7"
+                /// }
+                /// ```
+                pub fn enumerate(iter: $arg_type) -> LazyIter {
+                    LazyIter::from(iter).enumerate()
+                }
+                /// Return a lazy iterator that continues with the elements of `other`
+                /// (any iterable value) after all the elements of this iterator.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (1..3).chain([42, 99]);
+                ///
+                /// print(x.collect());     // prints "[1, 2, 42, 99]"
+                /// ```
+                pub fn chain(iter: $arg_type, other: Dynamic) -> LazyIter {
+                    LazyIter::from(iter).chain(other)
+                }
+                /// Return a lazy iterator that pairs each element with an element of `other`
+                /// (any iterable value) into an array of `[element, other element]`.
+                ///
+                /// The iterator stops when either runs out of elements.
+                ///
+                /// # Example
+                ///
+                /// ```rhai
+                /// let x = (1..100).zip(["a", "b"]);
+                ///
+                /// print(x.collect());     // prints "[[1, "a"], [2, "b"]]"
+                /// ```
+                pub fn zip(iter: $arg_type, other: Dynamic) -> LazyIter {
+                    LazyIter::from(iter).zip(other)
+                }
+            }
+
+            combine_with_exported_module!($lib, stringify!($arg_type), adapter_functions);
+        })*
+    };
+}
+
+def_package! {
+    /// Package of lazy iterator utilities.
+    pub LazyIteratorPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "lazy_iter", lazy_iter_functions);
+
+        reg_adapters!(lib => LazyIter, ExclusiveRange, InclusiveRange);
+    }
+}
+
+#[export_module]
+mod lazy_iter_functions {
+    /// Return a lazy iterator over any iterable value (e.g. an array, a string or a range).
+    ///
+    /// An error is raised if the value is not iterable.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5].iter().map(|v| v * 10).skip(2);
+    ///
+    /// for v in x {
+    ///     print(v);       // prints 30, 40, 50
+    /// }
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn iter(ctx: NativeCallContext, value: Dynamic) -> RhaiResultOf<LazyIter> {
+        let value = match value.try_cast_result::<LazyIter>() {
+            Ok(iter) => return Ok(iter),
+            Err(value) => value,
+        };
+
+        if ctx
+            .engine()
+            .get_type_iterator(ctx.global_runtime_state(), value.type_id())
+            .is_none()
+        {
+            return Err(ERR::ErrorMismatchDataType(
+                "iterable value".into(),
+                ctx.engine().map_type_name(value.type_name()).into(),
+                Position::NONE,
+            )
+            .into());
+        }
+
+        Ok(LazyIter::new(value))
+    }
+    /// Run the lazy iterator and collect all the elements into an array.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = (1..=10).filter(|v| v % 2 == 0).map(|v| v * v);
+    ///
+    /// print(x.collect());     // prints "[4, 16, 36, 64, 100]"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn collect(ctx: NativeCallContext, iter: LazyIter) -> RhaiResultOf<Array> {
+        iter.collect(&ctx)
+    }
+    /// Convert the lazy iterator into a string.
+    #[rhai_fn(
+        name = "print",
+        name = "to_string",
+        name = "debug",
+        name = "to_debug",
+        pure
+    )]
+    pub fn to_string(_iter: &mut LazyIter) -> String {
+        "<iterator>".into()
+    }
+}
@@ -79,6 +79,26 @@ def_package! {
             #[cfg(not(feature = "only_i64"))]
             gen_conv_functions!(lib => to_decimal(i8, u8, i16, u16, i32, u32, i64, u64).into() -> Decimal);
         }
+
+        // BigInt functions
+        #[cfg(feature = "bigint")]
+        {
+            use crate::BigInt;
+
+            combine_with_exported_module!(lib, "bigint", bigint_functions);
+
+            gen_conv_functions!(lib => to_bigint(BigInt) -> BigInt);
+            gen_conv_functions!(lib => to_bigint(INT).into() -> BigInt);
+
+            #[cfg(not(feature = "only_i32"))]
+            #[cfg(not(feature = "only_i64"))]
+            {
+                gen_conv_functions!(lib => to_bigint(i8, u8, i16, u16, i32, u32, i64, u64).into() -> BigInt);
+
+                #[cfg(not(target_family = "wasm"))]
+                gen_conv_functions!(lib => to_bigint(i128, u128).into() -> BigInt);
+            }
+        }
     }
 }
 
@@ -626,3 +646,115 @@ mod decimal_functions {
         })
     }
 }
+
+#[cfg(feature = "bigint")]
+#[export_module]
+mod bigint_functions {
+    use crate::{BigInt, ImmutableString};
+    use num_traits::{Num, ToPrimitive};
+
+    /// Parse a string into a big integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = parse_bigint("123456789012345678901234567890");
+    ///
+    /// print(x * 10);  // prints 1234567890123456789012345678900
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn parse_bigint(string: &str) -> RhaiResultOf<BigInt> {
+        parse_bigint_radix(string, 10)
+    }
+    /// Parse a string into a big integer of the specified `radix`.
+    ///
+    /// `radix` must be between 2 and 36.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = parse_bigint("ffffffffffffffffffff", 16);
+    ///
+    /// print(x);       // prints 1208925819614629174706175
+    /// ```
+    #[rhai_fn(name = "parse_bigint", return_raw)]
+    pub fn parse_bigint_radix(string: &str, radix: INT) -> RhaiResultOf<BigInt> {
+        if !(2..=36).contains(&radix) {
+            return Err(
+                ERR::ErrorArithmetic(format!("Invalid radix: '{radix}'"), Position::NONE).into(),
+            );
+        }
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        BigInt::from_str_radix(string.trim(), radix as u32).map_err(|err| {
+            ERR::ErrorArithmetic(
+                format!("Error parsing big integer '{string}': {err}"),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+    /// Convert the big integer into a string of the specified `radix`.
+    ///
+    /// `radix` must be between 2 and 36.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = to_bigint(255);
+    ///
+    /// print(x.to_string(16));     // prints "ff"
+    /// ```
+    #[rhai_fn(name = "to_string", return_raw)]
+    pub fn to_string_radix(x: &mut BigInt, radix: INT) -> RhaiResultOf<ImmutableString> {
+        if !(2..=36).contains(&radix) {
+            return Err(
+                ERR::ErrorArithmetic(format!("Invalid radix: '{radix}'"), Position::NONE).into(),
+            );
+        }
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Ok(x.to_str_radix(radix as u32).into())
+    }
+    /// Convert the big integer into an integer.
+    #[rhai_fn(return_raw)]
+    pub fn to_int(x: BigInt) -> RhaiResultOf<INT> {
+        #[cfg(feature = "only_i32")]
+        let n = x.to_i32();
+        #[cfg(not(feature = "only_i32"))]
+        let n = x.to_i64();
+
+        n.ok_or_else(|| {
+            ERR::ErrorArithmetic(format!("Integer overflow: to_int({x})"), Position::NONE).into()
+        })
+    }
+    /// Convert the big integer to floating-point.
+    ///
+    /// Numbers too large for floating-point become infinity.
+    #[cfg(not(feature = "no_float"))]
+    pub fn to_float(x: BigInt) -> FLOAT {
+        #[cfg(not(feature = "f32_float"))]
+        return x.to_f64().unwrap_or(FLOAT::NAN);
+        #[cfg(feature = "f32_float")]
+        return x.to_f32().unwrap_or(FLOAT::NAN);
+    }
+    /// Convert the floating-point number into a big integer, truncating any fractional part.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "to_bigint", return_raw)]
+    pub fn float_to_bigint(x: FLOAT) -> RhaiResultOf<BigInt> {
+        use num_traits::FromPrimitive;
+
+        #[cfg(not(feature = "f32_float"))]
+        let n = BigInt::from_f64(x);
+        #[cfg(feature = "f32_float")]
+        let n = BigInt::from_f32(x);
+
+        n.ok_or_else(|| {
+            ERR::ErrorArithmetic(
+                format!("Cannot convert to BigInt: to_bigint({x})"),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+}
@@ -6,18 +6,26 @@ pub(crate) mod arithmetic;
 pub(crate) mod array_basic;
 pub(crate) mod bit_field;
 pub(crate) mod blob_basic;
+pub(crate) mod csv_basic;
+pub(crate) mod datetime_basic;
 pub(crate) mod debugging;
 pub(crate) mod fn_basic;
 pub(crate) mod iter_basic;
+pub(crate) mod iter_lazy;
 pub(crate) mod lang_core;
 pub(crate) mod logic;
 pub(crate) mod map_basic;
 pub(crate) mod math_basic;
 pub(crate) mod pkg_core;
 pub(crate) mod pkg_std;
+pub(crate) mod random_basic;
+pub(crate) mod regex_basic;
+pub(crate) mod set_basic;
 pub(crate) mod string_basic;
 pub(crate) mod string_more;
 pub(crate) mod time_basic;
+pub(crate) mod toml_basic;
+pub(crate) mod yaml_basic;
 
 pub use arithmetic::ArithmeticPackage;
 #[cfg(not(feature = "no_index"))]
@@ -25,10 +33,17 @@ pub use array_basic::BasicArrayPackage;
 pub use bit_field::BitFieldPackage;
 #[cfg(not(feature = "no_index"))]
 pub use blob_basic::BasicBlobPackage;
+#[cfg(feature = "csv")]
+#[cfg(not(feature = "no_index"))]
+pub use csv_basic::CsvPackage;
+#[cfg(not(feature = "no_time"))]
+pub use datetime_basic::DateTimePackage;
 #[cfg(feature = "debugging")]
 pub use debugging::DebuggingPackage;
 pub use fn_basic::BasicFnPackage;
 pub use iter_basic::BasicIteratorPackage;
+#[cfg(not(feature = "no_index"))]
+pub use iter_lazy::LazyIteratorPackage;
 pub use lang_core::LanguageCorePackage;
 pub use logic::LogicPackage;
 #[cfg(not(feature = "no_object"))]
@@ -36,10 +51,20 @@ pub use map_basic::BasicMapPackage;
 pub use math_basic::BasicMathPackage;
 pub use pkg_core::CorePackage;
 pub use pkg_std::StandardPackage;
+pub use random_basic::RandomPackage;
+#[cfg(feature = "regex")]
+pub use regex_basic::RegexPackage;
+#[cfg(not(feature = "no_index"))]
+pub use set_basic::BasicSetPackage;
 pub use string_basic::BasicStringPackage;
 pub use string_more::MoreStringPackage;
 #[cfg(not(feature = "no_time"))]
 pub use time_basic::BasicTimePackage;
+#[cfg(feature = "toml")]
+#[cfg(not(feature = "no_object"))]
+pub use toml_basic::TomlPackage;
+#[cfg(feature = "yaml")]
+pub use yaml_basic::YamlPackage;
 
 /// Trait that all packages must implement.
 pub trait Package {
@@ -257,3 +282,34 @@ macro_rules! def_package {
         }
     };
 }
+
+/// Create an error for a data text (e.g. TOML, YAML or CSV) that fails to parse in the function
+/// `fn_name`.
+///
+/// The parse error is wrapped inside an [`ErrorInFunctionCall`][crate::EvalAltResult::ErrorInFunctionCall]
+/// so that it can be caught by scripts, and it keeps the 1-based `line` and `column` within the text.
+#[cfg(any(feature = "toml", feature = "yaml", feature = "csv"))]
+#[cold]
+#[inline(never)]
+pub(crate) fn make_data_parse_err(
+    fn_name: &str,
+    msg: impl Into<String>,
+    line: usize,
+    column: usize,
+) -> crate::RhaiError {
+    use crate::{EvalAltResult, LexError, Position};
+
+    #[allow(clippy::cast_possible_truncation)]
+    let pos = if line == 0 {
+        Position::NONE
+    } else {
+        Position::new(
+            line.min(u16::MAX as usize) as u16,
+            column.min(u16::MAX as usize) as u16,
+        )
+    };
+
+    let err = LexError::Runtime(msg.into()).into_err(pos).into();
+
+    EvalAltResult::ErrorInFunctionCall(fn_name.into(), String::new(), err, Position::NONE).into()
+}
@@ -15,9 +15,17 @@ def_package! {
     /// * [`BasicMathPackage`][super::BasicMathPackage]
     /// * [`BasicArrayPackage`][super::BasicArrayPackage]
     /// * [`BasicBlobPackage`][super::BasicBlobPackage]
+    /// * [`BasicSetPackage`][super::BasicSetPackage]
+    /// * [`LazyIteratorPackage`][super::LazyIteratorPackage]
     /// * [`BasicMapPackage`][super::BasicMapPackage]
     /// * [`BasicTimePackage`][super::BasicTimePackage]
+    /// * [`DateTimePackage`][super::DateTimePackage]
     /// * [`MoreStringPackage`][super::MoreStringPackage]
+    /// * [`RandomPackage`][super::RandomPackage]
+    /// * [`RegexPackage`][super::RegexPackage]
+    /// * [`TomlPackage`][super::TomlPackage]
+    /// * [`YamlPackage`][super::YamlPackage]
+    /// * [`CsvPackage`][super::CsvPackage]
     pub StandardPackage(lib) :
             CorePackage,
             BitFieldPackage,
@@ -25,9 +33,17 @@ def_package! {
             BasicMathPackage,
             #[cfg(not(feature = "no_index"))] BasicArrayPackage,
             #[cfg(not(feature = "no_index"))] BasicBlobPackage,
+            #[cfg(not(feature = "no_index"))] BasicSetPackage,
+            #[cfg(not(feature = "no_index"))] LazyIteratorPackage,
             #[cfg(not(feature = "no_object"))] BasicMapPackage,
             #[cfg(not(feature = "no_time"))] BasicTimePackage,
-            MoreStringPackage
+            #[cfg(not(feature = "no_time"))] DateTimePackage,
+            MoreStringPackage,
+            RandomPackage,
+            #[cfg(feature = "regex")] RegexPackage,
+            #[cfg(all(feature = "toml", not(feature = "no_object")))] TomlPackage,
+            #[cfg(feature = "yaml")] YamlPackage,
+            #[cfg(all(feature = "csv", not(feature = "no_index")))] CsvPackage
     {
         lib.set_standard_lib(true);
     }
@@ -0,0 +1,365 @@
+use crate::func::native::locked_write;
+use crate::plugin::*;
+use crate::{def_package, ExclusiveRange, InclusiveRange, Position, RhaiResultOf, ERR, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_float"))]
+use crate::FLOAT;
+
+#[cfg(not(feature = "no_index"))]
+use crate::Array;
+
+def_package! {
+    /// Package of random number utilities.
+    ///
+    /// Random numbers are generated from a per-[`Engine`][crate::Engine] pseudo-random generator
+    /// which can be seeded via [`Engine::set_random_seed`][crate::Engine::set_random_seed] for
+    /// reproducible results.
+    pub RandomPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "random", random_functions);
+    }
+}
+
+/// Seed used when no source of entropy is available.
+const DEFAULT_SEED: u64 = 0x853C_49E6_748F_EA9B;
+
+/// A small, fast pseudo-random number generator (`xoshiro256**`).
+///
+/// This is _not_ cryptographically secure.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Rng {
+    /// Generator state, or [`None`] if not yet seeded.
+    state: Option<[u64; 4]>,
+}
+
+impl Rng {
+    /// Create a new [`Rng`] which is seeded on first use.
+    ///
+    /// Under `no_std`, it is seeded with a fixed seed, otherwise with a random seed.
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { state: None }
+    }
+    /// Create a new [`Rng`] with a specific seed.
+    #[inline]
+    #[must_use]
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            state: Some(Self::expand_seed(seed)),
+        }
+    }
+    /// Expand a seed into the full generator state via `SplitMix64`.
+    #[must_use]
+    fn expand_seed(mut seed: u64) -> [u64; 4] {
+        let mut state = [0; 4];
+
+        for s in &mut state {
+            seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            *s = z ^ (z >> 31);
+        }
+
+        state
+    }
+    /// Get a seed from the environment.
+    #[must_use]
+    fn default_seed() -> u64 {
+        #[cfg(feature = "no_std")]
+        return DEFAULT_SEED;
+
+        #[cfg(not(feature = "no_std"))]
+        {
+            use std::hash::{BuildHasher, Hasher};
+
+            let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+            hasher.write_u64(DEFAULT_SEED);
+            hasher.finish()
+        }
+    }
+    /// Generate the next random [`u64`].
+    #[must_use]
+    pub fn next_u64(&mut self) -> u64 {
+        let s = self
+            .state
+            .get_or_insert_with(|| Self::expand_seed(Self::default_seed()));
+
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        result
+    }
+    /// Generate a random [`u64`] in the range `0..n`, or any [`u64`] if `n` is zero.
+    #[must_use]
+    pub fn next_below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            return self.next_u64();
+        }
+
+        // Lemire's nearly-divisionless method, without bias
+        let threshold = n.wrapping_neg() % n;
+
+        loop {
+            let m = u128::from(self.next_u64()) * u128::from(n);
+
+            if (m as u64) >= threshold {
+                return (m >> 64) as u64;
+            }
+        }
+    }
+    /// Generate a random floating-point number in the range `0.0..1.0`.
+    #[cfg(not(feature = "no_float"))]
+    #[must_use]
+    pub fn next_float(&mut self) -> FLOAT {
+        #[cfg(not(feature = "f32_float"))]
+        return (self.next_u64() >> 11) as FLOAT / (1_u64 << 53) as FLOAT;
+
+        #[cfg(feature = "f32_float")]
+        return (self.next_u64() >> 40) as FLOAT / (1_u64 << 24) as FLOAT;
+    }
+}
+
+impl Default for Rng {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a function with the [`Engine`][crate::Engine]'s random number generator.
+fn with_rng<T>(ctx: &NativeCallContext, f: impl FnOnce(&mut Rng) -> T) -> RhaiResultOf<T> {
+    locked_write(&ctx.engine().rng)
+        .map(|mut rng| f(&mut rng))
+        .ok_or_else(|| {
+            ERR::ErrorRuntime(
+                "random number generator is not available".into(),
+                Position::NONE,
+            )
+            .into()
+        })
+}
+
+/// Generate a random integer between `start` and `end` (inclusive).
+fn rand_between(ctx: &NativeCallContext, start: INT, end: INT) -> RhaiResultOf<INT> {
+    if start > end {
+        return Err(ERR::ErrorArithmetic(
+            format!("Range is empty: {start}..={end}"),
+            Position::NONE,
+        )
+        .into());
+    }
+
+    #[allow(clippy::cast_sign_loss, clippy::unnecessary_cast)]
+    let span = end.wrapping_sub(start) as crate::UNSIGNED_INT as u64;
+
+    #[allow(clippy::cast_possible_truncation)]
+    with_rng(ctx, |rng| {
+        start.wrapping_add(rng.next_below(span.wrapping_add(1)) as INT)
+    })
+}
+
+#[export_module]
+mod random_functions {
+    /// Return a random integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = rand();
+    ///
+    /// print(`I'm feeling lucky: ${x}`);
+    /// ```
+    #[rhai_fn(volatile, return_raw)]
+    pub fn rand(ctx: NativeCallContext) -> RhaiResultOf<INT> {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        with_rng(&ctx, |rng| rng.next_u64() as INT)
+    }
+    /// Return a random integer within an exclusive range.
+    ///
+    /// An error is raised if the range is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let dice = rand(1..7);
+    ///
+    /// print(`You rolled ${dice}`);
+    /// ```
+    #[rhai_fn(name = "rand", volatile, return_raw)]
+    pub fn rand_exclusive_range(
+        ctx: NativeCallContext,
+        range: ExclusiveRange,
+    ) -> RhaiResultOf<INT> {
+        if range.is_empty() {
+            return Err(ERR::ErrorArithmetic(
+                format!("Range is empty: {}..{}", range.start, range.end),
+                Position::NONE,
+            )
+            .into());
+        }
+        rand_between(&ctx, range.start, range.end - 1)
+    }
+    /// Return a random integer within an inclusive range.
+    ///
+    /// An error is raised if the range is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let dice = rand(1..=6);
+    ///
+    /// print(`You rolled ${dice}`);
+    /// ```
+    #[rhai_fn(name = "rand", volatile, return_raw)]
+    pub fn rand_inclusive_range(
+        ctx: NativeCallContext,
+        range: InclusiveRange,
+    ) -> RhaiResultOf<INT> {
+        rand_between(&ctx, *range.start(), *range.end())
+    }
+    /// Return a random floating-point number between `0.0` (inclusive) and `1.0` (exclusive).
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = rand_float();
+    ///
+    /// print(`Probability: ${x}`);
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(volatile, return_raw)]
+    pub fn rand_float(ctx: NativeCallContext) -> RhaiResultOf<FLOAT> {
+        with_rng(&ctx, Rng::next_float)
+    }
+    /// Return a random boolean value.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// if rand_bool() {
+    ///     print("Heads");
+    /// } else {
+    ///     print("Tails");
+    /// }
+    /// ```
+    #[rhai_fn(volatile, return_raw)]
+    pub fn rand_bool(ctx: NativeCallContext) -> RhaiResultOf<bool> {
+        with_rng(&ctx, |rng| rng.next_u64() >> 63 == 1)
+    }
+    /// Return `true` with a probability of `probability`, which must be between `0.0` and `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// if rand_bool(0.1) {
+    ///     print("Rare event!");
+    /// }
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "rand_bool", volatile, return_raw)]
+    pub fn rand_bool_with_probability(
+        ctx: NativeCallContext,
+        probability: FLOAT,
+    ) -> RhaiResultOf<bool> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(ERR::ErrorArithmetic(
+                format!("Invalid probability: {probability}"),
+                Position::NONE,
+            )
+            .into());
+        }
+        with_rng(&ctx, |rng| rng.next_float() < probability)
+    }
+
+    /// Shuffle the elements in the array randomly.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x.shuffle();
+    ///
+    /// print(x);       // prints the numbers 1 to 5 in a random order
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(volatile, return_raw)]
+    pub fn shuffle(ctx: NativeCallContext, array: &mut Array) -> RhaiResultOf<()> {
+        with_rng(&ctx, |rng| {
+            for i in (1..array.len()).rev() {
+                #[allow(clippy::cast_possible_truncation)]
+                let j = rng.next_below(i as u64 + 1) as usize;
+                array.swap(i, j);
+            }
+        })
+    }
+    /// Return a random element from the array, or `()` if the array is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = ["rock", "paper", "scissors"];
+    ///
+    /// print(x.sample());
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(volatile, pure, return_raw)]
+    pub fn sample(ctx: NativeCallContext, array: &mut Array) -> RhaiResultOf<Dynamic> {
+        if array.is_empty() {
+            return Ok(Dynamic::UNIT);
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let index = with_rng(&ctx, |rng| rng.next_below(array.len() as u64) as usize)?;
+
+        Ok(array[index].clone())
+    }
+    /// Return an array of `n` elements picked randomly (without replacement) from the array.
+    ///
+    /// * If `n` ≤ 0, an empty array is returned.
+    /// * If `n` ≥ length of array, all elements are returned in a random order.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x.sample(2));     // prints two distinct elements, e.g. "[4, 1]"
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(name = "sample", volatile, pure, return_raw)]
+    pub fn sample_n(ctx: NativeCallContext, array: &mut Array, n: INT) -> RhaiResultOf<Array> {
+        if n <= 0 || array.is_empty() {
+            return Ok(Array::new());
+        }
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let n = (n as u64).min(array.len() as u64) as usize;
+        let len = array.len();
+
+        // Partial Fisher-Yates shuffle over the indices
+        let mut indices: Vec<_> = (0..len).collect();
+
+        with_rng(&ctx, |rng| {
+            for i in 0..n {
+                #[allow(clippy::cast_possible_truncation)]
+                let j = i + rng.next_below((len - i) as u64) as usize;
+                indices.swap(i, j);
+            }
+        })?;
+
+        Ok(indices[..n].iter().map(|&i| array[i].clone()).collect())
+    }
+}
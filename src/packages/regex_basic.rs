@@ -0,0 +1,447 @@
+#![cfg(feature = "regex")]
+
+use crate::api::default_limits::MAX_REGEX_CACHED;
+use crate::func::native::{locked_read, locked_write};
+use crate::plugin::*;
+use crate::{def_package, Dynamic, ImmutableString, Position, RhaiResultOf, ERR};
+use regex::{Regex, RegexBuilder};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_index"))]
+use crate::{Array, INT, MAX_USIZE_INT};
+
+#[cfg(not(feature = "no_object"))]
+use crate::Map;
+
+/// Maximum size (in bytes) of a compiled regular expression.
+///
+/// This guards against patterns that compile into huge automata, e.g. `\w{1000}{1000}`.
+const MAX_REGEX_SIZE: usize = 1024 * 1024;
+
+def_package! {
+    /// Package of regular expression utilities.
+    ///
+    /// Matching is guaranteed to run in time linear to the length of the input string, so
+    /// malicious patterns cannot hang the host.
+    pub RegexPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "regex", regex_functions);
+    }
+}
+
+/// Compile a regular expression, using the [`Engine`][crate::Engine]'s cache of compiled patterns.
+fn compile(ctx: &NativeCallContext, pattern: &str) -> RhaiResultOf<Regex> {
+    let cache = &ctx.engine().regex_cache;
+
+    if let Some(re) = locked_read(cache).and_then(|c| c.get(pattern).cloned()) {
+        return Ok(re);
+    }
+
+    let re = RegexBuilder::new(pattern)
+        .size_limit(MAX_REGEX_SIZE)
+        .build()
+        .map_err(|err| {
+            ERR::ErrorArithmetic(
+                format!("Error parsing regular expression '{pattern}': {err}"),
+                Position::NONE,
+            )
+        })?;
+
+    if let Some(mut c) = locked_write(cache) {
+        if c.len() >= MAX_REGEX_CACHED {
+            c.clear();
+        }
+        c.insert(pattern.into(), re.clone());
+    }
+
+    Ok(re)
+}
+
+/// Raise an error if processing `count` matches would take the number of operations over the
+/// limit, or if the result would be over the data size limits.
+#[allow(unused_variables)]
+#[inline]
+fn check_limits(
+    ctx: &NativeCallContext,
+    count: usize,
+    sizes: (usize, usize, usize),
+) -> RhaiResultOf<()> {
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let engine = ctx.engine();
+        let max = engine.max_operations();
+
+        if max > 0 && ctx.global_runtime_state().num_operations + count as u64 > max {
+            return Err(ERR::ErrorTooManyOperations(ctx.position()).into());
+        }
+
+        engine.throw_on_size(sizes)?;
+    }
+
+    Ok(())
+}
+
+#[export_module]
+mod regex_functions {
+    /// Compile a regular expression `pattern`.
+    ///
+    /// Compiled patterns are cached, so compiling the same pattern again is cheap.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let re = regex("h(e|a)llo");
+    ///
+    /// print("hallo, world!".matches(re));     // prints true
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn regex(ctx: NativeCallContext, pattern: &str) -> RhaiResultOf<Regex> {
+        compile(&ctx, pattern)
+    }
+    /// Return the pattern of the regular expression.
+    #[rhai_fn(name = "print", name = "to_string", get = "pattern", pure)]
+    pub fn pattern(re: &mut Regex) -> ImmutableString {
+        re.as_str().into()
+    }
+    /// Convert the regular expression into a string for debugging.
+    #[rhai_fn(name = "debug", name = "to_debug", pure)]
+    pub fn to_debug(re: &mut Regex) -> ImmutableString {
+        format!("regex({:?})", re.as_str()).into()
+    }
+
+    /// Return `true` if the regular expression matches anywhere in the string.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "hello, world!";
+    ///
+    /// print(text.matches(regex("w.r")));     // prints true
+    /// ```
+    pub fn matches(string: &str, re: Regex) -> bool {
+        re.is_match(string)
+    }
+    /// Return `true` if the regular expression `pattern` matches anywhere in the string.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "hello, world!";
+    ///
+    /// print(text.matches("w.r"));     // prints true
+    ///
+    /// print(text.matches("^w"));      // prints false
+    /// ```
+    #[rhai_fn(name = "matches", return_raw)]
+    pub fn matches_str(ctx: NativeCallContext, string: &str, pattern: &str) -> RhaiResultOf<bool> {
+        Ok(matches(string, compile(&ctx, pattern)?))
+    }
+
+    /// Find the first match of the regular expression in the string and return the matched text.
+    ///
+    /// If there is no match, `()` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "order #123, #456";
+    ///
+    /// print(text.find(regex("#\\d+")));      // prints "#123"
+    /// ```
+    pub fn find(string: &str, re: Regex) -> Dynamic {
+        re.find(string).map_or(Dynamic::UNIT, |m| m.as_str().into())
+    }
+    /// Find the first match of the regular expression `pattern` in the string and return the
+    /// matched text.
+    ///
+    /// If there is no match, `()` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "order #123, #456";
+    ///
+    /// print(text.find("#\\d+"));     // prints "#123"
+    ///
+    /// print(text.find("x+") == ());  // prints true
+    /// ```
+    #[rhai_fn(name = "find", return_raw)]
+    pub fn find_str(ctx: NativeCallContext, string: &str, pattern: &str) -> RhaiResultOf<Dynamic> {
+        Ok(find(string, compile(&ctx, pattern)?))
+    }
+
+    /// Find all non-overlapping matches of the regular expression in the string and return an
+    /// array of the matched text.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "order #123, #456";
+    ///
+    /// print(text.find_all(regex("#\\d+")));  // prints ["#123", "#456"]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw)]
+    pub fn find_all(ctx: NativeCallContext, string: &str, re: Regex) -> RhaiResultOf<Array> {
+        let mut result = Array::new();
+
+        for m in re.find_iter(string) {
+            check_limits(&ctx, result.len() + 1, (result.len() + 1, 0, 0))?;
+            result.push(m.as_str().into());
+        }
+
+        Ok(result)
+    }
+    /// Find all non-overlapping matches of the regular expression `pattern` in the string and
+    /// return an array of the matched text.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "order #123, #456";
+    ///
+    /// print(text.find_all("#\\d+"));     // prints ["#123", "#456"]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(name = "find_all", return_raw)]
+    pub fn find_all_str(
+        ctx: NativeCallContext,
+        string: &str,
+        pattern: &str,
+    ) -> RhaiResultOf<Array> {
+        let re = compile(&ctx, pattern)?;
+        find_all(ctx, string, re)
+    }
+
+    /// Find the first match of the regular expression in the string and return an object map
+    /// containing the text of all capture groups.
+    ///
+    /// Each group is keyed by its index (with `"0"` being the entire match) and, if it is named,
+    /// also by its name. Groups that did not participate in the match are `()`.
+    ///
+    /// If there is no match, `()` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let re = regex("(?<key>\\w+)=(?<value>\\w+)");
+    ///
+    /// let m = "x: a=42".captures(re);
+    ///
+    /// print(m.key);       // prints "a"
+    /// print(m.value);     // prints "42"
+    /// print(m["0"]);      // prints "a=42"
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    pub fn captures(string: &str, re: Regex) -> Dynamic {
+        let Some(caps) = re.captures(string) else {
+            return Dynamic::UNIT;
+        };
+
+        let mut map = Map::new();
+
+        for (i, name) in re.capture_names().enumerate() {
+            let value = caps.get(i).map_or(Dynamic::UNIT, |m| m.as_str().into());
+
+            if let Some(name) = name {
+                map.insert(name.into(), value.clone());
+            }
+            map.insert(i.to_string().into(), value);
+        }
+
+        map.into()
+    }
+    /// Find the first match of the regular expression `pattern` in the string and return an
+    /// object map containing the text of all capture groups.
+    ///
+    /// Each group is keyed by its index (with `"0"` being the entire match) and, if it is named,
+    /// also by its name. Groups that did not participate in the match are `()`.
+    ///
+    /// If there is no match, `()` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = "2024-02-29".captures("(?<y>\\d+)-(?<m>\\d+)-(?<d>\\d+)");
+    ///
+    /// print(m.y);         // prints "2024"
+    /// print(m["2"]);      // prints "02"
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    #[rhai_fn(name = "captures", return_raw)]
+    pub fn captures_str(
+        ctx: NativeCallContext,
+        string: &str,
+        pattern: &str,
+    ) -> RhaiResultOf<Dynamic> {
+        Ok(captures(string, compile(&ctx, pattern)?))
+    }
+
+    /// Replace all matches of the regular expression in the string with the `replacement` string.
+    ///
+    /// The replacement can refer to capture groups via `$1` or `${name}`. Use `$$` for a literal `$`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "hello, world!";
+    ///
+    /// text.replace_regex(regex("(\\w+), (\\w+)"), "$2, $1");
+    ///
+    /// print(text);        // prints "world, hello!"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn replace_regex(
+        ctx: NativeCallContext,
+        string: &mut ImmutableString,
+        re: Regex,
+        replacement: &str,
+    ) -> RhaiResultOf<()> {
+        let mut buf = String::with_capacity(string.len());
+        let mut last = 0;
+        let mut count = 0;
+
+        for caps in re.captures_iter(string) {
+            let m = caps.get(0).unwrap();
+
+            buf.push_str(&string[last..m.start()]);
+            caps.expand(replacement, &mut buf);
+            last = m.end();
+            count += 1;
+
+            check_limits(&ctx, count, (0, 0, buf.len()))?;
+        }
+
+        if count == 0 {
+            return Ok(());
+        }
+
+        buf.push_str(&string[last..]);
+        check_limits(&ctx, count, (0, 0, buf.len()))?;
+
+        *string = buf.into();
+        Ok(())
+    }
+    /// Replace all matches of the regular expression `pattern` in the string with the `replacement`
+    /// string.
+    ///
+    /// The replacement can refer to capture groups via `$1` or `${name}`. Use `$$` for a literal `$`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "order #123, #456";
+    ///
+    /// text.replace_regex("#(?<n>\\d+)", "No. ${n}");
+    ///
+    /// print(text);        // prints "order No. 123, No. 456"
+    /// ```
+    #[rhai_fn(name = "replace_regex", return_raw)]
+    pub fn replace_regex_str(
+        ctx: NativeCallContext,
+        string: &mut ImmutableString,
+        pattern: &str,
+        replacement: &str,
+    ) -> RhaiResultOf<()> {
+        let re = compile(&ctx, pattern)?;
+        replace_regex(ctx, string, re, replacement)
+    }
+
+    /// Split the string into segments separated by matches of the regular expression, returning
+    /// an array of the segments.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "a, b;c ,d";
+    ///
+    /// print(text.split_regex(regex("\\s*[,;]\\s*")));    // prints ["a", "b", "c", "d"]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw)]
+    pub fn split_regex(ctx: NativeCallContext, string: &str, re: Regex) -> RhaiResultOf<Array> {
+        split_regex_n(ctx, string, re, INT::MAX)
+    }
+    /// Split the string into segments separated by matches of the regular expression `pattern`,
+    /// returning an array of the segments.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "a, b;c ,d";
+    ///
+    /// print(text.split_regex("\\s*[,;]\\s*"));   // prints ["a", "b", "c", "d"]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(name = "split_regex", return_raw)]
+    pub fn split_regex_str(
+        ctx: NativeCallContext,
+        string: &str,
+        pattern: &str,
+    ) -> RhaiResultOf<Array> {
+        let re = compile(&ctx, pattern)?;
+        split_regex_n(ctx, string, re, INT::MAX)
+    }
+    /// Split the string into at most the specified number of `segments` separated by matches of
+    /// the regular expression, returning an array of the segments.
+    ///
+    /// If `segments` < 1, only one segment is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "a, b;c ,d";
+    ///
+    /// print(text.split_regex(regex("\\s*[,;]\\s*"), 2)); // prints ["a", "b;c ,d"]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(name = "split_regex", return_raw)]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub fn split_regex_n(
+        ctx: NativeCallContext,
+        string: &str,
+        re: Regex,
+        segments: INT,
+    ) -> RhaiResultOf<Array> {
+        let segments = segments.clamp(1, MAX_USIZE_INT) as usize;
+        let mut result = Array::new();
+        let mut last = 0;
+
+        for m in re.find_iter(string) {
+            if result.len() + 1 >= segments {
+                break;
+            }
+            check_limits(&ctx, result.len() + 1, (result.len() + 2, 0, 0))?;
+            result.push(string[last..m.start()].into());
+            last = m.end();
+        }
+
+        result.push(string[last..].into());
+        Ok(result)
+    }
+    /// Split the string into at most the specified number of `segments` separated by matches of
+    /// the regular expression `pattern`, returning an array of the segments.
+    ///
+    /// If `segments` < 1, only one segment is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "a, b;c ,d";
+    ///
+    /// print(text.split_regex("\\s*[,;]\\s*", 3));    // prints ["a", "b", "c ,d"]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(name = "split_regex", return_raw)]
+    pub fn split_regex_n_str(
+        ctx: NativeCallContext,
+        string: &str,
+        pattern: &str,
+        segments: INT,
+    ) -> RhaiResultOf<Array> {
+        let re = compile(&ctx, pattern)?;
+        split_regex_n(ctx, string, re, segments)
+    }
+}
@@ -0,0 +1,254 @@
+#![cfg(not(feature = "no_index"))]
+
+use crate::plugin::*;
+use crate::{def_package, Array, Dynamic, ImmutableString, RhaiResultOf, Set, ERR, INT};
+use std::any::TypeId;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+use super::string_basic::{print_with_func, FUNC_TO_DEBUG};
+
+def_package! {
+    /// Package of basic set utilities.
+    pub BasicSetPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "set", set_functions);
+
+        // Register set iterator
+        lib.set_iter(TypeId::of::<Set>(), |value| Box::new(value.cast::<Set>().into_iter()));
+    }
+}
+
+/// Add a value to a set, raising an error if the value cannot be placed in a set.
+fn insert_item(ctx: &NativeCallContext, set: &mut Set, value: Dynamic) -> RhaiResultOf<bool> {
+    set.insert(value).map_err(|err| match *err {
+        ERR::ErrorMismatchDataType(expected, actual, pos) => {
+            ERR::ErrorMismatchDataType(expected, ctx.engine().map_type_name(&actual).into(), pos)
+                .into()
+        }
+        _ => err,
+    })
+}
+
+#[export_module]
+mod set_functions {
+    /// Create an empty set.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set();
+    ///
+    /// s.insert(42);
+    ///
+    /// print(s);       // prints "{42}"
+    /// ```
+    #[rhai_fn(name = "set")]
+    pub const fn new_set() -> Set {
+        Set::new()
+    }
+    /// Create a set with all the unique elements of an array.
+    ///
+    /// Only `()`, booleans, numbers, characters and strings can be placed in a set.
+    /// An error is raised if the array contains any other type of value.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2, 3, 2, 1]);
+    ///
+    /// print(s);       // prints "{1, 2, 3}"
+    /// ```
+    #[rhai_fn(name = "set", name = "to_set", return_raw)]
+    pub fn from_array(ctx: NativeCallContext, array: Array) -> RhaiResultOf<Set> {
+        let mut set = Set::new();
+
+        for value in array {
+            insert_item(&ctx, &mut set, value)?;
+        }
+
+        Ok(set)
+    }
+    /// Convert the set into an array, with elements in order.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set(["b", "a", "c"]);
+    ///
+    /// print(s.to_array());    // prints "["a", "b", "c"]"
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn to_array(set: &mut Set) -> Array {
+        set.iter().cloned().collect()
+    }
+
+    /// Number of elements in the set.
+    #[rhai_fn(name = "len", get = "len", pure)]
+    pub fn len(set: &mut Set) -> INT {
+        set.len() as INT
+    }
+    /// Return true if the set is empty.
+    #[rhai_fn(name = "is_empty", get = "is_empty", pure)]
+    pub fn is_empty(set: &mut Set) -> bool {
+        set.is_empty()
+    }
+    /// Return `true` if the set contains a value.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2, 3]);
+    ///
+    /// // The 'in' operator calls 'contains' in the background
+    /// if 2 in s {
+    ///     print("found!");
+    /// }
+    ///
+    /// print(s.contains(42));      // prints false
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn contains(set: &mut Set, value: Dynamic) -> bool {
+        set.contains(&value)
+    }
+    /// Add a value to the set.
+    ///
+    /// Return `true` if the value was not already in the set.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2]);
+    ///
+    /// print(s.insert(3));     // prints true
+    /// print(s.insert(1));     // prints false
+    ///
+    /// print(s);               // prints "{1, 2, 3}"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn insert(ctx: NativeCallContext, set: &mut Set, value: Dynamic) -> RhaiResultOf<bool> {
+        insert_item(&ctx, set, value)
+    }
+    /// Remove a value from the set.
+    ///
+    /// Return `true` if the value was in the set.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2, 3]);
+    ///
+    /// print(s.remove(2));     // prints true
+    /// print(s.remove(42));    // prints false
+    ///
+    /// print(s);               // prints "{1, 3}"
+    /// ```
+    pub fn remove(set: &mut Set, value: Dynamic) -> bool {
+        set.remove(&value)
+    }
+    /// Remove all elements from the set.
+    pub fn clear(set: &mut Set) {
+        set.clear();
+    }
+
+    /// Return a new set with all the elements in either set.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2]) | set([2, 3]);
+    ///
+    /// print(s);       // prints "{1, 2, 3}"
+    /// ```
+    #[rhai_fn(name = "union", name = "|", pure)]
+    pub fn union(set1: &mut Set, set2: Set) -> Set {
+        set1.union(&set2)
+    }
+    /// Return a new set with the elements in both sets.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2]) & set([2, 3]);
+    ///
+    /// print(s);       // prints "{2}"
+    /// ```
+    #[rhai_fn(name = "intersection", name = "&", pure)]
+    pub fn intersection(set1: &mut Set, set2: Set) -> Set {
+        set1.intersection(&set2)
+    }
+    /// Return a new set with the elements in the first set but not in the second set.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2]) - set([2, 3]);
+    ///
+    /// print(s);       // prints "{1}"
+    /// ```
+    #[rhai_fn(name = "difference", name = "-", pure)]
+    pub fn difference(set1: &mut Set, set2: Set) -> Set {
+        set1.difference(&set2)
+    }
+    /// Return a new set with the elements in either set but not in both.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let s = set([1, 2]) ^ set([2, 3]);
+    ///
+    /// print(s);       // prints "{1, 3}"
+    /// ```
+    #[rhai_fn(name = "symmetric_difference", name = "^", pure)]
+    pub fn symmetric_difference(set1: &mut Set, set2: Set) -> Set {
+        set1.symmetric_difference(&set2)
+    }
+    /// Return `true` if all the elements in the first set are also in the second set.
+    #[rhai_fn(pure)]
+    pub fn is_subset(set1: &mut Set, set2: Set) -> bool {
+        set1.is_subset(&set2)
+    }
+    /// Return `true` if all the elements in the second set are also in the first set.
+    #[rhai_fn(pure)]
+    pub fn is_superset(set1: &mut Set, set2: Set) -> bool {
+        set2.is_subset(set1)
+    }
+    /// Return `true` if the two sets have no elements in common.
+    #[rhai_fn(pure)]
+    pub fn is_disjoint(set1: &mut Set, set2: Set) -> bool {
+        set1.is_disjoint(&set2)
+    }
+    /// Return `true` if the two sets contain the same elements.
+    #[rhai_fn(name = "==", pure)]
+    pub fn equals(set1: &mut Set, set2: Set) -> bool {
+        *set1 == set2
+    }
+    /// Return `true` if the two sets do not contain the same elements.
+    #[rhai_fn(name = "!=", pure)]
+    pub fn not_equals(set1: &mut Set, set2: Set) -> bool {
+        *set1 != set2
+    }
+
+    /// Convert the set into a string.
+    #[rhai_fn(
+        name = "print",
+        name = "to_string",
+        name = "debug",
+        name = "to_debug",
+        pure
+    )]
+    pub fn to_string(ctx: NativeCallContext, set: &mut Set) -> ImmutableString {
+        let mut result = String::from("{");
+
+        for (i, value) in set.iter().enumerate() {
+            if i > 0 {
+                result.push_str(", ");
+            }
+            result.push_str(&print_with_func(FUNC_TO_DEBUG, &ctx, &mut value.clone()));
+        }
+
+        result.push('}');
+        result.into()
+    }
+}
@@ -1,6 +1,10 @@
 use super::iter_basic::CharsStream;
 use crate::plugin::*;
-use crate::{def_package, FnPtr, ImmutableString, SmartString, INT, MAX_USIZE_INT};
+use crate::types::format_spec::{FormatKind, FormatSpec};
+use crate::{
+    def_package, FnPtr, ImmutableString, RhaiError, RhaiResultOf, SmartString, ERR, INT,
+    MAX_USIZE_INT,
+};
 use std::any::TypeId;
 use std::fmt::{Binary, LowerHex, Octal, Write};
 #[cfg(feature = "no_std")]
@@ -30,6 +34,7 @@ def_package! {
         combine_with_exported_module!(lib, "print_debug", print_debug_functions);
         combine_with_exported_module!(lib, "number_formatting", number_formatting);
         combine_with_exported_module!(lib, "char", char_functions);
+        combine_with_exported_module!(lib, "format", format_functions);
 
         // Register characters iterator
         lib.set_iterator::<CharsStream>();
@@ -447,3 +452,290 @@ mod char_functions {
         (ch as u32) as INT
     }
 }
+
+/// Make an error for a malformed format string or specifier.
+fn make_format_err(msg: impl Into<String>) -> RhaiError {
+    ERR::ErrorArithmetic(msg.into(), Position::NONE).into()
+}
+
+/// Format a single value according to a format specifier.
+fn format_value(
+    ctx: &NativeCallContext,
+    spec: &FormatSpec,
+    value: &mut Dynamic,
+) -> RhaiResultOf<String> {
+    #[cfg(not(feature = "unchecked"))]
+    ctx.engine().throw_on_size((
+        0,
+        0,
+        spec.width.unwrap_or(0).max(spec.precision.unwrap_or(0)),
+    ))?;
+
+    let type_err = || {
+        make_format_err(format!(
+            "Format specifier is not valid for {}",
+            ctx.engine().map_type_name(value.type_name())
+        ))
+    };
+
+    if let Ok(n) = value.as_int() {
+        let (digits, prefix) = match spec.kind {
+            FormatKind::LowerHex => (format!("{n:x}"), "0x"),
+            FormatKind::UpperHex => (format!("{n:X}"), "0x"),
+            FormatKind::Octal => (format!("{n:o}"), "0o"),
+            FormatKind::Binary => (format!("{n:b}"), "0b"),
+            FormatKind::LowerExp => match spec.precision {
+                Some(p) => (format!("{:.*e}", p, n.unsigned_abs()), ""),
+                None => (format!("{:e}", n.unsigned_abs()), ""),
+            },
+            FormatKind::UpperExp => match spec.precision {
+                Some(p) => (format!("{:.*E}", p, n.unsigned_abs()), ""),
+                None => (format!("{:E}", n.unsigned_abs()), ""),
+            },
+            #[cfg(not(feature = "no_float"))]
+            FormatKind::Display | FormatKind::Debug if spec.precision.is_some() => {
+                let p = spec.precision.unwrap();
+                (format!("{:.*}", p, (n as crate::FLOAT).abs()), "")
+            }
+            FormatKind::Display | FormatKind::Debug => (n.unsigned_abs().to_string(), ""),
+        };
+        let prefix = if spec.alternate { prefix } else { "" };
+        return Ok(spec.pad(&digits, Some((n < 0 && !spec.is_radix(), prefix))));
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    if let Ok(f) = value.as_float() {
+        let digits = match (spec.kind, spec.precision) {
+            _ if spec.is_radix() => return Err(type_err()),
+            (FormatKind::LowerExp, Some(p)) => format!("{:.*e}", p, f.abs()),
+            (FormatKind::LowerExp, None) => format!("{:e}", f.abs()),
+            (FormatKind::UpperExp, Some(p)) => format!("{:.*E}", p, f.abs()),
+            (FormatKind::UpperExp, None) => format!("{:E}", f.abs()),
+            (_, Some(p)) => format!("{:.*}", p, f.abs()),
+            (_, None) => crate::types::FloatWrapper::new(f.abs()).to_string(),
+        };
+        return Ok(spec.pad(&digits, Some((f.is_sign_negative(), ""))));
+    }
+
+    #[cfg(feature = "decimal")]
+    if let Ok(d) = value.as_decimal() {
+        let digits = match (spec.kind, spec.precision) {
+            _ if spec.is_radix() => return Err(type_err()),
+            (FormatKind::LowerExp, Some(p)) => format!("{:.*e}", p, d.abs()),
+            (FormatKind::LowerExp, None) => format!("{:e}", d.abs()),
+            (FormatKind::UpperExp, Some(p)) => format!("{:.*E}", p, d.abs()),
+            (FormatKind::UpperExp, None) => format!("{:E}", d.abs()),
+            (_, Some(p)) => format!("{:.*}", p, d.abs()),
+            (_, None) => d.abs().to_string(),
+        };
+        return Ok(spec.pad(&digits, Some((d.is_sign_negative(), ""))));
+    }
+
+    let text = match spec.kind {
+        FormatKind::Display => print_with_func(FUNC_TO_STRING, ctx, value),
+        FormatKind::Debug => print_with_func(FUNC_TO_DEBUG, ctx, value),
+        _ => return Err(type_err()),
+    };
+
+    Ok(match spec.precision {
+        Some(p) => spec.pad(&text.chars().take(p).collect::<String>(), None),
+        None => spec.pad(&text, None),
+    })
+}
+
+/// Format a list of values according to a template.
+fn format_template(
+    ctx: &NativeCallContext,
+    template: &str,
+    args: &mut [Dynamic],
+) -> RhaiResultOf<ImmutableString> {
+    let mut result = String::with_capacity(template.len());
+    let mut chars = template.chars();
+    let mut next_index = 0;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                result.push('{');
+            }
+            '{' => {
+                let rest = chars.as_str();
+                let Some(end) = rest.find('}') else {
+                    return Err(make_format_err(format!(
+                        "Error parsing format string '{template}': unterminated '{{'"
+                    )));
+                };
+                let field = &rest[..end];
+                chars = rest[end + 1..].chars();
+
+                let (index, spec) = field.split_once(':').unwrap_or((field, ""));
+
+                let index = match index.trim() {
+                    "" => {
+                        next_index += 1;
+                        next_index - 1
+                    }
+                    s => s.parse::<usize>().map_err(|_| {
+                        make_format_err(format!(
+                            "Error parsing format string '{template}': invalid argument index '{s}'"
+                        ))
+                    })?,
+                };
+                let spec = FormatSpec::parse(spec).ok_or_else(|| {
+                    make_format_err(format!(
+                        "Error parsing format string '{template}': invalid format specifier '{spec}'"
+                    ))
+                })?;
+                let value = args.get_mut(index).ok_or_else(|| {
+                    make_format_err(format!(
+                        "Error parsing format string '{template}': argument index {index} out of bounds"
+                    ))
+                })?;
+
+                result.push_str(&format_value(ctx, &spec, value)?);
+
+                #[cfg(not(feature = "unchecked"))]
+                ctx.engine().throw_on_size((0, 0, result.len()))?;
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                result.push('}');
+            }
+            '}' => {
+                return Err(make_format_err(format!(
+                    "Error parsing format string '{template}': unmatched '}}'"
+                )))
+            }
+            _ => result.push(ch),
+        }
+    }
+
+    Ok(result.into())
+}
+
+#[export_module]
+mod format_functions {
+    /// Format a string template.
+    ///
+    /// Each `{}` in the template is replaced by the next value, and `{N}` by the `N`-th value
+    /// (starting from zero).  A format specifier can be added after a colon (e.g. `{:>8.2}`)
+    /// with the syntax `[[fill]align][sign]['#']['0'][width]['.' precision][type]`, where `type`
+    /// is one of `?` (debug), `x`, `X`, `o`, `b` (hex, octal or binary) and `e` or `E` (exponent).
+    ///
+    /// Use `{{` and `}}` for literal braces.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = format("{:>8.3}|{:<5}|{:#x}", 3.14159, "hi", 255);
+    ///
+    /// print(x);       // prints "   3.142|hi   |0xff"
+    /// ```
+    #[rhai_fn(name = "format", return_raw)]
+    pub fn format_0(ctx: NativeCallContext, template: &str) -> RhaiResultOf<ImmutableString> {
+        format_template(&ctx, template, &mut [])
+    }
+    /// Format a string template with one value.
+    ///
+    /// See `format` for the template syntax.
+    #[rhai_fn(name = "format", return_raw)]
+    pub fn format_1(
+        ctx: NativeCallContext,
+        template: &str,
+        a: Dynamic,
+    ) -> RhaiResultOf<ImmutableString> {
+        format_template(&ctx, template, &mut [a])
+    }
+    /// Format a string template with two values.
+    ///
+    /// See `format` for the template syntax.
+    #[rhai_fn(name = "format", return_raw)]
+    pub fn format_2(
+        ctx: NativeCallContext,
+        template: &str,
+        a: Dynamic,
+        b: Dynamic,
+    ) -> RhaiResultOf<ImmutableString> {
+        format_template(&ctx, template, &mut [a, b])
+    }
+    /// Format a string template with three values.
+    ///
+    /// See `format` for the template syntax.
+    #[rhai_fn(name = "format", return_raw)]
+    pub fn format_3(
+        ctx: NativeCallContext,
+        template: &str,
+        a: Dynamic,
+        b: Dynamic,
+        c: Dynamic,
+    ) -> RhaiResultOf<ImmutableString> {
+        format_template(&ctx, template, &mut [a, b, c])
+    }
+    /// Format a string template with four values.
+    ///
+    /// See `format` for the template syntax.
+    #[rhai_fn(name = "format", return_raw)]
+    pub fn format_4(
+        ctx: NativeCallContext,
+        template: &str,
+        a: Dynamic,
+        b: Dynamic,
+        c: Dynamic,
+        d: Dynamic,
+    ) -> RhaiResultOf<ImmutableString> {
+        format_template(&ctx, template, &mut [a, b, c, d])
+    }
+    /// Format a string template with five values.
+    ///
+    /// See `format` for the template syntax.
+    #[rhai_fn(name = "format", return_raw)]
+    pub fn format_5(
+        ctx: NativeCallContext,
+        template: &str,
+        a: Dynamic,
+        b: Dynamic,
+        c: Dynamic,
+        d: Dynamic,
+        e: Dynamic,
+    ) -> RhaiResultOf<ImmutableString> {
+        format_template(&ctx, template, &mut [a, b, c, d, e])
+    }
+    /// Format a string template with six values.
+    ///
+    /// See `format` for the template syntax.
+    #[rhai_fn(name = "format", return_raw)]
+    pub fn format_6(
+        ctx: NativeCallContext,
+        template: &str,
+        a: Dynamic,
+        b: Dynamic,
+        c: Dynamic,
+        d: Dynamic,
+        e: Dynamic,
+        f: Dynamic,
+    ) -> RhaiResultOf<ImmutableString> {
+        format_template(&ctx, template, &mut [a, b, c, d, e, f])
+    }
+    /// Format a string template with the values in an array.
+    ///
+    /// See `format` for the template syntax.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = format_array("{1}-{0}", [1, 2]);
+    ///
+    /// print(x);       // prints "2-1"
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw)]
+    pub fn format_array(
+        ctx: NativeCallContext,
+        template: &str,
+        values: Array,
+    ) -> RhaiResultOf<ImmutableString> {
+        let mut values = values;
+        format_template(&ctx, template, &mut values)
+    }
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "toml")]
+#![cfg(not(feature = "no_object"))]
+
+use super::make_data_parse_err;
+use crate::plugin::*;
+use crate::{def_package, Dynamic, Map, Position, RhaiResultOf, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_index"))]
+use crate::Array;
+
+/// Name of the special field that the [`toml`] crate deserializes date/time values into.
+const TOML_DATETIME_FIELD: &str = "$__toml_private_datetime";
+
+def_package! {
+    /// Package of TOML utilities.
+    pub TomlPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "toml", toml_functions);
+    }
+}
+
+/// Get the 1-based line and column numbers of a byte `offset` within a text.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = text.get(..offset).unwrap_or(text);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |s| s.chars().count()) + 1;
+
+    (line, column)
+}
+
+/// Replace all date/time values, which are deserialized into special object maps, with strings.
+fn convert_datetimes(value: &mut Dynamic) {
+    #[cfg(not(feature = "no_index"))]
+    if let Some(mut array) = value.write_lock::<Array>() {
+        array.iter_mut().for_each(convert_datetimes);
+        return;
+    }
+
+    let datetime = match value.write_lock::<Map>() {
+        Some(map) if map.len() == 1 && map.contains_key(TOML_DATETIME_FIELD) => {
+            map[TOML_DATETIME_FIELD].clone()
+        }
+        Some(mut map) => {
+            map.values_mut().for_each(convert_datetimes);
+            return;
+        }
+        None => return,
+    };
+
+    *value = datetime;
+}
+
+#[export_module]
+mod toml_functions {
+    /// Parse a TOML text into an object map.
+    ///
+    /// Date/time values are returned as strings.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = parse_toml(`
+    ///     name = "hello"
+    ///
+    ///     [server]
+    ///     port = 8080
+    /// `);
+    ///
+    /// print(m.server.port);       // prints 8080
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn parse_toml(text: &str) -> RhaiResultOf<Map> {
+        let mut map: Map = toml::from_str(text).map_err(|err| {
+            let (line, column) = err
+                .span()
+                .map_or((0, 0), |span| line_column(text, span.start));
+            make_data_parse_err(
+                "parse_toml",
+                format!("Invalid TOML: {}", err.message()),
+                line,
+                column,
+            )
+        })?;
+
+        map.values_mut().for_each(convert_datetimes);
+
+        Ok(map)
+    }
+    /// Return the TOML representation of the object map.
+    ///
+    /// An error is raised if the object map contains values that cannot be represented in TOML,
+    /// such as `()`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = #{name: "hello", server: #{port: 8080}};
+    ///
+    /// print(m.to_toml());
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn to_toml(map: &mut Map) -> RhaiResultOf<String> {
+        toml::to_string(map).map_err(|err| {
+            ERR::ErrorRuntime(
+                format!("Cannot convert to TOML: {err}").into(),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+}
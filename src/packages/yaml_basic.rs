@@ -0,0 +1,64 @@
+#![cfg(feature = "yaml")]
+
+use super::make_data_parse_err;
+use crate::plugin::*;
+use crate::{def_package, Dynamic, Position, RhaiResultOf, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+def_package! {
+    /// Package of YAML utilities.
+    pub YamlPackage(lib) {
+        lib.set_standard_lib(true);
+
+        combine_with_exported_module!(lib, "yaml", yaml_functions);
+    }
+}
+
+#[export_module]
+mod yaml_functions {
+    /// Parse a YAML text into a value.
+    ///
+    /// Mappings are returned as object maps and sequences as arrays.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = parse_yaml(`
+    /// name: hello
+    /// ports:
+    ///   - 80
+    ///   - 443
+    /// `);
+    ///
+    /// print(m.ports[1]);          // prints 443
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn parse_yaml(text: &str) -> RhaiResultOf<Dynamic> {
+        serde_yaml::from_str(text).map_err(|err| {
+            let (line, column) = err
+                .location()
+                .map_or((0, 0), |loc| (loc.line(), loc.column()));
+            make_data_parse_err("parse_yaml", format!("Invalid YAML: {err}"), line, column)
+        })
+    }
+    /// Return the YAML representation of a value.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = #{name: "hello", ports: [80, 443]};
+    ///
+    /// print(m.to_yaml());
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn to_yaml(value: Dynamic) -> RhaiResultOf<String> {
+        serde_yaml::to_string(&value).map_err(|err| {
+            ERR::ErrorRuntime(
+                format!("Cannot convert to YAML: {err}").into(),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+}
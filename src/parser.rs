@@ -3,7 +3,7 @@
 use crate::api::options::LangOptions;
 use crate::ast::{
     ASTFlags, BinaryExpr, CaseBlocksList, Expr, FlowControl, FnCallExpr, FnCallHashes, Ident,
-    OpAssignment, RangeCase, ScriptFuncDef, Stmt, StmtBlock, StmtBlockContainer,
+    MatchArm, OpAssignment, Pattern, RangeCase, ScriptFuncDef, Stmt, StmtBlock, StmtBlockContainer,
     SwitchCasesCollection,
 };
 use crate::engine::{Precedence, OP_CONTAINS, OP_NOT};
@@ -14,6 +14,7 @@ use crate::tokenizer::{
     TokenizerControl,
 };
 use crate::types::dynamic::{AccessMode, Union};
+use crate::types::format_spec::FormatSpec;
 use crate::{
     calc_fn_hash, Dynamic, Engine, EvalAltResult, EvalContext, ExclusiveRange, FnArgsVec,
     ImmutableString, InclusiveRange, LexError, ParseError, Position, Scope, Shared, SmartString,
@@ -212,6 +213,8 @@ bitflags! {
         const DISALLOW_STATEMENTS_IN_BLOCKS = 0b0001_0000;
         /// Disallow unquoted map properties?
         const DISALLOW_UNQUOTED_MAP_PROPERTIES = 0b0010_0000;
+        /// Is the block being parsed an interpolated expression inside a string literal?
+        const INTERPOLATED_BLOCK = 0b0100_0000;
     }
 }
 
@@ -442,6 +445,36 @@ fn parse_var_name(input: &mut TokenStream) -> ParseResult<(SmartString, Position
     }
 }
 
+/// Is this [token][Token] the start of a destructuring pattern?
+#[inline]
+#[must_use]
+const fn is_destructuring_start(token: &Token) -> bool {
+    match token {
+        #[cfg(not(feature = "no_index"))]
+        Token::LeftBracket => true,
+        #[cfg(not(feature = "no_object"))]
+        Token::MapStart => true,
+        _ => false,
+    }
+}
+
+/// Make the name of a hidden variable holding a value to be destructured.
+///
+/// The name is not a valid identifier, so it never clashes with variables in the script.
+#[inline]
+#[must_use]
+fn make_destructured_var(index: usize) -> SmartString {
+    format!("${index}").into()
+}
+
+/// Prepend statements to a statement, turning it into a block.
+#[must_use]
+fn prepend_stmts(stmts: impl IntoIterator<Item = Stmt>, stmt: Stmt) -> Stmt {
+    let block = StmtBlock::from(stmt);
+    let span = block.span();
+    Stmt::Block(StmtBlock::new(stmts.into_iter().chain(block), span.start(), span.end()).into())
+}
+
 /// Optimize the structure of a chained expression where the root expression is another chained expression.
 ///
 /// # Panics
@@ -1120,6 +1153,7 @@ impl Engine {
 
         let mut expressions = FnArgsVec::<BinaryExpr>::new();
         let mut cases = StraightHashMap::<CaseBlocksList>::default();
+        let mut values = StaticVec::<Dynamic>::new();
         let mut ranges = StaticVec::<RangeCase>::new();
         let mut def_case = None;
         let mut def_case_pos = Position::NONE;
@@ -1265,7 +1299,10 @@ impl Engine {
 
                     cases
                         .entry(hash)
-                        .or_insert(CaseBlocksList::new_const())
+                        .or_insert_with(|| {
+                            values.push(value);
+                            CaseBlocksList::new_const()
+                        })
                         .push(index);
                 }
             }
@@ -1295,11 +1332,13 @@ impl Engine {
 
         expressions.shrink_to_fit();
         cases.shrink_to_fit();
+        values.shrink_to_fit();
         ranges.shrink_to_fit();
 
         let cases = SwitchCasesCollection {
             expressions,
             cases,
+            values,
             ranges,
             def_case,
         };
@@ -1307,6 +1346,485 @@ impl Engine {
         Ok(Stmt::Switch((item, cases).into(), settings.pos))
     }
 
+    /// Parse a match expression.
+    fn parse_match(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
+        // match ...
+        let settings = settings.level_up_with_position(eat_token(state.input, &Token::Match))?;
+
+        let item = self.parse_expr(state, settings)?;
+
+        match state.input.next().unwrap() {
+            (Token::LeftBrace, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(PERR::MissingToken(
+                    Token::LeftBrace.into(),
+                    "to start a match block".into(),
+                )
+                .into_err(pos))
+            }
+        }
+
+        let mut arms = StaticVec::<MatchArm>::new();
+
+        loop {
+            const MISSING_RBRACE: &str = "to end this match block";
+
+            match state.input.peek().unwrap() {
+                (Token::RightBrace, ..) => {
+                    eat_token(state.input, &Token::RightBrace);
+                    break;
+                }
+                (Token::EOF, pos) => {
+                    return Err(
+                        PERR::MissingToken(Token::RightBrace.into(), MISSING_RBRACE.into())
+                            .into_err(*pos),
+                    )
+                }
+                _ => (),
+            }
+
+            let mut vars = StaticVec::new_const();
+            let pattern = self.parse_pattern(state, settings.level_up()?, &mut vars)?;
+
+            // Variables bound by the pattern are visible in the condition and the expression
+            let prev_stack_len = state.stack.len();
+            for var in &vars {
+                state.stack.push(var.name.clone(), ());
+            }
+
+            let condition = if match_token(state.input, &Token::If).0 {
+                ensure_not_statement_expr(state.input, "a boolean")?;
+                let guard = self.parse_expr(state, settings)?.ensure_bool_expr()?;
+                ensure_not_assignment(state.input)?;
+                guard
+            } else {
+                Expr::BoolConstant(true, Position::NONE)
+            };
+
+            match state.input.next().unwrap() {
+                (Token::DoubleArrow, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::DoubleArrow.into(),
+                        "in this match arm".into(),
+                    )
+                    .into_err(pos))
+                }
+            };
+
+            let (expr, need_comma) =
+                if settings.has_flag(ParseSettingFlags::DISALLOW_STATEMENTS_IN_BLOCKS) {
+                    (self.parse_expr(state, settings)?, true)
+                } else {
+                    let stmt = self.parse_stmt(state, settings)?;
+                    let need_comma = !stmt.is_self_terminated();
+
+                    let stmt_block: StmtBlock = stmt.into();
+                    (Expr::Stmt(stmt_block.into()), need_comma)
+                };
+
+            state.stack.rewind(prev_stack_len);
+
+            arms.push(MatchArm {
+                pattern,
+                vars,
+                condition,
+                expr,
+            });
+
+            match state.input.peek().unwrap() {
+                (Token::Comma, ..) => {
+                    eat_token(state.input, &Token::Comma);
+                }
+                (Token::RightBrace, ..) => (),
+                (Token::EOF, pos) => {
+                    return Err(
+                        PERR::MissingToken(Token::RightBrace.into(), MISSING_RBRACE.into())
+                            .into_err(*pos),
+                    )
+                }
+                (Token::LexError(err), pos) => return Err(err.clone().into_err(*pos)),
+                (.., pos) if need_comma => {
+                    return Err(PERR::MissingToken(
+                        Token::Comma.into(),
+                        "to separate the arms of this match block".into(),
+                    )
+                    .into_err(*pos))
+                }
+                _ => (),
+            }
+        }
+
+        arms.shrink_to_fit();
+
+        Ok(Stmt::Match((item, arms).into(), settings.pos))
+    }
+
+    /// Parse a pattern, which may consist of alternatives.
+    ///
+    /// Variables bound by the pattern are added to `vars`.
+    fn parse_pattern(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        vars: &mut StaticVec<Ident>,
+    ) -> ParseResult<Pattern> {
+        let start = vars.len();
+        let pattern = self.parse_single_pattern(state, settings, vars)?;
+
+        if state.input.peek().unwrap().0 != Token::Pipe {
+            return Ok(pattern);
+        }
+
+        let mut alternatives = StaticVec::new_const();
+        alternatives.push(pattern);
+
+        while match_token(state.input, &Token::Pipe).0 {
+            let mut alt_vars = vars[..start].iter().cloned().collect::<StaticVec<_>>();
+            let mut alt = self.parse_single_pattern(state, settings, &mut alt_vars)?;
+
+            // All alternatives must bind the same variables
+            let bound = &vars[start..];
+            let alt_bound = &alt_vars[start..];
+
+            if let Some(var) = bound
+                .iter()
+                .find(|v| alt_bound.iter().all(|a| a.name != v.name))
+                .or_else(|| {
+                    alt_bound
+                        .iter()
+                        .find(|a| bound.iter().all(|v| v.name != a.name))
+                })
+            {
+                return Err(PERR::MalformedPattern(format!(
+                    "Variable '{}' must be bound in all alternatives of this pattern",
+                    var.name
+                ))
+                .into_err(alt.position()));
+            }
+
+            alt.remap_vars(&|index| {
+                if index < start {
+                    index
+                } else {
+                    let name = &alt_vars[index].name;
+                    start + bound.iter().position(|v| v.name == *name).unwrap()
+                }
+            });
+
+            alternatives.push(alt);
+        }
+
+        Ok(Pattern::Or(alternatives.into()))
+    }
+
+    /// Parse a single pattern (i.e. not alternatives), with an optional type.
+    fn parse_single_pattern(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        vars: &mut StaticVec<Ident>,
+    ) -> ParseResult<Pattern> {
+        let pattern = match state.input.peek().unwrap() {
+            // _
+            (Token::Underscore, ..) => {
+                Pattern::Wildcard(eat_token(state.input, &Token::Underscore))
+            }
+            // var
+            (Token::Identifier(..), ..) => {
+                let (name, pos) = parse_var_name(state.input)?;
+                let index = self.bind_pattern_var(vars, name, pos)?;
+                Pattern::Bind(vars[index].clone(), index)
+            }
+            // [ ...
+            #[cfg(not(feature = "no_index"))]
+            (Token::LeftBracket, ..) => {
+                return self.parse_array_pattern(state, settings.level_up()?, vars)
+            }
+            // #{ ...
+            #[cfg(not(feature = "no_object"))]
+            (Token::MapStart, ..) => {
+                return self.parse_map_pattern(state, settings.level_up()?, vars)
+            }
+            // literal
+            _ => {
+                let filter = state.expr_filter;
+                state.expr_filter = |t| t != &Token::Pipe;
+                let expr = self.parse_expr(state, settings);
+                state.expr_filter = filter;
+
+                let expr = expr?;
+                let pos = expr.start_position();
+                let value = expr.get_literal_value().ok_or_else(|| {
+                    PERR::ExprExpected("a literal or a pattern".into()).into_err(pos)
+                })?;
+
+                return Ok(Pattern::Value(value.into(), pos));
+            }
+        };
+
+        if !match_token(state.input, &Token::Colon).0 {
+            return Ok(pattern);
+        }
+
+        // pattern : type
+        match state.input.next().unwrap() {
+            (Token::Identifier(s), pos) => {
+                let typ = Ident {
+                    name: self.get_interned_string(*s),
+                    pos,
+                };
+                Ok(Pattern::Typed((pattern, typ).into()))
+            }
+            (Token::LexError(err), pos) => Err(err.into_err(pos)),
+            (.., pos) => Err(PERR::MalformedPattern("Expecting a type name".into()).into_err(pos)),
+        }
+    }
+
+    /// Parse an array pattern.
+    #[cfg(not(feature = "no_index"))]
+    fn parse_array_pattern(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        vars: &mut StaticVec<Ident>,
+    ) -> ParseResult<Pattern> {
+        // [ ...
+        let pos = eat_token(state.input, &Token::LeftBracket);
+
+        let mut items = StaticVec::new_const();
+        let mut has_rest = false;
+
+        loop {
+            const MISSING_RBRACKET: &str = "to end this array pattern";
+
+            match state.input.peek().unwrap() {
+                (Token::RightBracket, ..) => {
+                    eat_token(state.input, &Token::RightBracket);
+                    break;
+                }
+                (Token::EOF, pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::RightBracket.into(),
+                        MISSING_RBRACKET.into(),
+                    )
+                    .into_err(*pos))
+                }
+                // .. var
+                (Token::ExclusiveRange, ..) => {
+                    let pos = eat_token(state.input, &Token::ExclusiveRange);
+
+                    if has_rest {
+                        return Err(PERR::MalformedPattern(
+                            "An array pattern can only contain one '..'".into(),
+                        )
+                        .into_err(pos));
+                    }
+                    has_rest = true;
+
+                    let var = if matches!(state.input.peek().unwrap().0, Token::Identifier(..)) {
+                        let (name, var_pos) = parse_var_name(state.input)?;
+                        let index = self.bind_pattern_var(vars, name, var_pos)?;
+                        Some((vars[index].clone(), index))
+                    } else {
+                        None
+                    };
+
+                    items.push(Pattern::Rest(var, pos));
+                }
+                _ => items.push(self.parse_pattern(state, settings.level_up()?, vars)?),
+            }
+
+            match state.input.peek().unwrap() {
+                (Token::Comma, ..) => {
+                    eat_token(state.input, &Token::Comma);
+                }
+                (Token::RightBracket, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.clone().into_err(*pos)),
+                (.., pos) => {
+                    return Err(PERR::MissingToken(
+                        Token::Comma.into(),
+                        "to separate the items of this array pattern".into(),
+                    )
+                    .into_err(*pos))
+                }
+            }
+        }
+
+        items.shrink_to_fit();
+
+        Ok(Pattern::Array(items.into(), pos))
+    }
+
+    /// Parse an object map pattern.
+    #[cfg(not(feature = "no_object"))]
+    fn parse_map_pattern(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        vars: &mut StaticVec<Ident>,
+    ) -> ParseResult<Pattern> {
+        // #{ ...
+        let pos = eat_token(state.input, &Token::MapStart);
+
+        let mut items = StaticVec::<(Ident, Pattern)>::new_const();
+
+        loop {
+            const MISSING_RBRACE: &str = "to end this object map pattern";
+
+            match state.input.peek().unwrap() {
+                (Token::RightBrace, ..) => {
+                    eat_token(state.input, &Token::RightBrace);
+                    break;
+                }
+                (Token::EOF, pos) => {
+                    return Err(
+                        PERR::MissingToken(Token::RightBrace.into(), MISSING_RBRACE.into())
+                            .into_err(*pos),
+                    )
+                }
+                _ => (),
+            }
+
+            let (name, name_pos, is_identifier) = match state.input.next().unwrap() {
+                (Token::Identifier(s), pos) => (*s, pos, true),
+                (Token::StringConstant(s), pos) => (*s, pos, false),
+                (Token::Reserved(s), pos) if is_valid_identifier(&s) => {
+                    return Err(PERR::Reserved(s.to_string()).into_err(pos));
+                }
+                (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                (.., pos) => return Err(PERR::PropertyExpected.into_err(pos)),
+            };
+
+            if items.iter().any(|(p, ..)| p.as_str() == name.as_str()) {
+                return Err(PERR::DuplicatedProperty(name.to_string()).into_err(name_pos));
+            }
+
+            let pattern = if match_token(state.input, &Token::Colon).0 {
+                self.parse_pattern(state, settings.level_up()?, vars)?
+            } else if is_identifier {
+                // #{ name } is short for #{ name: name }
+                let index = self.bind_pattern_var(vars, name.clone(), name_pos)?;
+                Pattern::Bind(vars[index].clone(), index)
+            } else {
+                return Err(PERR::MissingToken(
+                    Token::Colon.into(),
+                    format!("to follow the property '{name}' in this object map pattern"),
+                )
+                .into_err(state.input.peek().unwrap().1));
+            };
+
+            let name = self.get_interned_string(name);
+            items.push((
+                Ident {
+                    name,
+                    pos: name_pos,
+                },
+                pattern,
+            ));
+
+            match state.input.peek().unwrap() {
+                (Token::Comma, ..) => {
+                    eat_token(state.input, &Token::Comma);
+                }
+                (Token::RightBrace, ..) => (),
+                (Token::LexError(err), pos) => return Err(err.clone().into_err(*pos)),
+                (.., pos) => {
+                    return Err(
+                        PERR::MissingToken(Token::RightBrace.into(), MISSING_RBRACE.into())
+                            .into_err(*pos),
+                    )
+                }
+            }
+        }
+
+        items.shrink_to_fit();
+
+        Ok(Pattern::Map(items.into(), pos))
+    }
+
+    /// Add a variable bound by a pattern to `vars`, returning its index.
+    fn bind_pattern_var(
+        &self,
+        vars: &mut StaticVec<Ident>,
+        name: SmartString,
+        pos: Position,
+    ) -> ParseResult<usize> {
+        if vars.iter().any(|v| v.as_str() == name.as_str()) {
+            return Err(PERR::DuplicatedVariable(name.into()).into_err(pos));
+        }
+
+        vars.push(Ident {
+            name: self.get_interned_string(name),
+            pos,
+        });
+
+        Ok(vars.len() - 1)
+    }
+
+    /// Parse a destructuring pattern, which may only contain variables, `_`, types, arrays and
+    /// object maps.
+    ///
+    /// Variables bound by the pattern are added to `vars`.
+    fn parse_destructuring_pattern(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        vars: &mut StaticVec<Ident>,
+    ) -> ParseResult<Pattern> {
+        fn check(pattern: &Pattern) -> ParseResult<()> {
+            match pattern {
+                Pattern::Value(..) | Pattern::Or(..) => Err(PERR::MalformedPattern(
+                    "A destructuring pattern cannot contain literals or alternatives".into(),
+                )
+                .into_err(pattern.position())),
+                Pattern::Typed(x) => check(&x.0),
+                #[cfg(not(feature = "no_index"))]
+                Pattern::Array(x, ..) => x.iter().try_for_each(check),
+                #[cfg(not(feature = "no_object"))]
+                Pattern::Map(x, ..) => x.iter().try_for_each(|(_, p)| check(p)),
+                _ => Ok(()),
+            }
+        }
+
+        let pattern = self.parse_single_pattern(state, settings, vars)?;
+        check(&pattern)?;
+        Ok(pattern)
+    }
+
+    /// Make a statement that destructures the value of a hidden variable, and push the variables
+    /// bound by the pattern onto the stack.
+    fn make_destructure_stmt(
+        &self,
+        state: &mut ParseState,
+        name: ImmutableString,
+        pattern: Pattern,
+        vars: StaticVec<Ident>,
+        pos: Position,
+    ) -> Stmt {
+        let index = NonZeroUsize::new(state.find_var(&name).0);
+        let short_index = index
+            .and_then(|x| u8::try_from(x.get()).ok())
+            .and_then(NonZeroU8::new);
+
+        let expr = Expr::Variable(
+            #[cfg(not(feature = "no_module"))]
+            (index, name, crate::ast::Namespace::NONE, 0).into(),
+            #[cfg(feature = "no_module")]
+            (index, name).into(),
+            short_index,
+            pos,
+        );
+
+        for var in &vars {
+            state.stack.push(var.name.clone(), ());
+        }
+
+        Stmt::Destructure((pattern, vars, expr).into(), ASTFlags::empty(), pos)
+    }
+
     /// Parse a primary expression.
     fn parse_primary(
         &self,
@@ -1412,6 +1930,10 @@ impl Engine {
             Token::Switch if settings.has_option(LangOptions::SWITCH_EXPR) => Expr::Stmt(Box::new(
                 self.parse_switch(state, settings.level_up()?)?.into(),
             )),
+            // Match expression
+            Token::Match => Expr::Stmt(Box::new(
+                self.parse_match(state, settings.level_up()?)?.into(),
+            )),
 
             // | ...
             #[cfg(not(feature = "no_function"))]
@@ -1443,7 +1965,10 @@ impl Engine {
                 }
 
                 loop {
-                    let expr = match self.parse_block(state, settings)? {
+                    let mut block_settings = settings;
+                    block_settings.flags |= ParseSettingFlags::INTERPOLATED_BLOCK;
+
+                    let expr = match self.parse_block(state, block_settings)? {
                         block @ Stmt::Block(..) => Expr::Stmt(Box::new(block.into())),
                         stmt => unreachable!("Stmt::Block expected but gets {:?}", stmt),
                     };
@@ -2731,10 +3256,11 @@ impl Engine {
         let mut settings = settings.level_up_with_position(eat_token(state.input, &Token::For))?;
 
         // for name ...
+        let mut pattern = None;
         let (name, name_pos, counter_name, counter_pos) =
             if match_token(state.input, &Token::LeftParen).0 {
                 // ( name, counter )
-                let (name, name_pos) = parse_var_name(state.input)?;
+                let (name, name_pos) = self.parse_loop_var(state, settings, &mut pattern)?;
                 let (has_comma, pos) = match_token(state.input, &Token::Comma);
                 if !has_comma {
                     return Err(PERR::MissingToken(
@@ -2745,7 +3271,11 @@ impl Engine {
                 }
                 let (counter_name, counter_pos) = parse_var_name(state.input)?;
 
-                if counter_name == name {
+                if counter_name == name
+                    || pattern.as_ref().map_or(false, |(_, vars)| {
+                        vars.iter().any(|v| v.name == counter_name)
+                    })
+                {
                     return Err(PERR::DuplicatedVariable(counter_name.into()).into_err(counter_pos));
                 }
 
@@ -2760,7 +3290,7 @@ impl Engine {
                 (name, name_pos, Some(counter_name), counter_pos)
             } else {
                 // name
-                let (name, name_pos) = parse_var_name(state.input)?;
+                let (name, name_pos) = self.parse_loop_var(state, settings, &mut pattern)?;
                 (name, name_pos, None, Position::NONE)
             };
 
@@ -2802,8 +3332,19 @@ impl Engine {
             prev_stack_len
         };
 
+        // for pattern in expr { let pattern = name; body }
+        let destructure = pattern.map(|(pattern, vars)| {
+            self.make_destructure_stmt(state, loop_var.name.clone(), pattern, vars, name_pos)
+        });
+
         settings.flags |= ParseSettingFlags::BREAKABLE;
-        let body = self.parse_block(state, settings)?.into();
+        let mut body = self.parse_block(state, settings)?;
+
+        if let Some(stmt) = destructure {
+            body = prepend_stmts([stmt], body);
+        }
+
+        let body = body.into();
 
         state.stack.rewind(prev_stack_len);
 
@@ -2815,6 +3356,30 @@ impl Engine {
         ))
     }
 
+    /// Parse the iteration variable of a `for` loop, which may be a destructuring pattern.
+    ///
+    /// For a pattern, the pattern and the variables bound by it are stored into `pattern`, and the
+    /// name of a hidden variable holding each iterated value is returned instead.
+    fn parse_loop_var(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        pattern: &mut Option<(Pattern, StaticVec<Ident>)>,
+    ) -> ParseResult<(SmartString, Position)> {
+        let (token, pos) = state.input.peek().unwrap();
+
+        if !is_destructuring_start(token) {
+            return parse_var_name(state.input);
+        }
+
+        let pos = *pos;
+        let mut vars = StaticVec::new_const();
+        let p = self.parse_destructuring_pattern(state, settings.level_up()?, &mut vars)?;
+        *pattern = Some((p, vars));
+
+        Ok((make_destructured_var(0), pos))
+    }
+
     /// Parse a variable definition statement.
     fn parse_let(
         &self,
@@ -2826,37 +3391,15 @@ impl Engine {
         // let/const... (specified in `var_type`)
         settings.pos = state.input.next().unwrap().1;
 
-        // let name ...
-        let (name, pos) = parse_var_name(state.input)?;
-
-        if !self.allow_shadowing() && state.stack.get(&name).is_some() {
-            return Err(PERR::VariableExists(name.into()).into_err(pos));
+        // let pattern ...
+        if is_destructuring_start(&state.input.peek().unwrap().0) {
+            return self.parse_let_pattern(state, settings, access, is_export);
         }
 
-        if let Some(ref filter) = self.def_var_filter {
-            let will_shadow = state.stack.get(&name).is_some();
-
-            let global = state
-                .global
-                .get_or_insert_with(|| self.new_global_runtime_state().into());
-
-            global.level = settings.level;
-            let is_const = access == AccessMode::ReadOnly;
-            let info = VarDefInfo::new(&name, is_const, settings.level, will_shadow);
-            let caches = &mut Caches::new();
-            let context = EvalContext::new(self, global, caches, &mut state.stack, None);
+        // let name ...
+        let (name, pos) = parse_var_name(state.input)?;
 
-            match filter(false, info, context) {
-                Ok(true) => (),
-                Ok(false) => return Err(PERR::ForbiddenVariable(name.into()).into_err(pos)),
-                Err(err) => {
-                    return Err(match *err {
-                        EvalAltResult::ErrorParsing(e, pos) => e.into_err(pos),
-                        _ => PERR::ForbiddenVariable(name.into()).into_err(pos),
-                    })
-                }
-            }
-        }
+        self.check_def_var(state, settings, &name, pos, access)?;
 
         let name = self.get_interned_string(name);
 
@@ -2914,6 +3457,109 @@ impl Engine {
         })
     }
 
+    /// Check whether a variable can be defined, running the variable definition filter (if any).
+    fn check_def_var(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        name: &str,
+        pos: Position,
+        access: AccessMode,
+    ) -> ParseResult<()> {
+        if !self.allow_shadowing() && state.stack.get(name).is_some() {
+            return Err(PERR::VariableExists(name.into()).into_err(pos));
+        }
+
+        if let Some(ref filter) = self.def_var_filter {
+            let will_shadow = state.stack.get(name).is_some();
+
+            let global = state
+                .global
+                .get_or_insert_with(|| self.new_global_runtime_state().into());
+
+            global.level = settings.level;
+            let is_const = access == AccessMode::ReadOnly;
+            let info = VarDefInfo::new(name, is_const, settings.level, will_shadow);
+            let caches = &mut Caches::new();
+            let context = EvalContext::new(self, global, caches, &mut state.stack, None);
+
+            match filter(false, info, context) {
+                Ok(true) => (),
+                Ok(false) => return Err(PERR::ForbiddenVariable(name.into()).into_err(pos)),
+                Err(err) => {
+                    return Err(match *err {
+                        EvalAltResult::ErrorParsing(e, pos) => e.into_err(pos),
+                        _ => PERR::ForbiddenVariable(name.into()).into_err(pos),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a destructuring variable definition statement.
+    fn parse_let_pattern(
+        &self,
+        state: &mut ParseState,
+        settings: ParseSettings,
+        access: AccessMode,
+        is_export: bool,
+    ) -> ParseResult<Stmt> {
+        // let pattern ...
+        let mut vars = StaticVec::new_const();
+        let pattern = self.parse_destructuring_pattern(state, settings.level_up()?, &mut vars)?;
+
+        for var in &vars {
+            self.check_def_var(state, settings, &var.name, var.pos, access)?;
+        }
+
+        // let pattern = expr
+        match state.input.next().unwrap() {
+            (Token::Equals, ..) => (),
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (.., pos) => {
+                return Err(PERR::MissingToken(
+                    Token::Equals.into(),
+                    "to assign a value to the destructuring pattern".into(),
+                )
+                .into_err(pos))
+            }
+        }
+
+        let expr = self.parse_expr(state, settings.level_up()?)?;
+
+        let mut flags = if is_export {
+            ASTFlags::EXPORTED
+        } else {
+            ASTFlags::empty()
+        };
+        if access == AccessMode::ReadOnly {
+            flags |= ASTFlags::CONSTANT;
+        }
+
+        for var in &vars {
+            state
+                .stack
+                .push_entry(var.name.clone(), access, Dynamic::UNIT);
+
+            #[cfg(not(feature = "no_module"))]
+            if is_export {
+                state
+                    .stack
+                    .add_alias_by_index(state.stack.len() - 1, var.name.clone());
+            }
+        }
+
+        vars.shrink_to_fit();
+
+        Ok(Stmt::Destructure(
+            (pattern, vars, expr).into(),
+            flags,
+            settings.pos,
+        ))
+    }
+
     /// Parse an import statement.
     #[cfg(not(feature = "no_module"))]
     fn parse_import(&self, state: &mut ParseState, settings: ParseSettings) -> ParseResult<Stmt> {
@@ -3016,10 +3662,16 @@ impl Engine {
         };
         let mut settings = settings.level_up_with_position(brace_start_pos)?;
 
+        let is_interpolated = settings.has_flag(ParseSettingFlags::INTERPOLATED_BLOCK);
+        settings.flags.remove(ParseSettingFlags::INTERPOLATED_BLOCK);
+
         let mut block = StmtBlock::empty(settings.pos);
 
         if settings.has_flag(ParseSettingFlags::DISALLOW_STATEMENTS_IN_BLOCKS) {
-            let stmt = self.parse_expr_stmt(state, settings)?;
+            let mut stmt = self.parse_expr_stmt(state, settings)?;
+            if is_interpolated && matches!(state.input.peek().unwrap(), (Token::Colon, ..)) {
+                stmt = self.parse_interpolation_format(state, stmt)?;
+            }
             block.statements_mut().push(stmt);
 
             // Must end with }
@@ -3059,7 +3711,12 @@ impl Engine {
             // Parse statements inside the block
             settings.flags.remove(ParseSettingFlags::GLOBAL_LEVEL);
 
-            let stmt = self.parse_stmt(state, settings)?;
+            let mut stmt = self.parse_stmt(state, settings)?;
+
+            // { ... stmt:spec }
+            if is_interpolated && matches!(state.input.peek().unwrap(), (Token::Colon, ..)) {
+                stmt = self.parse_interpolation_format(state, stmt)?;
+            }
 
             if stmt.is_noop() {
                 continue;
@@ -3108,6 +3765,51 @@ impl Engine {
         ))
     }
 
+    /// Parse the format specifier (e.g. `:>8.2`) after an expression in an interpolated string,
+    /// turning the statement into a call to `format`.
+    fn parse_interpolation_format(&self, state: &mut ParseState, stmt: Stmt) -> ParseResult<Stmt> {
+        let pos = eat_token(state.input, &Token::Colon);
+
+        // Make sure to parse the following as the raw text of a format specifier
+        state.tokenizer_control.borrow_mut().is_within_format_spec = true;
+
+        let spec = match state.input.next().unwrap() {
+            (Token::StringConstant(s), ..) => s,
+            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+            (token, ..) => unreachable!("format specifier expected but gets {:?}", token),
+        };
+        let spec = spec.trim();
+
+        if FormatSpec::parse(spec).is_none() {
+            return Err(LexError::ImproperSymbol(
+                spec.to_string(),
+                format!("Invalid format specifier: '{spec}'"),
+            )
+            .into_err(pos));
+        }
+
+        let expr = match stmt {
+            Stmt::Expr(expr) => *expr,
+            stmt => Expr::Stmt(Box::new(stmt.into())),
+        };
+        let stmt_pos = expr.start_position();
+        let template = Expr::StringConstant(self.get_interned_string(format!("{{:{spec}}}")), pos);
+
+        Ok(Stmt::Expr(
+            FnCallExpr {
+                #[cfg(not(feature = "no_module"))]
+                namespace: crate::ast::Namespace::NONE,
+                name: self.get_interned_string("format"),
+                hashes: FnCallHashes::from_native_only(calc_fn_hash(None, "format", 2)),
+                args: IntoIterator::into_iter([template, expr]).collect(),
+                op_token: None,
+                capture_parent_scope: false,
+            }
+            .into_fn_call_expr(stmt_pos)
+            .into(),
+        ))
+    }
+
     /// Parse an expression as a statement.
     fn parse_expr_stmt(
         &self,
@@ -3311,6 +4013,7 @@ impl Engine {
 
             Token::If => self.parse_if(state, settings.level_up()?),
             Token::Switch => self.parse_switch(state, settings.level_up()?),
+            Token::Match => self.parse_match(state, settings.level_up()?),
             Token::While | Token::Loop if self.allow_looping() => {
                 self.parse_while_loop(state, settings.level_up()?)
             }
@@ -3376,6 +4079,26 @@ impl Engine {
                 }
             }
 
+            #[cfg(feature = "resumable")]
+            Token::Yield if settings.has_flag(ParseSettingFlags::FN_SCOPE) => {
+                let pos = eat_token(state.input, &Token::Yield);
+
+                let current_pos = state.input.peek().unwrap().1;
+
+                match self.parse_expr(state, settings.level_up()?) {
+                    Ok(expr) => Ok(Stmt::Yield(Some(expr.into()), pos)),
+                    Err(err) => {
+                        if state.input.peek().unwrap().1 == current_pos {
+                            Ok(Stmt::Yield(None, pos))
+                        } else {
+                            return Err(err);
+                        }
+                    }
+                }
+            }
+            #[cfg(feature = "resumable")]
+            Token::Yield => Err(PERR::WrongYield.into_err(token_pos)),
+
             Token::Try => self.parse_try_catch(state, settings.level_up()?),
 
             Token::Let => self.parse_let(state, settings.level_up()?, ReadWrite, false),
@@ -3537,32 +4260,58 @@ impl Engine {
         };
 
         let mut params = StaticVec::<(ImmutableString, _)>::new_const();
+        let mut destructured = StaticVec::<(ImmutableString, Pattern, StaticVec<Ident>)>::new();
 
         if !no_params {
             let sep_err = format!("to separate the parameters of function '{name}'");
 
             loop {
-                match state.input.next().unwrap() {
-                    (Token::RightParen, ..) => break,
-                    (Token::Identifier(s), pos) => {
-                        if params.iter().any(|(p, _)| p == &*s) {
-                            return Err(
-                                PERR::FnDuplicatedParam(name.into(), s.to_string()).into_err(pos)
-                            );
+                let is_duplicated = |s: &str| {
+                    params.iter().any(|(p, _)| p == s)
+                        || destructured
+                            .iter()
+                            .any(|(.., vars)| vars.iter().any(|v| v.name == s))
+                };
+
+                match state.input.peek().unwrap() {
+                    // [ ... ] or #{ ... }
+                    (token, pos) if is_destructuring_start(token) => {
+                        let pos = *pos;
+                        let mut vars = StaticVec::new_const();
+                        let pattern =
+                            self.parse_destructuring_pattern(state, settings, &mut vars)?;
+
+                        if let Some(var) = vars.iter().find(|v| is_duplicated(&v.name)) {
+                            return Err(PERR::FnDuplicatedParam(name.into(), var.name.to_string())
+                                .into_err(var.pos));
                         }
 
-                        let s = self.get_interned_string(*s);
+                        let s = self.get_interned_string(make_destructured_var(params.len()));
                         state.stack.push(s.clone(), ());
+                        destructured.push((s.clone(), pattern, vars));
                         params.push((s, pos));
                     }
-                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
-                    (.., pos) => {
-                        return Err(PERR::MissingToken(
-                            Token::RightParen.into(),
-                            format!("to close the parameters list of function '{name}'"),
-                        )
-                        .into_err(pos))
-                    }
+                    _ => match state.input.next().unwrap() {
+                        (Token::RightParen, ..) => break,
+                        (Token::Identifier(s), pos) => {
+                            if is_duplicated(&s) {
+                                return Err(PERR::FnDuplicatedParam(name.into(), s.to_string())
+                                    .into_err(pos));
+                            }
+
+                            let s = self.get_interned_string(*s);
+                            state.stack.push(s.clone(), ());
+                            params.push((s, pos));
+                        }
+                        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                        (.., pos) => {
+                            return Err(PERR::MissingToken(
+                                Token::RightParen.into(),
+                                format!("to close the parameters list of function '{name}'"),
+                            )
+                            .into_err(pos))
+                        }
+                    },
                 }
 
                 match state.input.next().unwrap() {
@@ -3576,17 +4325,33 @@ impl Engine {
             }
         }
 
+        // Destructure parameters at the start of the function body
+        let destructure = destructured
+            .into_iter()
+            .map(|(param, pattern, vars)| {
+                let pos = pattern.position();
+                self.make_destructure_stmt(state, param, pattern, vars, pos)
+            })
+            .collect::<StaticVec<_>>();
+
         // Parse function body
-        let body = match state.input.peek().unwrap() {
+        let mut body = match state.input.peek().unwrap() {
             (Token::LeftBrace, ..) => self.parse_block(state, settings)?,
             (.., pos) => return Err(PERR::FnMissingBody(name.into()).into_err(*pos)),
+        };
+
+        if !destructure.is_empty() {
+            body = prepend_stmts(destructure, body);
         }
-        .into();
+
+        let body = body.into();
 
         let mut params: FnArgsVec<_> = params.into_iter().map(|(p, ..)| p).collect();
         params.shrink_to_fit();
 
         Ok(ScriptFuncDef {
+            #[cfg(feature = "resumable")]
+            is_generator: ScriptFuncDef::is_generator_body(&body),
             name: self.get_interned_string(name),
             access,
             #[cfg(not(feature = "no_object"))]
@@ -3697,6 +4462,7 @@ impl Engine {
         }
 
         let mut params_list = StaticVec::<ImmutableString>::new_const();
+        let mut destructured = StaticVec::<(ImmutableString, Pattern, StaticVec<Ident>)>::new();
 
         // Parse parameters
         if !skip_parameters
@@ -3704,27 +4470,55 @@ impl Engine {
             && !match_token(new_state.input, &Token::Pipe).0
         {
             loop {
-                match new_state.input.next().unwrap() {
-                    (Token::Pipe, ..) => break,
-                    (Token::Identifier(s), pos) => {
-                        if params_list.iter().any(|p| p == &*s) {
-                            return Err(
-                                PERR::FnDuplicatedParam(String::new(), s.to_string()).into_err(pos)
-                            );
+                let is_duplicated = |s: &str| {
+                    params_list.iter().any(|p| p == s)
+                        || destructured
+                            .iter()
+                            .any(|(.., vars)| vars.iter().any(|v| v.name == s))
+                };
+
+                match new_state.input.peek().unwrap() {
+                    // [ ... ] or #{ ... }
+                    (token, ..) if is_destructuring_start(token) => {
+                        let mut vars = StaticVec::new_const();
+                        let pattern =
+                            self.parse_destructuring_pattern(new_state, settings, &mut vars)?;
+
+                        if let Some(var) = vars.iter().find(|v| is_duplicated(&v.name)) {
+                            return Err(PERR::FnDuplicatedParam(
+                                String::new(),
+                                var.name.to_string(),
+                            )
+                            .into_err(var.pos));
                         }
 
-                        let s = self.get_interned_string(*s);
+                        let s = self.get_interned_string(make_destructured_var(params_list.len()));
                         new_state.stack.push(s.clone(), ());
+                        destructured.push((s.clone(), pattern, vars));
                         params_list.push(s);
                     }
-                    (Token::LexError(err), pos) => return Err(err.into_err(pos)),
-                    (.., pos) => {
-                        return Err(PERR::MissingToken(
-                            Token::Pipe.into(),
-                            "to close the parameters list of anonymous function or closure".into(),
-                        )
-                        .into_err(pos))
-                    }
+                    _ => match new_state.input.next().unwrap() {
+                        (Token::Pipe, ..) => break,
+                        (Token::Identifier(s), pos) => {
+                            if is_duplicated(&s) {
+                                return Err(PERR::FnDuplicatedParam(String::new(), s.to_string())
+                                    .into_err(pos));
+                            }
+
+                            let s = self.get_interned_string(*s);
+                            new_state.stack.push(s.clone(), ());
+                            params_list.push(s);
+                        }
+                        (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                        (.., pos) => {
+                            return Err(PERR::MissingToken(
+                                Token::Pipe.into(),
+                                "to close the parameters list of anonymous function or closure"
+                                    .into(),
+                            )
+                            .into_err(pos))
+                        }
+                    },
                 }
 
                 match new_state.input.next().unwrap() {
@@ -3761,8 +4555,21 @@ impl Engine {
             ..settings
         };
 
+        // Destructure parameters at the start of the function body
+        let destructure = destructured
+            .into_iter()
+            .map(|(param, pattern, vars)| {
+                let pos = pattern.position();
+                self.make_destructure_stmt(new_state, param, pattern, vars, pos)
+            })
+            .collect::<StaticVec<_>>();
+
         // Parse function body
-        let body = self.parse_stmt(new_state, new_settings.level_up()?)?;
+        let mut body = self.parse_stmt(new_state, new_settings.level_up()?)?;
+
+        if !destructure.is_empty() {
+            body = prepend_stmts(destructure, body);
+        }
 
         let _ = new_settings; // Make sure it doesn't leak into code below
 
@@ -3795,13 +4602,17 @@ impl Engine {
         let fn_name = self.get_interned_string(make_anonymous_fn(hash));
 
         // Define the function
+        let body: StmtBlock = body.into();
+
         let fn_def = Shared::new(ScriptFuncDef {
+            #[cfg(feature = "resumable")]
+            is_generator: ScriptFuncDef::is_generator_body(&body),
             name: fn_name.clone(),
             access: crate::FnAccess::Public,
             #[cfg(not(feature = "no_object"))]
             this_type: None,
             params,
-            body: body.into(),
+            body,
             #[cfg(not(feature = "no_function"))]
             #[cfg(feature = "metadata")]
             comments: <_>::default(),
@@ -173,6 +173,11 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
             Union::Variant(ref value, ..) if value.is::<u64>() => self.deserialize_u64(visitor),
             Union::Variant(ref value, ..) if value.is::<u128>() => self.deserialize_u128(visitor),
 
+            #[cfg(not(feature = "no_index"))]
+            Union::Variant(ref value, ..) if value.is::<crate::Set>() => {
+                self.deserialize_seq(visitor)
+            }
+
             Union::Variant(..) => self.type_error(),
 
             #[cfg(not(feature = "no_closure"))]
@@ -397,6 +402,11 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
     }
 
     fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> RhaiResultOf<V::Value> {
+        #[cfg(not(feature = "no_index"))]
+        if let Some(set) = self.0.downcast_ref::<crate::Set>() {
+            return _visitor.visit_seq(IterateDynamicArray::new(set.iter()));
+        }
+
         #[cfg(not(feature = "no_index"))]
         return self.0.downcast_ref::<crate::Array>().map_or_else(
             || self.type_error(),
@@ -270,6 +270,20 @@ impl<'de> Deserialize<'de> for ImmutableString {
     }
 }
 
+#[cfg(not(feature = "no_index"))]
+impl<'de> Deserialize<'de> for crate::Set {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values: Vec<Dynamic> = Deserialize::deserialize(deserializer)?;
+        let mut set = Self::new();
+
+        for value in values {
+            set.insert(value).map_err(Error::custom)?;
+        }
+
+        Ok(set)
+    }
+}
+
 impl<'de> Deserialize<'de> for Scope<'_> {
     #[inline(always)]
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
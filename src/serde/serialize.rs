@@ -71,6 +71,10 @@ impl Serialize for Dynamic {
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(ref x, ..) => ser.serialize_str(x.as_ref().type_name()),
 
+            #[cfg(not(feature = "no_index"))]
+            Union::Variant(ref v, ..) if (***v).is::<crate::Set>() => {
+                ser.collect_seq(self.downcast_ref::<crate::Set>().unwrap().iter())
+            }
             Union::Variant(ref v, ..) => ser.serialize_str((***v).type_name()),
 
             #[cfg(not(feature = "no_closure"))]
@@ -120,3 +124,11 @@ impl Serialize for Scope<'_> {
         ser.end()
     }
 }
+
+#[cfg(not(feature = "no_index"))]
+impl Serialize for crate::Set {
+    #[inline(always)]
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_seq(self.iter())
+    }
+}
@@ -11,6 +11,7 @@ use std::{
     cell::RefCell,
     char, fmt,
     iter::{repeat, FusedIterator, Peekable},
+    mem,
     rc::Rc,
     str::{Chars, FromStr},
 };
@@ -22,6 +23,11 @@ pub struct TokenizerControlBlock {
     ///
     /// This flag allows switching the tokenizer back to _text_ parsing after an interpolation stream.
     pub is_within_text: bool,
+    /// Is the next token the format specifier of an interpolated expression (i.e. the text after
+    /// `:` in `${x:>8}`)?
+    ///
+    /// This flag allows switching the tokenizer to read the raw text up to the closing `}`.
+    pub is_within_format_spec: bool,
     /// Global comments.
     #[cfg(feature = "metadata")]
     pub global_comments: String,
@@ -38,6 +44,7 @@ impl TokenizerControlBlock {
     pub const fn new() -> Self {
         Self {
             is_within_text: false,
+            is_within_format_spec: false,
             #[cfg(feature = "metadata")]
             global_comments: String::new(),
             compressed: None,
@@ -163,6 +170,8 @@ pub enum Token {
     Else,
     /// `switch`
     Switch,
+    /// `match`
+    Match,
     /// `do`
     Do,
     /// `while`
@@ -214,6 +223,11 @@ pub enum Token {
     Return,
     /// `throw`
     Throw,
+    /// `yield`
+    ///
+    /// Reserved without the `resumable` feature.
+    #[cfg(feature = "resumable")]
+    Yield,
     /// `try`
     Try,
     /// `catch`
@@ -320,7 +334,7 @@ static KEYWORD_ASSOC_VALUES: [u8; 257] = [
     105, 40, 80, 2, 20, 25, 125, 95, 15, 40, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 55,
     35, 10, 5, 0, 30, 110, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
     153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 120, 105, 100, 85, 90, 153, 125, 5,
-    0, 125, 35, 10, 100, 153, 20, 0, 153, 10, 0, 45, 55, 0, 153, 50, 55, 5, 0, 153, 0, 0, 35, 153,
+    0, 125, 35, 10, 100, 153, 20, 0, 153, 10, 5, 45, 55, 0, 153, 50, 55, 5, 0, 153, 0, 0, 35, 153,
     45, 50, 30, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
     153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
     153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153, 153,
@@ -346,7 +360,7 @@ static KEYWORDS_LIST: [(&str, Token); 153] = [
     ("<=", Token::LessThanEqualsTo),
     ("for", Token::For),
     ("loop", Token::Loop),
-    ("", Token::EOF),
+    ("match", Token::Match),
     (".", Token::Period),
     ("<<", Token::LeftShift),
     ("<<=", Token::LeftShiftAssign),
@@ -370,12 +384,15 @@ static KEYWORDS_LIST: [(&str, Token); 153] = [
     (";", Token::SemiColon),
     ("=>", Token::DoubleArrow),
     ("", Token::EOF),
-    ("else", Token::Else),
+    ("", Token::EOF),
+    #[cfg(feature = "resumable")]
+    ("yield", Token::Yield),
+    #[cfg(not(feature = "resumable"))]
     ("", Token::EOF),
     ("/", Token::Divide),
     ("/=", Token::DivideAssign),
     ("", Token::EOF),
-    ("", Token::EOF),
+    ("else", Token::Else),
     ("", Token::EOF),
     ("{", Token::LeftBrace),
     ("**", Token::PowerOf),
@@ -567,12 +584,12 @@ static RESERVED_LIST: [(&str, bool, bool, bool); 150] = [
     ("?.", cfg!(feature = "no_object"), false, false),
     ("", false, false, false),
     ("is_def_fn", cfg!(not(feature = "no_function")), true, false),
-    ("yield", true, false, false),
+    ("yield", cfg!(not(feature = "resumable")), false, false),
     ("", false, false, false),
     ("fn", cfg!(feature = "no_function"), false, false),
     ("new", true, false, false),
     ("call", true, true, true),
-    ("match", true, false, false),
+    ("match", false, false, false),
     ("~", true, false, false),
     ("!.", true, false, false),
     ("", false, false, false),
@@ -758,6 +775,7 @@ impl Token {
             If => "if",
             Else => "else",
             Switch => "switch",
+            Match => "match",
             Do => "do",
             While => "while",
             Until => "until",
@@ -804,6 +822,9 @@ impl Token {
             #[cfg(not(feature = "no_function"))]
             Private => "private",
 
+            #[cfg(feature = "resumable")]
+            Yield => "yield",
+
             #[cfg(not(feature = "no_module"))]
             Import => "import",
             #[cfg(not(feature = "no_module"))]
@@ -1005,6 +1026,9 @@ impl Token {
             Return           |
             Throw               => true,
 
+            #[cfg(feature = "resumable")]
+            Yield               => true,
+
             #[cfg(not(feature = "no_index"))]
             QuestionBracket     => true,    // ?[ - is unary
 
@@ -1101,6 +1125,9 @@ impl Token {
             #[cfg(not(feature = "no_module"))]
             Import | Export | As => true,
 
+            #[cfg(feature = "resumable")]
+            Yield => true,
+
             True | False | Let | Const | If | Else | Do | While | Until | Loop | For | In
             | Continue | Break | Return | Throw | Try | Catch => true,
 
@@ -1313,6 +1340,36 @@ pub fn parse_raw_string_literal(
     Ok((result, first_char))
 }
 
+/// Parse the raw text of a format specifier up to (but not including) the closing `}`.
+fn parse_format_spec(
+    stream: &mut (impl InputStream + ?Sized),
+    state: &mut TokenizeState,
+    pos: &mut Position,
+) -> (Token, Position) {
+    let start = *pos;
+    let mut result = SmartString::new_const();
+
+    loop {
+        match stream.peek_next() {
+            Some('}') => break,
+            Some('\n' | '`') | None => {
+                return (Token::LexError(LERR::UnterminatedString.into()), start)
+            }
+            Some(ch) => {
+                stream.eat_next_and_advance(pos);
+                result.push(ch);
+            }
+        }
+    }
+
+    if let Some(ref mut last) = state.last_token {
+        last.clear();
+        last.push_str(&result);
+    }
+
+    (Token::StringConstant(result.into()), start)
+}
+
 /// _(internals)_ Parse a string literal ended by a specified termination character.
 /// Exported under the `internals` feature only.
 ///
@@ -2087,6 +2144,7 @@ fn get_next_token_inner(
 
                         // Long streams of `///...` are not doc-comments
                         match stream.peek_next() {
+                            Some('/') if state.include_comments => Some("///".into()),
                             Some('/') => None,
                             _ => Some("///".into()),
                         }
@@ -2121,7 +2179,7 @@ fn get_next_token_inner(
 
                 match comment {
                     #[cfg(feature = "metadata")]
-                    Some(comment) if comment.starts_with("//!") => {
+                    Some(comment) if comment.starts_with("//!") && !state.include_comments => {
                         let g = &mut state.tokenizer_control.borrow_mut().global_comments;
                         if !g.is_empty() {
                             *g += "\n";
@@ -2144,6 +2202,7 @@ fn get_next_token_inner(
 
                         // Long streams of `/****...` are not doc-comments
                         match stream.peek_next() {
+                            Some('*') if state.include_comments => Some("/**".into()),
                             Some('*') => None,
                             _ => Some("/**".into()),
                         }
@@ -2635,7 +2694,7 @@ impl<'a> Iterator for TokenIterator<'a> {
     type Item = (Token, Position);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (within_interpolated, compress_script) = {
+        let (within_interpolated, within_format_spec, compress_script) = {
             let control = &mut *self.state.tokenizer_control.borrow_mut();
 
             if control.is_within_text {
@@ -2647,11 +2706,18 @@ impl<'a> Iterator for TokenIterator<'a> {
 
             (
                 self.state.is_within_text_terminated_by.is_some(),
+                mem::take(&mut control.is_within_format_spec),
                 control.compressed.is_some(),
             )
         };
 
-        let (token, pos) = match get_next_token(&mut self.stream, &mut self.state, &mut self.pos) {
+        let next = if within_format_spec {
+            parse_format_spec(&mut self.stream, &mut self.state, &mut self.pos)
+        } else {
+            get_next_token(&mut self.stream, &mut self.state, &mut self.pos)
+        };
+
+        let (token, pos) = match next {
             // {EOF}
             r @ (Token::EOF, _) => return Some(r),
             // {EOF} after unterminated string.
@@ -0,0 +1,865 @@
+//! Module defining the wall-clock [`DateTime`] type.
+#![cfg(not(feature = "no_time"))]
+
+use crate::{Position, RhaiResultOf, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Write},
+    hash::{Hash, Hasher},
+};
+
+/// Number of seconds in a day.
+const SECS_PER_DAY: i64 = 86_400;
+/// Number of nanoseconds in a second.
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// Earliest supported year.
+const MIN_YEAR: i64 = -9999;
+/// Latest supported year.
+const MAX_YEAR: i64 = 9999;
+
+/// Earliest supported timestamp (local time), i.e. `-9999-01-01T00:00:00`.
+const MIN_SECS: i64 = days_from_civil(MIN_YEAR, 1, 1) * SECS_PER_DAY;
+/// Latest supported timestamp (local time), i.e. `9999-12-31T23:59:59`.
+const MAX_SECS: i64 = days_from_civil(MAX_YEAR + 1, 1, 1) * SECS_PER_DAY - 1;
+
+/// Names of the months.
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+/// Names of the days of the week, starting from Monday.
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Number of days since the Unix epoch of a date in the proleptic Gregorian calendar.
+#[must_use]
+const fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Date (year, month, day) in the proleptic Gregorian calendar of a number of days since the
+/// Unix epoch.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Is this a leap year?
+#[inline]
+#[must_use]
+const fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Number of days in a month.
+#[must_use]
+const fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Make an error for a date/time that cannot be parsed.
+#[cold]
+#[inline(never)]
+fn make_parse_err(input: &str, msg: impl fmt::Display) -> Box<crate::EvalAltResult> {
+    ERR::ErrorArithmetic(
+        format!("Error parsing date/time '{input}': {msg}"),
+        Position::NONE,
+    )
+    .into()
+}
+
+/// A wall-clock date/time with a fixed offset from UTC.
+///
+/// Dates are in the proleptic Gregorian calendar, with years between -9999 and 9999.
+///
+/// Two [`DateTime`]'s are equal if they represent the same instant in time, regardless of their
+/// offsets.
+///
+/// Not available under `no_time`.
+///
+/// # Example
+///
+/// ```
+/// use rhai::DateTime;
+///
+/// let dt = DateTime::from_ymd_hms(2024, 2, 29, 12, 30, 0).unwrap();
+///
+/// assert_eq!(dt.to_string(), "2024-02-29T12:30:00Z");
+/// assert_eq!(dt.weekday(), 4);    // Thursday
+///
+/// let dt = dt.with_offset(8 * 3600).unwrap();
+///
+/// assert_eq!(dt.to_string(), "2024-02-29T20:30:00+08:00");
+/// assert_eq!(dt.unix_timestamp(), 1_709_209_800);
+/// ```
+#[derive(Clone, Copy)]
+pub struct DateTime {
+    /// Number of seconds since the Unix epoch.
+    secs: i64,
+    /// Nanoseconds within the second.
+    nanos: u32,
+    /// Offset from UTC in seconds.
+    offset: i32,
+}
+
+impl DateTime {
+    /// The Unix epoch, i.e. `1970-01-01T00:00:00Z`.
+    pub const UNIX_EPOCH: Self = Self {
+        secs: 0,
+        nanos: 0,
+        offset: 0,
+    };
+
+    /// Create a [`DateTime`] in UTC from the number of seconds and nanoseconds since the Unix
+    /// epoch.
+    ///
+    /// Returns [`None`] if the date/time is out of range, or `nanos` is not less than one billion.
+    #[inline]
+    #[must_use]
+    pub const fn from_unix_timestamp(secs: i64, nanos: u32) -> Option<Self> {
+        if secs < MIN_SECS || secs > MAX_SECS || nanos >= NANOS_PER_SEC {
+            return None;
+        }
+        Some(Self {
+            secs,
+            nanos,
+            offset: 0,
+        })
+    }
+    /// Create a [`DateTime`] in UTC from a date and a time.
+    ///
+    /// Returns [`None`] if any component is out of range.
+    #[must_use]
+    pub fn from_ymd_hms(
+        year: i64,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        Self::from_local(year, month, day, hour, minute, second, 0, 0)
+    }
+    /// Create a [`DateTime`] from the local date/time components and an offset from UTC in
+    /// seconds.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    fn from_local(
+        year: i64,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        nanos: u32,
+        offset: i32,
+    ) -> Option<Self> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year)
+            || !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+            || nanos >= NANOS_PER_SEC
+            || i64::from(offset).abs() >= SECS_PER_DAY
+        {
+            return None;
+        }
+
+        let local = days_from_civil(year, month, day) * SECS_PER_DAY
+            + i64::from(hour * 3600 + minute * 60 + second);
+
+        Self::from_unix_timestamp(local - i64::from(offset), nanos)?.with_offset(offset)
+    }
+    /// Get the current date/time from the system clock, in UTC.
+    ///
+    /// Not available on WASM targets without an operating system.
+    #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
+    #[must_use]
+    pub fn now() -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        #[allow(clippy::cast_possible_wrap)]
+        let (secs, nanos) = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+            Err(err) => {
+                let d = err.duration();
+                match d.subsec_nanos() {
+                    0 => (-(d.as_secs() as i64), 0),
+                    n => (-(d.as_secs() as i64) - 1, NANOS_PER_SEC - n),
+                }
+            }
+        };
+
+        Self::from_unix_timestamp(secs, nanos).unwrap_or(Self::UNIX_EPOCH)
+    }
+    /// Convert this [`DateTime`] into one with a different offset from UTC in seconds, keeping the
+    /// same instant in time.
+    ///
+    /// Returns [`None`] if the offset is not within one day, or the local date/time is out of range.
+    #[must_use]
+    pub const fn with_offset(self, offset: i32) -> Option<Self> {
+        if offset <= -(SECS_PER_DAY as i32) || offset >= SECS_PER_DAY as i32 {
+            return None;
+        }
+        let local = self.secs + offset as i64;
+        if local < MIN_SECS || local > MAX_SECS {
+            return None;
+        }
+        Some(Self { offset, ..self })
+    }
+    /// Convert this [`DateTime`] into UTC, keeping the same instant in time.
+    #[inline(always)]
+    #[must_use]
+    pub const fn to_utc(self) -> Self {
+        Self { offset: 0, ..self }
+    }
+    /// Number of whole seconds since the Unix epoch.
+    #[inline(always)]
+    #[must_use]
+    pub const fn unix_timestamp(&self) -> i64 {
+        self.secs
+    }
+    /// Offset from UTC in seconds.
+    #[inline(always)]
+    #[must_use]
+    pub const fn offset(&self) -> i32 {
+        self.offset
+    }
+    /// Local number of seconds since the Unix epoch.
+    #[inline(always)]
+    #[must_use]
+    const fn local_secs(&self) -> i64 {
+        self.secs + self.offset as i64
+    }
+    /// Local date.
+    #[inline]
+    #[must_use]
+    const fn date(&self) -> (i64, u32, u32) {
+        civil_from_days(self.local_secs().div_euclid(SECS_PER_DAY))
+    }
+    /// Local number of seconds since midnight.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    const fn seconds_of_day(&self) -> u32 {
+        self.local_secs().rem_euclid(SECS_PER_DAY) as u32
+    }
+    /// Year.
+    #[inline]
+    #[must_use]
+    pub const fn year(&self) -> i64 {
+        self.date().0
+    }
+    /// Month, from 1 (January) to 12 (December).
+    #[inline]
+    #[must_use]
+    pub const fn month(&self) -> u32 {
+        self.date().1
+    }
+    /// Day of the month, starting from 1.
+    #[inline]
+    #[must_use]
+    pub const fn day(&self) -> u32 {
+        self.date().2
+    }
+    /// Hour, from 0 to 23.
+    #[inline]
+    #[must_use]
+    pub const fn hour(&self) -> u32 {
+        self.seconds_of_day() / 3600
+    }
+    /// Minute, from 0 to 59.
+    #[inline]
+    #[must_use]
+    pub const fn minute(&self) -> u32 {
+        self.seconds_of_day() / 60 % 60
+    }
+    /// Second, from 0 to 59.
+    #[inline]
+    #[must_use]
+    pub const fn second(&self) -> u32 {
+        self.seconds_of_day() % 60
+    }
+    /// Nanoseconds within the second.
+    #[inline(always)]
+    #[must_use]
+    pub const fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+    /// Day of the week, from 1 (Monday) to 7 (Sunday).
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub const fn weekday(&self) -> u32 {
+        // 1970-01-01 is a Thursday
+        (self.local_secs().div_euclid(SECS_PER_DAY) + 3).rem_euclid(7) as u32 + 1
+    }
+    /// Day of the year, starting from 1.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn day_of_year(&self) -> u32 {
+        let days = self.local_secs().div_euclid(SECS_PER_DAY);
+        (days - days_from_civil(self.year(), 1, 1)) as u32 + 1
+    }
+    /// Add a number of seconds and nanoseconds (which can be negative).
+    ///
+    /// Returns [`None`] if the result is out of range.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn checked_add(self, secs: i64, nanos: i64) -> Option<Self> {
+        let nanos = i64::from(self.nanos).checked_add(nanos)?;
+        let secs = self
+            .secs
+            .checked_add(secs)?
+            .checked_add(nanos.div_euclid(i64::from(NANOS_PER_SEC)))?;
+        let nanos = nanos.rem_euclid(i64::from(NANOS_PER_SEC)) as u32;
+
+        Self::from_unix_timestamp(secs, nanos)?.with_offset(self.offset)
+    }
+    /// Add a number of calendar months (which can be negative), keeping the local time of day.
+    ///
+    /// The day of the month is clamped to the last day of the resulting month,
+    /// e.g. `2024-01-31` plus one month is `2024-02-29`.
+    ///
+    /// Returns [`None`] if the result is out of range.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn checked_add_months(self, months: i64) -> Option<Self> {
+        let (year, month, day) = self.date();
+        let total = (year * 12 + i64::from(month) - 1).checked_add(months)?;
+        let year = total.div_euclid(12);
+        let month = total.rem_euclid(12) as u32 + 1;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return None;
+        }
+
+        let day = day.min(days_in_month(year, month));
+        let sod = self.seconds_of_day();
+
+        Self::from_local(
+            year,
+            month,
+            day,
+            sod / 3600,
+            sod / 60 % 60,
+            sod % 60,
+            self.nanos,
+            self.offset,
+        )
+    }
+    /// Parse a date/time in [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339) format,
+    /// e.g. `2024-02-29T12:30:00Z` or `2024-02-29 20:30:00.5+08:00`.
+    pub fn parse_rfc3339(input: &str) -> RhaiResultOf<Self> {
+        let mut parser = Parser::new(input);
+
+        let year = parser.number(4, 4)?;
+        parser.literal('-')?;
+        let month = parser.number(2, 2)?;
+        parser.literal('-')?;
+        let day = parser.number(2, 2)?;
+        if !parser.eat('T') && !parser.eat('t') {
+            parser.literal(' ')?;
+        }
+        let hour = parser.number(2, 2)?;
+        parser.literal(':')?;
+        let minute = parser.number(2, 2)?;
+        parser.literal(':')?;
+        let second = parser.number(2, 2)?;
+        let nanos = if parser.eat('.') {
+            parser.fraction()?
+        } else {
+            0
+        };
+        let offset = parser.offset(true)?;
+        parser.finish()?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        Self::from_local(
+            year,
+            month as u32,
+            day as u32,
+            hour as u32,
+            minute as u32,
+            second as u32,
+            nanos,
+            offset,
+        )
+        .ok_or_else(|| make_parse_err(input, "date/time out of range"))
+    }
+    /// Parse a date/time according to a `strftime`-like pattern.
+    ///
+    /// The following specifiers are supported:
+    ///
+    /// | Specifier | Meaning                                              |
+    /// |-----------|------------------------------------------------------|
+    /// | `%Y`      | year, at least four digits                           |
+    /// | `%y`      | two-digit year, 1969 to 2068                         |
+    /// | `%m`      | month number, 01 to 12                               |
+    /// | `%b`      | abbreviated month name, e.g. `Feb`                   |
+    /// | `%B`      | full month name, e.g. `February`                     |
+    /// | `%d`      | day of the month, 01 to 31                           |
+    /// | `%e`      | day of the month, space-padded                       |
+    /// | `%a`      | abbreviated weekday name, e.g. `Thu` (ignored)       |
+    /// | `%A`      | full weekday name, e.g. `Thursday` (ignored)         |
+    /// | `%H`      | hour, 00 to 23                                       |
+    /// | `%I`      | hour, 01 to 12                                       |
+    /// | `%p`      | `AM` or `PM`                                         |
+    /// | `%M`      | minute, 00 to 59                                     |
+    /// | `%S`      | second, 00 to 59                                     |
+    /// | `%f`      | fraction of a second, up to nine digits              |
+    /// | `%.f`     | optional `.` followed by a fraction of a second      |
+    /// | `%z`      | offset from UTC, e.g. `+0800`                        |
+    /// | `%:z`     | offset from UTC, e.g. `+08:00`, or `Z`               |
+    /// | `%s`      | number of seconds since the Unix epoch               |
+    /// | `%F`      | same as `%Y-%m-%d`                                   |
+    /// | `%T`      | same as `%H:%M:%S`                                   |
+    /// | `%%`      | a literal `%`                                        |
+    ///
+    /// Missing time components default to zero, and a missing offset defaults to UTC.
+    pub fn parse_from_str(input: &str, pattern: &str) -> RhaiResultOf<Self> {
+        let mut parser = Parser::new(input);
+
+        let mut year = None;
+        let mut month = None;
+        let mut day = None;
+        let mut hour = 0;
+        let mut hour12 = None;
+        let mut is_pm = None;
+        let mut minute = 0;
+        let mut second = 0;
+        let mut nanos = 0;
+        let mut offset = 0;
+        let mut timestamp = None;
+
+        let pattern = pattern.replace("%F", "%Y-%m-%d").replace("%T", "%H:%M:%S");
+        let mut chars = pattern.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                parser.literal(ch)?;
+                continue;
+            }
+
+            match chars.next() {
+                Some('Y') => year = Some(parser.signed_number(4, 9)?),
+                Some('y') => {
+                    let y = parser.number(2, 2)?;
+                    year = Some(if y < 69 { 2000 + y } else { 1900 + y });
+                }
+                Some('m') => month = Some(parser.number(1, 2)?),
+                Some('b') => month = Some(parser.name(&MONTH_NAMES, true)? as i64 + 1),
+                Some('B') => month = Some(parser.name(&MONTH_NAMES, false)? as i64 + 1),
+                Some('d') => day = Some(parser.number(1, 2)?),
+                Some('e') => {
+                    parser.eat(' ');
+                    day = Some(parser.number(1, 2)?);
+                }
+                Some('a') => _ = parser.name(&WEEKDAY_NAMES, true)?,
+                Some('A') => _ = parser.name(&WEEKDAY_NAMES, false)?,
+                Some('H') => hour = parser.number(1, 2)?,
+                Some('I') => hour12 = Some(parser.number(1, 2)?),
+                Some('p') => is_pm = Some(parser.name(&["AM", "PM"], false)? == 1),
+                Some('M') => minute = parser.number(1, 2)?,
+                Some('S') => second = parser.number(1, 2)?,
+                Some('f') => nanos = parser.fraction()?,
+                Some('.') if chars.next() == Some('f') => {
+                    if parser.eat('.') {
+                        nanos = parser.fraction()?;
+                    }
+                }
+                Some('z') => offset = parser.offset(false)?,
+                Some(':') if chars.next() == Some('z') => offset = parser.offset(true)?,
+                Some('s') => timestamp = Some(parser.signed_number(1, 19)?),
+                Some('%') => parser.literal('%')?,
+                Some(c) => return Err(make_parse_err(input, format!("unknown specifier '%{c}'"))),
+                None => return Err(make_parse_err(input, "incomplete specifier '%'")),
+            }
+        }
+
+        parser.finish()?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        if let Some(secs) = timestamp {
+            return Self::from_unix_timestamp(secs, nanos)
+                .and_then(|dt| dt.with_offset(offset))
+                .ok_or_else(|| make_parse_err(input, "date/time out of range"));
+        }
+
+        match (hour12, is_pm) {
+            (Some(h), Some(pm)) if (1..=12).contains(&h) => hour = h % 12 + if pm { 12 } else { 0 },
+            (Some(..), Some(..)) => return Err(make_parse_err(input, "invalid hour")),
+            (Some(..), None) => return Err(make_parse_err(input, "missing AM/PM")),
+            (None, Some(..)) => return Err(make_parse_err(input, "missing 12-hour clock hour")),
+            (None, None) => (),
+        }
+
+        let (Some(year), Some(month), Some(day)) = (year, month, day) else {
+            return Err(make_parse_err(input, "missing year, month or day"));
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Self::from_local(
+            year,
+            month as u32,
+            day as u32,
+            hour as u32,
+            minute as u32,
+            second as u32,
+            nanos,
+            offset,
+        )
+        .ok_or_else(|| make_parse_err(input, "date/time out of range"))
+    }
+    /// Format this [`DateTime`] according to a `strftime`-like pattern.
+    ///
+    /// In addition to the specifiers supported by [`parse_from_str`][DateTime::parse_from_str],
+    /// the following specifiers are supported:
+    ///
+    /// | Specifier | Meaning                                              |
+    /// |-----------|------------------------------------------------------|
+    /// | `%3f`     | milliseconds, three digits                           |
+    /// | `%6f`     | microseconds, six digits                             |
+    /// | `%9f`     | nanoseconds, nine digits (same as `%f`)              |
+    /// | `%j`      | day of the year, 001 to 366                          |
+    /// | `%u`      | day of the week, 1 (Monday) to 7 (Sunday)            |
+    /// | `%w`      | day of the week, 0 (Sunday) to 6 (Saturday)          |
+    ///
+    /// Unknown specifiers are output as-is.
+    #[must_use]
+    pub fn format(&self, pattern: &str) -> String {
+        let mut buf = String::with_capacity(pattern.len() + 16);
+        self.write_formatted(&mut buf, pattern).unwrap();
+        buf
+    }
+    /// Write this [`DateTime`] formatted according to a `strftime`-like pattern.
+    fn write_formatted(&self, f: &mut impl Write, pattern: &str) -> fmt::Result {
+        let (year, month, day) = self.date();
+        let (hour, minute, second) = (self.hour(), self.minute(), self.second());
+        let weekday = self.weekday();
+
+        let mut chars = pattern.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                f.write_char(ch)?;
+                continue;
+            }
+
+            match chars.next() {
+                Some('Y') if year < 0 => write!(f, "-{:04}", -year)?,
+                Some('Y') => write!(f, "{year:04}")?,
+                Some('y') => write!(f, "{:02}", year.rem_euclid(100))?,
+                Some('m') => write!(f, "{month:02}")?,
+                Some('b') => f.write_str(&MONTH_NAMES[month as usize - 1][..3])?,
+                Some('B') => f.write_str(MONTH_NAMES[month as usize - 1])?,
+                Some('d') => write!(f, "{day:02}")?,
+                Some('e') => write!(f, "{day:2}")?,
+                Some('a') => f.write_str(&WEEKDAY_NAMES[weekday as usize - 1][..3])?,
+                Some('A') => f.write_str(WEEKDAY_NAMES[weekday as usize - 1])?,
+                Some('H') => write!(f, "{hour:02}")?,
+                Some('I') => write!(f, "{:02}", (hour + 11) % 12 + 1)?,
+                Some('p') => f.write_str(if hour < 12 { "AM" } else { "PM" })?,
+                Some('M') => write!(f, "{minute:02}")?,
+                Some('S') => write!(f, "{second:02}")?,
+                Some('f') => write!(f, "{:09}", self.nanos)?,
+                Some('3') if chars.peek() == Some(&'f') => {
+                    chars.next();
+                    write!(f, "{:03}", self.nanos / 1_000_000)?;
+                }
+                Some('6') if chars.peek() == Some(&'f') => {
+                    chars.next();
+                    write!(f, "{:06}", self.nanos / 1_000)?;
+                }
+                Some('9') if chars.peek() == Some(&'f') => {
+                    chars.next();
+                    write!(f, "{:09}", self.nanos)?;
+                }
+                Some('.') if chars.peek() == Some(&'f') => {
+                    chars.next();
+                    self.write_fraction(f)?;
+                }
+                Some('j') => write!(f, "{:03}", self.day_of_year())?,
+                Some('u') => write!(f, "{weekday}")?,
+                Some('w') => write!(f, "{}", weekday % 7)?,
+                Some('z') => self.write_offset(f, false)?,
+                Some(':') if chars.peek() == Some(&'z') => {
+                    chars.next();
+                    self.write_offset(f, true)?;
+                }
+                Some('s') => write!(f, "{}", self.secs)?,
+                Some('F') => self.write_formatted(f, "%Y-%m-%d")?,
+                Some('T') => self.write_formatted(f, "%H:%M:%S")?,
+                Some('%') => f.write_char('%')?,
+                Some(c) => {
+                    f.write_char('%')?;
+                    f.write_char(c)?;
+                }
+                None => f.write_char('%')?,
+            }
+        }
+
+        Ok(())
+    }
+    /// Write the fraction of a second, if any, as `.` followed by three, six or nine digits.
+    fn write_fraction(&self, f: &mut impl Write) -> fmt::Result {
+        match self.nanos {
+            0 => Ok(()),
+            n if n % 1_000_000 == 0 => write!(f, ".{:03}", n / 1_000_000),
+            n if n % 1_000 == 0 => write!(f, ".{:06}", n / 1_000),
+            n => write!(f, ".{n:09}"),
+        }
+    }
+    /// Write the offset from UTC.
+    fn write_offset(&self, f: &mut impl Write, colon: bool) -> fmt::Result {
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let offset = self.offset.unsigned_abs() / 60;
+        let (hours, minutes) = (offset / 60, offset % 60);
+
+        if colon {
+            write!(f, "{sign}{hours:02}:{minutes:02}")
+        } else {
+            write!(f, "{sign}{hours:02}{minutes:02}")
+        }
+    }
+}
+
+impl PartialEq for DateTime {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.secs == other.secs && self.nanos == other.nanos
+    }
+}
+
+impl Eq for DateTime {}
+
+impl PartialOrd for DateTime {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DateTime {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.secs
+            .cmp(&other.secs)
+            .then(self.nanos.cmp(&other.nanos))
+    }
+}
+
+impl Hash for DateTime {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.secs.hash(state);
+        self.nanos.hash(state);
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Format the [`DateTime`] in RFC 3339 format.
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_formatted(f, "%Y-%m-%dT%H:%M:%S%.f")?;
+
+        if self.offset == 0 {
+            f.write_char('Z')
+        } else {
+            self.write_offset(f, true)
+        }
+    }
+}
+
+impl fmt::Debug for DateTime {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DateTime({self})")
+    }
+}
+
+/// Parse an offset from UTC, e.g. `+08:00`, `-0530` or `Z`, into a number of seconds.
+pub(crate) fn parse_offset(input: &str) -> RhaiResultOf<i32> {
+    let mut parser = Parser::new(input);
+    let offset = parser.offset(false)?;
+    parser.finish()?;
+    Ok(offset)
+}
+
+/// A simple parser for date/time strings.
+struct Parser<'a> {
+    /// The input string.
+    input: &'a str,
+    /// The remaining input.
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    /// Create a new [`Parser`].
+    #[inline(always)]
+    const fn new(input: &'a str) -> Self {
+        Self { input, rest: input }
+    }
+    /// Make an error.
+    #[cold]
+    fn error(&self, msg: &str) -> Box<crate::EvalAltResult> {
+        let at = self.input.len() - self.rest.len();
+        make_parse_err(self.input, format!("{msg} at position {}", at + 1))
+    }
+    /// Consume a character if it matches.
+    fn eat(&mut self, ch: char) -> bool {
+        match self.rest.strip_prefix(ch) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Consume a character, which must match.
+    fn literal(&mut self, ch: char) -> RhaiResultOf<()> {
+        if self.eat(ch) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expecting '{ch}'")))
+        }
+    }
+    /// Consume an unsigned number of between `min` and `max` digits.
+    fn number(&mut self, min: usize, max: usize) -> RhaiResultOf<i64> {
+        let len = self
+            .rest
+            .bytes()
+            .take(max)
+            .take_while(u8::is_ascii_digit)
+            .count();
+
+        if len < min {
+            return Err(self.error("expecting a number"));
+        }
+
+        let (digits, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Ok(digits.parse().unwrap())
+    }
+    /// Consume a number with an optional sign and between `min` and `max` digits.
+    fn signed_number(&mut self, min: usize, max: usize) -> RhaiResultOf<i64> {
+        if self.eat('-') {
+            Ok(-self.number(min, max)?)
+        } else {
+            self.eat('+');
+            self.number(min, max)
+        }
+    }
+    /// Consume the fraction of a second as nanoseconds, with up to nine digits.
+    fn fraction(&mut self) -> RhaiResultOf<u32> {
+        let len = self.rest.bytes().take_while(u8::is_ascii_digit).count();
+
+        if !(1..=9).contains(&len) {
+            return Err(self.error("expecting one to nine digits for the fraction of a second"));
+        }
+
+        let (digits, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        let value: u32 = digits.parse().unwrap();
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(value * 10_u32.pow(9 - len as u32))
+    }
+    /// Consume an offset from UTC in seconds, e.g. `+08:00`, `-0530` or `Z`.
+    fn offset(&mut self, colon: bool) -> RhaiResultOf<i32> {
+        if self.eat('Z') || self.eat('z') {
+            return Ok(0);
+        }
+
+        let sign = if self.eat('+') {
+            1
+        } else if self.eat('-') {
+            -1
+        } else {
+            return Err(self.error("expecting an offset from UTC"));
+        };
+
+        let hours = self.number(2, 2)?;
+        if colon {
+            self.literal(':')?;
+        } else {
+            self.eat(':');
+        }
+        let minutes = self.number(2, 2)?;
+
+        if hours > 23 || minutes > 59 {
+            return Err(self.error("invalid offset from UTC"));
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(sign * (hours * 3600 + minutes * 60) as i32)
+    }
+    /// Consume one of a list of names (case-insensitive), returning its index.
+    fn name(&mut self, names: &[&str], abbreviated: bool) -> RhaiResultOf<usize> {
+        for (i, name) in names.iter().enumerate() {
+            let name = if abbreviated { &name[..3] } else { name };
+
+            if let Some(prefix) = self.rest.get(..name.len()) {
+                if prefix.eq_ignore_ascii_case(name) {
+                    self.rest = &self.rest[name.len()..];
+                    return Ok(i);
+                }
+            }
+        }
+
+        Err(self.error("unrecognized name"))
+    }
+    /// Make sure that the entire input is consumed.
+    fn finish(&self) -> RhaiResultOf<()> {
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected trailing characters"))
+        }
+    }
+}
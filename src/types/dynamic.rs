@@ -467,6 +467,11 @@ impl Hash for Dynamic {
                     return range.hash(state);
                 }
 
+                #[cfg(feature = "bigint")]
+                if let Some(value) = _value_any.downcast_ref::<crate::BigInt>() {
+                    return value.hash(state);
+                }
+
                 unimplemented!("Custom type {} cannot be hashed", self.type_name())
             }
 
@@ -542,6 +547,11 @@ impl fmt::Display for Dynamic {
                     return fmt::Display::fmt(value, f);
                 }
 
+                #[cfg(feature = "bigint")]
+                if let Some(value) = _value_any.downcast_ref::<crate::BigInt>() {
+                    return fmt::Display::fmt(value, f);
+                }
+
                 if let Some(range) = _value_any.downcast_ref::<ExclusiveRange>() {
                     return if range.end == INT::MAX {
                         write!(f, "{}..", range.start)
@@ -710,6 +720,11 @@ impl fmt::Debug for Dynamic {
                     return fmt::Debug::fmt(value, f);
                 }
 
+                #[cfg(feature = "bigint")]
+                if let Some(value) = _value_any.downcast_ref::<crate::BigInt>() {
+                    return fmt::Debug::fmt(value, f);
+                }
+
                 if let Some(range) = _value_any.downcast_ref::<ExclusiveRange>() {
                     return if range.end == INT::MAX {
                         write!(f, "{}..", range.start)
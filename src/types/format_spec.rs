@@ -0,0 +1,236 @@
+//! Module defining format specifiers used by `format` and string interpolation.
+
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::str::Chars;
+
+/// Alignment of text within a field.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FormatAlign {
+    /// Left-aligned (`<`).
+    Left,
+    /// Centered (`^`).
+    Center,
+    /// Right-aligned (`>`).
+    Right,
+}
+
+/// Form of the formatted output.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum FormatKind {
+    /// Normal display (no type character).
+    #[default]
+    Display,
+    /// Debug (`?`).
+    Debug,
+    /// Lower-case hexadecimal (`x`).
+    LowerHex,
+    /// Upper-case hexadecimal (`X`).
+    UpperHex,
+    /// Octal (`o`).
+    Octal,
+    /// Binary (`b`).
+    Binary,
+    /// Lower-case exponent (`e`).
+    LowerExp,
+    /// Upper-case exponent (`E`).
+    UpperExp,
+}
+
+/// A parsed format specifier.
+///
+/// The syntax follows Rust's [`std::fmt`] closely:
+///
+/// > `[[fill]align][sign]['#']['0'][width]['.' precision][type]`
+///
+/// where `align` is one of `<`, `^` or `>`, `sign` is `+` or `-`, and `type` is empty or one of
+/// `?`, `x`, `X`, `o`, `b`, `e` or `E`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FormatSpec {
+    /// Fill character.
+    pub fill: char,
+    /// Alignment, if any.
+    pub align: Option<FormatAlign>,
+    /// Always show the sign of numbers?
+    pub sign_plus: bool,
+    /// Alternate form (i.e. `0x`, `0o` and `0b` prefixes)?
+    pub alternate: bool,
+    /// Pad numbers with leading zeros?
+    pub zero_pad: bool,
+    /// Minimum width of the field, in characters.
+    pub width: Option<usize>,
+    /// Number of decimal places for numbers, or maximum number of characters for other values.
+    pub precision: Option<usize>,
+    /// Form of the formatted output.
+    pub kind: FormatKind,
+}
+
+impl Default for FormatSpec {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            sign_plus: false,
+            alternate: false,
+            zero_pad: false,
+            width: None,
+            precision: None,
+            kind: FormatKind::Display,
+        }
+    }
+}
+
+impl FormatSpec {
+    /// Parse a format specifier (the text after `:`).
+    ///
+    /// Returns [`None`] if the specifier is malformed.
+    #[must_use]
+    pub fn parse(spec: &str) -> Option<Self> {
+        fn parse_align(ch: char) -> Option<FormatAlign> {
+            match ch {
+                '<' => Some(FormatAlign::Left),
+                '^' => Some(FormatAlign::Center),
+                '>' => Some(FormatAlign::Right),
+                _ => None,
+            }
+        }
+        fn parse_number(chars: &mut std::iter::Peekable<Chars>) -> Option<Option<usize>> {
+            let mut value: Option<usize> = None;
+
+            while let Some(&ch) = chars.peek() {
+                let Some(digit) = ch.to_digit(10) else {
+                    break;
+                };
+                chars.next();
+                value = Some(
+                    value
+                        .unwrap_or(0)
+                        .checked_mul(10)?
+                        .checked_add(digit as usize)?,
+                );
+            }
+
+            Some(value)
+        }
+
+        let mut result = Self::default();
+
+        let mut chars = spec.chars().peekable();
+
+        let mut lookahead = spec.chars();
+        match (lookahead.next(), lookahead.next().and_then(parse_align)) {
+            (Some(fill), Some(align)) => {
+                result.fill = fill;
+                result.align = Some(align);
+                chars.next();
+                chars.next();
+            }
+            (Some(ch), _) if parse_align(ch).is_some() => {
+                result.align = parse_align(ch);
+                chars.next();
+            }
+            (Some(_), _) => (),
+            (None, _) => return Some(result),
+        }
+
+        match chars.peek() {
+            Some('+') => {
+                result.sign_plus = true;
+                chars.next();
+            }
+            Some('-') => {
+                chars.next();
+            }
+            _ => (),
+        }
+        if chars.peek() == Some(&'#') {
+            result.alternate = true;
+            chars.next();
+        }
+        if chars.peek() == Some(&'0') {
+            result.zero_pad = true;
+            chars.next();
+        }
+
+        result.width = parse_number(&mut chars)?;
+
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            result.precision = Some(parse_number(&mut chars)??);
+        }
+
+        result.kind = match chars.next() {
+            None => return Some(result),
+            Some('?') => FormatKind::Debug,
+            Some('x') => FormatKind::LowerHex,
+            Some('X') => FormatKind::UpperHex,
+            Some('o') => FormatKind::Octal,
+            Some('b') => FormatKind::Binary,
+            Some('e') => FormatKind::LowerExp,
+            Some('E') => FormatKind::UpperExp,
+            Some(_) => return None,
+        };
+
+        if chars.next().is_some() {
+            return None;
+        }
+
+        Some(result)
+    }
+
+    /// Is this a radix (i.e. hexadecimal, octal or binary) form?
+    #[inline]
+    #[must_use]
+    pub const fn is_radix(&self) -> bool {
+        matches!(
+            self.kind,
+            FormatKind::LowerHex | FormatKind::UpperHex | FormatKind::Octal | FormatKind::Binary
+        )
+    }
+
+    /// Pad already-formatted text to the field width.
+    ///
+    /// For numbers, `numeric` holds `(is_negative, prefix)`; `text` should not contain the sign,
+    /// and `prefix` (e.g. `0x`) is placed between the sign and the digits.
+    /// Numbers are right-aligned by default, while other values are left-aligned.
+    #[must_use]
+    pub fn pad(&self, text: &str, numeric: Option<(bool, &str)>) -> String {
+        let mut head = String::new();
+
+        if let Some((is_negative, prefix)) = numeric {
+            if is_negative {
+                head.push('-');
+            } else if self.sign_plus {
+                head.push('+');
+            }
+            head.push_str(prefix);
+        }
+
+        let len = head.chars().count() + text.chars().count();
+        let padding = self.width.map_or(0, |w| w.saturating_sub(len));
+
+        let mut result = String::with_capacity(head.len() + text.len() + padding);
+
+        if numeric.is_some() && self.zero_pad {
+            result.push_str(&head);
+            result.extend(std::iter::repeat('0').take(padding));
+            result.push_str(text);
+            return result;
+        }
+
+        let (before, after) = match self.align {
+            Some(FormatAlign::Left) => (0, padding),
+            Some(FormatAlign::Center) => (padding / 2, padding - padding / 2),
+            Some(FormatAlign::Right) => (padding, 0),
+            None if numeric.is_some() => (padding, 0),
+            None => (0, padding),
+        };
+
+        result.extend(std::iter::repeat(self.fill).take(before));
+        result.push_str(&head);
+        result.push_str(text);
+        result.extend(std::iter::repeat(self.fill).take(after));
+        result
+    }
+}
@@ -0,0 +1,268 @@
+//! The `Generator` type.
+#![cfg(feature = "resumable")]
+#![cfg(not(feature = "no_function"))]
+
+use crate::api::resumable::without_suspend;
+use crate::ast::{EncapsulatedEnviron, ScriptFuncDef};
+use crate::eval::{Caches, GlobalRuntimeState};
+use crate::func::FnCallArgs;
+use crate::{
+    Dynamic, Engine, FnArgsVec, Position, RhaiResultOf, Scope, Shared, SharedModule, ThinVec, ERR,
+};
+use corosensei::stack::DefaultStack;
+use corosensei::{CoroutineResult, ScopedCoroutine, Yielder};
+use std::cell::Cell;
+use std::fmt;
+use std::ptr;
+#[cfg(not(feature = "unchecked"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A running generator, which receives the number of operations when resumed and yields a value
+/// together with the updated number of operations.
+type Coroutine<'a> =
+    ScopedCoroutine<'a, u64, (Dynamic, u64), (RhaiResultOf<()>, u64), DefaultStack>;
+
+thread_local! {
+    /// [`Yielder`] of the generator currently running on this thread, if any.
+    static YIELDER: Cell<*const Yielder<u64, (Dynamic, u64)>> = const { Cell::new(ptr::null()) };
+}
+
+/// A generator, returned by calling a script-defined function that contains `yield` statements.
+///
+/// The body of the function is not run when it is called. Instead, it is run lazily when the
+/// generator is iterated over by a `for` loop, which then receives each value passed to `yield`
+/// one at a time. Each `for` loop runs the function from the start.
+///
+/// Only available under the `resumable` feature.
+///
+/// # Cost
+///
+/// While a `for` loop iterates over a generator, the generator function runs on its own stack,
+/// the size of which is set via [`Engine::set_resumable_stack_size`] (2 MB by default, of which
+/// only touched pages are actually allocated). The number of generators running at any instant
+/// is limited via [`Engine::set_max_generators`].
+///
+/// # Example
+///
+/// ```rhai
+/// fn count_to(n) {
+///     let x = 1;
+///
+///     while x <= n {
+///         yield x;
+///         x += 1;
+///     }
+/// }
+///
+/// for x in count_to(3) {
+///     print(x);       // prints 1, 2, 3
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Generator {
+    /// The generator function.
+    fn_def: Shared<ScriptFuncDef>,
+    /// Encapsulated environment of the generator function, if any.
+    env: Option<EncapsulatedEnviron>,
+    /// Functions available when the generator function was called.
+    lib: ThinVec<SharedModule>,
+    /// Bound `this` pointer, if any.
+    this: Option<Dynamic>,
+    /// Arguments to the generator function.
+    args: FnArgsVec<Dynamic>,
+}
+
+impl fmt::Debug for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Generator").field(&self.fn_def.name).finish()
+    }
+}
+
+impl Generator {
+    /// Get the name of the generator function.
+    #[inline(always)]
+    #[must_use]
+    pub fn fn_name(&self) -> &str {
+        &self.fn_def.name
+    }
+}
+
+/// Set the [`Yielder`] of the generator currently running on this thread.
+///
+/// This is never inlined, so that the address of the thread-local is not reused across a
+/// suspension, after which the generator may be running on another thread.
+#[inline(never)]
+fn set_yielder(yielder: *const Yielder<u64, (Dynamic, u64)>) {
+    YIELDER.with(|y| y.set(yielder));
+}
+
+/// Guard restoring the outer running generator (if any) when dropped, even on panic.
+struct Restore(*const Yielder<u64, (Dynamic, u64)>);
+
+impl Drop for Restore {
+    #[inline(always)]
+    fn drop(&mut self) {
+        YIELDER.with(|y| y.set(self.0));
+    }
+}
+
+/// Guard counting a running [`Generator`] towards the [limit][Engine::max_generators].
+#[cfg(not(feature = "unchecked"))]
+struct Running<'a>(&'a AtomicUsize);
+
+#[cfg(not(feature = "unchecked"))]
+impl Drop for Running<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A [`Generator`] running on its own stack.
+pub(crate) struct GeneratorIter<'a> {
+    /// The running generator, or [`None`] once the generator function has returned.
+    coroutine: Option<Coroutine<'a>>,
+    /// Guard counting the generator as running until its stack is freed.
+    #[cfg(not(feature = "unchecked"))]
+    _running: Running<'a>,
+}
+
+impl GeneratorIter<'_> {
+    /// Run the generator until the next `yield`, returning the value.
+    ///
+    /// Returns [`None`] when the generator function returns.
+    pub fn next(&mut self, global: &mut GlobalRuntimeState) -> Option<RhaiResultOf<Dynamic>> {
+        let coroutine = self.coroutine.as_mut()?;
+
+        let result = {
+            let _restore = Restore(YIELDER.with(Cell::get));
+            without_suspend(|| coroutine.resume(global.num_operations))
+        };
+
+        match result {
+            CoroutineResult::Yield((value, num_operations)) => {
+                global.num_operations = num_operations;
+                Some(Ok(value))
+            }
+            CoroutineResult::Return((result, num_operations)) => {
+                global.num_operations = num_operations;
+                self.coroutine = None;
+                result.err().map(Err)
+            }
+        }
+    }
+}
+
+/// Pass a value to the `for` loop iterating over the generator currently running, suspending it.
+///
+/// Returns `false` if not called within a running generator.
+pub(crate) fn yield_value(global: &mut GlobalRuntimeState, value: Dynamic) -> bool {
+    let yielder = YIELDER.with(Cell::get);
+
+    if yielder.is_null() {
+        return false;
+    }
+
+    // SAFETY: The pointer is only set while a generator is running on this thread, so this code
+    //         is running on the stack of that generator, which owns the `Yielder`.
+    let yielder = unsafe { &*yielder };
+
+    global.num_operations = yielder.suspend((value, global.num_operations));
+
+    // Nested generators may have run in the meantime, and the evaluation may have been resumed on
+    // another thread (under `sync`)
+    set_yielder(yielder);
+
+    true
+}
+
+impl Engine {
+    /// Create a [`Generator`] for a call to a script-defined generator function.
+    pub(crate) fn make_generator(
+        &self,
+        global: &GlobalRuntimeState,
+        this_ptr: Option<&mut Dynamic>,
+        env: Option<&EncapsulatedEnviron>,
+        fn_def: &Shared<ScriptFuncDef>,
+        args: &mut FnCallArgs,
+    ) -> Generator {
+        Generator {
+            fn_def: fn_def.clone(),
+            env: env.cloned(),
+            lib: global.lib.clone(),
+            this: this_ptr.map(|v| v.clone()),
+            args: args.iter_mut().map(|v| v.take()).collect(),
+        }
+    }
+    /// Start running a [`Generator`] on its own stack.
+    ///
+    /// The generator function is not run until [`GeneratorIter::next`] is called.
+    pub(crate) fn start_generator(
+        &self,
+        global: &GlobalRuntimeState,
+        generator: Generator,
+        pos: Position,
+    ) -> RhaiResultOf<GeneratorIter<'_>> {
+        // Each running generator takes up its own stack
+        #[cfg(not(feature = "unchecked"))]
+        let running = {
+            let running = Running(&self.running_generators);
+
+            if running.0.fetch_add(1, Ordering::Relaxed) >= self.max_generators() {
+                return Err(
+                    ERR::ErrorDataTooLarge("Number of running generators".into(), pos).into(),
+                );
+            }
+            running
+        };
+
+        let stack = DefaultStack::new(self.resumable_stack_size)
+            .map_err(|err| ERR::ErrorSystem("Cannot allocate stack".into(), err.into()))?;
+
+        let Generator {
+            fn_def,
+            env,
+            lib,
+            mut this,
+            mut args,
+        } = generator;
+
+        let mut global = global.clone();
+        global.lib = lib;
+        global.level += 1;
+
+        let coroutine = Coroutine::with_stack(stack, move |yielder, num_operations| {
+            set_yielder(yielder);
+
+            global.num_operations = num_operations;
+
+            let caches = &mut Caches::new();
+            let scope = &mut Scope::new();
+            let this_ptr = this.as_mut();
+            let env = env.as_ref();
+            let args = &mut args.iter_mut().collect::<FnArgsVec<_>>();
+
+            let result = self
+                .run_script_fn(
+                    &mut global,
+                    caches,
+                    scope,
+                    this_ptr,
+                    env,
+                    &fn_def,
+                    args,
+                    true,
+                    pos,
+                )
+                .map(|_| ());
+
+            (result, global.num_operations)
+        });
+
+        Ok(GeneratorIter {
+            coroutine: Some(coroutine),
+            #[cfg(not(feature = "unchecked"))]
+            _running: running,
+        })
+    }
+}
@@ -0,0 +1,366 @@
+//! The `LazyIter` type.
+#![cfg(not(feature = "no_index"))]
+
+use crate::{Array, Dynamic, FnPtr, NativeCallContext, RhaiResultOf, ERR, INT};
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// A lazy iterator, created from any iterable value (e.g. a range, an array or a custom type with a
+/// registered type iterator).
+///
+/// Adapters such as `map` and `filter` do not run when they are called. Instead, they are recorded
+/// and then run one element at a time when the iterator is iterated over by a `for` loop or
+/// `collect`ed into an array, so no intermediate arrays are allocated.
+///
+/// A [`LazyIter`] is a plain value which starts from the beginning every time it is iterated over.
+///
+/// Not available under `no_index`.
+///
+/// # Example
+///
+/// ```rhai
+/// let x = (0..1_000_000).filter(|n| n % 7 == 0).map(|n| n * n).take(3);
+///
+/// for n in x {
+///     print(n);       // prints 0, 49, 196
+/// }
+/// ```
+#[derive(Clone)]
+pub struct LazyIter {
+    /// The iterable value.
+    source: Dynamic,
+    /// Adapters to apply, in order.
+    adapters: Vec<Adapter>,
+}
+
+/// An adapter applied to the elements of a [`LazyIter`].
+#[derive(Debug, Clone)]
+enum Adapter {
+    /// Transform each element.
+    Map(FnPtr),
+    /// Keep only elements matching a predicate.
+    Filter(FnPtr),
+    /// Keep elements while they match a predicate.
+    TakeWhile(FnPtr),
+    /// Keep only the first number of elements.
+    Take(usize),
+    /// Skip the first number of elements.
+    Skip(usize),
+    /// Keep every number-th element.
+    StepBy(usize),
+    /// Pair each element with its index.
+    Enumerate,
+    /// Continue with the elements of another iterable value.
+    Chain(Dynamic),
+    /// Pair each element with an element from another iterable value.
+    Zip(Dynamic),
+}
+
+impl fmt::Debug for LazyIter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyIter")
+            .field("source", &self.source)
+            .field("adapters", &self.adapters)
+            .finish()
+    }
+}
+
+impl LazyIter {
+    /// Create a new [`LazyIter`] over an iterable value.
+    ///
+    /// The value is not checked for being iterable until the iterator is run.
+    #[inline(always)]
+    #[must_use]
+    pub fn new(source: impl Into<Dynamic>) -> Self {
+        Self {
+            source: source.into(),
+            adapters: Vec::new(),
+        }
+    }
+    /// Add an adapter.
+    #[inline]
+    #[must_use]
+    fn with(mut self, adapter: Adapter) -> Self {
+        self.adapters.push(adapter);
+        self
+    }
+    /// Transform each element via a function pointer.
+    #[inline(always)]
+    #[must_use]
+    pub fn map(self, mapper: FnPtr) -> Self {
+        self.with(Adapter::Map(mapper))
+    }
+    /// Keep only the elements for which a function pointer returns `true`.
+    #[inline(always)]
+    #[must_use]
+    pub fn filter(self, filter: FnPtr) -> Self {
+        self.with(Adapter::Filter(filter))
+    }
+    /// Keep elements for as long as a function pointer returns `true`.
+    #[inline(always)]
+    #[must_use]
+    pub fn take_while(self, filter: FnPtr) -> Self {
+        self.with(Adapter::TakeWhile(filter))
+    }
+    /// Keep only the first `n` elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn take(self, n: usize) -> Self {
+        self.with(Adapter::Take(n))
+    }
+    /// Skip the first `n` elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn skip(self, n: usize) -> Self {
+        self.with(Adapter::Skip(n))
+    }
+    /// Keep the first element and then every `step`-th element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    #[inline]
+    #[must_use]
+    pub fn step_by(self, step: usize) -> Self {
+        assert!(step > 0, "step must be positive");
+        self.with(Adapter::StepBy(step))
+    }
+    /// Turn each element into an array of `[index, element]`.
+    #[inline(always)]
+    #[must_use]
+    pub fn enumerate(self) -> Self {
+        self.with(Adapter::Enumerate)
+    }
+    /// Continue with the elements of another iterable value.
+    #[inline(always)]
+    #[must_use]
+    pub fn chain(self, other: impl Into<Dynamic>) -> Self {
+        self.with(Adapter::Chain(other.into()))
+    }
+    /// Turn each element into an array of `[element, other element]`, where the other elements
+    /// come from another iterable value.
+    ///
+    /// Iteration stops when either runs out of elements.
+    #[inline(always)]
+    #[must_use]
+    pub fn zip(self, other: impl Into<Dynamic>) -> Self {
+        self.with(Adapter::Zip(other.into()))
+    }
+    /// Start running the iterator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorFor`][ERR::ErrorFor] if the source (or any value to chain or zip with) is not
+    /// iterable.
+    pub(crate) fn start(&self, ctx: &NativeCallContext) -> RhaiResultOf<LazyIterState> {
+        let mut state = LazyIterState::from_value(ctx, self.source.clone())?;
+
+        for adapter in &self.adapters {
+            let stage = match adapter {
+                Adapter::Map(f) => Stage::Map(f.clone()),
+                Adapter::Filter(f) => Stage::Filter(f.clone()),
+                Adapter::TakeWhile(f) => Stage::TakeWhile(f.clone(), false),
+                Adapter::Take(n) => Stage::Take(*n),
+                Adapter::Skip(n) => Stage::Skip(*n),
+                Adapter::StepBy(step) => Stage::StepBy(*step, true),
+                Adapter::Enumerate => Stage::Enumerate(0),
+                Adapter::Chain(other) => {
+                    Stage::Chain(LazyIterState::from_value(ctx, other.clone())?.into(), false)
+                }
+                Adapter::Zip(other) => {
+                    Stage::Zip(LazyIterState::from_value(ctx, other.clone())?.into())
+                }
+            };
+
+            state = LazyIterState::Stage(state.into(), stage);
+        }
+
+        Ok(state)
+    }
+    /// Run the iterator and collect all the elements into an [`Array`].
+    pub(crate) fn collect(&self, ctx: &NativeCallContext) -> RhaiResultOf<Array> {
+        let mut state = self.start(ctx)?;
+        let mut array = Array::new();
+
+        while let Some(value) = state.next(ctx) {
+            array.push(value?);
+
+            #[cfg(not(feature = "unchecked"))]
+            ctx.engine().throw_on_size((array.len(), 0, 0))?;
+        }
+
+        Ok(array)
+    }
+}
+
+/// A stage of a running [`LazyIter`].
+pub(crate) enum Stage {
+    /// Transform each element.
+    Map(FnPtr),
+    /// Keep only elements matching a predicate.
+    Filter(FnPtr),
+    /// Keep elements while they match a predicate, and whether it is done.
+    TakeWhile(FnPtr, bool),
+    /// Number of elements left to keep.
+    Take(usize),
+    /// Number of elements left to skip.
+    Skip(usize),
+    /// Keep every number-th element, and whether this is the first element.
+    StepBy(usize, bool),
+    /// Index of the next element.
+    Enumerate(usize),
+    /// Elements to continue with, and whether the upstream elements are done.
+    Chain(Box<LazyIterState>, bool),
+    /// Elements to pair with.
+    Zip(Box<LazyIterState>),
+}
+
+/// A running [`LazyIter`].
+pub(crate) enum LazyIterState {
+    /// Elements from a type iterator.
+    Source(Box<dyn Iterator<Item = RhaiResultOf<Dynamic>>>),
+    /// Elements from an upstream iterator passed through a stage.
+    Stage(Box<LazyIterState>, Stage),
+}
+
+impl LazyIterState {
+    /// Start iterating over an iterable value.
+    fn from_value(ctx: &NativeCallContext, value: Dynamic) -> RhaiResultOf<Self> {
+        let value = value.flatten();
+
+        let value = match value.try_cast_result::<LazyIter>() {
+            Ok(iter) => return iter.start(ctx),
+            Err(value) => value,
+        };
+
+        let iter_func = ctx
+            .engine()
+            .get_type_iterator(ctx.global_runtime_state(), value.type_id())
+            .ok_or_else(|| ERR::ErrorFor(ctx.position()))?;
+
+        Ok(Self::Source(iter_func(value)))
+    }
+    /// Call a predicate function pointer on an element.
+    fn test(
+        ctx: &NativeCallContext,
+        fn_name: &str,
+        f: &FnPtr,
+        value: &Dynamic,
+    ) -> RhaiResultOf<bool> {
+        let mut value = value.clone();
+
+        Ok(
+            f.call_raw_with_extra_args(fn_name, ctx, Some(&mut value), [], [], Some(0))?
+                .as_bool()
+                .unwrap_or(false),
+        )
+    }
+    /// Get the next element.
+    ///
+    /// Errors are returned as elements, after which the iterator should no longer be used.
+    pub(crate) fn next(&mut self, ctx: &NativeCallContext) -> Option<RhaiResultOf<Dynamic>> {
+        let (upstream, stage) = match self {
+            Self::Source(iter) => return iter.next(),
+            Self::Stage(upstream, stage) => (upstream, stage),
+        };
+
+        match stage {
+            Stage::Map(f) => Some(upstream.next(ctx)?.and_then(|mut value| {
+                f.call_raw_with_extra_args("map", ctx, Some(&mut value), [], [], Some(0))
+            })),
+            Stage::Filter(f) => loop {
+                match upstream.next(ctx)? {
+                    Ok(value) => match Self::test(ctx, "filter", f, &value) {
+                        Ok(true) => return Some(Ok(value)),
+                        Ok(false) => (),
+                        Err(err) => return Some(Err(err)),
+                    },
+                    Err(err) => return Some(Err(err)),
+                }
+            },
+            Stage::TakeWhile(_, true) => None,
+            Stage::TakeWhile(f, done) => match upstream.next(ctx)? {
+                Ok(value) => match Self::test(ctx, "take_while", f, &value) {
+                    Ok(true) => Some(Ok(value)),
+                    Ok(false) => {
+                        *done = true;
+                        None
+                    }
+                    Err(err) => Some(Err(err)),
+                },
+                Err(err) => Some(Err(err)),
+            },
+            Stage::Take(0) => None,
+            Stage::Take(n) => {
+                *n -= 1;
+                upstream.next(ctx)
+            }
+            Stage::Skip(n) => {
+                while *n > 0 {
+                    *n -= 1;
+
+                    if let Err(err) = upstream.next(ctx)? {
+                        return Some(Err(err));
+                    }
+                }
+                upstream.next(ctx)
+            }
+            Stage::StepBy(step, first) => {
+                if !*first {
+                    for _ in 1..*step {
+                        if let Err(err) = upstream.next(ctx)? {
+                            return Some(Err(err));
+                        }
+                    }
+                }
+                *first = false;
+                upstream.next(ctx)
+            }
+            Stage::Enumerate(index) => {
+                let value = upstream.next(ctx)?;
+
+                #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+                let i = *index as INT;
+                *index += 1;
+
+                Some(value.map(|value| Dynamic::from_array(vec![i.into(), value])))
+            }
+            Stage::Chain(other, done) => {
+                if !*done {
+                    match upstream.next(ctx) {
+                        Some(value) => return Some(value),
+                        None => *done = true,
+                    }
+                }
+                other.next(ctx)
+            }
+            Stage::Zip(other) => {
+                let value = match upstream.next(ctx)? {
+                    Ok(value) => value,
+                    Err(err) => return Some(Err(err)),
+                };
+                let other_value = match other.next(ctx)? {
+                    Ok(value) => value,
+                    Err(err) => return Some(Err(err)),
+                };
+
+                Some(Ok(Dynamic::from_array(vec![value, other_value])))
+            }
+        }
+    }
+}
+
+impl From<crate::ExclusiveRange> for LazyIter {
+    #[inline(always)]
+    fn from(value: crate::ExclusiveRange) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<crate::InclusiveRange> for LazyIter {
+    #[inline(always)]
+    fn from(value: crate::InclusiveRange) -> Self {
+        Self::new(value)
+    }
+}
@@ -2,21 +2,28 @@
 
 pub mod bloom_filter;
 pub mod custom_types;
+pub mod datetime;
 pub mod dynamic;
 pub mod error;
 pub mod float;
 pub mod fn_ptr;
+pub mod format_spec;
+pub mod generator;
 pub mod immutable_string;
 pub mod interner;
+pub mod lazy_iter;
 pub mod parse_error;
 pub mod position;
 pub mod position_none;
 pub mod scope;
+pub mod set;
 pub mod var_def;
 pub mod variant;
 
 pub use bloom_filter::BloomFilterU64;
 pub use custom_types::{CustomTypeInfo, CustomTypesCollection};
+#[cfg(not(feature = "no_time"))]
+pub use datetime::DateTime;
 pub use dynamic::Dynamic;
 #[cfg(not(feature = "no_time"))]
 pub use dynamic::Instant;
@@ -24,8 +31,13 @@ pub use error::EvalAltResult;
 #[cfg(not(feature = "no_float"))]
 pub use float::FloatWrapper;
 pub use fn_ptr::FnPtr;
+#[cfg(feature = "resumable")]
+#[cfg(not(feature = "no_function"))]
+pub use generator::Generator;
 pub use immutable_string::ImmutableString;
 pub use interner::StringsInterner;
+#[cfg(not(feature = "no_index"))]
+pub use lazy_iter::LazyIter;
 pub use parse_error::{LexError, ParseError, ParseErrorType};
 pub use var_def::VarDefInfo;
 
@@ -35,4 +47,6 @@ pub use position::{Position, Span};
 pub use position_none::{Position, Span};
 
 pub use scope::Scope;
+#[cfg(not(feature = "no_index"))]
+pub use set::Set;
 pub use variant::Variant;
@@ -109,6 +109,8 @@ pub enum ParseErrorType {
     MalformedInExpr(String),
     /// A capturing  has syntax error. Wrapped value is the error description (if any).
     MalformedCapture(String),
+    /// A pattern in a `match` expression has syntax error. Wrapped value is the error description (if any).
+    MalformedPattern(String),
     /// A map definition has duplicated property names. Wrapped value is the property name.
     DuplicatedProperty(String),
     /// A `switch` case is duplicated.
@@ -185,6 +187,8 @@ pub enum ParseErrorType {
     LiteralTooLarge(String, usize),
     /// Break statement not inside a loop.
     LoopBreak,
+    /// Yield statement not inside a function.
+    WrongYield,
 }
 
 impl fmt::Display for ParseErrorType {
@@ -200,6 +204,9 @@ impl fmt::Display for ParseErrorType {
             Self::MalformedCapture(s) if s.is_empty()  => f.write_str("Invalid capturing"),
             Self::MalformedCapture(s) => f.write_str(s),
 
+            Self::MalformedPattern(s) if s.is_empty()  => f.write_str("Invalid pattern"),
+            Self::MalformedPattern(s) => f.write_str(s),
+
             Self::FnDuplicatedDefinition(s, n) => {
                 write!(f, "Function {s} with ")?;
                 match n {
@@ -252,6 +259,7 @@ impl fmt::Display for ParseErrorType {
             Self::ExprTooDeep => f.write_str("Expression exceeds maximum complexity"),
             Self::TooManyFunctions => f.write_str("Number of functions defined exceeds maximum limit"),
             Self::LoopBreak => f.write_str("Break statement should only be used inside a loop"),
+            Self::WrongYield => f.write_str("Yield statement should only be used inside a function"),
 
             #[allow(deprecated)]
             Self::DuplicatedSwitchCase => f.write_str("Duplicated switch case"),
@@ -0,0 +1,243 @@
+//! The `Set` type.
+#![cfg(not(feature = "no_index"))]
+
+use crate::types::dynamic::Union;
+use crate::{Dynamic, Position, RhaiResultOf, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    cmp::Ordering,
+    collections::BTreeSet,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+};
+
+/// A set of unique values.
+///
+/// Only values of primitive types can be placed in a [`Set`]: `()`, `bool`, integers,
+/// floating-point numbers, [`Decimal`][rust_decimal::Decimal], characters and strings.
+/// Values of different types are never equal, i.e. `1` and `1.0` are distinct elements.
+///
+/// Elements are kept in a well-defined order (first by type, then by value) so iteration is
+/// deterministic.
+///
+/// Not available under `no_index`.
+#[derive(Clone, Default, Eq, PartialEq, Hash)]
+pub struct Set(BTreeSet<SetItem>);
+
+/// An element of a [`Set`], ordered first by type and then by value.
+#[derive(Debug, Clone)]
+struct SetItem(Dynamic);
+
+impl SetItem {
+    /// Rank of the type of the value, used to order values of different types.
+    #[must_use]
+    const fn rank(&self) -> u8 {
+        match self.0 .0 {
+            Union::Unit(..) => 0,
+            Union::Bool(..) => 1,
+            Union::Int(..) => 2,
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(..) => 3,
+            #[cfg(feature = "decimal")]
+            Union::Decimal(..) => 4,
+            Union::Char(..) => 5,
+            Union::Str(..) => 6,
+            _ => u8::MAX,
+        }
+    }
+}
+
+impl Ord for SetItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.0 .0, &other.0 .0) {
+            (Union::Bool(a, ..), Union::Bool(b, ..)) => a.cmp(b),
+            (Union::Int(a, ..), Union::Int(b, ..)) => a.cmp(b),
+            #[cfg(not(feature = "no_float"))]
+            (Union::Float(a, ..), Union::Float(b, ..)) => a.total_cmp(b),
+            #[cfg(feature = "decimal")]
+            (Union::Decimal(a, ..), Union::Decimal(b, ..)) => a.cmp(b),
+            (Union::Char(a, ..), Union::Char(b, ..)) => a.cmp(b),
+            (Union::Str(a, ..), Union::Str(b, ..)) => a.as_str().cmp(b.as_str()),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for SetItem {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SetItem {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SetItem {}
+
+impl Hash for SetItem {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl fmt::Debug for Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Set {
+    /// Create a new, empty [`Set`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(BTreeSet::new())
+    }
+    /// Can a value be placed in a [`Set`]?
+    #[inline]
+    #[must_use]
+    pub fn is_valid_item(value: &Dynamic) -> bool {
+        SetItem(value.flatten_clone()).rank() != u8::MAX
+    }
+    /// Number of elements in the [`Set`].
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    /// Is the [`Set`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Does the [`Set`] contain a value?
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, value: &Dynamic) -> bool {
+        self.0.contains(&SetItem(value.flatten_clone()))
+    }
+    /// Add a value to the [`Set`].
+    ///
+    /// Returns `true` if the value was not already present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorMismatchDataType`][ERR::ErrorMismatchDataType] if the value cannot be placed
+    /// in a [`Set`].
+    pub fn insert(&mut self, value: Dynamic) -> RhaiResultOf<bool> {
+        let item = SetItem(value.flatten());
+
+        if item.rank() == u8::MAX {
+            return Err(ERR::ErrorMismatchDataType(
+                "()/bool/number/char/string".into(),
+                item.0.type_name().into(),
+                Position::NONE,
+            )
+            .into());
+        }
+
+        Ok(self.0.insert(item))
+    }
+    /// Remove a value from the [`Set`].
+    ///
+    /// Returns `true` if the value was present.
+    #[inline]
+    pub fn remove(&mut self, value: &Dynamic) -> bool {
+        self.0.remove(&SetItem(value.flatten_clone()))
+    }
+    /// Remove all elements from the [`Set`].
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+    /// Iterate through all the elements in the [`Set`], in order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Dynamic> {
+        self.0.iter().map(|item| &item.0)
+    }
+    /// Return a new [`Set`] containing all elements in either `self` or `other`.
+    #[inline]
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0.union(&other.0).cloned().collect())
+    }
+    /// Return a new [`Set`] containing all elements in both `self` and `other`.
+    #[inline]
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0.intersection(&other.0).cloned().collect())
+    }
+    /// Return a new [`Set`] containing all elements in `self` but not in `other`.
+    #[inline]
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(self.0.difference(&other.0).cloned().collect())
+    }
+    /// Return a new [`Set`] containing all elements in either `self` or `other` but not both.
+    #[inline]
+    #[must_use]
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        Self(self.0.symmetric_difference(&other.0).cloned().collect())
+    }
+    /// Are all elements in `self` also in `other`?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.0.is_subset(&other.0)
+    }
+    /// Do `self` and `other` have no elements in common?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.0.is_disjoint(&other.0)
+    }
+}
+
+/// An iterator over the elements of a [`Set`], in order.
+#[derive(Debug)]
+pub struct SetIntoIter(std::collections::btree_set::IntoIter<SetItem>);
+
+impl Iterator for SetIntoIter {
+    type Item = Dynamic;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|item| item.0)
+    }
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for SetIntoIter {}
+
+impl IntoIterator for Set {
+    type Item = Dynamic;
+    type IntoIter = SetIntoIter;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        SetIntoIter(self.0.into_iter())
+    }
+}
+
+impl FromIterator<Dynamic> for Set {
+    /// Collect values into a [`Set`], skipping values that cannot be placed in a [`Set`].
+    fn from_iter<T: IntoIterator<Item = Dynamic>>(iter: T) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|value| {
+            let _ = set.insert(value);
+        });
+        set
+    }
+}
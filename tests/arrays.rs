@@ -492,6 +492,72 @@ fn test_arrays_map_reduce() {
         .unwrap();
 }
 
+#[test]
+fn test_arrays_stats() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("sum([1, 2, 3, 4, 5])").unwrap(), 15);
+    assert_eq!(engine.eval::<INT>("product([1, 2, 3, 4, 5])").unwrap(), 120);
+    assert_eq!(engine.eval::<INT>("sum([])").unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("product([])").unwrap(), 1);
+    assert!(matches!(*engine.run(r#"sum([1, "x"])"#).unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(*engine.run(&format!("sum([{}, 1])", INT::MAX)).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        use rhai::FLOAT;
+
+        assert_eq!(engine.eval::<FLOAT>("sum([1, 2.5])").unwrap(), 3.5);
+        assert_eq!(engine.eval::<FLOAT>("mean([1, 2, 3, 4])").unwrap(), 2.5);
+        assert_eq!(engine.eval::<FLOAT>("median([5, 1, 4, 2])").unwrap(), 3.0);
+        assert_eq!(engine.eval::<FLOAT>("median([5, 1.5, 4])").unwrap(), 4.0);
+        assert_eq!(engine.eval::<FLOAT>("variance([2, 4, 4, 4, 5, 5, 7, 9])").unwrap(), 4.0);
+        assert_eq!(engine.eval::<FLOAT>("std_dev([2, 4, 4, 4, 5, 5, 7, 9])").unwrap(), 2.0);
+        assert_eq!(engine.eval::<FLOAT>("percentile([5, 4, 3, 2, 1], 25)").unwrap(), 2.0);
+        assert_eq!(engine.eval::<FLOAT>("percentile([1, 2, 3, 4, 5], 62.5)").unwrap(), 3.5);
+        assert_eq!(engine.eval::<FLOAT>("percentile([1, 2, 3, 4, 5], 100)").unwrap(), 5.0);
+        assert!(engine.eval::<()>("mean([])").is_ok());
+        assert!(matches!(*engine.run("percentile([1, 2, 3], 101)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+        assert!(matches!(*engine.run("median([1, ()])").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+    }
+
+    #[cfg(feature = "decimal")]
+    {
+        use rust_decimal::Decimal;
+
+        assert_eq!(engine.eval::<Decimal>("sum([1, to_decimal(2)])").unwrap(), Decimal::from(3));
+        assert_eq!(engine.eval::<Decimal>("mean([1, 2, to_decimal(6)])").unwrap(), Decimal::from(3));
+        assert_eq!(engine.eval::<Decimal>("std_dev([to_decimal(2), 4, 4, 4, 5, 5, 7, 9])").unwrap(), Decimal::from(2));
+    }
+}
+
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_arrays_helpers() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"["hello", "a", "world", "!"].min_by(|a, b| a.len() - b.len())"#).unwrap(), "a");
+    assert_eq!(engine.eval::<String>(r#"["hello", "a", "world", "!"].max_by(|a, b| a.len() - b.len())"#).unwrap(), "world");
+    assert!(engine.eval::<()>("[].min_by(|a, b| a - b)").is_ok());
+    assert_eq!(engine.eval::<String>(r#"let x = ["hello", "a", "world", "!!"]; x.sort_by_key(|v| v.len()); x.to_string()"#).unwrap(), r#"["a", "!!", "hello", "world"]"#);
+    assert_eq!(engine.eval::<INT>("let x = [3, 1, 2]; x.sort_by_key(|v, i| -i); x[0]").unwrap(), 2);
+    assert_eq!(engine.eval::<String>(r#"[1, 2, 3, 4, 5].group_by(|v| if v % 2 == 0 { "even" } else { "odd" }).to_string()"#).unwrap(), r#"#{"even": [2, 4], "odd": [1, 3, 5]}"#);
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].group_by(|v| v % 2)[\"1\"].len()").unwrap(), 2);
+    assert_eq!(engine.eval::<String>("[1, 2, 3, 4, 5].partition(|v| v < 3).to_string()").unwrap(), "[[1, 2], [3, 4, 5]]");
+    assert_eq!(engine.eval::<String>("[1, 2, 3, 4, 5].chunks(2).to_string()").unwrap(), "[[1, 2], [3, 4], [5]]");
+    assert_eq!(engine.eval::<String>("[1, 2, 3, 4].windows(3).to_string()").unwrap(), "[[1, 2, 3], [2, 3, 4]]");
+    assert_eq!(engine.eval::<INT>("[1, 2].windows(3).len()").unwrap(), 0);
+    assert!(matches!(*engine.run("[1, 2].chunks(0)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert_eq!(engine.eval::<String>("[1, [2, 3], [[4], 5], 6].flatten().to_string()").unwrap(), "[1, 2, 3, [4], 5, 6]");
+    assert_eq!(engine.eval::<INT>("[1, 3, 5, 7, 9].binary_search(7)").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("[1, 3, 5, 7, 9].binary_search(4)").unwrap(), -3);
+    assert_eq!(engine.eval::<INT>("[1, 3, 5, 7, 9].binary_search(10)").unwrap(), -6);
+    assert_eq!(engine.eval::<INT>(r#"["a", "c", "e"].binary_search("a")"#).unwrap(), 0);
+    assert!(matches!(*engine.run("[[1], [2]].binary_search([1])").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+}
+
 #[test]
 fn test_arrays_elvis() {
     let engine = Engine::new();
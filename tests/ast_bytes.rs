@@ -0,0 +1,157 @@
+use rhai::{Engine, EvalAltResult, Scope, INT};
+
+fn round_trip(engine: &Engine, script: &str) -> Result<INT, Box<EvalAltResult>> {
+    let ast = engine.compile(script).unwrap();
+    let bytes = ast.to_bytes()?;
+    let ast = engine.compile_from_bytes(&bytes)?;
+    engine.eval_ast(&ast)
+}
+
+#[test]
+fn test_ast_bytes() {
+    let engine = Engine::new();
+
+    assert_eq!(round_trip(&engine, "let x = 40; x + 2").unwrap(), 42);
+    assert_eq!(round_trip(&engine, r#"let s = "hello"; let t = `${s}, world!`; len(t)"#).unwrap(), 13);
+    assert_eq!(round_trip(&engine, "let x = 0; for i in 0..10 { if i % 2 == 0 { continue; } x += i; } x").unwrap(), 25);
+    assert_eq!(round_trip(&engine, "let x = 0; while x < 42 { x += 1; } x").unwrap(), 42);
+    assert_eq!(round_trip(&engine, "let x = 0; do { x -= 1; } until x < -10; -x").unwrap(), 11);
+    assert_eq!(round_trip(&engine, "let x = 0; try { throw 42; } catch (err) { x = err; } x").unwrap(), 42);
+    assert_eq!(round_trip(&engine, "let x = (); x ?? 42").unwrap(), 42);
+    assert_eq!(round_trip(&engine, "let x = 42; switch x { 1 => 1, 2 | 3 => 2, 10..20 => 3, 40..=50 if x > 41 => x, _ => 0 }").unwrap(), 42);
+    assert_eq!(round_trip(&engine, "let x = 'x'; switch x { 'a' => 1, \"x\" => 2, 'x' => 42, _ => 0 }").unwrap(), 42);
+    assert_eq!(round_trip(&engine, "let x = 42; match x { 1 | 2 => 1, 10..20 => 3, n: int if n > 40 => n, _ => 0 }").unwrap(), 42);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(round_trip(&engine, "let a = [1, 2, [3, 4]]; a[2][1] *= 10; a[2][1] + len(a)").unwrap(), 43);
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(round_trip(&engine, "match [1, [2, 39]] { [a, [b, ..rest]] | [b, a, rest] => a + b + rest[0] }").unwrap(), 42);
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(round_trip(&engine, "let [a, ..rest] = [40, 1, 1]; for [x, y] in [rest] { a += x + y; } a").unwrap(), 42);
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(round_trip(&engine, "let m = #{a: 1, b: #{c: 40}}; m.b.c += 1; m.a + m.b.c").unwrap(), 42);
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(round_trip(&engine, r#"match #{a: 40, "b c": 2} { #{ a: x: int, "b c": y } => x + y, _ => 0 }"#).unwrap(), 42);
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(round_trip(&engine, "fn f(#{x, y: z}) { x + z } const #{a} = #{a: 40}; f(#{x: a, y: 2})").unwrap(), 42);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(round_trip(&engine, "fn add(x, y) { x + y } add(40, 2)").unwrap(), 42);
+        assert_eq!(round_trip(&engine, "fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } } fib(6)").unwrap(), 8);
+        assert_eq!(round_trip(&engine, "private fn f() { 42 } let f = Fn(\"f\"); call(f)").unwrap(), 42);
+
+        #[cfg(not(feature = "no_object"))]
+        assert_eq!(round_trip(&engine, "fn inc() { this += 1; } let x = 41; x.inc(); x").unwrap(), 42);
+
+        #[cfg(not(feature = "no_closure"))]
+        assert_eq!(round_trip(&engine, "let x = 40; let f = |y| { x += y; x }; call(f, 1); call(f, 1)").unwrap(), 42);
+
+        #[cfg(feature = "resumable")]
+        assert_eq!(round_trip(&engine, "fn gen() { yield 40; yield; yield 2; } let x = 0; for v in gen() { x += v ?? 0; } x").unwrap(), 42);
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_module"))]
+    assert_eq!(round_trip(&engine, "fn foo(x) { global::ANSWER + x } const ANSWER = 40; foo(2)").unwrap(), 42);
+
+    // Inlined function bodies
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        let mut engine = Engine::new();
+        engine.set_optimization_level(rhai::OptimizationLevel::Full);
+        assert_eq!(round_trip(&engine, "fn add1(x) { x + 1 } let a = 41; add1(a)").unwrap(), 42);
+    }
+}
+
+#[test]
+fn test_ast_bytes_source() {
+    let engine = Engine::new();
+
+    let mut ast = engine.compile("42").unwrap();
+    ast.set_source("hello");
+
+    let ast = engine.compile_from_bytes(ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(ast.source(), Some("hello"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+}
+
+#[test]
+#[cfg(not(feature = "no_optimize"))]
+#[cfg(not(feature = "no_index"))]
+fn test_ast_bytes_constants() {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push_constant("x", 40 as INT);
+
+    let ast = engine.compile_with_scope(&scope, "let y = [x, 1, 1]; y[0] + len(y) - 1").unwrap();
+    let ast = engine.compile_from_bytes(ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+}
+
+#[test]
+fn test_ast_bytes_errors() {
+    let engine = Engine::new();
+
+    let bytes = engine.compile("let x = 42; x").unwrap().to_bytes().unwrap();
+
+    assert!(engine.compile_from_bytes(b"hello world").is_err());
+    assert!(engine.compile_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    let mut bytes = bytes;
+    bytes.push(0);
+    assert!(engine.compile_from_bytes(&bytes).is_err());
+
+    #[derive(Debug, Clone)]
+    struct Foo;
+
+    let mut scope = Scope::new();
+    scope.push_constant("foo", Foo);
+
+    #[cfg(not(feature = "no_optimize"))]
+    assert!(engine.compile_with_scope(&scope, "foo").unwrap().to_bytes().is_err());
+}
+
+#[test]
+fn test_ast_bytes_nesting() {
+    fn script(n: usize) -> String {
+        format!("let x = 1; {}", vec!["x"; n].join(" + "))
+    }
+
+    let mut engine = Engine::new();
+    #[cfg(not(feature = "unchecked"))]
+    engine.set_max_expr_depths(
+        0,
+        #[cfg(not(feature = "no_function"))]
+        0,
+    );
+
+    let bytes = engine.compile(script(50)).unwrap().to_bytes().unwrap();
+    assert_eq!(engine.eval_ast::<INT>(&engine.compile_from_bytes(&bytes).unwrap()).unwrap(), 50);
+
+    // Building a deeply-nested AST needs a large stack
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(|| {
+            let mut engine = Engine::new();
+            #[cfg(not(feature = "unchecked"))]
+            engine.set_max_expr_depths(
+                0,
+                #[cfg(not(feature = "no_function"))]
+                0,
+            );
+
+            let bytes = engine.compile(script(1000)).unwrap().to_bytes().unwrap();
+            let err = engine.compile_from_bytes(&bytes).unwrap_err();
+            assert!(err.to_string().contains("nesting"));
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "bigint")]
+use rhai::{BigInt, Engine, EvalAltResult, Scope, INT};
+
+#[test]
+fn test_bigint() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<BigInt>(r#"parse_bigint("123456789012345678901234567890") * 10"#).unwrap(), "1234567890123456789012345678900".parse().unwrap());
+    assert_eq!(engine.eval::<BigInt>(r#"parse_bigint("-ff", 16)"#).unwrap(), BigInt::from(-255));
+    assert_eq!(engine.eval::<String>(r#"to_string(to_bigint(255), 16)"#).unwrap(), "ff");
+    assert_eq!(engine.eval::<String>("let x = to_bigint(1); for n in 1..=25 { x *= n; } to_string(x)").unwrap(), "15511210043330985984000000");
+    assert_eq!(engine.eval::<String>("let x = to_bigint(2) ** 100; `${x}`").unwrap(), "1267650600228229401496703205376");
+    assert_eq!(engine.eval::<String>("type_of(to_bigint(42))").unwrap(), "bigint");
+
+    // Mixed arithmetic with integers
+    #[cfg(not(feature = "only_i32"))]
+    assert_eq!(engine.eval::<BigInt>("let x = 9223372036854775807; to_bigint(x) + 1").unwrap(), "9223372036854775808".parse().unwrap());
+    assert_eq!(engine.eval::<BigInt>("1 - to_bigint(3)").unwrap(), BigInt::from(-2));
+    assert_eq!(engine.eval::<BigInt>("let x = 6; x *= to_bigint(7); x").unwrap(), BigInt::from(42));
+    assert_eq!(engine.eval::<BigInt>("(to_bigint(17) / 5) + (to_bigint(17) % 5)").unwrap(), BigInt::from(5));
+    assert_eq!(engine.eval::<BigInt>("(2 ** to_bigint(70)) >> 68").unwrap(), BigInt::from(4));
+    assert_eq!(engine.eval::<BigInt>("to_bigint(1) << 70 >> 69").unwrap(), BigInt::from(2));
+    assert_eq!(engine.eval::<BigInt>("to_bigint(-7) >> 1").unwrap(), BigInt::from(-4));
+    assert_eq!(engine.eval::<BigInt>("(to_bigint(12) & 10) | 1 ^ to_bigint(3)").unwrap(), BigInt::from(10));
+    assert_eq!(engine.eval::<BigInt>("let x = to_bigint(12); x &= 10; x <<= 2; x").unwrap(), BigInt::from(32));
+    assert_eq!(engine.eval::<BigInt>("-to_bigint(5)").unwrap(), BigInt::from(-5));
+
+    // Comparisons
+    #[cfg(not(feature = "only_i32"))]
+    assert!(engine.eval::<bool>("to_bigint(2) ** 64 > 9223372036854775807").unwrap());
+    assert!(engine.eval::<bool>("42 == to_bigint(42)").unwrap());
+    assert!(engine.eval::<bool>("to_bigint(1) <= to_bigint(1)").unwrap());
+    assert!(!engine.eval::<bool>("to_bigint(1) != 1").unwrap());
+    assert!(!engine.eval::<bool>(r#"to_bigint(1) == "1""#).unwrap());
+
+    // Conversions
+    assert_eq!(engine.eval::<INT>("to_int(to_bigint(42)) + 1").unwrap(), 43);
+    assert_eq!(engine.eval::<INT>("sign(to_bigint(-42))").unwrap(), -1);
+    assert_eq!(engine.eval::<INT>("bits(to_bigint(255))").unwrap(), 8);
+    assert!(engine.eval::<bool>("is_odd(to_bigint(3)) && is_even(to_bigint(4)) && is_zero(to_bigint(0))").unwrap());
+    #[cfg(not(feature = "no_float"))]
+    #[cfg(not(feature = "f32_float"))]
+    assert_eq!(engine.eval::<BigInt>("to_bigint(1.5e20)").unwrap(), "150000000000000000000".parse().unwrap());
+
+    let mut scope = Scope::new();
+    scope.push("x", BigInt::from(1) << 100);
+    assert_eq!(engine.eval_with_scope::<BigInt>(&mut scope, "x + x").unwrap(), BigInt::from(1) << 101);
+}
+
+#[test]
+fn test_bigint_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(*engine.run(r#"parse_bigint("12x")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run(r#"parse_bigint("12", 99)"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run("to_bigint(1) / 0").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run("let x = to_bigint(1); x %= to_bigint(0)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run("to_bigint(2) ** -1").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run("to_int(to_bigint(2) ** 100)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run("2 ** (to_bigint(2) ** 100)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_bigint_limits() {
+    let mut engine = Engine::new();
+    engine.set_max_string_size(100);
+
+    assert_eq!(engine.eval::<INT>("bits(to_bigint(2) ** 500)").unwrap(), 501);
+    assert!(matches!(*engine.run("to_bigint(2) ** 1_000_000_000").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert!(matches!(*engine.run("to_bigint(1) << 1_000_000_000").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert!(engine.run("to_bigint(2) ** 1_000_000_000").unwrap_err().to_string().contains("Size of number"));
+    assert!(engine.run("to_bigint(1) << 1_000_000_000").unwrap_err().to_string().contains("Size of number"));
+    assert!(matches!(*engine.run("let x = to_bigint(3); loop { x *= x; }").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert!(matches!(*engine.run("let x = to_bigint(3); loop { x = x * x; }").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+
+    #[cfg(not(feature = "no_index"))]
+    assert!(matches!(*engine.run("let x = [to_bigint(2) ** 600, to_bigint(2) ** 600]; x").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+}
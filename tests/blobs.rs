@@ -114,3 +114,104 @@ fn test_blobs_write_string() {
     assert_eq!(engine.eval::<Blob>(r#"let x = blob(10, 0); write_utf8(x, 3..9, "❤❤❤❤"); x"#).unwrap(), "\0\0\0\u{2764}\u{2764}\0".as_bytes());
     assert_eq!(engine.eval::<Blob>(r#"let x = blob(10, 0); write_utf8(x, 3..7, "❤❤❤❤"); x"#).unwrap(), vec![0, 0, 0, 226, 157, 164, 226, 0, 0, 0]);
 }
+
+#[test]
+fn test_blobs_pack() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<Blob>(r#"pack(">HBx4s", [0x1234, 42, "abc"])"#).unwrap(), [0x12, 0x34, 42, 0, b'a', b'b', b'c', 0]);
+    assert_eq!(engine.eval::<Blob>(r#"pack("<hI", [-2, 0x0eadbeef])"#).unwrap(), [0xfe, 0xff, 0xef, 0xbe, 0xad, 0x0e]);
+    assert_eq!(engine.eval::<Blob>(r#"pack("3B ?", [1, 2, 3, true])"#).unwrap(), [1, 2, 3, 1]);
+    assert_eq!(engine.eval::<INT>(r#"pack_size("<HBx4s")"#).unwrap(), 8);
+
+    assert_eq!(engine.eval::<INT>(r#"let x = unpack(pack(">HBx4s", [0x1234, 42, "abc"]), ">HBx4s"); x[0] + x[1]"#).unwrap(), 0x1234 + 42);
+    assert_eq!(engine.eval::<Blob>(r#"unpack(pack("2s", ["abc"]), "2s")[0]"#).unwrap(), b"ab");
+    assert_eq!(engine.eval::<INT>(r#"unpack(pack("<bh", [-1, -300]), "<bh")[1]"#).unwrap(), -300);
+    assert!(engine.eval::<bool>(r#"unpack(pack("?", [true]), "?")[0]"#).unwrap());
+    assert_eq!(engine.eval::<INT>(r#"let b = blob(1, 0xff); b += pack("!hh", [7, 8]); unpack(b, "!h", 3)[0]"#).unwrap(), 8);
+    assert_eq!(engine.eval::<INT>(r#"unpack(pack("<hh", [7, 8]), "<h", -2)[0]"#).unwrap(), 8);
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<rhai::FLOAT>(r#"unpack(pack("<fd", [1.5, 42]), "<fd")[1]"#).unwrap(), 42.0);
+        assert_eq!(engine.eval::<rhai::FLOAT>(r#"unpack(pack(">fd", [1.5, 42]), ">fd")[0]"#).unwrap(), 1.5);
+    }
+
+    #[cfg(not(feature = "only_i32"))]
+    assert!(engine.eval::<rhai::Array>(r#"unpack(pack("<q", [-1]), "<Q")"#).is_err());
+    #[cfg(feature = "only_i32")]
+    assert!(engine.eval::<rhai::Array>(r#"unpack(pack("<i", [-1]), "<I")"#).is_err());
+
+    assert!(engine.eval::<Blob>(r#"pack("B", [256])"#).is_err());
+    assert!(engine.eval::<Blob>(r#"pack("b", [-129])"#).is_err());
+    assert!(engine.eval::<Blob>(r#"pack("H", [1, 2])"#).is_err());
+    assert!(engine.eval::<Blob>(r#"pack("H", ["x"])"#).is_err());
+    assert!(engine.eval::<Blob>(r#"pack("z", [])"#).is_err());
+    assert!(engine.eval::<Blob>(r#"pack("3", [])"#).is_err());
+    assert!(engine.eval::<rhai::Array>(r#"unpack(blob(3), "I")"#).is_err());
+    assert!(engine.eval::<rhai::Array>(r#"unpack(blob(4), "I", 1)"#).is_err());
+}
+
+#[test]
+fn test_blobs_encoding() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"to_hex(pack(">I", [0x00ab12ff]))"#).unwrap(), "00ab12ff");
+    assert_eq!(engine.eval::<Blob>(r#"from_hex("DEADbeef")"#).unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+    assert!(engine.eval::<Blob>(r#"from_hex("abc")"#).is_err());
+    assert!(engine.eval::<Blob>(r#"from_hex("zz")"#).is_err());
+
+    for (text, encoded) in [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foobar", "Zm9vYmFy")] {
+        let script = format!(r#"let b = blob(); append(b, "{text}"); to_base64(b)"#);
+        assert_eq!(engine.eval::<String>(&script).unwrap(), encoded);
+        let script = format!(r#"as_string(from_base64("{encoded}"))"#);
+        assert_eq!(engine.eval::<String>(&script).unwrap(), text);
+    }
+    assert_eq!(engine.eval::<String>(r#"as_string(from_base64("Zm8"))"#).unwrap(), "fo");
+    assert!(engine.eval::<Blob>(r#"from_base64("Z")"#).is_err());
+    assert!(engine.eval::<Blob>(r#"from_base64("Zm9v!")"#).is_err());
+
+    // Malformed padding
+    for encoded in ["Zg=", "Zg===", "Zm9v=", "Zm9v==", "Zg==Zg==", "Zm=9", "=", "==", "===="] {
+        let script = format!(r#"from_base64("{encoded}")"#);
+        assert!(engine.eval::<Blob>(&script).is_err(), "{}", encoded);
+    }
+
+    assert_eq!(engine.eval::<String>(r#"let b = blob(); append(b, "123456789"); to_hex(crc32(b))"#).unwrap(), "cbf43926");
+    assert_eq!(engine.eval::<INT>("crc32(blob())").unwrap(), 0);
+
+    assert_eq!(engine.eval::<Blob>("let b = blob(); append_uleb128(b, 624485); b").unwrap(), [0xe5, 0x8e, 0x26]);
+    assert_eq!(engine.eval::<Blob>("let b = blob(); append_sleb128(b, -123456); b").unwrap(), [0xc0, 0xbb, 0x78]);
+    assert_eq!(engine.eval::<Blob>("let b = blob(); append_sleb128(b, 63); append_sleb128(b, 64); b").unwrap(), [0x3f, 0xc0, 0x00]);
+    assert!(engine.eval::<Blob>("let b = blob(); append_uleb128(b, -1); b").is_err());
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let b = blob();
+                    append_uleb128(b, 624485);
+                    append_sleb128(b, -123456);
+                    let x = read_uleb128(b, 0);
+                    let y = read_sleb128(b, x[1]);
+                    x[0] + y[0] + y[1]
+                "
+            )
+            .unwrap(),
+        624485 - 123456 + 6
+    );
+
+    for value in [0, 1, -1, 63, -64, 64, -65, INT::MAX, INT::MIN] {
+        let script = format!("let b = blob(); append_sleb128(b, {value}); read_sleb128(b, 0)[0]");
+        let script = script.replace(&INT::MIN.to_string(), &format!("({} - 1)", INT::MIN + 1));
+        assert_eq!(engine.eval::<INT>(&script).unwrap(), value);
+    }
+    for value in [0, 1, 127, 128, INT::MAX] {
+        let script = format!("let b = blob(); append_uleb128(b, {value}); read_uleb128(b, 0)[0]");
+        assert_eq!(engine.eval::<INT>(&script).unwrap(), value);
+    }
+
+    assert!(engine.eval::<rhai::Array>("read_uleb128(blob(1, 0x80), 0)").is_err());
+    assert!(engine.eval::<rhai::Array>("read_uleb128(blob(), 0)").is_err());
+    assert!(engine.eval::<rhai::Array>("read_uleb128(blob(11, 0xff), 0)").is_err());
+}
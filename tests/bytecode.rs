@@ -0,0 +1,214 @@
+#![cfg(feature = "bytecode")]
+use rhai::{Dynamic, Engine, EvalAltResult, Position, Scope, INT};
+
+/// Evaluate a script both by walking the AST and as bytecode, making sure the results match.
+fn eval_both(engine: &Engine, script: &str) -> Dynamic {
+    let ast = engine.compile(script).unwrap();
+    let bytecode = engine.compile_bytecode(&ast);
+
+    let expected = engine.eval_ast::<Dynamic>(&ast).unwrap();
+    let actual = engine.eval_bytecode::<Dynamic>(&bytecode).unwrap();
+
+    assert_eq!(actual.to_string(), expected.to_string(), "{script}");
+    actual
+}
+
+#[test]
+fn test_bytecode() {
+    let engine = Engine::new();
+
+    assert_eq!(eval_both(&engine, "let x = 40; x + 2").as_int().unwrap(), 42);
+    assert_eq!(eval_both(&engine, "let x = 0; for i in 0..10 { x += i; } x").as_int().unwrap(), 45);
+    assert_eq!(
+        eval_both(&engine, "let x = 0; let i = 0; while i < 10 { i += 1; if i % 2 == 0 { continue; } x += i; } x")
+            .as_int()
+            .unwrap(),
+        25
+    );
+    assert_eq!(eval_both(&engine, "let x = 0; loop { x += 1; if x >= 7 { break; } } x").as_int().unwrap(), 7);
+    assert_eq!(eval_both(&engine, "let x = 0; do { x += 3; } while x < 10; x").as_int().unwrap(), 12);
+    assert_eq!(eval_both(&engine, "let x = 0; do { x += 3; } until x >= 10; x").as_int().unwrap(), 12);
+    assert_eq!(eval_both(&engine, "let x = loop { break 42; }; x").as_int().unwrap(), 42);
+    assert_eq!(eval_both(&engine, "let x = 0; for (v, i) in 10..15 { x += v * i; } x").as_int().unwrap(), 130);
+    assert_eq!(eval_both(&engine, "let x = (); x ?? 42").as_int().unwrap(), 42);
+    assert!(eval_both(&engine, "let x = 1; x > 0 && x < 2 || false").as_bool().unwrap());
+    assert!(!eval_both(&engine, "let x = 1; !(x > 0)").as_bool().unwrap());
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(eval_both(&engine, r#"let s = ""; for c in "hello".chars() { s += c; } s"#).into_string().unwrap(), "hello");
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(eval_both(&engine, "let a = []; for i in 0..5 { a.push(i * i); } a[4] + a.len()").as_int().unwrap(), 21);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        eval_both(&engine, "let x = 0; for i in 0..10 { switch i { 3 => continue, 6 => break, _ => () } x += i; } x")
+            .as_int()
+            .unwrap(),
+        12
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(eval_both(&engine, "let [a, b] = [1, 2]; a * 10 + b").as_int().unwrap(), 12);
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(eval_both(&engine, "let m = #{a: 1}; for i in 0..3 { m.a += i; } m.a").as_int().unwrap(), 4);
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_bytecode_functions() {
+    let engine = Engine::new();
+
+    assert_eq!(eval_both(&engine, "fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } } fib(6)").as_int().unwrap(), 8);
+    assert_eq!(eval_both(&engine, "fn sum(n) { let x = 0; for i in 0..n { x += i; } return x; } sum(10) + sum(5)").as_int().unwrap(), 55);
+    assert_eq!(eval_both(&engine, "fn add(x, y) { x + y } let x = 0; for i in 0..10 { x = add(x, i); } x").as_int().unwrap(), 45);
+    assert_eq!(eval_both(&engine, "fn abs(x) { -x } let x = 0; for i in 0..10 { x += abs(i); } x").as_int().unwrap(), -45);
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(eval_both(&engine, "fn inc() { this += 1; } let x = 0; for i in 0..10 { x.inc(); } x").as_int().unwrap(), 10);
+
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(eval_both(&engine, "let x = 0; let f = || x += 1; for i in 0..10 { call(f); } x").as_int().unwrap(), 10);
+
+    let ast = engine.compile("fn foo(x) { x * 2 } foo(21)").unwrap();
+    let bytecode = engine.compile_bytecode(&ast);
+    assert!(bytecode.is_fully_compiled());
+    assert_eq!(engine.eval_bytecode::<INT>(&bytecode).unwrap(), 42);
+
+    assert!(matches!(
+        *engine
+            .eval_bytecode::<INT>(&engine.compile_bytecode(&engine.compile("fn foo(x) { throw x; } foo(42)").unwrap()))
+            .unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(..)
+    ));
+}
+
+#[test]
+fn test_bytecode_fallback() {
+    let engine = Engine::new();
+
+    let ast = engine.compile(r#"let x = 40; eval("x += 2"); x"#).unwrap();
+    let bytecode = engine.compile_bytecode(&ast);
+
+    assert!(!bytecode.is_fully_compiled());
+    assert_eq!(engine.eval_bytecode::<INT>(&bytecode).unwrap(), 42);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let ast = engine.compile(r#"fn foo(x) { eval("x * 2") } let x = 0; for i in 0..3 { x += foo(i); } x"#).unwrap();
+        let bytecode = engine.compile_bytecode(&ast);
+
+        assert!(!bytecode.is_fully_compiled());
+        assert_eq!(engine.eval_bytecode::<INT>(&bytecode).unwrap(), 6);
+    }
+
+    // Changed Engine settings
+    let mut engine = Engine::new();
+    let bytecode = engine.compile_bytecode(&engine.compile("let x = 1; x + 2").unwrap());
+
+    engine.register_fn("+", |x: INT, y: INT| x * y);
+    engine.set_fast_operators(false);
+
+    assert_eq!(engine.eval_bytecode::<INT>(&bytecode).unwrap(), 2);
+}
+
+#[test]
+fn test_bytecode_scope() {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push("x", 40 as INT);
+    scope.push_constant("y", 2 as INT);
+
+    let bytecode = engine.compile_bytecode(&engine.compile("let z = x + y; x = z; z").unwrap());
+
+    assert_eq!(engine.eval_bytecode_with_scope::<INT>(&mut scope, &bytecode).unwrap(), 42);
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 42);
+    assert_eq!(scope.get_value::<INT>("z").unwrap(), 42);
+
+    engine.run_bytecode_with_scope(&mut scope, &engine.compile_bytecode(&engine.compile("x += 1").unwrap())).unwrap();
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 43);
+
+    let bytecode = engine.compile_bytecode(&engine.compile("y = 1").unwrap());
+
+    assert!(matches!(
+        *engine.run_bytecode_with_scope(&mut scope, &bytecode).unwrap_err(),
+        EvalAltResult::ErrorAssignmentToConstant(ref name, pos) if name == "y" && pos == Position::new(1, 1)
+    ));
+}
+
+#[test]
+fn test_bytecode_errors() {
+    let engine = Engine::new();
+
+    let bytecode = engine.compile_bytecode(&engine.compile("let x = 0;\nwhile x { x += 1; }").unwrap());
+
+    assert_eq!(engine.run_bytecode(&bytecode).unwrap_err().position(), Position::new(2, 7));
+
+    let bytecode = engine.compile_bytecode(&engine.compile("let x = 0;\nfor i in x { x += i; }").unwrap());
+
+    assert!(matches!(*engine.run_bytecode(&bytecode).unwrap_err(), EvalAltResult::ErrorFor(pos) if pos == Position::new(2, 10)));
+
+    let bytecode = engine.compile_bytecode(&engine.compile("let x = 42;\nthrow x;").unwrap());
+
+    assert!(matches!(
+        *engine.run_bytecode(&bytecode).unwrap_err(),
+        EvalAltResult::ErrorRuntime(ref v, pos) if v.as_int().unwrap() == 42 && pos == Position::new(2, 1)
+    ));
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_bytecode_operations() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    let scripts = [
+        "let x = 0; for i in 0..10 { x += i; } x",
+        "let x = 0; while x < 20 { x += 1; if x == 15 { break; } } x",
+        "let x = 0; do { x += 2; } while x < 10; x > 5 && true || false",
+        #[cfg(not(feature = "no_index"))]
+        "let a = [1, 2, 3]; let s = 0; for (v, i) in a { s += v * i; } s",
+        #[cfg(not(feature = "no_function"))]
+        "fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } } fib(6)",
+    ];
+
+    for script in scripts {
+        let mut engine = Engine::new();
+        let count = Arc::new(AtomicU64::new(0));
+        let counter = count.clone();
+
+        engine.on_progress(move |n| {
+            counter.store(n, Ordering::SeqCst);
+            None
+        });
+
+        let ast = engine.compile(script).unwrap();
+        let bytecode = engine.compile_bytecode(&ast);
+
+        engine.run_ast(&ast).unwrap();
+        let expected = count.swap(0, Ordering::SeqCst);
+
+        engine.run_bytecode(&bytecode).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), expected, "{script}");
+    }
+
+    let mut engine = Engine::new();
+    #[cfg(not(feature = "no_optimize"))]
+    engine.set_optimization_level(rhai::OptimizationLevel::None);
+    engine.set_max_operations(500);
+
+    let bytecode = engine.compile_bytecode(&engine.compile("for x in 0..500 {}").unwrap());
+
+    assert!(matches!(*engine.run_bytecode(&bytecode).unwrap_err(), EvalAltResult::ErrorTooManyOperations(..)));
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        engine.set_max_operations(0);
+        engine.set_max_call_levels(10);
+
+        let bytecode = engine.compile_bytecode(&engine.compile("fn f(n) { f(n + 1) } f(0)").unwrap());
+
+        assert!(matches!(*engine.run_bytecode(&bytecode).unwrap_err(), EvalAltResult::ErrorStackOverflow(..)));
+    }
+}
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_function"))]
-use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, FnPtr, Func, FuncArgs, Scope, AST, INT};
+use rhai::{CallFnOptions, Dynamic, Engine, EngineCache, EvalAltResult, FnPtr, Func, FuncArgs, Scope, AST, INT};
 use std::any::TypeId;
 
 #[test]
@@ -57,6 +57,79 @@ fn test_call_fn() {
     assert!(!scope.contains("scale"));
 }
 
+#[test]
+fn test_call_fn_cache() {
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+    let mut cache = EngineCache::new();
+
+    engine.register_fn("calc", |x: INT| x + 1);
+
+    let ast = engine.compile("fn foo(x) { calc(x) * 2 }").unwrap();
+
+    for _ in 0..3 {
+        let options = CallFnOptions::new().with_cache(&mut cache);
+        let r = engine.call_fn_with_options::<INT>(options, &mut scope, &ast, "foo", (20 as INT,)).unwrap();
+        assert_eq!(r, 42);
+    }
+
+    assert!(!cache.is_empty());
+
+    // Registering functions invalidates the cache
+    engine.register_fn("calc", |x: INT| x - 1);
+
+    let options = CallFnOptions::new().with_cache(&mut cache);
+    let r = engine.call_fn_with_options::<INT>(options, &mut scope, &ast, "foo", (22 as INT,)).unwrap();
+    assert_eq!(r, 42);
+
+    // So does calling into a different AST
+    let ast2 = engine.compile("fn calc(x) { x } fn foo(x) { calc(x) * 2 }").unwrap();
+
+    for _ in 0..3 {
+        let options = CallFnOptions::new().with_cache(&mut cache);
+        let r = engine.call_fn_with_options::<INT>(options, &mut scope, &ast2, "foo", (21 as INT,)).unwrap();
+        assert_eq!(r, 42);
+    }
+
+    let options = CallFnOptions::new().with_cache(&mut cache);
+    let r = engine.call_fn_with_options::<INT>(options, &mut scope, &ast, "foo", (22 as INT,)).unwrap();
+    assert_eq!(r, 42);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+#[cfg(not(feature = "no_module"))]
+fn test_call_fn_cache_import() {
+    use rhai::{module_resolvers::StaticModuleResolver, FnNamespace, FuncRegistration, Module};
+
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+    let mut cache = EngineCache::new();
+
+    let mut module = Module::new();
+    FuncRegistration::new("calc").with_namespace(FnNamespace::Global).set_into_module(&mut module, |x: INT| x + 1);
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("calc", module);
+    engine.set_module_resolver(resolver);
+
+    let ast = engine.compile(r#"import "calc" as m; fn foo(x) { calc(x) * 2 }"#).unwrap();
+
+    for _ in 0..3 {
+        let options = CallFnOptions::new().with_cache(&mut cache);
+        let r = engine.call_fn_with_options::<INT>(options, &mut scope, &ast, "foo", (20 as INT,)).unwrap();
+        assert_eq!(r, 42);
+    }
+
+    // Functions from imported modules are not kept
+    assert!(cache.is_empty());
+
+    let options = CallFnOptions::new().eval_ast(false).with_cache(&mut cache);
+    assert!(matches!(*engine.call_fn_with_options::<INT>(options, &mut scope, &ast, "foo", (20 as INT,)).unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+}
+
 #[test]
 fn test_call_fn_scope() {
     let engine = Engine::new();
@@ -0,0 +1,254 @@
+#![cfg(feature = "debugging")]
+#![cfg(feature = "metadata")]
+#![cfg(not(feature = "no_function"))]
+#![cfg(not(feature = "no_position"))]
+#![cfg(not(feature = "no_object"))]
+#![cfg(not(feature = "no_index"))]
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// A scripted DAP client driving the `rhai-dap` tool.
+struct Client {
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    seq: i64,
+    events: Vec<Value>,
+}
+
+impl Client {
+    fn new() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rhai-dap")).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+
+        let input = child.stdin.take().unwrap();
+        let output = BufReader::new(child.stdout.take().unwrap());
+
+        Self { child, input, output, seq: 1, events: Vec::new() }
+    }
+    fn read(&mut self) -> Value {
+        let mut len = 0;
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            assert!(self.output.read_line(&mut line).unwrap() > 0, "unexpected end of output");
+
+            match line.trim_end() {
+                "" => break,
+                header => {
+                    if let Some(n) = header.strip_prefix("Content-Length:") {
+                        len = n.trim().parse().unwrap();
+                    }
+                }
+            }
+        }
+
+        let mut body = vec![0; len];
+        self.output.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+    fn request(&mut self, command: &str, arguments: Value) -> Value {
+        let seq = self.seq;
+        self.seq += 1;
+
+        let body = json!({ "seq": seq, "type": "request", "command": command, "arguments": arguments }).to_string();
+        write!(self.input, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.input.flush().unwrap();
+
+        loop {
+            let message = self.read();
+
+            match message["type"].as_str() {
+                Some("response") if message["request_seq"] == seq => return message,
+                Some("event") => self.events.push(message),
+                _ => (),
+            }
+        }
+    }
+    fn wait_event(&mut self, event: &str) -> Value {
+        if let Some(n) = self.events.iter().position(|e| e["event"] == event) {
+            return self.events.remove(n)["body"].clone();
+        }
+
+        loop {
+            let message = self.read();
+
+            if message["event"] == event {
+                return message["body"].clone();
+            }
+            self.events.push(message);
+        }
+    }
+    fn top_frame_line(&mut self) -> Value {
+        let response = self.request("stackTrace", json!({ "threadId": 1 }));
+        response["body"]["stackFrames"][0]["line"].clone()
+    }
+    fn variables(&mut self, reference: &Value) -> Vec<Value> {
+        let response = self.request("variables", json!({ "variablesReference": reference }));
+        response["body"]["variables"].as_array().unwrap().clone()
+    }
+    fn launch(&mut self, script: &str, name: &str, arguments: Value) {
+        let path = std::env::temp_dir().join(format!("rhai-dap-{}-{name}.rhai", std::process::id()));
+        std::fs::write(&path, script).unwrap();
+
+        let mut arguments = arguments;
+        arguments["program"] = json!(path.to_string_lossy());
+
+        let response = self.request("launch", arguments);
+        assert_eq!(response["success"], true, "{response}");
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+#[test]
+fn test_dap() {
+    let script = "fn add(a, b) {
+    a + b
+}
+let x = 40;
+let y = add(x, 2);
+print(y);
+let m = #{ a: [1, 2] };
+let z = m.a[1];
+";
+
+    let mut client = Client::new();
+
+    let response = client.request("initialize", json!({ "adapterID": "rhai" }));
+    assert_eq!(response["success"], true);
+    assert_eq!(response["body"]["supportsConfigurationDoneRequest"], true);
+    client.wait_event("initialized");
+
+    client.launch(script, "main", json!({}));
+
+    let path = std::env::temp_dir().join(format!("rhai-dap-{}-main.rhai", std::process::id()));
+    let response = client.request("setBreakpoints", json!({ "source": { "path": path.to_string_lossy() }, "breakpoints": [{ "line": 7 }] }));
+    assert_eq!(response["body"]["breakpoints"][0]["verified"], true);
+    client.request("setFunctionBreakpoints", json!({ "breakpoints": [{ "name": "add" }] }));
+    client.request("configurationDone", json!({}));
+
+    // Function break-point
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["reason"], "function breakpoint");
+    assert_eq!(client.top_frame_line(), 5);
+
+    let variables = client.variables(&json!(1));
+    assert!(variables.iter().any(|v| v["name"] == "x" && v["value"] == "40"));
+
+    // Line break-point
+    client.request("continue", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["reason"], "breakpoint");
+    assert_eq!(client.top_frame_line(), 7);
+    assert_eq!(client.wait_event("output")["output"], "42\n");
+
+    let response = client.request("evaluate", json!({ "expression": "y * 2" }));
+    assert_eq!(response["body"]["result"], "84");
+
+    // Step to the next statement
+    client.request("next", json!({ "threadId": 1 }));
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["reason"], "step");
+    assert_eq!(client.top_frame_line(), 8);
+
+    let variables = client.variables(&json!(1));
+    let m = variables.iter().find(|v| v["name"] == "m").unwrap();
+    let fields = client.variables(&m["variablesReference"]);
+    assert_eq!(fields[0]["name"], "a");
+    let items = client.variables(&fields[0]["variablesReference"]);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[1]["value"], "2");
+
+    client.request("continue", json!({ "threadId": 1 }));
+    assert_eq!(client.wait_event("exited")["exitCode"], 0);
+    client.wait_event("terminated");
+
+    let response = client.request("disconnect", json!({}));
+    assert_eq!(response["success"], true);
+    assert!(client.child.wait().unwrap().success());
+}
+
+#[test]
+fn test_dap_pause() {
+    let mut client = Client::new();
+
+    client.request("initialize", json!({ "adapterID": "rhai" }));
+    client.launch("let n = 0;\nloop {\n    n += 1;\n}\n", "pause", json!({ "stopOnEntry": true }));
+    client.request("configurationDone", json!({}));
+
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["reason"], "entry");
+    assert_eq!(client.top_frame_line(), 1);
+
+    client.request("continue", json!({ "threadId": 1 }));
+    client.request("pause", json!({ "threadId": 1 }));
+
+    let stopped = client.wait_event("stopped");
+    assert_eq!(stopped["reason"], "pause");
+
+    let response = client.request("disconnect", json!({}));
+    assert_eq!(response["success"], true);
+    client.child.wait().unwrap();
+}
+
+#[test]
+fn test_dap_conditional_break_points() {
+    let mut client = Client::new();
+
+    let response = client.request("initialize", json!({ "adapterID": "rhai" }));
+    assert_eq!(response["body"]["supportsConditionalBreakpoints"], true);
+    assert_eq!(response["body"]["supportsLogPoints"], true);
+
+    client.launch("let s = 0;\nfor i in 0..10 {\n    s += i;\n}\nprint(s);\n", "conditional", json!({}));
+
+    let path = std::env::temp_dir().join(format!("rhai-dap-{}-conditional.rhai", std::process::id()));
+    let breakpoints = json!([{ "line": 3, "condition": "i % 3 == 0", "hitCondition": ">= 2" }, { "line": 5, "logMessage": "s = {s}" }]);
+    let response = client.request("setBreakpoints", json!({ "source": { "path": path.to_string_lossy() }, "breakpoints": breakpoints }));
+    assert_eq!(response["body"]["breakpoints"][0]["verified"], true);
+    client.request("configurationDone", json!({}));
+
+    for i in [3, 6, 9] {
+        let stopped = client.wait_event("stopped");
+        assert_eq!(stopped["reason"], "breakpoint");
+        assert_eq!(client.top_frame_line(), 3);
+
+        let response = client.request("evaluate", json!({ "expression": "i" }));
+        assert_eq!(response["body"]["result"], i.to_string());
+
+        client.request("continue", json!({ "threadId": 1 }));
+    }
+
+    let output = client.wait_event("output");
+    assert_eq!(output["category"], "console");
+    assert!(output["output"].as_str().unwrap().ends_with("s = 45\n"), "{}", output);
+    assert_eq!(client.wait_event("output")["output"], "45\n");
+    assert_eq!(client.wait_event("exited")["exitCode"], 0);
+
+    client.request("disconnect", json!({}));
+    client.child.wait().unwrap();
+}
+
+#[test]
+fn test_dap_message_too_large() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rhai-dap"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // A huge message is rejected without allocating memory for it
+    let mut input = child.stdin.take().unwrap();
+    write!(input, "Content-Length: {}\r\n\r\n{{}}", u64::MAX / 4).unwrap();
+    input.flush().unwrap();
+    drop(input);
+
+    let status = child.wait().unwrap();
+    assert!(status.success(), "{}", status);
+}
@@ -0,0 +1,92 @@
+#![cfg(any(feature = "toml", feature = "yaml", feature = "csv"))]
+use rhai::{Engine, EvalAltResult, INT};
+
+#[cfg(feature = "toml")]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_toml() {
+    let engine = Engine::new();
+
+    let script = r#"
+        parse_toml(`
+            name = "hello"
+            when = 1979-05-27T07:32:00Z
+
+            [server]
+            port = 8080
+        `)
+    "#;
+
+    assert_eq!(engine.eval::<INT>(&format!("{script}.server.port")).unwrap(), 8080);
+    assert_eq!(engine.eval::<String>(&format!("{script}.when")).unwrap(), "1979-05-27T07:32:00Z");
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<String>(r#"parse_toml(`hosts = ["a", "b"]`).hosts[1]"#).unwrap(), "b");
+    assert_eq!(engine.eval::<String>(r#"#{name: "hello", server: #{port: 8080}}.to_toml()"#).unwrap(), "name = \"hello\"\n\n[server]\nport = 8080\n");
+    assert_eq!(engine.eval::<INT>(r#"parse_toml(#{a: #{b: 42}}.to_toml()).a.b"#).unwrap(), 42);
+
+    let err = engine.run("parse_toml(`a = 1\nb = `)").unwrap_err();
+    match *err {
+        EvalAltResult::ErrorInFunctionCall(ref f, _, ref err, ..) if f == "parse_toml" => {
+            assert!(matches!(**err, EvalAltResult::ErrorParsing(..)));
+            #[cfg(not(feature = "no_position"))]
+            assert_eq!(err.position().line(), Some(2));
+        }
+        _ => panic!("unexpected error: {}", err),
+    }
+    assert!(engine.eval::<bool>(r#"let caught = false; try { parse_toml("x"); } catch { caught = true; } caught"#).unwrap());
+    assert!(matches!(*engine.run("#{a: ()}.to_toml()").unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml() {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("parse_yaml(`name: hello\nports:\n  - 80\n  - 443\n`).ports[1]").unwrap(), 443);
+        assert_eq!(engine.eval::<String>(r#"#{name: "hello", ports: [80, 443], x: ()}.to_yaml()"#).unwrap(), "name: hello\nports:\n- 80\n- 443\nx: null\n");
+        assert_eq!(engine.eval::<INT>("parse_yaml(#{a: [1, 2, 3]}.to_yaml()).a[2]").unwrap(), 3);
+    }
+    assert_eq!(engine.eval::<INT>("parse_yaml(`42`)").unwrap(), 42);
+    assert_eq!(engine.eval::<String>("to_yaml(true)").unwrap(), "true\n");
+
+    let err = engine.run(r#"parse_yaml("\n\n'abc")"#).unwrap_err();
+    match *err {
+        EvalAltResult::ErrorInFunctionCall(ref f, _, ref err, ..) if f == "parse_yaml" => {
+            assert!(matches!(**err, EvalAltResult::ErrorParsing(..)));
+            #[cfg(not(feature = "no_position"))]
+            assert_eq!(err.position().line(), Some(3));
+        }
+        _ => panic!("unexpected error: {}", err),
+    }
+}
+
+#[cfg(feature = "csv")]
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_csv() {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<String>(r#"parse_csv("name,age\nfoo,42\nbar,7")[0].name"#).unwrap(), "foo");
+        assert_eq!(engine.eval::<INT>(r#"parse_csv("name,age\nfoo,42\nbar,7")[1].age"#).unwrap(), 7);
+        assert_eq!(engine.eval::<String>(r#"[#{name: "foo", age: 42}, #{name: "bar"}].to_csv()"#).unwrap(), "age,name\n42,foo\n,bar\n");
+        assert!(matches!(*engine.run("[[1], #{a: 1}].to_csv()").unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+    }
+    assert_eq!(engine.eval::<String>(r#"to_string(parse_csv("foo,42\nbar,", false))"#).unwrap(), r#"[["foo", 42], ["bar", ""]]"#);
+    assert_eq!(engine.eval::<String>(r#"to_csv([["a, b", 1, true], ["x\"y", "", 0]])"#).unwrap(), "\"a, b\",1,true\n\"x\"\"y\",,0\n");
+    assert!(matches!(*engine.run("to_csv([1])").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+
+    let err = engine.run(r#"parse_csv("a,b\n1,2\n3", false)"#).unwrap_err();
+    match *err {
+        EvalAltResult::ErrorInFunctionCall(ref f, _, ref err, ..) if f == "parse_csv" => {
+            assert!(matches!(**err, EvalAltResult::ErrorParsing(..)));
+            #[cfg(not(feature = "no_position"))]
+            assert_eq!(err.position().line(), Some(3));
+        }
+        _ => panic!("unexpected error: {}", err),
+    }
+}
@@ -105,6 +105,8 @@ fn test_max_array_size() {
         10
     );
 
+    assert!(matches!(*engine.run("windows([1, 2, 3, 4, 5, 6], 3)").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+
     #[cfg(not(feature = "no_closure"))]
     assert!(matches!(
         *engine
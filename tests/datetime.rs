@@ -0,0 +1,94 @@
+#![cfg(not(feature = "no_time"))]
+use rhai::DateTime;
+#[cfg(not(feature = "no_float"))]
+#[cfg(not(feature = "no_object"))]
+use rhai::FLOAT;
+#[cfg(not(feature = "no_object"))]
+use rhai::{Engine, EvalAltResult, INT};
+
+#[test]
+fn test_datetime_rust() {
+    let dt = DateTime::from_ymd_hms(2024, 2, 29, 12, 30, 5).unwrap();
+
+    assert_eq!(dt.to_string(), "2024-02-29T12:30:05Z");
+    assert_eq!(dt.unix_timestamp(), 1_709_209_805);
+    assert_eq!(dt.weekday(), 4);
+    assert_eq!(dt.day_of_year(), 60);
+
+    let dt2 = dt.with_offset(-(5 * 3600 + 30 * 60)).unwrap();
+    assert_eq!(dt2.to_string(), "2024-02-29T07:00:05-05:30");
+    assert_eq!(dt, dt2);
+
+    assert_eq!(DateTime::from_unix_timestamp(-1, 0).unwrap().to_string(), "1969-12-31T23:59:59Z");
+    assert_eq!(DateTime::from_ymd_hms(-44, 3, 15, 0, 0, 0).unwrap().to_string(), "-0044-03-15T00:00:00Z");
+    assert!(DateTime::from_ymd_hms(2023, 2, 29, 0, 0, 0).is_none());
+    assert!(DateTime::from_ymd_hms(2024, 13, 1, 0, 0, 0).is_none());
+
+    assert_eq!(DateTime::parse_rfc3339("2024-02-29T20:30:00.25+08:00").unwrap().to_utc().to_string(), "2024-02-29T12:30:00.250Z");
+    assert_eq!(DateTime::parse_from_str("Thu, 29 Feb 2024 08:30:00 PM +0100", "%a, %d %b %Y %I:%M:%S %p %z").unwrap().to_utc(), DateTime::from_ymd_hms(2024, 2, 29, 19, 30, 0).unwrap());
+    assert!(DateTime::parse_rfc3339("2024-02-30T00:00:00Z").is_err());
+    assert!(DateTime::parse_rfc3339("2024-02-29T00:00:00Zx").is_err());
+
+    assert_eq!(dt.format("%A %e %B %Y, %I:%M %p (%j) %%"), "Thursday 29 February 2024, 12:30 PM (060) %");
+    assert_eq!(dt.format("%F %T%.f %:z %s %q"), "2024-02-29 12:30:05 +00:00 1709209805 %q");
+
+    assert_eq!(dt.checked_add_months(12).unwrap().to_string(), "2025-02-28T12:30:05Z");
+    assert_eq!(dt.checked_add(86_400, 500_000_000).unwrap().to_string(), "2024-03-01T12:30:05.500Z");
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_datetime() {
+    let mut engine = Engine::new();
+
+    engine.set_clock(|| DateTime::from_ymd_hms(2024, 2, 29, 12, 30, 0).unwrap());
+
+    assert_eq!(engine.eval::<String>("type_of(now())").unwrap(), "datetime");
+    assert_eq!(engine.eval::<String>("now().to_string()").unwrap(), "2024-02-29T12:30:00Z");
+    assert_eq!(engine.eval::<String>(r#""" + datetime(2024, 2, 29)"#).unwrap(), "2024-02-29T00:00:00Z");
+    assert_eq!(engine.eval::<String>("to_debug(datetime(2024, 2, 29))").unwrap(), "DateTime(2024-02-29T00:00:00Z)");
+    assert_eq!(engine.eval::<DateTime>("now()").unwrap(), DateTime::from_unix_timestamp(1_709_209_800, 0).unwrap());
+
+    assert_eq!(engine.eval::<INT>("from_unix_timestamp(1709209800).day").unwrap(), 29);
+    assert_eq!(engine.eval::<INT>("datetime(2024, 2, 29, 12, 30, 0).unix_timestamp").unwrap(), 1_709_209_800);
+    assert_eq!(engine.eval::<INT>("let d = now(); d.year * 10000 + d.month * 100 + d.day").unwrap(), 20_240_229);
+    assert_eq!(engine.eval::<INT>("let d = now(); d.hour * 100 + d.minute").unwrap(), 1230);
+    assert_eq!(engine.eval::<INT>("now().weekday").unwrap(), 4);
+
+    assert_eq!(engine.eval::<String>(r#"now().to_offset("+08:00").to_string()"#).unwrap(), "2024-02-29T20:30:00+08:00");
+    assert_eq!(engine.eval::<INT>("now().to_offset(-3600).offset").unwrap(), -3600);
+    assert_eq!(engine.eval::<String>(r#"parse_datetime("2024-03-01T01:00:00+02:00").to_utc().to_string()"#).unwrap(), "2024-02-29T23:00:00Z");
+    assert_eq!(engine.eval::<String>(r#"parse_datetime("29/02/2024 20:30", "%d/%m/%Y %H:%M").format("%Y%m%d-%H%M")"#).unwrap(), "20240229-2030");
+
+    assert_eq!(engine.eval::<String>("(now() + 3600).to_string()").unwrap(), "2024-02-29T13:30:00Z");
+    assert_eq!(engine.eval::<String>("let d = now(); d -= 86400; d.to_string()").unwrap(), "2024-02-28T12:30:00Z");
+    assert_eq!(engine.eval::<String>("now().add_days(1).to_string()").unwrap(), "2024-03-01T12:30:00Z");
+    assert_eq!(engine.eval::<String>("datetime(2024, 1, 31).add_months(1).to_string()").unwrap(), "2024-02-29T00:00:00Z");
+    assert_eq!(engine.eval::<String>("now().add_years(-1).to_string()").unwrap(), "2023-02-28T12:30:00Z");
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<FLOAT>("datetime(2024, 3, 1) - datetime(2024, 2, 28)").unwrap(), 172_800.0);
+        assert_eq!(engine.eval::<String>("(now() + 0.5).to_string()").unwrap(), "2024-02-29T12:30:00.500Z");
+        #[cfg(not(feature = "f32_float"))]
+        assert_eq!(engine.eval::<String>("from_unix_timestamp(1709209800.25).to_string()").unwrap(), "2024-02-29T12:30:00.250Z");
+    }
+    #[cfg(feature = "no_float")]
+    assert_eq!(engine.eval::<INT>("datetime(2024, 3, 1) - datetime(2024, 2, 28)").unwrap(), 172_800);
+
+    assert!(engine.eval::<bool>("datetime(2024, 2, 28) < now()").unwrap());
+    assert!(engine.eval::<bool>(r#"now() == parse_datetime("2024-02-29T20:30:00+08:00")"#).unwrap());
+    assert!(engine.eval::<bool>("now() != now() + 1").unwrap());
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_datetime_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(*engine.eval::<DateTime>("datetime(2023, 2, 29)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<DateTime>(r#"parse_datetime("2024-02-29")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<DateTime>(r#"parse_datetime("2024", "%Y %m")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<DateTime>("datetime(9999, 12, 31) + 86400").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<DateTime>(r#"datetime(2024, 1, 1).to_offset("+25:00")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+}
@@ -71,3 +71,177 @@ fn test_debugger_state() {
 
     engine.run("let x = 42;").unwrap();
 }
+
+#[test]
+#[cfg(not(feature = "no_position"))]
+fn test_debugger_conditional_break_points() {
+    use rhai::debugger::{BreakPoint, DebuggerCommand, DebuggerEvent};
+    use rhai::Position;
+    use std::sync::{Arc, Mutex};
+
+    let hits = Arc::new(Mutex::new(Vec::new()));
+    let logs = Arc::new(Mutex::new(Vec::new()));
+    let mut engine = Engine::new();
+
+    let h = hits.clone();
+    let l = logs.clone();
+
+    engine.on_debug(move |s, _, pos| l.lock().unwrap().push(format!("{}: {s}", pos.line().unwrap())));
+    engine.register_debugger(
+        |_, mut debugger| {
+            let line = |n| BreakPoint::AtPosition {
+                source: None,
+                pos: Position::new(n, 0),
+                enabled: true,
+            };
+
+            debugger.break_points_mut().push(line(4).with_condition("i % 3 == 0").with_hit_count(2));
+            debugger.break_points_mut().push(line(6).with_log_message("sum = ${sum}"));
+            debugger
+        },
+        move |context, event, _, _, _| {
+            if let DebuggerEvent::BreakPoint(..) = event {
+                h.lock().unwrap().push(context.scope().get_value::<INT>("i").unwrap());
+            }
+            Ok(DebuggerCommand::Continue)
+        },
+    );
+
+    engine
+        .run(
+            "
+                let sum = 0;
+                for i in 0..10 {
+                    sum += i;
+                }
+                print(sum);
+            ",
+        )
+        .unwrap();
+
+    assert_eq!(*hits.lock().unwrap(), [3, 6, 9]);
+    assert_eq!(*logs.lock().unwrap(), ["6: sum = 45"]);
+}
+
+#[test]
+#[cfg(not(feature = "no_position"))]
+fn test_debugger_break_points_while_stepping() {
+    use rhai::debugger::{BreakPoint, DebuggerCommand, DebuggerEvent};
+    use rhai::Position;
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let logs = Arc::new(Mutex::new(Vec::new()));
+    let mut engine = Engine::new();
+
+    let e = events.clone();
+    let l = logs.clone();
+
+    engine.on_debug(move |s, _, _| l.lock().unwrap().push(s.to_string()));
+    engine.register_debugger(
+        |_, mut debugger| {
+            let line = |n| BreakPoint::AtPosition {
+                source: None,
+                pos: Position::new(n, 0),
+                enabled: true,
+            };
+
+            debugger.break_points_mut().push(line(3));
+            debugger.break_points_mut().push(line(4).with_log_message("x = ${x}"));
+            debugger
+        },
+        move |_, event, node, _, _| {
+            let line = node.position().line().unwrap_or(0);
+
+            match event {
+                DebuggerEvent::Step => e.lock().unwrap().push(format!("step {line}")),
+                DebuggerEvent::BreakPoint(..) => e.lock().unwrap().push(format!("break {line}")),
+                _ => (),
+            }
+            // Step over every statement
+            Ok(DebuggerCommand::StepOver)
+        },
+    );
+
+    engine
+        .run(
+            "
+                let x = 1;
+                x += 1;
+                x += 1;
+            ",
+        )
+        .unwrap();
+
+    // Break-points take precedence over stepping (for every node on the line), while log-points
+    // print and then step as usual
+    assert_eq!(*events.lock().unwrap(), ["break 3", "break 3", "step 4"]);
+    assert_eq!(*logs.lock().unwrap(), ["x = 2"]);
+}
+
+#[test]
+fn test_debugger_watch_points() {
+    use rhai::debugger::{BreakPoint, DebuggerCommand, DebuggerEvent};
+    #[cfg(not(feature = "no_object"))]
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone)]
+    struct TestStruct {
+        x: INT,
+    }
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let mut engine = Engine::new();
+
+    engine.register_type_with_name::<TestStruct>("TestStruct").register_fn("new_ts", || TestStruct { x: 1 });
+
+    #[cfg(not(feature = "no_object"))]
+    let reads = Arc::new(AtomicUsize::new(0));
+    #[cfg(not(feature = "no_object"))]
+    let r = reads.clone();
+
+    #[cfg(not(feature = "no_object"))]
+    engine.register_get_set(
+        "x",
+        move |t: &mut TestStruct| {
+            r.fetch_add(1, Ordering::SeqCst);
+            t.x
+        },
+        |t: &mut TestStruct, value: INT| t.x = value,
+    );
+
+    let c = changes.clone();
+
+    engine.register_debugger(
+        |_, mut debugger| {
+            debugger
+                .break_points_mut()
+                .push(BreakPoint::OnVariableChange { name: "x".into(), enabled: true }.with_condition("x > 1"));
+            #[cfg(not(feature = "no_object"))]
+            debugger.break_points_mut().push(BreakPoint::OnPropertyWrite { name: "x".into(), enabled: true });
+            debugger
+        },
+        move |_, event, _, _, _| {
+            if let DebuggerEvent::WatchPoint(n, old_value, new_value) = event {
+                c.lock().unwrap().push(format!("{n}: {old_value} => {new_value}"));
+            }
+            Ok(DebuggerCommand::Continue)
+        },
+    );
+
+    engine.run("let x = 1; x += 0; x *= 3; let y = 0; y = 2; x = 42;").unwrap();
+
+    assert_eq!(*changes.lock().unwrap(), ["0: 1 => 3", "0: 3 => 42"]);
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        changes.lock().unwrap().clear();
+
+        engine.run("let m = #{ x: 1 }; m.x += 1; let t = new_ts(); t.x = 7; t.x *= 2;").unwrap();
+
+        // The getter is only called for the op-assignment
+        assert_eq!(*changes.lock().unwrap(), ["1: 1 => 2", "1:  => 7", "1: 7 => 14"]);
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+    }
+}
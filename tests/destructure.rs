@@ -0,0 +1,120 @@
+#![cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+use rhai::{Engine, EvalAltResult, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::{ParseErrorType, Scope};
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_destructure_arrays() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let [a, b] = [1, 2]; a * 10 + b").unwrap(), 12);
+    assert_eq!(engine.eval::<INT>("let [a, _, c] = [1, 2, 3]; a * 10 + c").unwrap(), 13);
+    assert_eq!(engine.eval::<INT>("let [a, ..rest] = [1, 2, 3, 4]; a + len(rest) * 10").unwrap(), 31);
+    assert_eq!(engine.eval::<INT>("let [.., y, z] = [1, 2, 3, 4]; y * 10 + z").unwrap(), 34);
+    assert_eq!(engine.eval::<INT>("let [a, ..rest, z] = [1, 2]; a * 10 + z + len(rest) * 100").unwrap(), 12);
+    assert_eq!(engine.eval::<INT>("let [a, [b, c]] = [1, [2, 3]]; a + b + c").unwrap(), 6);
+    assert_eq!(engine.eval::<INT>("let [a, b: int] = [1, 2]; a + b").unwrap(), 3);
+
+    // Variables are mutable with `let` and constant with `const`
+    assert_eq!(engine.eval::<INT>("let [a, b] = [1, 2]; a += b; a").unwrap(), 3);
+    assert!(matches!(engine.compile("const [a, b] = [1, 2]; a += b;").unwrap_err().err_type(), ParseErrorType::AssignmentToConstant(..)));
+
+    // Shadowing
+    assert_eq!(engine.eval::<INT>("let a = 42; let [a, b] = [1, 2]; a").unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("let a = 42; { let [a, b] = [1, 2]; } a").unwrap(), 42);
+
+    let mut scope = Scope::new();
+    engine.run_with_scope(&mut scope, "let [x, y] = [40, 2];").unwrap();
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 40);
+    assert_eq!(scope.get_value::<INT>("y").unwrap(), 2);
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_destructure_maps() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let #{x, y: yy} = #{x: 1, y: 2, z: 3}; x * 10 + yy").unwrap(), 12);
+    assert_eq!(engine.eval::<INT>(r#"let #{"a b": c, d: _} = #{"a b": 42, d: 0}; c"#).unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("let #{a: #{b}} = #{a: #{b: 42}}; b").unwrap(), 42);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("let #{a: [x, ..], b} = #{a: [1, 2, 3], b: 41}; x + b").unwrap(), 42);
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_destructure_for() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let s = 0; for [a, b] in [[1, 2], [3, 4]] { s += a * b; } s").unwrap(), 14);
+    assert_eq!(engine.eval::<INT>("let s = 0; for ([a, b], i) in [[1, 2], [3, 4]] { s += a * b * i; } s").unwrap(), 12);
+    assert_eq!(engine.eval::<INT>("let s = 0; for [a, ..] in [[1, 2], [3, 4]] { let a = a * 10; s += a; } s").unwrap(), 40);
+    assert!(matches!(engine.compile("for ([a, b], a) in [] {}").unwrap_err().err_type(), ParseErrorType::DuplicatedVariable(..)));
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<INT>("let s = 0; for #{x, y} in [#{x: 1, y: 2}, #{x: 3, y: 4}] { s += x * y; } s").unwrap(), 14);
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_destructure_params() {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn add([a, b]) { a + b } add([40, 2])").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("fn f(x, [a, b], y) { x + a + b + y } f(1, [2, 3], 4)").unwrap(), 10);
+        assert_eq!(engine.eval::<INT>("let f = |[a, b]| a * b; call(f, [6, 7])").unwrap(), 42);
+        assert!(matches!(engine.compile("fn f(a, [a, b]) {}").unwrap_err().err_type(), ParseErrorType::FnDuplicatedParam(..)));
+        assert!(matches!(engine.compile("fn f([a, b], b) {}").unwrap_err().err_type(), ParseErrorType::FnDuplicatedParam(..)));
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn f(#{x, y}, #{z}) { x + y + z } f(#{x: 1, y: 2}, #{z: 3})").unwrap(), 6);
+        assert_eq!(engine.eval::<INT>("let f = |#{x}, y| x + y; call(f, #{x: 40}, 2)").unwrap(), 42);
+
+        #[cfg(not(feature = "no_closure"))]
+        assert_eq!(engine.eval::<INT>("let z = 40; let f = |#{x}, y| x + y + z; call(f, #{x: 1}, 1)").unwrap(), 42);
+    }
+}
+
+#[test]
+fn test_destructure_errors() {
+    let engine = Engine::new();
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert!(matches!(
+            *engine.run("let [a, b] = 42;").unwrap_err(),
+            EvalAltResult::ErrorMismatchDataType(ref t, ..) if t == "array"
+        ));
+        assert!(matches!(*engine.run("let [a, b] = [1];").unwrap_err(), EvalAltResult::ErrorIndexNotFound(..)));
+        assert!(matches!(*engine.run("let [a, .., b] = [1];").unwrap_err(), EvalAltResult::ErrorIndexNotFound(..)));
+        assert!(matches!(*engine.run("let [a, b] = [1, 2, 3];").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+        assert!(matches!(
+            *engine.run(r#"let [a: int] = ["x"];"#).unwrap_err(),
+            EvalAltResult::ErrorMismatchDataType(ref t, ref a, ..) if t == "int" && a == "string"
+        ));
+
+        assert!(matches!(engine.compile("let [a, 1] = x;").unwrap_err().err_type(), ParseErrorType::MalformedPattern(..)));
+        assert!(matches!(engine.compile("let [a, b | c] = x;").unwrap_err().err_type(), ParseErrorType::MalformedPattern(..)));
+        assert!(matches!(engine.compile("let [a, a] = x;").unwrap_err().err_type(), ParseErrorType::DuplicatedVariable(..)));
+        assert!(matches!(engine.compile("let [a, b];").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert!(matches!(
+            *engine.run("let #{x} = 42;").unwrap_err(),
+            EvalAltResult::ErrorMismatchDataType(ref t, ..) if t == "map"
+        ));
+        assert!(matches!(
+            *engine.run("let #{x, y} = #{x: 1};").unwrap_err(),
+            EvalAltResult::ErrorIndexNotFound(ref k, ..) if k.clone().into_string().unwrap() == "y"
+        ));
+    }
+}
@@ -0,0 +1,82 @@
+#![cfg(not(feature = "no_position"))]
+use rhai::{Engine, FormatOptions};
+
+#[test]
+fn test_format() {
+    let engine = Engine::new();
+    let options = FormatOptions::new();
+
+    assert_eq!(engine.format_script("let x=40;x+=2;if x>0{x}else{-x}", options).unwrap(), "let x = 40;\nx += 2;\nif x > 0 {\n    x\n} else {\n    -x\n}\n");
+    assert_eq!(engine.format_script("let s=`a ${ 1+2 } b`;print(s)", options).unwrap(), "let s = `a ${ 1+2 } b`;\nprint(s)\n");
+    assert_eq!(engine.format_script("let s=`${x:#x} ${ y:>8.2 }`;", options).unwrap(), "let s = `${x:#x} ${ y:>8.2 }`;\n");
+    assert_eq!(engine.format_script("let x = 0; do { x += 1; } while x < 10;", options).unwrap(), "let x = 0;\ndo {\n    x += 1;\n} while x < 10;\n");
+    assert_eq!(engine.format_script("switch x { 1 => 2, _ => { 3 } }", options).unwrap(), "switch x {\n    1 => 2,\n    _ => {\n        3\n    }\n}\n");
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.format_script("let a=[1,2,3];a[0]=a[1..=2];", options).unwrap(), "let a = [1, 2, 3];\na[0] = a[1..=2];\n");
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.format_script("let [a,..rest]=[1,2,3];let [..,z]=rest;", options).unwrap(), "let [a, ..rest] = [1, 2, 3];\nlet [.., z] = rest;\n");
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.format_script("let m=#{a:1,b:#{}};m.a", options).unwrap(), "let m = #{ a: 1, b: #{} };\nm.a\n");
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(engine.format_script("fn add(x,y){x+y}\nlet f=|x|add(x,1);", options).unwrap(), "fn add(x, y) {\n    x + y\n}\nlet f = |x| add(x, 1);\n");
+
+    assert_eq!(engine.format_script("if true {\nlet x = 1;\n}", options.with_tabs(true)).unwrap(), "if true {\n\tlet x = 1;\n}\n");
+    assert_eq!(engine.format_script("if true {\nlet x = 1;\n}", options.with_indent_size(2)).unwrap(), "if true {\n  let x = 1;\n}\n");
+}
+
+#[test]
+fn test_format_comments() {
+    let engine = Engine::new();
+    let options = FormatOptions::new();
+
+    assert_eq!(
+        engine
+            .format_script("// header\n\n\n\nlet x=1;   // trailing\n/* block */ let y=2;\nlet z = foo(\n1, // one\n2);", options)
+            .unwrap(),
+        "// header\n\nlet x = 1; // trailing\n/* block */ let y = 2;\nlet z = foo(\n    1, // one\n    2\n);\n"
+    );
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(engine.format_script("//! module\n/// doc\nfn foo(){42}", options).unwrap(), "//! module\n/// doc\nfn foo() {\n    42\n}\n");
+}
+
+#[test]
+fn test_format_width() {
+    let engine = Engine::new();
+
+    let script = "let x = foo(123456, 789012, bar(345678, 901234));";
+
+    assert_eq!(engine.format_script(script, FormatOptions::new()).unwrap(), format!("{script}\n"));
+    assert_eq!(engine.format_script(script, FormatOptions::new().with_max_width(40)).unwrap(), "let x = foo(\n    123456,\n    789012,\n    bar(345678, 901234)\n);\n");
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_format_idempotent() {
+    let engine = Engine::new();
+    let options = FormatOptions::new().with_max_width(30);
+
+    let script = r##"
+        // comment
+        let x = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];   /* block */
+        for (v, i) in x { if v > 5 { print(`${i}: ${v}`); } else { continue; } }
+
+        try { throw "error"; } catch (err) { print(err) }
+        let y = #"raw"# + 'c' + 0x1F + 1_000;
+    "##;
+
+    let formatted = engine.format_script(script, options).unwrap();
+
+    assert_eq!(engine.format_script(&formatted, options).unwrap(), formatted);
+}
+
+#[test]
+fn test_format_errors() {
+    let engine = Engine::new();
+
+    assert!(engine.format_script("let x = ;", FormatOptions::new()).is_err());
+    assert!(engine.format_script("if x {", FormatOptions::new()).is_err());
+}
@@ -0,0 +1,196 @@
+#![cfg(feature = "resumable")]
+#![cfg(not(feature = "no_function"))]
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_generators() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn count_to(n) {
+                        let x = 1;
+
+                        while x <= n {
+                            yield x;
+                            x += 1;
+                        }
+                    }
+
+                    let sum = 0;
+
+                    for (x, i) in count_to(5) {
+                        sum += x * 10 + i;
+                    }
+
+                    sum
+                "
+            )
+            .unwrap(),
+        160
+    );
+
+    // Generators are lazy
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn naturals() {
+                        let x = 0;
+                        loop { yield x; x += 1; }
+                    }
+
+                    let sum = 0;
+
+                    for x in naturals() {
+                        if x > 100 { break; }
+                        sum += x;
+                    }
+
+                    sum
+                "
+            )
+            .unwrap(),
+        5050
+    );
+
+    // Nested generators
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn evens(n) {
+                        for x in 0..n {
+                            if x % 2 == 0 { yield x; }
+                        }
+                    }
+                    fn squares(gen) {
+                        for x in gen { yield x * x; }
+                        yield;
+                    }
+
+                    let list = [];
+                    for x in squares(evens(7)) { list.push(x); }
+                    list.len() * 1000 + list[0] + list[1] + list[2] + list[3]
+                "
+            )
+            .unwrap(),
+        5056
+    );
+
+    // Each `for` loop runs the generator from the start
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                r#"
+                    fn gen(x) { yield x; return 42; yield x + 1; }
+
+                    let g = gen(1);
+                    let sum = 0;
+                    for x in g { sum += x; }
+                    for x in g { sum += x; }
+                    if type_of(g) != "generator" { throw "bad type"; }
+                    sum
+                "#
+            )
+            .unwrap(),
+        2
+    );
+
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let step = 3;
+                    let gen = |n| { let x = 0; while x < n { yield x; x += step; } };
+                    let sum = 0;
+                    for x in gen.call(10) { sum += x; }
+                    sum
+                "
+            )
+            .unwrap(),
+        18
+    );
+}
+
+#[test]
+fn test_generators_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(engine.compile("yield 42;").unwrap_err().err_type(), ParseErrorType::WrongYield));
+
+    let err = engine
+        .run(
+            r#"
+                fn gen() { yield 1; throw "oops"; }
+                for x in gen() {}
+            "#,
+        )
+        .unwrap_err();
+
+    assert!(matches!(*err, EvalAltResult::ErrorInFunctionCall(..)), "{}", err);
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_generators_limits() {
+    let mut engine = Engine::new();
+    engine.set_max_operations(500);
+
+    // Operations inside the generator count towards the limit
+    let err = engine
+        .run(
+            "
+                fn gen() { loop { yield 1; } }
+                for x in gen() {}
+            ",
+        )
+        .unwrap_err();
+
+    assert!(matches!(*err, EvalAltResult::ErrorTooManyOperations(..)), "{}", err);
+
+    let err = engine
+        .run(
+            "
+                fn gen() { for x in 0..200 { yield x; } }
+                fn gen2() { for x in gen() { yield x; } }
+                for x in gen2() {}
+            ",
+        )
+        .unwrap_err();
+
+    assert!(matches!(*err, EvalAltResult::ErrorTooManyOperations(..)), "{}", err);
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_generators_max_generators() {
+    let mut engine = Engine::new();
+    engine.set_max_generators(2);
+
+    let script = "
+        fn gen(n) { for x in 0..n { yield x; } }
+        fn gen2(n) { for x in gen(n) { yield x; } }
+        fn gen3(n) { for x in gen2(n) { yield x; } }
+    ";
+
+    // Generators that are no longer running do not count towards the limit
+    assert_eq!(
+        engine
+            .eval::<INT>(&format!("{script} let sum = 0; for x in gen2(4) {{ sum += x; }} for x in gen2(5) {{ sum += x; }} sum"))
+            .unwrap(),
+        16
+    );
+
+    let err = engine.run(&format!("{script} for x in gen3(3) {{}}")).unwrap_err();
+    assert!(err.to_string().contains("Number of running generators"), "{}", err);
+
+    engine.set_max_generators(0);
+    assert_eq!(engine.max_generators(), 0);
+
+    let err = engine.run(&format!("{script} for x in gen(3) {{}}")).unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorDataTooLarge(..)), "{}", err);
+}
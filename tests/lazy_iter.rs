@@ -0,0 +1,134 @@
+#![cfg(not(feature = "no_index"))]
+use rhai::{Engine, LazyIter, Module, INT};
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+use rhai::{Array, EvalAltResult};
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_lazy_iter() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let total = 0;
+                    for n in (0..1_000_000).filter(|n| n % 7 == 0).map(|n| n * n).take(3) {
+                        total += n;
+                    }
+                    total
+                "
+            )
+            .unwrap(),
+        49 + 196
+    );
+    assert_eq!(
+        engine
+            .eval::<Array>("(1..=10).filter(|v| v % 2 == 0).map(|v| v * v).collect()")
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>(),
+        [4, 16, 36, 64, 100]
+    );
+    assert_eq!(engine.eval::<INT>("(1..100).take_while(|v| v * v < 20).collect().len()").unwrap(), 4);
+    assert_eq!(engine.eval::<INT>("(1..=5).skip(3).collect()[0]").unwrap(), 4);
+    assert_eq!(engine.eval::<INT>("(0..10).step_by(3).collect()[3]").unwrap(), 9);
+    assert_eq!(engine.eval::<INT>("(1..3).chain([42, 99]).collect()[3]").unwrap(), 99);
+    assert_eq!(engine.eval::<String>(r#"(1..100).zip(["a", "b"]).collect()[1][1]"#).unwrap(), "b");
+    assert_eq!(engine.eval::<INT>("let x = 0; for [i, v] in (5..8).enumerate() { x += i * v; } x").unwrap(), 6 + 14);
+    assert_eq!(engine.eval::<INT>("[1, 2, 3, 4, 5].iter().map(|v| v * 10).skip(2).collect()[0]").unwrap(), 30);
+    assert_eq!(engine.eval::<char>(r#""hello".iter().map(|c| to_upper(c)).collect()[0]"#).unwrap(), 'H');
+    assert_eq!(engine.eval::<INT>("(1..4).map(|| this * 2).collect()[2]").unwrap(), 6);
+    assert_eq!(engine.eval::<INT>("let x = (0..3).iter(); x.chain(x).collect().len()").unwrap(), 6);
+
+    // Iterators start afresh every time
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let x = (1..=3).map(|v| v * 2);
+                    let total = 0;
+                    for v in x { total += v; }
+                    for v in x { total += v; }
+                    total
+                "
+            )
+            .unwrap(),
+        24
+    );
+
+    // Adapters are lazy
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    let x = (1..10).map(|v| { if v > 3 { throw v; } v });
+                    let total = 0;
+                    for v in x.take(3) { total += v; }
+                    total
+                "
+            )
+            .unwrap(),
+        6
+    );
+    assert_eq!(engine.eval::<String>("type_of((1..2).iter())").unwrap(), "iterator");
+    assert_eq!(engine.eval::<String>("(1..2).iter().to_string()").unwrap(), "<iterator>");
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_lazy_iter_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(*engine.run("iter(42)").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+    assert!(matches!(*engine.run("(1..2).step_by(0)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run("for x in (1..2).chain(42) {}").unwrap_err(), EvalAltResult::ErrorFor(..)));
+    assert!(matches!(*engine.run("for x in (1..10).map(|v| { throw v; }) {}").unwrap_err(), EvalAltResult::ErrorInFunctionCall(..) | EvalAltResult::ErrorRuntime(..)));
+    assert!(matches!(*engine.run("(1..10).filter(|v| { throw v; }).collect()").unwrap_err(), EvalAltResult::ErrorInFunctionCall(..) | EvalAltResult::ErrorRuntime(..)));
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_lazy_iter_limits() {
+    let mut engine = Engine::new();
+    engine.set_max_array_size(10);
+
+    assert_eq!(engine.eval::<INT>("(0..1_000_000).map(|v| v * 2).take(10).collect().len()").unwrap(), 10);
+    assert!(matches!(*engine.run("(0..1_000_000).map(|v| v * 2).collect()").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+}
+
+#[test]
+fn test_lazy_iter_custom_type() {
+    #[derive(Debug, Clone)]
+    struct Countdown(INT);
+
+    impl IntoIterator for Countdown {
+        type Item = INT;
+        type IntoIter = std::iter::Rev<std::ops::RangeInclusive<INT>>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            (1..=self.0).rev()
+        }
+    }
+
+    let mut engine = Engine::new();
+    let mut module = Module::new();
+    module.set_iterable::<Countdown>();
+    engine.register_global_module(module.into());
+    engine.register_fn("countdown", Countdown);
+
+    assert_eq!(engine.eval::<INT>("let x = 0; for v in take(iter(countdown(5)), 2) { x += v; } x").unwrap(), 9);
+    assert_eq!(engine.eval::<INT>("len(collect(skip(iter(countdown(5)), 1)))").unwrap(), 4);
+
+    let iter = LazyIter::new(vec![rhai::Dynamic::from(1 as INT), rhai::Dynamic::from(2 as INT)]).take(1);
+    let mut scope = rhai::Scope::new();
+    scope.push("x", iter);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "let n = 0; for v in x { n += v; } n").unwrap(), 1);
+}
@@ -0,0 +1,188 @@
+#![cfg(feature = "metadata")]
+#![cfg(feature = "internals")]
+#![cfg(not(feature = "no_function"))]
+#![cfg(not(feature = "no_position"))]
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// A scripted LSP client driving the `rhai-lsp` tool.
+struct Client {
+    child: Child,
+    input: Option<ChildStdin>,
+    output: BufReader<ChildStdout>,
+    id: i64,
+    notifications: Vec<Value>,
+}
+
+impl Client {
+    fn new() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rhai-lsp")).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+
+        let input = child.stdin.take();
+        let output = BufReader::new(child.stdout.take().unwrap());
+
+        Self {
+            child,
+            input,
+            output,
+            id: 1,
+            notifications: Vec::new(),
+        }
+    }
+    fn read(&mut self) -> Value {
+        let mut len = 0;
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            assert!(self.output.read_line(&mut line).unwrap() > 0, "unexpected end of output");
+
+            match line.trim_end() {
+                "" => break,
+                header => {
+                    if let Some(n) = header.strip_prefix("Content-Length:") {
+                        len = n.trim().parse().unwrap();
+                    }
+                }
+            }
+        }
+
+        let mut body = vec![0; len];
+        self.output.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+    fn send(&mut self, message: Value) {
+        let body = message.to_string();
+        let input = self.input.as_mut().unwrap();
+        write!(input, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        input.flush().unwrap();
+    }
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.id;
+        self.id += 1;
+
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+
+        loop {
+            let message = self.read();
+
+            if message["id"] == id {
+                return message;
+            }
+            self.notifications.push(message);
+        }
+    }
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+    fn wait_notification(&mut self, method: &str) -> Value {
+        if let Some(n) = self.notifications.iter().position(|m| m["method"] == method) {
+            return self.notifications.remove(n)["params"].clone();
+        }
+
+        loop {
+            let message = self.read();
+
+            if message["method"] == method {
+                return message["params"].clone();
+            }
+            self.notifications.push(message);
+        }
+    }
+    fn open(&mut self, uri: &str, text: &str) -> Value {
+        self.notify("textDocument/didOpen", json!({ "textDocument": { "uri": uri, "languageId": "rhai", "version": 1, "text": text } }));
+        self.wait_notification("textDocument/publishDiagnostics")
+    }
+    fn at(&mut self, method: &str, uri: &str, line: usize, character: usize) -> Value {
+        self.request(method, json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } }))["result"].clone()
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_lsp() {
+    let mut client = Client::new();
+
+    let result = client.request("initialize", json!({ "capabilities": {} }))["result"].clone();
+    assert_eq!(result["serverInfo"]["name"], "rhai-lsp");
+    assert_eq!(result["capabilities"]["hoverProvider"], true);
+    assert_eq!(result["capabilities"]["definitionProvider"], true);
+    client.notify("initialized", json!({}));
+
+    // Diagnostics
+    let diagnostics = client.open("file:///bad.rhai", "let x = 1;\nlet = 2;");
+    assert_eq!(diagnostics["uri"], "file:///bad.rhai");
+    let diagnostics = diagnostics["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+
+    let script = "/// Add two numbers.
+fn add(a, b) { a + b }
+fn add(a) { a + 1 }
+let x = add(1, 2);
+add(x)";
+
+    let diagnostics = client.open("file:///test.rhai", script);
+    assert_eq!(diagnostics["diagnostics"], json!([]));
+
+    // Hover
+    let hover = client.at("textDocument/hover", "file:///test.rhai", 3, 9);
+    let text = hover["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("add(a, b)"));
+    assert!(text.contains("Add two numbers."));
+
+    let hover = client.at("textDocument/hover", "file:///test.rhai", 3, 5);
+    assert_eq!(hover, Value::Null);
+
+    // Definition - overloads are resolved by the number of arguments
+    let definition = client.at("textDocument/definition", "file:///test.rhai", 3, 9);
+    let definition = definition.as_array().unwrap();
+    assert_eq!(definition.len(), 1);
+    assert_eq!(definition[0]["uri"], "file:///test.rhai");
+    assert_eq!(definition[0]["range"]["start"]["line"], 1);
+
+    let definition = client.at("textDocument/definition", "file:///test.rhai", 4, 1);
+    let definition = definition.as_array().unwrap();
+    assert_eq!(definition.len(), 1);
+    assert_eq!(definition[0]["range"]["start"]["line"], 2);
+
+    // Definition of the function name itself lists all overloads
+    let definition = client.at("textDocument/definition", "file:///test.rhai", 1, 4);
+    assert_eq!(definition.as_array().unwrap().len(), 2);
+
+    // Not a script-defined function
+    let definition = client.at("textDocument/definition", "file:///test.rhai", 3, 5);
+    assert_eq!(definition, Value::Null);
+
+    // Functions in other modules are not resolved
+    #[cfg(not(feature = "no_module"))]
+    {
+        client.open("file:///module.rhai", "fn add(a, b) { a + b }\nimport \"foo\" as foo;\nfoo::add(1, 2)");
+        assert_eq!(client.at("textDocument/definition", "file:///module.rhai", 2, 6), Value::Null);
+    }
+
+    let reply = client.request("shutdown", Value::Null);
+    assert_eq!(reply["result"], Value::Null);
+}
+
+#[test]
+fn test_lsp_message_too_large() {
+    let mut client = Client::new();
+
+    // A huge message is rejected without allocating memory for it
+    let input = client.input.as_mut().unwrap();
+    write!(input, "Content-Length: {}\r\n\r\n{{}}", u64::MAX / 4).unwrap();
+    input.flush().unwrap();
+    drop(client.input.take());
+
+    let status = client.child.wait().unwrap();
+    assert_eq!(status.code(), Some(1));
+}
@@ -0,0 +1,127 @@
+use rhai::{Engine, ParseErrorType, Scope, INT};
+
+#[test]
+fn test_match() {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+    scope.push("x", 42 as INT);
+
+    assert_eq!(engine.eval::<char>("match 2 { 1 => (), 2 => 'a', 42 => true }").unwrap(), 'a');
+    engine.run("match 3 { 1 => (), 2 => 'a', 42 => true }").unwrap();
+    assert_eq!(engine.eval::<INT>("match 3 { 1 => (), 2 => 'a', _ => 123 }").unwrap(), 123);
+    assert_eq!(engine.eval::<INT>("match 3 { 1 | 2 => 1, 3..=5 => 2, _ => 3 }").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>(r#"match "hello" { "hi" | "hello" => 1, _ => 2 }"#).unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("match -1 { -1 => 1, _ => 2 }").unwrap(), 1);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "match x { n if n < 40 => 1, n => n + 1 }").unwrap(), 43);
+
+    // Bound variables do not leak out of the match arm
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "let y = 1; match 5 { y if y > 10 => 0, z => y + z }").unwrap(), 6);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "match 5 { x => x * 2 }; x").unwrap(), 42);
+
+    // Match as a statement
+    assert_eq!(engine.eval::<INT>("let r = 0; match 1 { 1 => { r = 42; } _ => () } r").unwrap(), 42);
+
+    // Type patterns
+    assert_eq!(engine.eval::<INT>("match 42 { x: string => 1, x: int => x + 1, _ => 0 }").unwrap(), 43);
+    assert_eq!(engine.eval::<INT>(r#"match "x" { _: char => 1, _: string => 2, _ => 0 }"#).unwrap(), 2);
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(engine.eval::<INT>("match 1.5 { _: int => 1, _: float => 2, _ => 0 }").unwrap(), 2);
+
+    // Constants are shadowed by bound variables
+    assert_eq!(engine.eval::<INT>("const c = 1; match 5 { c => c }").unwrap(), 5);
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_match_arrays() {
+    let engine = Engine::new();
+
+    let describe = |value: &str| {
+        engine
+            .eval::<String>(&format!(
+                r#"
+                    match {value} {{
+                        [] => "empty",
+                        [x] => `one: ${{x}}`,
+                        [1, 2 | 3, x: int] => `special: ${{x}}`,
+                        [first, .., last] => `${{first}}..${{last}}`,
+                        _ => "not an array",
+                    }}
+                "#
+            ))
+            .unwrap()
+    };
+
+    assert_eq!(describe("[]"), "empty");
+    assert_eq!(describe("[42]"), "one: 42");
+    assert_eq!(describe("[1, 3, 5]"), "special: 5");
+    assert_eq!(describe("[1, 3, true]"), "1..true");
+    assert_eq!(describe("[1, 2, 3, 4]"), "1..4");
+    assert_eq!(describe("42"), "not an array");
+
+    assert_eq!(engine.eval::<INT>("match [1, 2, 3, 4] { [a, ..rest] => a + len(rest) * 10 }").unwrap(), 31);
+    assert_eq!(engine.eval::<INT>("match [1, [2, 3]] { [a, [b, c]] => a + b + c }").unwrap(), 6);
+    assert_eq!(engine.eval::<INT>("match [1, 2] { [a, b] | [b, a, _] if a > b => 1, [a, b] => a * 10 + b }").unwrap(), 12);
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_match_maps() {
+    let engine = Engine::new();
+
+    let script = r#"
+        match payload {
+            #{ kind: "user", age } if age < 18 => "minor",
+            #{ kind: "user", name, age: _: int } => `adult ${name}`,
+            #{ kind: "admin" | "root", "full name": name } => `admin ${name}`,
+            #{ kind } => `unknown kind ${kind}`,
+            _ => "invalid",
+        }
+    "#;
+
+    let mut scope = Scope::new();
+    let mut check = |payload: &str, expected: &str| {
+        scope.clear();
+        let payload = engine.parse_json(payload, true).unwrap();
+        scope.push("payload", payload);
+        assert_eq!(engine.eval_with_scope::<String>(&mut scope, script).unwrap(), expected);
+    };
+
+    check(r#"{ "kind": "user", "name": "Bob", "age": 12 }"#, "minor");
+    check(r#"{ "kind": "user", "name": "Bob", "age": 42, "extra": true }"#, "adult Bob");
+    check(r#"{ "kind": "user", "name": "Bob", "age": "old" }"#, "unknown kind user");
+    check(r#"{ "kind": "root", "full name": "Alice" }"#, "admin Alice");
+    check(r#"{ "name": "Bob" }"#, "invalid");
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("match #{ a: [1, #{ b: 2 }] } { #{ a: [x, #{ b }] } => x + b }").unwrap(), 3);
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_closure"))]
+fn test_match_closures() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let f = match 5 { x => |y| x + y }; call(f, 1)").unwrap(), 6);
+}
+
+#[test]
+fn test_match_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(engine.compile("match x { 1 + x => 0 }").unwrap_err().err_type(), ParseErrorType::ExprExpected(..)));
+    assert!(matches!(engine.compile("match x { 1 => 0 2 => 1 }").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+    assert!(matches!(engine.compile("match x { 1 | y => 0 }").unwrap_err().err_type(), ParseErrorType::MalformedPattern(..)));
+    assert!(matches!(engine.compile("match x { y: 42 => 0 }").unwrap_err().err_type(), ParseErrorType::MalformedPattern(..)));
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert!(matches!(engine.compile("match x { [a, a] => 0 }").unwrap_err().err_type(), ParseErrorType::DuplicatedVariable(..)));
+        assert!(matches!(engine.compile("match x { [a] | [b] => 0 }").unwrap_err().err_type(), ParseErrorType::MalformedPattern(..)));
+        assert!(matches!(engine.compile("match x { [.., ..] => 0 }").unwrap_err().err_type(), ParseErrorType::MalformedPattern(..)));
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(engine.compile("match x { #{ a, a: b } => 0 }").unwrap_err().err_type(), ParseErrorType::DuplicatedProperty(..)));
+}
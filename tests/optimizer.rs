@@ -223,3 +223,319 @@ fn test_optimizer_volatile() {
     // Make sure the call is optimized away
     assert!(!text_ast.contains(r#"name: "foo""#));
 }
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_optimizer_inline() {
+    let mut engine = Engine::new();
+
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    // Constant arguments are propagated through the function body and dead branches are removed
+    let ast = engine.compile("fn sign(x) { if x > 0 { 1 } else if x < 0 { -1 } else { 0 } } sign(-5)").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "sign""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), -1);
+
+    let ast = engine.compile("fn add(x, y) { return x + y; } const a = 10; const b = 3; add(b, a) * add(a, b)").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "add""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 169);
+
+    let ast = engine.compile("fn pick(x, y) { let z = x; z } let a = 41; pick(a, 0) + a").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "pick""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 82);
+
+    // Non-constant arguments
+    let ast = engine.compile("fn inc(x) { x += 1; x } let a = 41; inc(a) + a").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "inc""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 83);
+
+    let ast = engine.compile("fn add1(x) { x + 1 } fn sq(x) { x * x } let a = 6; sq(add1(a)) + sq(a + 2)").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "add1""#));
+    assert!(!format!("{ast:?}").contains(r#"name: "sq""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 113);
+
+    // Errors are still raised from within the function, but not those in the arguments
+    #[cfg(not(feature = "unchecked"))]
+    for script in [
+        format!("fn f(x) {{ x + 1 }} let a = {}; f(a)", INT::MAX),
+        format!("fn f(x) {{ x + 1 }} let a = {}; f(a + 1)", INT::MAX),
+        "fn f(x) { x / 0 } let a = 1; f(a)".to_string(),
+        "fn f(x) { let y = x * 2; y / 0 } let a = 1; try { f(a) } catch (e) { throw e }".to_string(),
+    ] {
+        let ast = engine.compile(&script).unwrap();
+        assert!(!format!("{ast:?}").contains(r#"name: "f""#));
+        let err = engine.eval_ast::<rhai::Dynamic>(&ast).unwrap_err();
+        engine.set_optimization_level(OptimizationLevel::None);
+        let expected = engine.eval::<rhai::Dynamic>(&script).unwrap_err();
+        engine.set_optimization_level(OptimizationLevel::Full);
+        assert_eq!(err.to_string(), expected.to_string());
+        assert_eq!(err.position(), expected.position());
+    }
+
+    // Functions calling impure functions are not inlined
+    let ast = engine.compile("fn roll() { rand(); 42 } roll()").unwrap();
+    assert!(format!("{ast:?}").contains(r#"name: "roll""#));
+
+    // Recursive functions are not inlined
+    let ast = engine.compile("fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } } fib(6)").unwrap();
+    assert!(format!("{ast:?}").contains(r#"name: "fib""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 8);
+
+    // Functions accessing the calling scope are not inlined
+    assert!(engine.eval::<INT>("fn foo() { y } let y = 42; foo()").is_err());
+    assert!(!engine.eval::<bool>(r#"fn foo() { is_def_var("y") } let y = 42; foo()"#).unwrap());
+
+    // Function body too large
+    engine.set_max_inline_size(2);
+    let ast = engine.compile("fn add(x, y) { x + y } add(40, 2)").unwrap();
+    assert!(format!("{ast:?}").contains(r#"name: "add""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    engine.set_max_inline_size(0);
+    let ast = engine.compile("fn add(x, y) { x + y } add(40, 2)").unwrap();
+    assert!(format!("{ast:?}").contains(r#"name: "add""#));
+
+    // Functions are only inlined under full optimization
+    engine.set_max_inline_size(rhai::Engine::new().max_inline_size());
+    engine.set_optimization_level(OptimizationLevel::Simple);
+    let ast = engine.compile("fn add(x, y) { x + y } add(40, 2)").unwrap();
+    assert!(format!("{ast:?}").contains(r#"name: "add""#));
+}
+
+#[test]
+fn test_optimizer_loop_invariants() {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = count.clone();
+
+    FuncRegistration::new("foo").with_volatility(false).register_into_engine(&mut engine, move |x: INT| {
+        counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        x * 2
+    });
+
+    let script = "let n = 5; let i = 0; while i < foo(n) { i += 1; } i";
+
+    let ast = engine.compile(script).unwrap();
+    assert!(format!("{ast:?}").contains("$temp"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 10);
+    assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // Invariants in the bodies of `while` and `for` loops are evaluated on the first iteration
+    for (script, expected, calls) in [
+        ("let n = 5; let s = 0; let i = 0; while i < 4 { let k = foo(n); s += k; i += 1; } s", 40, 1),
+        ("let n = 5; let s = 0; for x in 0..4 { let k = foo(n); s += k + x; } let t = s + n; t", 51, 1),
+        ("let n = 5; let s = 0; for (x, j) in 0..3 { let k = foo(n); s += k * j + x; } s", 33, 1),
+        ("let n = 5; let s = 0; let i = 0; while i < 0 { let k = foo(n); s += k; i += 1; } s", 0, 0),
+        ("let n = 5; let s = 0; for x in 0..0 { let k = foo(n); s += k; } s", 0, 0),
+    ] {
+        count.store(0, std::sync::atomic::Ordering::SeqCst);
+        let ast = engine.compile(script).unwrap();
+        assert!(format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), expected);
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), calls);
+    }
+
+    // Expressions depending on the loop variable are not hoisted
+    count.store(0, std::sync::atomic::Ordering::SeqCst);
+    let ast = engine.compile("let s = 0; for x in 0..3 { let k = foo(x); s += k; } s").unwrap();
+    assert!(!format!("{ast:?}").contains("$temp"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 6);
+    assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+    // Volatile functions are not hoisted
+    FuncRegistration::new("foo").with_volatility(true).register_into_engine(&mut engine, |x: INT| x * 2);
+    let ast = engine.compile(script).unwrap();
+    assert!(!format!("{ast:?}").contains("$temp"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 10);
+
+    // Expressions not evaluated on every iteration are not hoisted
+    let ast = engine.compile("let n = 5; let i = 0; while i < 10 { if i > 5 { i += abs(n); } i += 1; } i").unwrap();
+    assert!(!format!("{ast:?}").contains("$temp"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 12);
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let ast = engine
+            .compile("let a = [1, 2, 3]; let s = 0; let i = 0; do { let n = len(a); s += n; i += 1; } while i < 3; s")
+            .unwrap();
+        assert!(format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 9);
+
+        // Variables modified in the loop are not invariant
+        let ast = engine
+            .compile("let a = [1, 2, 3]; let i = 0; while i < len(a) { push(a, i); i += 1; if i > 20 { break; } } len(a)")
+            .unwrap();
+        assert!(!format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 24);
+
+        // Loops that do not run must not evaluate anything
+        let ast = engine.compile("let a = []; let i = 0; while i < 0 { let z = a[0]; i += 1; } i").unwrap();
+        assert!(format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 0);
+
+        let ast = engine.compile("let a = []; let s = 0; for x in a { let z = a[0]; s += z; } s").unwrap();
+        assert!(format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 0);
+
+        let ast = engine.compile("let a = [1, 2, 3]; let s = 0; for x in a { let z = a[0] + a[2]; s += z * x; } s").unwrap();
+        assert!(format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 24);
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        let ast = engine
+            .compile("let m = #{b: #{c: 3}}; let s = 0; let i = 0; loop { let k = m.b.c * 2; s += k + i; i += 1; if i > 4 { break; } } s")
+            .unwrap();
+        assert!(format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 40);
+    }
+
+    // Only under full optimization
+    engine.set_optimization_level(OptimizationLevel::Simple);
+    let ast = engine.compile("let n = 5; let i = 0; while i < foo(n) { i += 1; } i").unwrap();
+    assert!(!format!("{ast:?}").contains("$temp"));
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_optimizer_common_subexpr() {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Full);
+
+    let ast = engine.compile("let m = #{b: #{c: 3}}; let x = 0; { let p = m.b.c + 1; let q = m.b.c * 2; x = p + q; } x").unwrap();
+    assert!(format!("{ast:?}").contains("$temp"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 10);
+
+    // Properties modified in between are re-evaluated
+    let ast = engine
+        .compile("let m = #{b: #{c: 3}}; let x = 0; { let p = m.b.c + 1; m.b.c = 10; let q = m.b.c * 2; x = p + q; } x")
+        .unwrap();
+    assert!(!format!("{ast:?}").contains("$temp"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 24);
+
+    // Function bodies
+    #[cfg(not(feature = "no_function"))]
+    {
+        let ast = engine.compile("fn f(m) { m.b.c + m.b.c } f(#{b: #{c: 3}})").unwrap();
+        assert!(format!("{ast:?}").contains("$temp"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 6);
+    }
+
+    // Not at the top level of a script, where new variables would be left in the scope
+    let ast = engine.compile("let m = #{b: #{c: 3}}; let p = m.b.c + 1; let q = m.b.c * 2; p + q").unwrap();
+    assert!(!format!("{ast:?}").contains("$temp"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 10);
+}
+
+#[cfg(feature = "internals")]
+#[test]
+fn test_optimizer_pass() {
+    use rhai::{Expr, OptimizerContext, OptimizerPass, Stmt};
+
+    // Replace calls to `old_inc` with `inc`
+    struct ReplaceOldInc;
+
+    impl OptimizerPass for ReplaceOldInc {
+        fn optimize_stmt(&self, stmt: &mut Stmt, context: &mut OptimizerContext) -> bool {
+            match stmt {
+                Stmt::FnCall(x, ..) if x.name == "old_inc" => {
+                    x.name = "inc".into();
+                    x.hashes = context.calc_fn_call_hashes(&x.name, x.args.len());
+                    true
+                }
+                _ => false,
+            }
+        }
+        fn optimize_expr(&self, expr: &mut Expr, context: &mut OptimizerContext) -> bool {
+            match expr {
+                Expr::FnCall(x, ..) if x.name == "old_inc" => {
+                    x.name = "inc".into();
+                    x.hashes = context.calc_fn_call_hashes(&x.name, x.args.len());
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    // Replace `double(CONSTANT)` with the result
+    struct FoldDouble;
+
+    impl OptimizerPass for FoldDouble {
+        fn optimize_expr(&self, expr: &mut Expr, context: &mut OptimizerContext) -> bool {
+            match expr {
+                Expr::FnCall(x, pos) if x.name == "double" && x.args.len() == 1 => {
+                    let value = match x.args[0] {
+                        Expr::IntegerConstant(n, ..) => n,
+                        Expr::Variable(ref v, ..) => match context.find_constant(&v.1).and_then(|v| v.as_int().ok()) {
+                            Some(n) => n,
+                            None => return false,
+                        },
+                        _ => return false,
+                    };
+                    *expr = Expr::IntegerConstant(value * 2, *pos);
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    // Remove calls to pure functions whose results are not used
+    struct RemovePureCalls;
+
+    impl OptimizerPass for RemovePureCalls {
+        fn optimize_stmt(&self, stmt: &mut Stmt, context: &mut OptimizerContext) -> bool {
+            match stmt {
+                Stmt::FnCall(x, pos) if x.args.iter().all(Expr::is_pure) && context.is_pure_call(x) => {
+                    assert!(context.is_pure_fn(&x.name, x.args.len()));
+                    *stmt = Stmt::Noop(*pos);
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    let mut engine = Engine::new();
+    engine.register_fn("inc", |x: INT| x + 1);
+    engine.register_fn("double", |x: INT| x * 2);
+    engine.register_optimizer_pass(ReplaceOldInc);
+    engine.register_optimizer_pass(FoldDouble);
+
+    assert_eq!(engine.eval::<INT>("old_inc(41)").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("let x = old_inc(41); x").unwrap(), 42);
+
+    let ast = engine.compile("const A = 21; let x = double(A); x").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "double""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    // Constants from the scope
+    let mut scope = Scope::new();
+    scope.push_constant("B", 5 as INT);
+    let ast = engine.compile_with_scope(&scope, "let x = double(B); x").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "double""#));
+    assert_eq!(engine.eval_ast_with_scope::<INT>(&mut scope, &ast).unwrap(), 10);
+
+    // Variables are not constants
+    let ast = engine.compile("let a = 21; let x = double(a); x").unwrap();
+    assert!(format!("{ast:?}").contains(r#"name: "double""#));
+
+    // Passes do not run without optimization
+    engine.set_optimization_level(OptimizationLevel::None);
+    assert!(engine.eval::<INT>("old_inc(41)").is_err());
+
+    let mut engine = Engine::new();
+    FuncRegistration::new("foo").with_volatility(false).register_into_engine(&mut engine, |x: INT| x + 1);
+    FuncRegistration::new("bar").with_volatility(true).register_into_engine(&mut engine, |x: INT| x + 1);
+    engine.register_optimizer_pass(RemovePureCalls);
+
+    // Volatile functions are not pure
+    let ast = engine.compile("foo(1); bar(2); 42").unwrap();
+    assert!(!format!("{ast:?}").contains(r#"name: "foo""#));
+    assert!(format!("{ast:?}").contains(r#"name: "bar""#));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+}
@@ -0,0 +1,121 @@
+use rhai::{Engine, EvalAltResult, Scope, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+
+#[test]
+fn test_random() {
+    let mut engine = Engine::new();
+
+    engine.set_random_seed(42);
+
+    for _ in 0..100 {
+        let x = engine.eval::<INT>("rand(1..7)").unwrap();
+        assert!((1..7).contains(&x));
+        let x = engine.eval::<INT>("rand(-3..=3)").unwrap();
+        assert!((-3..=3).contains(&x));
+    }
+
+    assert_eq!(engine.eval::<INT>("rand(5..=5)").unwrap(), 5);
+
+    let mut scope = Scope::new();
+    scope.push("min", INT::MIN).push("max", INT::MAX);
+    engine.eval_with_scope::<INT>(&mut scope, "rand(min..=max)").unwrap();
+
+    assert!(engine.eval::<bool>("let seen = 0; for i in 0..100 { if rand_bool() { seen += 1; } } seen > 0 && seen < 100").unwrap());
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert!(engine
+            .eval::<bool>("let ok = true; for i in 0..100 { let x = rand_float(); if x < 0.0 || x >= 1.0 { ok = false; } } ok")
+            .unwrap());
+        assert!(!engine.eval::<bool>("rand_bool(0.0)").unwrap());
+        assert!(engine.eval::<bool>("rand_bool(1.0)").unwrap());
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let mut result = engine
+            .eval::<Array>("let x = [1, 2, 3, 4, 5, 6, 7, 8]; shuffle(x); x")
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+        assert_eq!(result, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let result = engine
+            .eval::<Array>("let x = [1, 2, 3, 4, 5]; sample(x, 3)")
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|x| (1..=5).contains(x)));
+        assert!(result[0] != result[1] && result[1] != result[2] && result[0] != result[2]);
+
+        assert_eq!(engine.eval::<INT>("len(sample([1, 2, 3], 10))").unwrap(), 3);
+        assert_eq!(engine.eval::<INT>("len(sample([1, 2, 3], 0))").unwrap(), 0);
+        assert!((1..=3).contains(&engine.eval::<INT>("sample([1, 2, 3])").unwrap()));
+        assert!(engine.eval::<bool>("sample([]) == ()").unwrap());
+    }
+}
+
+#[test]
+fn test_random_seed() {
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let script = "let x = []; for i in 0..10 { push(x, rand()); } x";
+
+        engine.set_random_seed(123);
+        let a = engine.eval::<Array>(script).unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>();
+        engine.set_random_seed(123);
+        let b = engine.eval::<Array>(script).unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>();
+        engine.set_random_seed(456);
+        let c = engine.eval::<Array>(script).unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        engine.set_random_seed(123);
+        let a = engine
+            .eval::<Array>("let x = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]; shuffle(x); x")
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>();
+        engine.set_random_seed(123);
+        let b = engine
+            .eval::<Array>("let x = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]; shuffle(x); x")
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_int().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(a, b);
+    }
+
+    engine.set_random_seed(7);
+    let a = engine.eval::<INT>("rand(0..1000000)").unwrap();
+    engine.set_random_seed(7);
+    assert_eq!(engine.eval::<INT>("rand(0..1000000)").unwrap(), a);
+
+    // Random functions are volatile and never optimized into constants
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        engine.set_optimization_level(rhai::OptimizationLevel::Full);
+        assert!(engine.eval::<bool>("let x = rand(); let y = rand(); x != y").unwrap());
+    }
+}
+
+#[test]
+fn test_random_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(*engine.eval::<INT>("rand(5..5)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("rand(5..=4)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    #[cfg(not(feature = "no_float"))]
+    assert!(matches!(*engine.eval::<bool>("rand_bool(1.5)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "regex")]
+use rhai::{Engine, EvalAltResult, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+
+#[test]
+fn test_regex() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"type_of(regex("a+"))"#).unwrap(), "regex");
+    assert_eq!(engine.eval::<String>(r#"regex("a+").to_string()"#).unwrap(), "a+");
+    assert_eq!(engine.eval::<String>(r#"to_debug(regex("a+"))"#).unwrap(), r#"regex("a+")"#);
+
+    assert!(engine.eval::<bool>(r#""hello, world!".matches("w.r")"#).unwrap());
+    assert!(!engine.eval::<bool>(r#""hello, world!".matches("^w")"#).unwrap());
+    assert!(engine.eval::<bool>(r#"let re = regex("h(e|a)llo"); "hallo".matches(re)"#).unwrap());
+
+    assert_eq!(engine.eval::<String>(r##""order #123, #456".find("#\\d+")"##).unwrap(), "#123");
+    assert!(engine.eval::<bool>(r##""order #123, #456".find(regex("x")) == ()"##).unwrap());
+
+    assert_eq!(engine.eval::<String>(r#"let s = "hello, world!"; s.replace_regex("(\\w+), (\\w+)", "$2, $1"); s"#).unwrap(), "world, hello!");
+    assert_eq!(engine.eval::<String>(r##"let s = "#1 #22"; s.replace_regex(regex("#(?<n>\\d+)"), "[${n}]"); s"##).unwrap(), "[1] [22]");
+    assert_eq!(engine.eval::<String>(r#"let s = "abc"; s.replace_regex("x", "y"); s"#).unwrap(), "abc");
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let result = engine.eval::<Array>(r##""order #123, #456".find_all("#\\d+")"##).unwrap();
+        assert_eq!(result.into_iter().map(|v| v.into_string().unwrap()).collect::<Vec<_>>(), ["#123", "#456"]);
+
+        let result = engine.eval::<Array>(r#""a, b;c ,d".split_regex(regex("\\s*[,;]\\s*"))"#).unwrap();
+        assert_eq!(result.into_iter().map(|v| v.into_string().unwrap()).collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+
+        let result = engine.eval::<Array>(r#""a, b;c ,d".split_regex("\\s*[,;]\\s*", 2)"#).unwrap();
+        assert_eq!(result.into_iter().map(|v| v.into_string().unwrap()).collect::<Vec<_>>(), ["a", "b;c ,d"]);
+
+        assert_eq!(engine.eval::<INT>(r#""abc".split_regex("x").len"#).unwrap(), 1);
+        assert_eq!(engine.eval::<INT>(r#""abc".split_regex("b", 0).len"#).unwrap(), 1);
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(
+            engine
+                .eval::<String>(r#"let m = "x: a=42".captures("(?<key>\\w+)=(?<value>\\w+)"); `${m.key}:${m.value}:${m["0"]}`"#)
+                .unwrap(),
+            "a:42:a=42"
+        );
+        assert!(engine.eval::<bool>(r#"let m = "ab".captures(regex("a(x)?(b)")); m["1"] == () && m["2"] == "b""#).unwrap());
+        assert!(engine.eval::<bool>(r#""ab".captures("x") == ()"#).unwrap());
+    }
+}
+
+#[test]
+fn test_regex_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(*engine.eval::<bool>(r#""abc".matches("(")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<bool>(r#""abc".matches("\\w{1000}{1000}")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_regex_limits() {
+    let mut engine = Engine::new();
+
+    engine.set_max_string_size(100);
+    assert!(matches!(*engine.run(r#"let s = "aaaaaaaaaa"; s.replace_regex("a", "0123456789ABCDEF");"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+
+    engine.set_max_string_size(0);
+    engine.set_max_operations(100);
+    assert!(matches!(*engine.run(r#"let s = ""; s.pad(200, 'a'); s.replace_regex("a", "b");"#).unwrap_err(), EvalAltResult::ErrorTooManyOperations(..)));
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert!(matches!(*engine.run(r#"let s = ""; s.pad(200, 'a'); s.find_all("a");"#).unwrap_err(), EvalAltResult::ErrorTooManyOperations(..)));
+
+        engine.set_max_operations(0);
+        engine.set_max_array_size(10);
+        assert!(matches!(*engine.run(r#"let s = "a,b,c,d,e,f,g,h,i,j,k,l"; s.split_regex(",");"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+        assert_eq!(engine.eval::<INT>(r#"let s = "a,b,c"; s.split_regex(",").len"#).unwrap(), 3);
+    }
+}
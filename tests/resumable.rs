@@ -0,0 +1,178 @@
+#![cfg(feature = "resumable")]
+use rhai::{Engine, EvalAltResult, Resumable, Scope, INT};
+
+#[test]
+fn test_resumable() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_fn("wait", |event: &str| -> Result<INT, Box<EvalAltResult>> {
+        let value = rhai::suspend(event).ok_or("not resumable")?;
+        Ok(value.as_int().unwrap())
+    });
+
+    let mut state = engine.eval_resumable::<INT>(
+        r#"
+            let x = wait("foo");
+            let y = wait("bar");
+            x * 10 + y
+        "#,
+    )?;
+
+    let mut events = Vec::new();
+
+    let result = loop {
+        match state {
+            Resumable::Done(result) => break result,
+            Resumable::Suspended(suspended) => {
+                events.push(suspended.value().to_string());
+                state = suspended.resume(events.len() as INT)?;
+            }
+        }
+    };
+
+    assert_eq!(result, 12);
+    assert_eq!(events, ["foo", "bar"]);
+
+    assert!(matches!(engine.eval_resumable::<INT>("42")?, Resumable::Done(42)));
+
+    assert!(matches!(*engine.eval::<INT>(r#"wait("foo")"#).unwrap_err(), EvalAltResult::ErrorRuntime(..) | EvalAltResult::ErrorInFunctionCall(..)));
+    assert!(rhai::suspend(()).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_resumable_with_scope() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_fn("pause", || {
+        rhai::suspend(());
+    });
+
+    let ast = engine.compile("x += 1; pause(); x += 1; pause(); x")?;
+    let mut scope = Scope::new();
+    scope.push("x", 40 as INT);
+
+    {
+        let mut state = engine.eval_ast_with_scope_resumable::<INT>(&mut scope, &ast)?;
+        let mut count = 0;
+
+        let result = loop {
+            match state {
+                Resumable::Done(result) => break result,
+                Resumable::Suspended(suspended) => state = suspended.resume(())?,
+            }
+            count += 1;
+        };
+
+        assert_eq!(result, 42);
+        assert_eq!(count, 2);
+    }
+
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 42);
+
+    // Dropping a suspended evaluation cancels it
+    let state = engine.eval_ast_with_scope_resumable::<INT>(&mut scope, &ast)?;
+    assert!(matches!(state, Resumable::Suspended(..)));
+    drop(state);
+
+    assert_eq!(scope.get_value::<INT>("x").unwrap(), 43);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_resumable_progress() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.on_progress(|ops| {
+        if ops % 10 == 0 {
+            rhai::suspend(ops as INT);
+        }
+        None
+    });
+
+    let mut state = engine.eval_resumable::<INT>("let x = 0; for n in 0..100 { x += n; } x")?;
+    let mut count = 0;
+
+    let result = loop {
+        match state {
+            Resumable::Done(result) => break result,
+            Resumable::Suspended(suspended) => {
+                assert_eq!(suspended.value().as_int().unwrap() % 10, 0);
+                state = suspended.resume(())?;
+            }
+        }
+        count += 1;
+    };
+
+    assert_eq!(result, 4950);
+    assert!(count >= 20);
+
+    Ok(())
+}
+
+#[test]
+fn test_resumable_interleaved() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.set_resumable_stack_size(256 * 1024);
+    assert_eq!(engine.resumable_stack_size(), 256 * 1024);
+
+    engine.register_fn("wait", || -> INT { rhai::suspend(()).unwrap().as_int().unwrap() });
+
+    let mut states = (0..10)
+        .map(|n| engine.eval_resumable::<INT>(&format!("let x = {n}; x + wait() * 10 + wait()")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Resume all evaluations in turn
+    for step in 1..=2 {
+        states = states
+            .into_iter()
+            .map(|state| match state {
+                Resumable::Suspended(suspended) => suspended.resume(step as INT),
+                Resumable::Done(..) => unreachable!(),
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    for (n, state) in states.into_iter().enumerate() {
+        match state {
+            Resumable::Done(result) => assert_eq!(result, n as INT + 12),
+            Resumable::Suspended(..) => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn test_resumable_send() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_fn("wait", || -> INT { rhai::suspend(()).unwrap().as_int().unwrap() });
+
+    let scripts = [
+        "let x = 40; for n in 0..2 { x += wait(); } x",
+        #[cfg(not(feature = "no_function"))]
+        "fn gen() { yield 20; yield 1; } let x = 21; for v in gen() { x += v * wait(); } x",
+    ];
+
+    for script in scripts {
+        let mut state = engine.eval_resumable::<INT>(script)?;
+
+        // Resume on a different thread each time
+        let result = loop {
+            state = match state {
+                Resumable::Done(result) => break result,
+                Resumable::Suspended(suspended) => std::thread::scope(|s| s.spawn(move || suspended.resume(1 as INT)).join().unwrap())?,
+            };
+        };
+
+        assert_eq!(result, 42);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+#![cfg(not(feature = "no_index"))]
+use rhai::{Engine, EvalAltResult, Set};
+
+#[cfg(any(not(feature = "no_object"), feature = "serde"))]
+use rhai::{Dynamic, INT};
+
+#[cfg(not(feature = "no_object"))]
+use rhai::Array;
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_set() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("type_of(set())").unwrap(), "set");
+    assert_eq!(engine.eval::<INT>("set([1, 2, 3, 2, 1]).len").unwrap(), 3);
+    assert!(engine.eval::<bool>("set().is_empty").unwrap());
+    assert_eq!(engine.eval::<String>(r#"set([3, "a", 1, 'x', true, ()]).to_string()"#).unwrap(), r#"{(), true, 1, 3, x, "a"}"#);
+    assert_eq!(engine.eval::<String>(r#"`${[1, 2].to_set()}`"#).unwrap(), "{1, 2}");
+
+    assert!(engine.eval::<bool>("let s = set([1, 2, 3]); 2 in s").unwrap());
+    assert!(engine.eval::<bool>("let s = set([1, 2, 3]); 42 !in s").unwrap());
+    assert!(!engine.eval::<bool>(r#"let s = set([1, 2, 3]); "1" in s"#).unwrap());
+
+    assert!(engine.eval::<bool>("let s = set(); s.insert(1)").unwrap());
+    assert!(!engine.eval::<bool>("let s = set([1]); s.insert(1)").unwrap());
+    assert_eq!(engine.eval::<INT>("let s = set([1, 2]); s.remove(1); s.remove(42); s.len").unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("let s = set([1, 2]); s.clear(); s.len").unwrap(), 0);
+
+    assert_eq!(engine.eval::<String>("`${set([1, 2]) | set([2, 3])}`").unwrap(), "{1, 2, 3}");
+    assert_eq!(engine.eval::<String>("`${set([1, 2]) & set([2, 3])}`").unwrap(), "{2}");
+    assert_eq!(engine.eval::<String>("`${set([1, 2]) - set([2, 3])}`").unwrap(), "{1}");
+    assert_eq!(engine.eval::<String>("`${set([1, 2]) ^ set([2, 3])}`").unwrap(), "{1, 3}");
+    assert_eq!(engine.eval::<String>("let s = set([1]); s |= set([2]); s -= set([1]); `${s}`").unwrap(), "{2}");
+
+    assert!(engine.eval::<bool>("set([1]).is_subset(set([1, 2]))").unwrap());
+    assert!(engine.eval::<bool>("set([1, 2]).is_superset(set([1]))").unwrap());
+    assert!(engine.eval::<bool>("set([1]).is_disjoint(set([2]))").unwrap());
+    assert!(engine.eval::<bool>("set([1, 2]) == set([2, 1])").unwrap());
+    assert!(engine.eval::<bool>("set([1, 2]) != set([1])").unwrap());
+
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in set([1, 2, 3, 3]) { sum += x; } sum").unwrap(), 6);
+    assert_eq!(
+        engine
+            .eval::<Array>(r#"set(["b", "a", "c", "a"]).to_array()"#)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.into_string().unwrap())
+            .collect::<Vec<_>>(),
+        ["a", "b", "c"]
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(engine.eval::<INT>("set([1, 1.0, 1.0]).len").unwrap(), 2);
+
+    let s = engine.eval::<Set>("set([1, 2])").unwrap();
+    assert!(s.contains(&Dynamic::from(1 as INT)));
+    assert_eq!(s.len(), 2);
+}
+
+#[test]
+fn test_set_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(*engine.eval::<Set>("set([[1]])").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+    assert!(matches!(*engine.eval::<bool>("insert(set(), [])").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(feature = "no_object"))]
+fn test_set_limits() {
+    let mut engine = Engine::new();
+
+    engine.set_max_array_size(10);
+
+    assert!(matches!(*engine.run("let s = set(); for i in 0..20 { s.insert(i); }").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert!(matches!(*engine.run("let s = set([1, 2, 3, 4, 5, 6]) | set([7, 8, 9, 10, 11, 12]);").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert_eq!(engine.eval::<INT>("let s = set(); for i in 0..10 { s.insert(i); } s.len").unwrap(), 10);
+
+    engine.set_max_array_size(0);
+    engine.set_max_string_size(10);
+
+    assert!(matches!(*engine.run(r#"let s = set(["hello", "world!"]);"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_set_json() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"#{a: set([2, 1]), b: set(["x"])}.to_json()"#).unwrap(), r#"{"a":[1,2],"b":["x"]}"#);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_set_serde() {
+    use rhai::serde::{from_dynamic, to_dynamic};
+    use std::collections::BTreeSet;
+
+    let engine = Engine::new();
+
+    let s = engine.eval::<Dynamic>("set([3, 1, 2])").unwrap();
+    let x: BTreeSet<INT> = from_dynamic(&s).unwrap();
+    assert_eq!(x, [1, 2, 3].iter().copied().collect());
+
+    let s = engine.eval::<Set>("set([3, 1, 2])").unwrap();
+    let d = to_dynamic(&s).unwrap();
+    assert!(d.is_array());
+    assert_eq!(d.into_array().unwrap().len(), 3);
+
+    let json = serde_json::to_string(&s).unwrap();
+    assert_eq!(json, "[1,2,3]");
+    let s2: Set = serde_json::from_str(&json).unwrap();
+    assert_eq!(s, s2);
+    assert!(serde_json::from_str::<Set>("[[1]]").is_err());
+}
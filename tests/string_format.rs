@@ -0,0 +1,84 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_string_format() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"format("{} + {} = {}", 1, 2, 3)"#).unwrap(), "1 + 2 = 3");
+    assert_eq!(engine.eval::<String>(r#"format("{1}-{0}-{1} {{}}", "a", "b")"#).unwrap(), "b-a-b {}");
+    assert_eq!(engine.eval::<String>(r#"format("[{:<5}|{:^5}|{:>5}|{:*^6}]", "ab", "ab", "ab", "ab")"#).unwrap(), "[ab   | ab  |   ab|**ab**]");
+    assert_eq!(engine.eval::<String>(r#"format("[{:5}|{:<5}|{:05}|{:+}]", 42, 42, -42, 42)"#).unwrap(), "[   42|42   |-0042|+42]");
+    assert_eq!(engine.eval::<String>(r#"format("{:x} {:X} {:o} {:b}", 255, 255, 8, 5)"#).unwrap(), "ff FF 10 101");
+    assert_eq!(engine.eval::<String>(r#"format("{:#x} {:#010b}", 255, 5)"#).unwrap(), "0xff 0b00000101");
+    assert_eq!(engine.eval::<String>(r#"format("{:.3} {:?}", "abcdef", "hi")"#).unwrap(), r#"abc "hi""#);
+    assert_eq!(engine.eval::<String>(r#"format("{:e}", 1234)"#).unwrap(), "1.234e3");
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<String>(r#"format("{:.2}|{:8.3}|{:<8.1}|{:+.1}", 3.14159, 2.5, -1.25, 1.0)"#).unwrap(), "3.14|   2.500|-1.2    |+1.0");
+        assert_eq!(engine.eval::<String>(r#"format("{:08.3} {:.2e} {:E}", -3.14159, 1234.5, 0.5)"#).unwrap(), "-003.142 1.23e3 5E-1");
+        assert_eq!(engine.eval::<String>(r#"format("{} {:.2}", 1.0, 7)"#).unwrap(), "1.0 7.00");
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<String>(r#"format_array("{1}{0}", [1, 2])"#).unwrap(), "21");
+}
+
+#[test]
+fn test_string_format_interpolation() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("let x = 42; `[${x:>6}] [${x:<6}] [${x:06}] [${x:#x}]`").unwrap(), "[    42] [42    ] [000042] [0x2a]");
+    assert_eq!(engine.eval::<String>(r#"let s = "hello"; `[${s:-^9}] [${s:.2}] [${s:?}]`"#).unwrap(), r#"[--hello--] [he] ["hello"]"#);
+    assert_eq!(engine.eval::<String>("let x = 10; `${ let y = x * 2; y : >4 }|${x}`").unwrap(), "  20|10");
+    assert_eq!(engine.eval::<String>("`${if true { 1 } else { 2 }:03}`").unwrap(), "001");
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(engine.eval::<String>("let x = 3.14159; `${x:08.3}`").unwrap(), "0003.142");
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<String>("let m = #{a: 1}; `${m.a:>3}${#{b: 2}.b:>3}`").unwrap(), "  1  2");
+}
+
+#[test]
+fn test_string_format_custom_type() {
+    #[derive(Debug, Clone)]
+    struct Point(INT, INT);
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Point>("Point")
+        .register_fn("point", |x: INT, y: INT| Point(x, y))
+        .register_fn("to_string", |p: &mut Point| format!("({}, {})", p.0, p.1))
+        .register_fn("to_debug", |p: &mut Point| format!("Point {{ x: {}, y: {} }}", p.0, p.1));
+
+    assert_eq!(engine.eval::<String>(r#"format("[{:>10}]", point(1, 2))"#).unwrap(), "[    (1, 2)]");
+    assert_eq!(engine.eval::<String>("let p = point(1, 2); `${p:?}`").unwrap(), "Point { x: 1, y: 2 }");
+}
+
+#[test]
+fn test_string_format_errors() {
+    let engine = Engine::new();
+
+    assert!(matches!(*engine.eval::<String>(r#"format("{2}", 1)"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<String>(r#"format("{:q}", 1)"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<String>(r#"format("{", 1)"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<String>(r#"format("}", 1)"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<String>(r#"format("{:x}", "a")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    assert!(matches!(engine.compile("`${1:q}`").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+    assert!(matches!(engine.compile("`${1:>5").unwrap_err().err_type(), ParseErrorType::BadInput(..)));
+}
+
+#[test]
+#[cfg(not(feature = "unchecked"))]
+fn test_string_format_limits() {
+    let mut engine = Engine::new();
+
+    engine.set_max_string_size(100);
+
+    assert!(matches!(*engine.eval::<String>(r#"format("{:1000}", 1)"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert!(matches!(*engine.eval::<String>("let x = 1; `${x:>1000}`").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    assert_eq!(engine.eval::<String>(r#"format("{:10}", 1)"#).unwrap(), "         1");
+}